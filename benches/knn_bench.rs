@@ -0,0 +1,67 @@
+// Compara o knn por força bruta com a busca na KdArvore, em dados aleatórios de
+// 100 a 100.000 pontos com 2, 10 e 50 características. Execute com:
+//
+//     cargo bench --bench knn_bench
+//
+// O relatório com os gráficos fica em target/criterion/report/index.html.
+
+use aprendizagem_knn::{knn, KdArvore, Ponto, Rng};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use std::hint::black_box;
+use std::time::Duration;
+
+const TAMANHOS: [usize; 4] = [100, 1_000, 10_000, 100_000];
+const DIMENSOES: [usize; 3] = [2, 10, 50];
+const K: usize = 5;
+// Os pontos de consulta se alternam entre as iterações, para não medir sempre o mesmo caminho na árvore
+const N_CONSULTAS: usize = 64;
+const SEMENTE: u64 = 42;
+
+// Pontos com características uniformes em [0, 1) e um de três rótulos, sempre os mesmos para a mesma semente
+fn gerar_pontos(rng: &mut Rng, quantidade: usize, dimensao: usize) -> Vec<Ponto> {
+    (0..quantidade)
+        .map(|i| Ponto::novo((0..dimensao).map(|_| rng.proximo_f64()).collect(), format!("Classe {}", i % 3)))
+        .collect()
+}
+
+fn comparar_buscas(c: &mut Criterion) {
+    for dimensao in DIMENSOES {
+        let mut grupo = c.benchmark_group(format!("knn_{dimensao}_caracteristicas"));
+        for tamanho in TAMANHOS {
+            let mut rng = Rng::novo(SEMENTE);
+            let dados = gerar_pontos(&mut rng, tamanho, dimensao);
+            let consultas = gerar_pontos(&mut rng, N_CONSULTAS, dimensao);
+            // A árvore é montada uma vez, fora da medição, como seria em uso real
+            let arvore = KdArvore::construir(&dados);
+
+            grupo.bench_with_input(BenchmarkId::new("forca_bruta", tamanho), &dados, |b, dados| {
+                let mut i = 0;
+                b.iter(|| {
+                    i = (i + 1) % N_CONSULTAS;
+                    knn(black_box(dados), black_box(&consultas[i]), K)
+                });
+            });
+            grupo.bench_with_input(BenchmarkId::new("kd_arvore", tamanho), &arvore, |b, arvore| {
+                let mut i = 0;
+                b.iter(|| {
+                    i = (i + 1) % N_CONSULTAS;
+                    arvore.buscar_k_vizinhos(black_box(&consultas[i]), K)
+                });
+            });
+        }
+        grupo.finish();
+    }
+}
+
+criterion_group! {
+    name = benches;
+    // Poucas amostras: com 100.000 pontos cada busca por força bruta leva milissegundos, e as
+    // 100 amostras padrão deixariam o benchmark inteiro muito longo
+    config = Criterion::default()
+        .with_plots()
+        .sample_size(10)
+        .warm_up_time(Duration::from_secs(1))
+        .measurement_time(Duration::from_secs(3));
+    targets = comparar_buscas
+}
+criterion_main!(benches);
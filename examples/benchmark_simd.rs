@@ -0,0 +1,45 @@
+// Compara a distância euclidiana escalar com a versão SIMD em pontos de 128 características.
+// Execute em modo release, senão as medidas não dizem nada:
+//
+//     cargo run --release --example benchmark_simd
+
+use aprendizagem_knn::{distancia_euclidiana, distancia_euclidiana_simd, simd_disponivel, Ponto, Rng};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const DIMENSAO: usize = 128;
+const N_PONTOS: usize = 10_000;
+const REPETICOES: usize = 20;
+
+// Mede o tempo de calcular a distância de um ponto de consulta até todos os pontos do conjunto
+fn medir(dados: &[Ponto], consulta: &Ponto, distancia: fn(&Ponto, &Ponto) -> f64) -> Duration {
+    let inicio = Instant::now();
+    for _ in 0..REPETICOES {
+        for ponto in dados {
+            // black_box impede o compilador de descartar um cálculo cujo resultado não é usado
+            black_box(distancia(black_box(consulta), black_box(ponto)));
+        }
+    }
+    inicio.elapsed()
+}
+
+fn main() {
+    let mut rng = Rng::novo(42);
+    let mut ponto_aleatorio = || {
+        Ponto::novo((0..DIMENSAO).map(|_| rng.proximo_f64()).collect(), String::new())
+    };
+    let dados: Vec<Ponto> = (0..N_PONTOS).map(|_| ponto_aleatorio()).collect();
+    let consulta = ponto_aleatorio();
+
+    let escalar = medir(&dados, &consulta, distancia_euclidiana);
+    let simd = medir(&dados, &consulta, distancia_euclidiana_simd);
+    let total = (N_PONTOS * REPETICOES) as f64;
+
+    println!(
+        "{} distâncias de {} características (AVX disponível: {})",
+        N_PONTOS * REPETICOES, DIMENSAO, simd_disponivel()
+    );
+    println!("  escalar: {:>8.1} ns por distância", escalar.as_nanos() as f64 / total);
+    println!("  simd:    {:>8.1} ns por distância", simd.as_nanos() as f64 / total);
+    println!("  ganho:   {:.2}x", escalar.as_secs_f64() / simd.as_secs_f64());
+}
@@ -0,0 +1,36 @@
+// Alvo de fuzzing da leitura de CSV: bytes quaisquer (UTF-8 inválido, quebras de linha
+// dentro de aspas, aspas sem fechar...) nunca podem causar pânico, só um Err.
+// Execute com `cargo +nightly fuzz run fuzz_csv` dentro da pasta fuzz/
+#![no_main]
+
+use std::io::Cursor;
+
+use aprendizagem_knn::{carregar_dados_de_leitor, carregar_dados_de_leitor_com_opcoes, OpcoesCsv, Ponto};
+use libfuzzer_sys::fuzz_target;
+
+// O que a leitura garante quando dá certo: ao menos um ponto, todos com o mesmo número de
+// características, números finitos e rótulo preenchido
+fn conferir(pontos: &[Ponto]) {
+    assert!(!pontos.is_empty());
+    let dimensao = pontos[0].caracteristicas.len();
+    for ponto in pontos {
+        assert_eq!(ponto.caracteristicas.len(), dimensao);
+        assert!(ponto.caracteristicas.iter().all(|valor| valor.is_finite()), "{:?}", ponto);
+        assert!(!ponto.rotulo.is_empty());
+    }
+}
+
+fuzz_target!(|bytes: &[u8]| {
+    // carregar_dados_do_csv só abre o arquivo e chama esta função: ler da memória testa o
+    // mesmo código sem gravar um arquivo por entrada
+    if let Ok(pontos) = carregar_dados_de_leitor(Cursor::new(bytes), "<fuzz>") {
+        conferir(&pontos);
+    }
+
+    // As opções que mais mudam o caminho da leitura: delimitador detectado, vírgula
+    // decimal conforme o delimitador e todos os erros coletados
+    let opcoes = OpcoesCsv { delimitador: None, virgula_decimal: None, coletar_erros: true, ..OpcoesCsv::default() };
+    if let Ok((_, pontos)) = carregar_dados_de_leitor_com_opcoes(Cursor::new(bytes), "<fuzz>", &opcoes) {
+        conferir(&pontos);
+    }
+});
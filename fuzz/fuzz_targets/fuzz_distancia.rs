@@ -0,0 +1,23 @@
+// Alvo de fuzzing da distância euclidiana: pontos quaisquer (vazios, de tamanhos
+// diferentes, com NaN, infinitos ou números enormes) nunca podem causar pânico.
+// Execute com `cargo +nightly fuzz run fuzz_distancia` dentro da pasta fuzz/
+#![no_main]
+
+use aprendizagem_knn::{distancia_euclidiana, Ponto};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|pontos: (Ponto, Ponto)| {
+    let (a, b) = pontos;
+    let distancia = distancia_euclidiana(&a, &b);
+
+    // Uma soma de quadrados nunca é negativa: o resultado é NaN (de um NaN na entrada, ou
+    // de infinito menos infinito) ou está em [0, ∞]
+    assert!(distancia.is_nan() || distancia >= 0.0, "{}", distancia);
+    // A ordem dos pontos não importa
+    let inversa = distancia_euclidiana(&b, &a);
+    assert!(distancia == inversa || (distancia.is_nan() && inversa.is_nan()), "{} e {}", distancia, inversa);
+    // Com os mesmos valores finitos, a distância é zero
+    if a.caracteristicas.iter().all(|valor| valor.is_finite()) {
+        assert_eq!(distancia_euclidiana(&a, &a), 0.0);
+    }
+});
@@ -0,0 +1,136 @@
+//! Agrupamento sem rótulos: k-means (algoritmo de Lloyd).
+
+use crate::aleatorio::Rng;
+use crate::distancia::distancia_euclidiana;
+use crate::ponto::Ponto;
+
+// ==================== K-MEANS ====================
+/// Separa os pontos em `k` grupos (clusters) pelo algoritmo de Lloyd, ignorando os rótulos.
+///
+/// 1. Sorteia `k` pontos distintos de `dados` (com a `semente`) como centróides iniciais
+/// 2. Atribui cada ponto ao centróide mais próximo (distância euclidiana; empates vão
+///    para o centróide de menor índice)
+/// 3. Recalcula cada centróide como a média das características dos seus pontos
+/// 4. Repete 2 e 3 até as atribuições pararem de mudar ou `max_iteracoes` acabarem
+///
+/// Devolve o índice do cluster (de `0` a `k - 1`) de cada ponto, na ordem de `dados`.
+/// Se um cluster ficar sem pontos, o seu centróide continua onde estava. O resultado
+/// depende da inicialização: rode com sementes diferentes e fique com a de menor
+/// [`calcular_inercia`].
+///
+/// Entra em pânico se `k` for 0 ou maior que o número de pontos.
+///
+/// ```
+/// use aprendizagem_knn::{kmeans, pontos};
+///
+/// // Dois grupos bem separados; os rótulos não são usados
+/// let dados = pontos![
+///     [0.0, 0.0] => "?", [0.5, 0.0] => "?", [0.0, 0.5] => "?",
+///     [10.0, 10.0] => "?", [10.5, 10.0] => "?", [10.0, 10.5] => "?",
+/// ];
+/// let grupos = kmeans(&dados, 2, 100, 42);
+///
+/// assert_eq!(grupos.len(), 6);
+/// assert!(grupos[0] == grupos[1] && grupos[1] == grupos[2]);
+/// assert!(grupos[3] == grupos[4] && grupos[4] == grupos[5]);
+/// assert_ne!(grupos[0], grupos[3]);
+/// ```
+pub fn kmeans(dados: &[Ponto], k: usize, max_iteracoes: usize, semente: u64) -> Vec<usize> {
+    assert!(k >= 1 && k <= dados.len(), "k deve estar entre 1 e {} (o número de pontos), recebido {}", dados.len(), k);
+
+    // Os centróides são guardados como pontos (sem rótulo) para usar distancia_euclidiana
+    let mut centroides: Vec<Ponto> = Rng::novo(semente)
+        .amostrar_indices(dados.len(), k)
+        .into_iter()
+        .map(|i| Ponto::novo(dados[i].caracteristicas.clone(), String::new()))
+        .collect();
+    let mut atribuicoes = atribuir_aos_centroides(dados, &centroides);
+
+    for _ in 0..max_iteracoes {
+        for (cluster, media) in calcular_centroides(dados, &atribuicoes, k).into_iter().enumerate() {
+            // Cluster vazio: a média não existe, então o centróide fica onde estava
+            if !media.iter().any(|valor| valor.is_nan()) {
+                centroides[cluster].caracteristicas = media;
+            }
+        }
+        let novas = atribuir_aos_centroides(dados, &centroides);
+        if novas == atribuicoes {
+            break;
+        }
+        atribuicoes = novas;
+    }
+
+    atribuicoes
+}
+
+// Índice do centróide mais próximo de cada ponto (o primeiro, em caso de empate)
+fn atribuir_aos_centroides(dados: &[Ponto], centroides: &[Ponto]) -> Vec<usize> {
+    dados.iter()
+        .map(|ponto| {
+            centroides.iter()
+                .map(|centroide| distancia_euclidiana(ponto, centroide))
+                .enumerate()
+                .min_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)))
+                .map(|(indice, _)| indice)
+                .expect("há ao menos um centróide")
+        })
+        .collect()
+}
+
+/// Centróide de cada um dos `k` clusters: a média das características dos pontos
+/// atribuídos a ele. Um cluster sem pontos não tem média e fica com valores NaN.
+///
+/// ```
+/// use aprendizagem_knn::{calcular_centroides, pontos};
+///
+/// let dados = pontos![[0.0, 2.0] => "?", [2.0, 4.0] => "?", [10.0, 10.0] => "?"];
+/// let centroides = calcular_centroides(&dados, &[0, 0, 1], 3);
+/// assert_eq!(centroides[0], vec![1.0, 3.0]);
+/// assert_eq!(centroides[1], vec![10.0, 10.0]);
+/// assert!(centroides[2].iter().all(|valor| valor.is_nan()));
+/// ```
+pub fn calcular_centroides(dados: &[Ponto], atribuicoes: &[usize], k: usize) -> Vec<Vec<f64>> {
+    let dimensao = dados.first().map_or(0, |ponto| ponto.caracteristicas.len());
+    let mut somas = vec![vec![0.0; dimensao]; k];
+    let mut contagens = vec![0usize; k];
+    for (ponto, &cluster) in dados.iter().zip(atribuicoes) {
+        contagens[cluster] += 1;
+        for (soma, valor) in somas[cluster].iter_mut().zip(&ponto.caracteristicas) {
+            *soma += valor;
+        }
+    }
+
+    somas.into_iter()
+        .zip(contagens)
+        .map(|(soma, contagem)| soma.into_iter().map(|total| total / contagem as f64).collect())
+        .collect()
+}
+
+/// Inércia de um agrupamento: a soma, sobre todos os pontos, do quadrado da distância
+/// euclidiana até o centróide do seu cluster.
+///
+/// Quanto menor, mais compactos são os clusters. Ela nunca aumenta entre uma iteração e
+/// outra do k-means, por isso serve para comparar execuções com sementes diferentes (a
+/// de menor inércia é a melhor); entre valores de `k` diferentes não vale comparar
+/// direto, já que mais clusters sempre reduzem a inércia.
+///
+/// ```
+/// use aprendizagem_knn::{calcular_centroides, calcular_inercia, kmeans, pontos};
+///
+/// let dados = pontos![[0.0] => "?", [2.0] => "?", [10.0] => "?", [12.0] => "?"];
+/// let atribuicoes = kmeans(&dados, 2, 100, 7);
+/// let centroides = calcular_centroides(&dados, &atribuicoes, 2);
+/// // Cada ponto está a 1 do centróide do seu grupo: 4 * 1²
+/// assert_eq!(calcular_inercia(&dados, &centroides, &atribuicoes), 4.0);
+/// ```
+pub fn calcular_inercia(dados: &[Ponto], centroides: &[Vec<f64>], atribuicoes: &[usize]) -> f64 {
+    dados.iter()
+        .zip(atribuicoes)
+        .map(|(ponto, &cluster)| {
+            ponto.caracteristicas.iter()
+                .zip(&centroides[cluster])
+                .map(|(a, b)| (a - b).powi(2))
+                .sum::<f64>()
+        })
+        .sum()
+}
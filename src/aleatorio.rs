@@ -0,0 +1,232 @@
+//! Gerador de números pseudoaleatórios com semente, para experimentos reproduzíveis.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+// ==================== SEMENTE GLOBAL ====================
+/// Semente usada quando [`definir_semente`] nunca foi chamada.
+///
+/// ```
+/// use aprendizagem_knn::{obter_semente, Rng, SEMENTE_PADRAO};
+///
+/// assert_eq!(obter_semente(), SEMENTE_PADRAO);
+/// assert_eq!(Rng::default().proximo_u64(), Rng::novo(SEMENTE_PADRAO).proximo_u64());
+/// ```
+pub const SEMENTE_PADRAO: u64 = 42;
+
+/// Semente compartilhada pelo programa inteiro, guardada em um `AtomicU64` para poder ser
+/// lida e trocada de qualquer thread sem `unsafe` nem `Mutex`.
+///
+/// Em geral não é preciso usar o tipo diretamente: [`definir_semente`] e
+/// [`obter_semente`] mexem na instância global, que começa com [`SEMENTE_PADRAO`].
+///
+/// ```
+/// use aprendizagem_knn::SementeGlobal;
+///
+/// let semente = SementeGlobal::nova(7);
+/// assert_eq!(semente.obter(), 7);
+/// semente.definir(8);
+/// assert_eq!(semente.obter(), 8);
+/// ```
+#[derive(Debug)]
+pub struct SementeGlobal(AtomicU64);
+
+impl SementeGlobal {
+    /// Cria uma semente com o valor inicial dado (é `const` para poder inicializar um `static`).
+    ///
+    /// ```
+    /// use aprendizagem_knn::SementeGlobal;
+    ///
+    /// static SEMENTE: SementeGlobal = SementeGlobal::nova(1);
+    /// assert_eq!(SEMENTE.obter(), 1);
+    /// ```
+    pub const fn nova(semente: u64) -> Self {
+        Self(AtomicU64::new(semente))
+    }
+
+    /// Troca o valor da semente.
+    ///
+    /// ```
+    /// use aprendizagem_knn::SementeGlobal;
+    ///
+    /// let semente = SementeGlobal::nova(1);
+    /// semente.definir(2);
+    /// assert_eq!(semente.obter(), 2);
+    /// ```
+    pub fn definir(&self, semente: u64) {
+        self.0.store(semente, Ordering::Relaxed);
+    }
+
+    /// Valor atual da semente.
+    ///
+    /// ```
+    /// use aprendizagem_knn::SementeGlobal;
+    ///
+    /// assert_eq!(SementeGlobal::nova(5).obter(), 5);
+    /// ```
+    pub fn obter(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+static SEMENTE_GLOBAL: SementeGlobal = SementeGlobal::nova(SEMENTE_PADRAO);
+
+/// Define a semente global, usada por [`Rng::default`] e devolvida por [`obter_semente`].
+///
+/// Chamada uma vez no começo do programa, torna o experimento inteiro reproduzível: passe
+/// `obter_semente()` como a `semente` de funções como
+/// [`dividir_treino_teste`](crate::dividir_treino_teste) e [`bootstrap_acuracia`](crate::bootstrap_acuracia),
+/// ou crie os geradores com `Rng::default()`.
+///
+/// ```
+/// use aprendizagem_knn::{definir_semente, obter_semente, Rng};
+///
+/// definir_semente(123);
+/// assert_eq!(obter_semente(), 123);
+/// assert_eq!(Rng::default().proximo_u64(), Rng::novo(123).proximo_u64());
+/// ```
+pub fn definir_semente(semente: u64) {
+    SEMENTE_GLOBAL.definir(semente);
+}
+
+/// Semente global atual: o último valor passado para [`definir_semente`] ou, se ela nunca
+/// foi chamada, [`SEMENTE_PADRAO`] (42).
+///
+/// ```
+/// use aprendizagem_knn::{dividir_treino_teste, obter_semente, pontos, SEMENTE_PADRAO};
+///
+/// assert_eq!(obter_semente(), SEMENTE_PADRAO);
+/// let dados = pontos![[1.0] => "A", [2.0] => "A", [3.0] => "B", [4.0] => "B"];
+/// let a = dividir_treino_teste(dados.clone(), 0.5, obter_semente(), false).unwrap();
+/// let b = dividir_treino_teste(dados, 0.5, obter_semente(), false).unwrap();
+/// assert_eq!(a, b);
+/// ```
+pub fn obter_semente() -> u64 {
+    SEMENTE_GLOBAL.obter()
+}
+
+// ==================== GERADOR DE NÚMEROS ALEATÓRIOS ====================
+/// Gerador congruencial linear (LCG) simples, sem dependências externas.
+///
+/// A mesma semente sempre produz a mesma sequência, o que torna os experimentos reproduzíveis.
+///
+/// # Exemplo
+///
+/// ```
+/// use aprendizagem_knn::Rng;
+///
+/// let mut a = Rng::novo(42);
+/// let mut b = Rng::novo(42);
+/// assert_eq!(a.proximo_u64(), b.proximo_u64());
+///
+/// let x = a.proximo_f64();
+/// assert!((0.0..1.0).contains(&x));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Rng {
+    estado: u64,
+}
+
+impl Rng {
+    /// Cria o gerador a partir de uma semente.
+    ///
+    /// ```
+    /// use aprendizagem_knn::Rng;
+    ///
+    /// let mut rng = Rng::novo(7);
+    /// assert!(rng.proximo_usize(10) < 10);
+    /// ```
+    pub fn novo(semente: u64) -> Self {
+        Self { estado: semente }
+    }
+
+    // Avança o LCG (constantes do MMIX de Knuth) e embaralha os bits da saída,
+    // já que os bits baixos de um LCG puro têm períodos muito curtos
+    /// Próximo número de 64 bits da sequência.
+    ///
+    /// ```
+    /// use aprendizagem_knn::Rng;
+    ///
+    /// let mut rng = Rng::novo(1);
+    /// assert_ne!(rng.proximo_u64(), rng.proximo_u64());
+    /// ```
+    pub fn proximo_u64(&mut self) -> u64 {
+        self.estado = self.estado
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        let mut x = self.estado;
+        x ^= x >> 33;
+        x = x.wrapping_mul(0xff51afd7ed558ccd);
+        x ^= x >> 33;
+        x
+    }
+
+    /// Número decimal no intervalo [0, 1), usando os 53 bits da mantissa de um f64.
+    ///
+    /// ```
+    /// use aprendizagem_knn::Rng;
+    ///
+    /// let mut rng = Rng::novo(3);
+    /// assert!((0..100).all(|_| (0.0..1.0).contains(&rng.proximo_f64())));
+    /// ```
+    pub fn proximo_f64(&mut self) -> f64 {
+        (self.proximo_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Número inteiro no intervalo [0, limite).
+    ///
+    /// ```
+    /// use aprendizagem_knn::Rng;
+    ///
+    /// let mut rng = Rng::novo(3);
+    /// assert!((0..100).all(|_| rng.proximo_usize(6) < 6));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Entra em pânico se `limite` for zero: o intervalo [0, 0) não tem nenhum número.
+    ///
+    /// ```should_panic
+    /// use aprendizagem_knn::Rng;
+    ///
+    /// Rng::novo(3).proximo_usize(0);
+    /// ```
+    pub fn proximo_usize(&mut self, limite: usize) -> usize {
+        assert!(limite > 0, "proximo_usize: o limite precisa ser maior que zero");
+        (self.proximo_u64() % limite as u64) as usize
+    }
+
+    /// Sorteia `quantidade` índices distintos de 0..n (sem reposição), usando
+    /// as primeiras posições de um embaralhamento de Fisher-Yates.
+    ///
+    /// ```
+    /// use aprendizagem_knn::Rng;
+    ///
+    /// let mut indices = Rng::novo(5).amostrar_indices(10, 4);
+    /// indices.sort();
+    /// indices.dedup();
+    /// assert_eq!(indices.len(), 4);
+    /// ```
+    pub fn amostrar_indices(&mut self, n: usize, quantidade: usize) -> Vec<usize> {
+        let mut indices: Vec<usize> = (0..n).collect();
+        let quantidade = quantidade.min(n);
+        for i in 0..quantidade {
+            let j = i + self.proximo_usize(n - i);
+            indices.swap(i, j);
+        }
+        indices.truncate(quantidade);
+        indices
+    }
+}
+
+impl Default for Rng {
+    /// Gerador com a semente global ([`obter_semente`]).
+    ///
+    /// ```
+    /// use aprendizagem_knn::{obter_semente, Rng};
+    ///
+    /// assert_eq!(Rng::default().proximo_f64(), Rng::novo(obter_semente()).proximo_f64());
+    /// ```
+    fn default() -> Self {
+        Self::novo(obter_semente())
+    }
+}
@@ -0,0 +1,185 @@
+//! Análise exploratória do conjunto de dados: contagem e distribuição das classes e o
+//! quanto as classes estão separadas umas das outras.
+
+use std::collections::HashMap;
+
+use crate::distancia::distancia_euclidiana;
+use crate::erro::ErroKnn;
+use crate::ponto::Ponto;
+
+// ==================== ANÁLISE DO CONJUNTO DE DADOS ====================
+/// Quantos pontos existem de cada classe.
+///
+/// ```
+/// use aprendizagem_knn::{contar_rotulos, pontos};
+///
+/// let contagem = contar_rotulos(&pontos![[1.0] => "A", [2.0] => "B", [3.0] => "A"]);
+/// assert_eq!(contagem["A"], 2);
+/// assert_eq!(contagem["B"], 1);
+/// ```
+pub fn contar_rotulos(dados: &[Ponto]) -> HashMap<String, usize> {
+    let mut contagem = HashMap::new();
+    for ponto in dados {
+        *contagem.entry(ponto.rotulo.clone()).or_insert(0) += 1;
+    }
+    contagem
+}
+
+// Contagens ordenadas pelo nome da classe (o HashMap não garante ordem)
+pub(crate) fn contagem_ordenada(dados: &[Ponto]) -> Vec<(String, usize)> {
+    let mut contagem: Vec<(String, usize)> = contar_rotulos(dados).into_iter().collect();
+    contagem.sort();
+    contagem
+}
+
+/// Mostra cada classe com sua quantidade e porcentagem do total.
+///
+/// ```
+/// use aprendizagem_knn::{imprimir_distribuicao, pontos};
+///
+/// // Distribuição das classes (2 pontos):
+/// //   A: 1 (50.00%)
+/// //   B: 1 (50.00%)
+/// imprimir_distribuicao(&pontos![[1.0] => "A", [2.0] => "B"]);
+/// ```
+pub fn imprimir_distribuicao(dados: &[Ponto]) {
+    println!("Distribuição das classes ({} pontos):", dados.len());
+    for (rotulo, quantidade) in contagem_ordenada(dados) {
+        let porcentagem = 100.0 * quantidade as f64 / dados.len() as f64;
+        println!("  {}: {} ({:.2}%)", rotulo, quantidade, porcentagem);
+    }
+}
+
+/// Classe com mais pontos (None para conjunto vazio).
+///
+/// Em caso de empate vence a classe de nome alfabeticamente menor.
+///
+/// ```
+/// use aprendizagem_knn::{classe_majoritaria, pontos};
+///
+/// let dados = pontos![[1.0] => "B", [2.0] => "A", [3.0] => "B", [4.0] => "C"];
+/// assert_eq!(classe_majoritaria(&dados), Some("B".to_string()));
+/// assert_eq!(classe_majoritaria(&[]), None);
+/// ```
+pub fn classe_majoritaria(dados: &[Ponto]) -> Option<String> {
+    contagem_ordenada(dados).into_iter()
+        .rev()
+        .max_by_key(|(_, quantidade)| *quantidade)
+        .map(|(rotulo, _)| rotulo)
+}
+
+/// Classe com menos pontos (None para conjunto vazio), com o mesmo critério de desempate.
+///
+/// ```
+/// use aprendizagem_knn::{classe_minoritaria, pontos};
+///
+/// let dados = pontos![[1.0] => "B", [2.0] => "A", [3.0] => "B", [4.0] => "C"];
+/// assert_eq!(classe_minoritaria(&dados), Some("A".to_string()));
+/// ```
+pub fn classe_minoritaria(dados: &[Ponto]) -> Option<String> {
+    contagem_ordenada(dados).into_iter()
+        .min_by_key(|(_, quantidade)| *quantidade)
+        .map(|(rotulo, _)| rotulo)
+}
+
+// ==================== SEPARABILIDADE DAS CLASSES ====================
+// Antes de escolher a métrica (ou de culpar o k), vale saber se as classes estão mesmo
+// separadas: se os pontos de classes diferentes estão, em média, tão perto quanto os da
+// mesma classe, nenhum ajuste do KNN vai separá-las bem
+
+/// Distâncias médias entre pontos da mesma classe e de classes diferentes, e o índice de
+/// Fisher que resume as duas; é devolvido por [`estatisticas_separabilidade`].
+///
+/// ```
+/// use aprendizagem_knn::{estatisticas_separabilidade, pontos};
+///
+/// let separabilidade = estatisticas_separabilidade(&pontos![[0.0] => "A", [2.0] => "A", [10.0] => "B"]).unwrap();
+/// assert_eq!(separabilidade.distancia_media_intraclasse, 2.0);
+/// assert_eq!(separabilidade.distancia_media_interclasse, 9.0);
+/// // Uma só distância intraclasse: variância zero e índice infinito
+/// assert_eq!(separabilidade.indice_fisher, f64::INFINITY);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeparabilidadeClasses {
+    /// Média das distâncias entre todos os pares de pontos da mesma classe
+    pub distancia_media_intraclasse: f64,
+    /// Média das distâncias entre todos os pares de pontos de classes diferentes
+    pub distancia_media_interclasse: f64,
+    /// `distancia_media_interclasse² / variância das distâncias intraclasse`: quanto maior,
+    /// mais longe as classes estão umas das outras em relação à dispersão dentro delas
+    pub indice_fisher: f64,
+}
+
+/// Mede o quanto as classes de `dados` estão separadas, pela distância euclidiana entre
+/// todos os pares de pontos: os pares da mesma classe (intraclasse) e os de classes
+/// diferentes (interclasse).
+///
+/// O índice de Fisher é o quadrado da distância média interclasse dividido pela variância
+/// (populacional) das distâncias intraclasse. Classes compactas e distantes dão um índice
+/// alto; classes misturadas, um índice perto de zero ou abaixo de um. Se todas as
+/// distâncias intraclasse forem iguais, a variância é zero e o índice é infinito (ou zero,
+/// se a distância média interclasse também for zero). Como olha todos os pares, o custo é
+/// O(n²).
+///
+/// Com menos de duas classes não há pares interclasse, e sem nenhuma classe com dois
+/// pontos não há pares intraclasse: os dois casos são [`ErroKnn::VetorVazio`]. Pontos com
+/// números de características diferentes são [`ErroKnn::DimensaoIncompativel`].
+///
+/// ```
+/// use aprendizagem_knn::{estatisticas_separabilidade, pontos, ErroKnn};
+///
+/// let dados = pontos![[0.0] => "A", [1.0] => "A", [3.0] => "A", [10.0] => "B", [11.0] => "B"];
+/// let separabilidade = estatisticas_separabilidade(&dados).unwrap();
+/// // Intraclasse: 1, 3, 2 (A) e 1 (B); interclasse: 10, 11, 9, 10, 7, 8
+/// assert_eq!(separabilidade.distancia_media_intraclasse, 1.75);
+/// assert_eq!(separabilidade.distancia_media_interclasse, 55.0 / 6.0);
+/// assert!(separabilidade.indice_fisher > 100.0);
+///
+/// let erro = estatisticas_separabilidade(&pontos![[0.0] => "A", [1.0] => "A"]).unwrap_err();
+/// assert!(matches!(erro, ErroKnn::VetorVazio(_)));
+/// ```
+pub fn estatisticas_separabilidade(dados: &[Ponto]) -> Result<SeparabilidadeClasses, ErroKnn> {
+    if contar_rotulos(dados).len() < 2 {
+        return Err(ErroKnn::VetorVazio("a separabilidade precisa de pontos de ao menos duas classes".to_string()));
+    }
+    let dimensao = dados[0].caracteristicas.len();
+    if let Some(ponto) = dados.iter().find(|ponto| ponto.caracteristicas.len() != dimensao) {
+        return Err(ErroKnn::DimensaoIncompativel { esperado: dimensao, obtido: ponto.caracteristicas.len() });
+    }
+
+    let mut intraclasse = Vec::new();
+    let (mut soma_interclasse, mut pares_interclasse) = (0.0, 0usize);
+    for (i, a) in dados.iter().enumerate() {
+        for b in &dados[i + 1..] {
+            let distancia = distancia_euclidiana(a, b);
+            if a.rotulo == b.rotulo {
+                intraclasse.push(distancia);
+            } else {
+                soma_interclasse += distancia;
+                pares_interclasse += 1;
+            }
+        }
+    }
+    if intraclasse.is_empty() {
+        return Err(ErroKnn::VetorVazio(
+            "nenhuma classe tem dois pontos, então não há distâncias intraclasse".to_string(),
+        ));
+    }
+
+    let media_intraclasse = intraclasse.iter().sum::<f64>() / intraclasse.len() as f64;
+    let variancia_intraclasse = intraclasse.iter().map(|distancia| (distancia - media_intraclasse).powi(2)).sum::<f64>()
+        / intraclasse.len() as f64;
+    let media_interclasse = soma_interclasse / pares_interclasse as f64;
+    // Com a média interclasse zero, as classes não estão separadas: 0, e não 0 / 0
+    let indice_fisher = if media_interclasse == 0.0 {
+        0.0
+    } else {
+        media_interclasse.powi(2) / variancia_intraclasse
+    };
+
+    Ok(SeparabilidadeClasses {
+        distancia_media_intraclasse: media_intraclasse,
+        distancia_media_interclasse: media_interclasse,
+        indice_fisher,
+    })
+}
@@ -0,0 +1,214 @@
+//! Detecção de anomalias com os k vizinhos mais próximos.
+
+use crate::distancia::DistanciaMetrica;
+use crate::knn::k_vizinhos_mais_proximos;
+use crate::metricas::area_trapezios;
+use crate::ponto::Ponto;
+
+// ==================== PONTUAÇÃO DE ANOMALIA ====================
+// Distância média até os k vizinhos mais próximos, com qualquer métrica e qualquer
+// coleção de pontos de referência (para poder deixar o próprio ponto de fora)
+pub(crate) fn pontuacao_anomalia_sobre<'a, I>(
+    referencia: I,
+    ponto_consulta: &Ponto,
+    k: usize,
+    metrica: &DistanciaMetrica,
+) -> f64
+where
+    I: IntoIterator<Item = &'a Ponto>,
+{
+    let vizinhos = k_vizinhos_mais_proximos(referencia, ponto_consulta, k, metrica);
+    assert!(!vizinhos.is_empty(), "é preciso ao menos um ponto de referência");
+    vizinhos.iter().map(|vizinho| vizinho.distancia).sum::<f64>() / vizinhos.len() as f64
+}
+
+/// Pontuação de anomalia de um ponto: a distância euclidiana média até os seus `k`
+/// vizinhos mais próximos no `treinamento` (que deve ter só pontos normais).
+///
+/// Pontos em regiões densas têm vizinhos perto e pontuação baixa; um ponto isolado, longe
+/// de tudo o que foi visto, tem pontuação alta. Os rótulos não são usados. Se o
+/// treinamento tiver menos de `k` pontos, a média é sobre todos eles.
+///
+/// Entra em pânico se `k` for 0 ou se o treinamento estiver vazio.
+///
+/// ```
+/// use aprendizagem_knn::{ponto, pontos, pontuacao_anomalia};
+///
+/// let normais = pontos![[0.0] => "", [1.0] => "", [2.0] => "", [3.0] => ""];
+/// // Vizinhos mais próximos de 1.5: 1.0 e 2.0, ambos a 0.5
+/// assert_eq!(pontuacao_anomalia(&normais, &ponto!([1.5], ""), 2), 0.5);
+/// // Vizinhos de 10.0: 3.0 e 2.0, a 7 e 8
+/// assert_eq!(pontuacao_anomalia(&normais, &ponto!([10.0], ""), 2), 7.5);
+/// ```
+pub fn pontuacao_anomalia(treinamento: &[Ponto], ponto_consulta: &Ponto, k: usize) -> f64 {
+    assert!(k > 0, "k deve ser maior que zero");
+    pontuacao_anomalia_sobre(treinamento, ponto_consulta, k, &DistanciaMetrica::Euclidiana)
+}
+
+/// Como [`pontuacao_anomalia`], mas com a [`distancia_canberra`](crate::distancia_canberra).
+///
+/// Na distância euclidiana, a característica de maior escala manda: um desvio de 30 numa
+/// coluna que vale 1000 esconde um valor dez vezes maior que o normal numa coluna que vale
+/// 0.01. Na de Canberra, cada característica contribui com `|a - b| / (|a| + |b|)`, entre 0
+/// e 1, então o que conta é a mudança relativa e todas as colunas pesam o mesmo, sem
+/// precisar normalizar antes. Por outro lado, valores perto de zero ficam sensíveis demais.
+///
+/// Entra em pânico se `k` for 0 ou se o treinamento estiver vazio.
+///
+/// ```
+/// use aprendizagem_knn::{ponto, pontos, pontuacao_anomalia_canberra};
+///
+/// let normais = pontos![[1.0, 100.0] => "", [1.0, 102.0] => ""];
+/// // Dobrar a primeira característica conta tanto quanto dobrar a segunda
+/// let a = pontuacao_anomalia_canberra(&normais, &ponto!([2.0, 100.0], ""), 1);
+/// let b = pontuacao_anomalia_canberra(&normais, &ponto!([1.0, 200.0], ""), 1);
+/// assert!((a - 1.0 / 3.0).abs() < 1e-12 && (b - 98.0 / 302.0).abs() < 1e-12);
+/// ```
+pub fn pontuacao_anomalia_canberra(treinamento: &[Ponto], ponto_consulta: &Ponto, k: usize) -> f64 {
+    assert!(k > 0, "k deve ser maior que zero");
+    pontuacao_anomalia_sobre(treinamento, ponto_consulta, k, &DistanciaMetrica::Canberra)
+}
+
+/// As duas pontuações de anomalia de um ponto, `(euclidiana, canberra)`: a de
+/// [`pontuacao_anomalia`] e a de [`pontuacao_anomalia_canberra`].
+///
+/// As escalas são diferentes (a de Canberra fica entre 0 e o número de características),
+/// então compare cada uma com as pontuações dos pontos normais na mesma métrica, e não uma
+/// com a outra.
+///
+/// Entra em pânico se `k` for 0 ou se o treinamento estiver vazio.
+///
+/// ```
+/// use aprendizagem_knn::{comparar_scores_anomalia, ponto, pontos};
+///
+/// // Uma característica na casa dos milhares e outra na dos centésimos
+/// let normais = pontos![[1000.0, 0.010] => "", [1002.0, 0.011] => "", [1004.0, 0.012] => ""];
+///
+/// // A segunda característica dez vezes maior: a euclidiana quase não vê
+/// let (euclidiana, canberra) = comparar_scores_anomalia(&normais, &ponto!([1002.0, 0.11], ""), 2);
+/// assert!(euclidiana < 2.0 && canberra > 0.8, "{} {}", euclidiana, canberra);
+///
+/// // 30 a mais na primeira (3%): grande para a euclidiana, pequeno para a de Canberra
+/// let (euclidiana, canberra) = comparar_scores_anomalia(&normais, &ponto!([1033.0, 0.011], ""), 2);
+/// assert!(euclidiana > 29.0 && canberra < 0.1, "{} {}", euclidiana, canberra);
+/// ```
+pub fn comparar_scores_anomalia(treinamento: &[Ponto], ponto_consulta: &Ponto, k: usize) -> (f64, f64) {
+    (
+        pontuacao_anomalia(treinamento, ponto_consulta, k),
+        pontuacao_anomalia_canberra(treinamento, ponto_consulta, k),
+    )
+}
+
+/// Marca como anômalo (`true`) cada ponto cuja [`pontuacao_anomalia`] em relação ao
+/// `treinamento` seja maior ou igual a `limiar`.
+///
+/// ```
+/// use aprendizagem_knn::{detectar_anomalias, pontos};
+///
+/// let normais = pontos![[0.0] => "", [1.0] => "", [2.0] => "", [3.0] => ""];
+/// let novos = pontos![[1.5] => "", [10.0] => ""];
+/// assert_eq!(detectar_anomalias(&normais, &novos, 2, 2.0), vec![false, true]);
+/// ```
+pub fn detectar_anomalias(treinamento: &[Ponto], pontos: &[Ponto], k: usize, limiar: f64) -> Vec<bool> {
+    pontos.iter()
+        .map(|ponto| pontuacao_anomalia(treinamento, ponto, k) >= limiar)
+        .collect()
+}
+
+// ==================== CURVA PRECISÃO × RECALL ====================
+/// Curva precisão × recall da detecção de anomalias: em vez de um único limiar, mostra o
+/// que acontece com todos.
+///
+/// Cada ponto normal é pontuado em relação aos outros normais (sem ele mesmo) e cada
+/// anômalo em relação a todos os normais. Para `n_pontos_curva` limiares igualmente
+/// espaçados entre a menor e a maior pontuação observadas, os pontos com pontuação maior
+/// ou igual ao limiar são os detectados, e cada item da curva é
+/// `(limiar, precisão, recall)`: a fração dos detectados que é de fato anômala e a fração
+/// dos anômalos que foi detectada. Limiares baixos detectam tudo (recall alto, precisão
+/// baixa); limiares altos só os casos mais extremos.
+///
+/// Os itens vêm em ordem crescente de limiar; com `n_pontos_curva` igual a 1 a curva tem
+/// só o menor limiar. Entra em pânico se `k` for 0, se houver menos de 2 pontos normais
+/// ou nenhum anômalo.
+///
+/// ```
+/// use aprendizagem_knn::{area_pr, curva_precisao_recall_anomalia, pontos};
+///
+/// let normais = pontos![[0.0] => "", [1.0] => "", [2.0] => "", [3.0] => "", [4.0] => ""];
+/// let anomalos = pontos![[20.0] => "", [30.0] => ""];
+///
+/// let curva = curva_precisao_recall_anomalia(&normais, &anomalos, 2, 5);
+/// assert_eq!(curva.len(), 5);
+/// // O menor limiar detecta todos os 7 pontos: precisão 2/7, recall 1
+/// assert_eq!(curva[0].1, 2.0 / 7.0);
+/// assert_eq!(curva[0].2, 1.0);
+/// // O maior só detecta o anômalo mais distante
+/// assert_eq!(curva[4], (curva[4].0, 1.0, 0.5));
+///
+/// // As anomalias estão todas acima dos normais: área 1
+/// assert_eq!(area_pr(&curva), 1.0);
+/// ```
+pub fn curva_precisao_recall_anomalia(
+    dados_normais: &[Ponto],
+    dados_anomalos: &[Ponto],
+    k: usize,
+    n_pontos_curva: usize,
+) -> Vec<(f64, f64, f64)> {
+    assert!(k > 0, "k deve ser maior que zero");
+    assert!(dados_normais.len() >= 2, "são precisos ao menos 2 pontos normais");
+    assert!(!dados_anomalos.is_empty(), "é preciso ao menos um ponto anômalo");
+
+    let metrica = DistanciaMetrica::Euclidiana;
+    // (pontuação, é anômalo?)
+    let mut pontuacoes: Vec<(f64, bool)> = dados_normais.iter()
+        .enumerate()
+        .map(|(i, ponto)| {
+            let outros = dados_normais.iter().enumerate().filter(|(j, _)| *j != i).map(|(_, outro)| outro);
+            (pontuacao_anomalia_sobre(outros, ponto, k, &metrica), false)
+        })
+        .collect();
+    pontuacoes.extend(
+        dados_anomalos.iter().map(|ponto| (pontuacao_anomalia_sobre(dados_normais, ponto, k, &metrica), true)),
+    );
+
+    let minimo = pontuacoes.iter().map(|&(pontuacao, _)| pontuacao).fold(f64::INFINITY, f64::min);
+    let maximo = pontuacoes.iter().map(|&(pontuacao, _)| pontuacao).fold(f64::NEG_INFINITY, f64::max);
+    let passo = if n_pontos_curva > 1 { (maximo - minimo) / (n_pontos_curva - 1) as f64 } else { 0.0 };
+
+    (0..n_pontos_curva)
+        .map(|i| {
+            // O último limiar é o máximo exato, sem erro de arredondamento de minimo + passo * i
+            let limiar = if i + 1 == n_pontos_curva && n_pontos_curva > 1 { maximo } else { minimo + passo * i as f64 };
+            let detectados = pontuacoes.iter().filter(|&&(pontuacao, _)| pontuacao >= limiar);
+            let (total, verdadeiros) = detectados.fold((0, 0), |(total, verdadeiros), &(_, anomalo)| {
+                (total + 1, verdadeiros + usize::from(anomalo))
+            });
+            // Todo limiar está entre o mínimo e o máximo, então ao menos um ponto é detectado
+            (limiar, verdadeiros as f64 / total as f64, verdadeiros as f64 / dados_anomalos.len() as f64)
+        })
+        .collect()
+}
+
+/// Área sob a curva precisão × recall (com o recall no eixo x), pela regra dos trapézios.
+///
+/// Os pontos são ordenados por recall; entre recall 0 e o menor recall da curva, vale a
+/// precisão desse ponto (a curva nunca chega a recall 0, já que o maior limiar ainda
+/// detecta algum ponto). Um detector perfeito tem área 1; um aleatório fica perto da
+/// fração de anomalias nos dados. Uma curva vazia tem área 0.
+///
+/// ```
+/// use aprendizagem_knn::area_pr;
+///
+/// // Precisão 1 até recall 0.5, caindo para 0.5 em recall 1
+/// let curva = [(0.0, 0.5, 1.0), (1.0, 1.0, 0.5)];
+/// assert_eq!(area_pr(&curva), 0.5 * 1.0 + 0.5 * 0.75);
+/// ```
+pub fn area_pr(curva: &[(f64, f64, f64)]) -> f64 {
+    // (recall, precisão), por recall crescente; no empate, a maior precisão primeiro
+    let mut pontos: Vec<(f64, f64)> = curva.iter().map(|&(_, precisao, recall)| (recall, precisao)).collect();
+    pontos.sort_by(|a, b| a.0.total_cmp(&b.0).then(b.1.total_cmp(&a.1)));
+    let Some(&(menor_recall, precisao_inicial)) = pontos.first() else {
+        return 0.0;
+    };
+    menor_recall * precisao_inicial + area_trapezios(&pontos)
+}
@@ -0,0 +1,157 @@
+//! Aprendizado ativo: escolher quais pontos sem rótulo vale a pena mandar rotular.
+
+use crate::aleatorio::Rng;
+use crate::distancia::DistanciaMetrica;
+use crate::erro::ErroKnn;
+use crate::knn::{knn, knn_com_confianca};
+use crate::ponto::Ponto;
+use crate::validacao::dividir_treino_teste;
+
+// ==================== AMOSTRAGEM POR INCERTEZA ====================
+// Rotular dados costuma ser a parte cara (alguém precisa olhar cada exemplo). No
+// aprendizado ativo, o próprio modelo escolhe o que rotular: os pontos em que ele está
+// mais em dúvida, perto da fronteira entre as classes, ensinam mais do que pontos no meio
+// de uma região em que todos os vizinhos já concordam.
+
+/// Escolhe os `n_selecionar` pontos de `nao_rotulados` sobre os quais o KNN está mais
+/// incerto, para serem rotulados.
+///
+/// A incerteza de cada ponto é `1.0 - confiança`, com a confiança do
+/// [`knn_com_confianca`] (a fração dos `k` vizinhos em `treinamento` que concordam com a
+/// classe prevista, distância euclidiana). Devolve os índices em `nao_rotulados`, do mais
+/// incerto para o menos incerto; num empate, o de menor índice vem antes. Se
+/// `n_selecionar` passar do número de pontos, devolve todos; com o treinamento vazio, não
+/// há como medir a incerteza e a lista volta vazia. Os erros são os de
+/// [`knn_com_confianca`] (`k` igual a 0, ou um ponto sem vizinhos).
+///
+/// ```
+/// use aprendizagem_knn::{pontos, selecionar_para_rotular};
+///
+/// let treinamento = pontos![
+///     [0.0] => "A", [1.0] => "A", [2.0] => "A",
+///     [8.0] => "B", [9.0] => "B", [10.0] => "B",
+/// ];
+/// // Os 3 vizinhos de 5.2 se dividem (2 B, 1 A); os de 0.5 e 9.5 são todos da mesma classe
+/// let nao_rotulados = pontos![[0.5] => "?", [5.2] => "?", [9.5] => "?"];
+///
+/// assert_eq!(selecionar_para_rotular(&treinamento, &nao_rotulados, 3, 1), Ok(vec![1]));
+/// assert_eq!(selecionar_para_rotular(&treinamento, &nao_rotulados, 3, 10), Ok(vec![1, 0, 2]));
+/// assert!(selecionar_para_rotular(&treinamento, &nao_rotulados, 0, 1).is_err());
+/// ```
+pub fn selecionar_para_rotular(
+    treinamento: &[Ponto],
+    nao_rotulados: &[Ponto],
+    k: usize,
+    n_selecionar: usize,
+) -> Result<Vec<usize>, ErroKnn> {
+    if treinamento.is_empty() {
+        return Ok(Vec::new());
+    }
+    let incertezas = nao_rotulados
+        .iter()
+        .map(|ponto| {
+            let (_, confianca) = knn_com_confianca(treinamento, ponto, k, &DistanciaMetrica::Euclidiana)?;
+            Ok(1.0 - confianca)
+        })
+        .collect::<Result<Vec<f64>, ErroKnn>>()?;
+
+    // sort_by é estável: num empate, a ordem dos índices é mantida
+    let mut indices: Vec<usize> = (0..nao_rotulados.len()).collect();
+    indices.sort_by(|&a, &b| incertezas[b].total_cmp(&incertezas[a]));
+    indices.truncate(n_selecionar);
+    Ok(indices)
+}
+
+// Fração dos pontos de teste reservada para medir a acurácia da simulação
+const FRACAO_TESTE_SIMULACAO: f64 = 0.3;
+
+/// Simula um ciclo de aprendizado ativo num conjunto já rotulado, para ver quanto a
+/// escolha por incerteza ajuda.
+///
+/// `pool` é dividido (estratificado, com `semente`) em teste (30%) e candidatos. Dos
+/// candidatos, `n_inicial` pontos sorteados começam rotulados e o resto finge não ter
+/// rótulo. A cada iteração, os `n_por_iteracao` candidatos mais incertos (veja
+/// [`selecionar_para_rotular`]) "recebem" o rótulo, isto é, passam para o treinamento.
+///
+/// Devolve a acurácia do KNN com `k_vizinhos` vizinhos no teste: a primeira com os pontos
+/// iniciais e depois uma após cada iteração (`n_iteracoes + 1` valores). Se os candidatos
+/// acabarem antes, ou se a incerteza não puder ser medida (com `k_vizinhos` igual a 0),
+/// a simulação para e a lista fica menor. `n_inicial` é ajustado para ficar entre 1 e o
+/// número de candidatos; um `pool` vazio devolve uma lista vazia.
+///
+/// ```
+/// use aprendizagem_knn::{simulacao_aprendizado_ativo, Ponto};
+///
+/// // Grade 10x10 dividida por uma reta
+/// let pool: Vec<Ponto> = (0..100)
+///     .map(|i| {
+///         let (x, y) = ((i % 10) as f64, (i / 10) as f64);
+///         Ponto::novo(vec![x, y], if x + 0.5 * y < 7.0 { "A" } else { "B" }.to_string())
+///     })
+///     .collect();
+///
+/// let acuracias = simulacao_aprendizado_ativo(pool.clone(), 4, 3, 5, 1, 42);
+/// assert_eq!(acuracias.len(), 6);
+/// // Rotulando os pontos da fronteira, a acurácia melhora
+/// assert!(acuracias[5] > acuracias[0], "{:?}", acuracias);
+/// // A mesma semente repete a simulação
+/// assert_eq!(simulacao_aprendizado_ativo(pool, 4, 3, 5, 1, 42), acuracias);
+/// ```
+pub fn simulacao_aprendizado_ativo(
+    pool: Vec<Ponto>,
+    n_inicial: usize,
+    n_por_iteracao: usize,
+    n_iteracoes: usize,
+    k_vizinhos: usize,
+    semente: u64,
+) -> Vec<f64> {
+    let Ok((candidatos, teste)) = dividir_treino_teste(pool, FRACAO_TESTE_SIMULACAO, semente, true) else {
+        return Vec::new();
+    };
+    if candidatos.is_empty() || teste.is_empty() {
+        return Vec::new();
+    }
+
+    // Sorteia os pontos que começam rotulados (outra semente, independente da divisão)
+    let n_inicial = n_inicial.clamp(1, candidatos.len());
+    let mut inicial = vec![false; candidatos.len()];
+    for indice in Rng::novo(semente.wrapping_add(1)).amostrar_indices(candidatos.len(), n_inicial) {
+        inicial[indice] = true;
+    }
+    let mut rotulados = Vec::with_capacity(candidatos.len());
+    let mut nao_rotulados = Vec::new();
+    for (ponto, rotulado) in candidatos.into_iter().zip(inicial) {
+        if rotulado {
+            rotulados.push(ponto);
+        } else {
+            nao_rotulados.push(ponto);
+        }
+    }
+
+    let mut acuracias = vec![acuracia_no_teste(&rotulados, &teste, k_vizinhos)];
+    for _ in 0..n_iteracoes {
+        if nao_rotulados.is_empty() {
+            break;
+        }
+        let Ok(mut escolhidos) = selecionar_para_rotular(&rotulados, &nao_rotulados, k_vizinhos, n_por_iteracao)
+        else {
+            break;
+        };
+        // Remove do fim para o começo, para que os índices ainda não removidos continuem valendo
+        escolhidos.sort_unstable_by(|a, b| b.cmp(a));
+        for indice in escolhidos {
+            rotulados.push(nao_rotulados.swap_remove(indice));
+        }
+        acuracias.push(acuracia_no_teste(&rotulados, &teste, k_vizinhos));
+    }
+    acuracias
+}
+
+// Fração dos pontos de teste que o KNN acerta usando `treinamento`
+fn acuracia_no_teste(treinamento: &[Ponto], teste: &[Ponto], k: usize) -> f64 {
+    // Com k = 0 o knn não vota, e nenhum ponto conta como acerto
+    let acertos = teste.iter()
+        .filter(|ponto| knn(treinamento, ponto, k).is_ok_and(|rotulo| rotulo == ponto.rotulo))
+        .count();
+    acertos as f64 / teste.len() as f64
+}
@@ -0,0 +1,194 @@
+//! Avaliação do modelo: medidas de desempenho do KNN em um conjunto de teste.
+
+use crate::aleatorio::Rng;
+use crate::erro::ErroKnn;
+use crate::estatisticas::percentil;
+use crate::knn::knn_batch;
+use crate::ponto::Ponto;
+use crate::validacao::RelatorioCV;
+
+// ==================== AVALIAÇÃO DO MODELO ====================
+/// Estima a incerteza da acurácia com bootstrap.
+///
+/// Sorteia (com reposição) n_amostras versões do conjunto de teste, mede a acurácia
+/// de cada uma e usa os percentis 2,5% e 97,5% como intervalo de confiança de 95%.
+/// Retorna (media, limite_inferior, limite_superior). Um teste vazio é
+/// [`ErroKnn::ConjuntoVazio`] e `n_amostras` igual a 0 é [`ErroKnn::ParametroInvalido`],
+/// como em [`bootstrap_previsoes`]; os outros erros são os de [`knn`](crate::knn()).
+///
+/// ```
+/// use aprendizagem_knn::{bootstrap_acuracia, pontos, ErroKnn};
+///
+/// let treinamento = pontos![[0.0] => "A", [1.0] => "A", [9.0] => "B", [10.0] => "B"];
+/// let teste = pontos![[0.5] => "A", [9.5] => "B", [2.0] => "B"];
+/// let (media, inferior, superior) = bootstrap_acuracia(&treinamento, &teste, 1, 200, 42).unwrap();
+/// assert!(inferior <= media && media <= superior);
+///
+/// assert_eq!(bootstrap_acuracia(&treinamento, &[], 1, 200, 42), Err(ErroKnn::ConjuntoVazio));
+/// assert!(bootstrap_acuracia(&treinamento, &teste, 1, 0, 42).is_err());
+/// ```
+pub fn bootstrap_acuracia(
+    treinamento: &[Ponto],
+    teste: &[Ponto],
+    k: usize,
+    n_amostras: usize,
+    semente: u64,
+) -> Result<(f64, f64, f64), ErroKnn> {
+    if teste.is_empty() {
+        return Err(ErroKnn::ConjuntoVazio);
+    }
+    if n_amostras == 0 {
+        return Err(ErroKnn::ParametroInvalido("é preciso ao menos uma amostra de bootstrap".to_string()));
+    }
+
+    // O KNN é determinístico, então classificamos cada ponto uma única vez
+    // e as réplicas apenas sorteiam quais acertos/erros entram na conta
+    let previstos = knn_batch(treinamento, teste, k)?;
+    let acertos: Vec<bool> = teste.iter()
+        .zip(&previstos)
+        .map(|(ponto, previsto)| ponto.rotulo == *previsto)
+        .collect();
+
+    let acuracias = acuracias_bootstrap(&acertos, n_amostras, semente);
+    let media = acuracias.iter().sum::<f64>() / acuracias.len() as f64;
+
+    Ok((media, percentil(&acuracias, 0.025), percentil(&acuracias, 0.975)))
+}
+
+// Acurácias de n_amostras réplicas de bootstrap (sorteio com reposição dos acertos/erros),
+// em ordem crescente para que os percentis possam ser lidos direto
+fn acuracias_bootstrap(acertos: &[bool], n_amostras: usize, semente: u64) -> Vec<f64> {
+    let mut rng = Rng::novo(semente);
+    let mut acuracias: Vec<f64> = (0..n_amostras)
+        .map(|_| {
+            let corretos = (0..acertos.len())
+                .filter(|_| acertos[rng.proximo_usize(acertos.len())])
+                .count();
+            corretos as f64 / acertos.len() as f64
+        })
+        .collect();
+
+    acuracias.sort_by(|a, b| a.total_cmp(b));
+    acuracias
+}
+
+// ==================== BOOTSTRAP SOBRE PREVISÕES JÁ FEITAS ====================
+/// Acurácia com um intervalo de confiança por bootstrap.
+///
+/// É devolvido por [`bootstrap_previsoes`].
+///
+/// ```
+/// use aprendizagem_knn::bootstrap_previsoes;
+///
+/// let reais: Vec<String> = "AAAABBBBBB".chars().map(String::from).collect();
+/// let previstos: Vec<String> = "AAABBBBBBA".chars().map(String::from).collect();
+/// let intervalo = bootstrap_previsoes(&reais, &previstos, 500, 0.9, 42).unwrap();
+/// assert_eq!(intervalo.estimativa, 0.8);
+/// assert_eq!(intervalo.nivel_confianca, 0.9);
+/// assert!(intervalo.inferior <= intervalo.estimativa && intervalo.estimativa <= intervalo.superior);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IntervaloBootstrap {
+    /// Acurácia das previsões originais (sem reamostragem)
+    pub estimativa: f64,
+    /// Limite inferior do intervalo (percentil (1 - nível) / 2 das réplicas)
+    pub inferior: f64,
+    /// Limite superior do intervalo (percentil (1 + nível) / 2 das réplicas)
+    pub superior: f64,
+    /// Nível de confiança do intervalo (0.95 para 95%)
+    pub nivel_confianca: f64,
+}
+
+/// Intervalo de confiança da acurácia por bootstrap sobre previsões já feitas: sorteia
+/// (com reposição, usando a `semente`) `n_amostras` versões dos pares (real, previsto),
+/// mede a acurácia de cada uma e usa os percentis das réplicas como limites do intervalo.
+///
+/// Como trabalha só com os rótulos, não roda o KNN de novo: serve, por exemplo, para as
+/// previsões de uma validação cruzada (veja [`RelatorioCV::intervalo_bootstrap`]).
+/// O intervalo mostra o quanto a acurácia poderia variar com outro conjunto de teste do
+/// mesmo tamanho; com poucos pontos ele fica largo.
+///
+/// É erro se as listas estiverem vazias ou tiverem tamanhos diferentes, se `n_amostras`
+/// for 0 ou se `nivel_confianca` estiver fora de (0.0, 1.0).
+///
+/// ```
+/// use aprendizagem_knn::bootstrap_previsoes;
+///
+/// // 8 acertos em 10
+/// let reais: Vec<String> = "AAAAABBBBB".chars().map(String::from).collect();
+/// let previstos: Vec<String> = "AAAABBBBBA".chars().map(String::from).collect();
+///
+/// let intervalo = bootstrap_previsoes(&reais, &previstos, 1000, 0.95, 42).unwrap();
+/// assert_eq!(intervalo.estimativa, 0.8);
+/// assert!(intervalo.inferior <= intervalo.estimativa && intervalo.estimativa <= intervalo.superior);
+/// assert_eq!((intervalo.inferior, intervalo.superior), (0.5, 1.0));
+///
+/// // A mesma semente repete o intervalo
+/// assert_eq!(bootstrap_previsoes(&reais, &previstos, 1000, 0.95, 42).unwrap(), intervalo);
+/// assert!(bootstrap_previsoes(&reais, &previstos, 1000, 1.0, 42).is_err());
+/// ```
+pub fn bootstrap_previsoes(
+    reais: &[String],
+    previstos: &[String],
+    n_amostras: usize,
+    nivel_confianca: f64,
+    semente: u64,
+) -> Result<IntervaloBootstrap, ErroKnn> {
+    if reais.len() != previstos.len() {
+        return Err(ErroKnn::ParametroInvalido(format!(
+            "há {} rótulos reais e {} previstos",
+            reais.len(),
+            previstos.len()
+        )));
+    }
+    if reais.is_empty() {
+        return Err(ErroKnn::ConjuntoVazio);
+    }
+    if n_amostras == 0 {
+        return Err(ErroKnn::ParametroInvalido("é preciso ao menos uma amostra de bootstrap".to_string()));
+    }
+    if !(nivel_confianca > 0.0 && nivel_confianca < 1.0) {
+        return Err(ErroKnn::ParametroInvalido(format!(
+            "o nível de confiança deve estar em (0.0, 1.0) (recebido {})",
+            nivel_confianca
+        )));
+    }
+
+    let acertos: Vec<bool> = reais.iter().zip(previstos).map(|(real, previsto)| real == previsto).collect();
+    let estimativa = acertos.iter().filter(|&&acerto| acerto).count() as f64 / acertos.len() as f64;
+    let acuracias = acuracias_bootstrap(&acertos, n_amostras, semente);
+    let cauda = (1.0 - nivel_confianca) / 2.0;
+
+    Ok(IntervaloBootstrap {
+        estimativa,
+        inferior: percentil(&acuracias, cauda),
+        superior: percentil(&acuracias, 1.0 - cauda),
+        nivel_confianca,
+    })
+}
+
+impl RelatorioCV {
+    /// Intervalo de confiança por bootstrap da acurácia da validação cruzada, usando as
+    /// previsões guardadas de todos os folds; veja [`bootstrap_previsoes`].
+    ///
+    /// ```
+    /// use aprendizagem_knn::{pontos, validacao_cruzada};
+    ///
+    /// let dados = pontos![
+    ///     [0.0] => "A", [1.0] => "A", [2.0] => "A", [3.0] => "B",
+    ///     [7.0] => "A", [8.0] => "B", [9.0] => "B", [10.0] => "B",
+    /// ];
+    /// let relatorio = validacao_cruzada(&dados, 1, 4, 0).unwrap();
+    /// let intervalo = relatorio.intervalo_bootstrap(500, 0.9, 7).unwrap();
+    /// assert!(intervalo.inferior <= intervalo.estimativa && intervalo.estimativa <= intervalo.superior);
+    /// ```
+    pub fn intervalo_bootstrap(
+        &self,
+        n_amostras: usize,
+        nivel_confianca: f64,
+        semente: u64,
+    ) -> Result<IntervaloBootstrap, ErroKnn> {
+        let (reais, previstos) = self.reais_e_previstos();
+        bootstrap_previsoes(&reais, &previstos, n_amostras, nivel_confianca, semente)
+    }
+}
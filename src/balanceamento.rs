@@ -0,0 +1,341 @@
+//! Balanceamento de classes: gera ou descarta pontos para equilibrar as contagens.
+
+use crate::aleatorio::Rng;
+use crate::analise::{classe_majoritaria, contagem_ordenada};
+use crate::distancia::{distancia_euclidiana, DistanciaMetrica};
+use crate::erro::ErroKnn;
+use crate::knn::k_vizinhos_mais_proximos;
+use crate::ponto::Ponto;
+
+// ==================== SMOTE ====================
+/// SMOTE (Synthetic Minority Over-sampling Technique): cria pontos sintéticos das classes
+/// minoritárias até cada uma ter pelo menos `razao_alvo` vezes a contagem da maior classe.
+///
+/// Cada ponto sintético fica sobre o segmento entre um ponto real da classe, sorteado, e um
+/// dos seus `k` vizinhos mais próximos da mesma classe: `base + t * (vizinho - base)`, com
+/// `t` sorteado em [0, 1). Assim os novos pontos preenchem a região da classe em vez de
+/// apenas repetir os existentes.
+///
+/// Devolve os pontos originais, na ordem de entrada, seguidos dos sintéticos. A mesma
+/// `semente` gera sempre os mesmos pontos. É erro se `razao_alvo` não estiver em (0, 1],
+/// se `k` for zero, se alguma classe que precisa de novos pontos tiver menos de 2 pontos
+/// (não há vizinho para interpolar) ou se algum desses pontos tiver característica não finita.
+///
+/// ```
+/// use aprendizagem_knn::{contar_rotulos, pontos, smote};
+///
+/// let dados = pontos![
+///     [0.0, 0.0] => "A", [1.0, 0.0] => "A", [0.0, 1.0] => "A", [1.0, 1.0] => "A",
+///     [5.0, 5.0] => "B", [6.0, 6.0] => "B",
+/// ];
+/// let balanceados = smote(&dados, 1, 1.0, 42).unwrap();
+/// assert_eq!(contar_rotulos(&balanceados)["B"], 4);
+/// assert_eq!(balanceados.len(), 8);
+///
+/// // os sintéticos de B estão sobre o segmento entre (5, 5) e (6, 6)
+/// for ponto in &balanceados[6..] {
+///     let [x, y] = ponto.caracteristicas[..] else { unreachable!() };
+///     assert!(x == y && (5.0..=6.0).contains(&x));
+/// }
+/// ```
+pub fn smote(treinamento: &[Ponto], k: usize, razao_alvo: f64, semente: u64) -> Result<Vec<Ponto>, ErroKnn> {
+    if !(razao_alvo > 0.0 && razao_alvo <= 1.0) {
+        return Err(ErroKnn::ParametroInvalido(format!(
+            "razao_alvo deve estar em (0.0, 1.0] (recebido {})",
+            razao_alvo
+        )));
+    }
+    if k == 0 {
+        return Err(ErroKnn::ParametroInvalido("k deve ser maior que zero".to_string()));
+    }
+
+    let contagem = contagem_ordenada(treinamento);
+    let maior = contagem.iter().map(|(_, quantidade)| *quantidade).max().ok_or(ErroKnn::ConjuntoVazio)?;
+    let alvo = (razao_alvo * maior as f64).ceil() as usize;
+
+    let mut resultado = treinamento.to_vec();
+    let mut rng = Rng::novo(semente);
+
+    // As classes são percorridas em ordem alfabética para que a semente dê sempre o mesmo resultado
+    for (rotulo, quantidade) in contagem {
+        if quantidade >= alvo {
+            continue;
+        }
+        let classe: Vec<&Ponto> = treinamento.iter().filter(|ponto| ponto.rotulo == rotulo).collect();
+        if classe.len() < 2 {
+            return Err(ErroKnn::ParametroInvalido(format!(
+                "a classe '{}' tem só {} ponto; o SMOTE precisa de ao menos 2 para interpolar",
+                rotulo,
+                classe.len()
+            )));
+        }
+        if classe.iter().any(|ponto| ponto.caracteristicas.iter().any(|valor| !valor.is_finite())) {
+            return Err(ErroKnn::ParametroInvalido(format!(
+                "a classe '{}' tem características ausentes ou infinitas; trate-as antes do SMOTE",
+                rotulo
+            )));
+        }
+
+        // Vizinhos de cada ponto dentro da própria classe, calculados uma vez só.
+        // O próprio ponto fica de fora da busca, e os índices voltam para a numeração de `classe`
+        let vizinhos: Vec<Vec<usize>> = (0..classe.len())
+            .map(|i| {
+                let outros = classe.iter().enumerate().filter(|(j, _)| *j != i).map(|(_, ponto)| *ponto);
+                k_vizinhos_mais_proximos(outros, classe[i], k, &DistanciaMetrica::Euclidiana)
+                    .into_iter()
+                    .map(|vizinho| vizinho.indice)
+                    .map(|posicao| if posicao < i { posicao } else { posicao + 1 })
+                    .collect()
+            })
+            .collect();
+
+        for _ in quantidade..alvo {
+            let base = rng.proximo_usize(classe.len());
+            let vizinho = vizinhos[base][rng.proximo_usize(vizinhos[base].len())];
+            let t = rng.proximo_f64();
+            // (1 - t) * a + t * b em vez de a + t * (b - a): a diferença de dois números
+            // muito grandes poderia estourar para infinito
+            let caracteristicas = classe[base].caracteristicas.iter()
+                .zip(&classe[vizinho].caracteristicas)
+                .map(|(a, b)| (1.0 - t) * a + t * b)
+                .collect();
+            resultado.push(Ponto::novo(caracteristicas, rotulo.clone()));
+        }
+    }
+
+    Ok(resultado)
+}
+
+// ==================== SUBAMOSTRAGEM ALEATÓRIA ====================
+/// Subamostragem aleatória: descarta pontos sorteados das classes com mais de
+/// `alvo_por_classe` pontos até cada uma ficar com exatamente `alvo_por_classe`.
+///
+/// É a alternativa barata ao [`smote`]: em vez de criar pontos nas classes pequenas,
+/// reduz as grandes. Classes que já estão no alvo ou abaixo dele ficam intactas, e
+/// nenhuma classe é zerada: um alvo 0 é tratado como 1.
+///
+/// Devolve os pontos mantidos, na ordem de entrada, e os índices (em ordem crescente)
+/// dos pontos descartados, para que seja possível conferir o que saiu. A mesma `semente`
+/// descarta sempre os mesmos pontos.
+///
+/// ```
+/// use aprendizagem_knn::{contar_rotulos, subamostrar, Ponto};
+///
+/// // 90 pontos de A para 10 de B
+/// let dados: Vec<Ponto> = (0..100)
+///     .map(|i| Ponto::novo(vec![i as f64], if i < 90 { "A" } else { "B" }.to_string()))
+///     .collect();
+///
+/// let (reduzidos, removidos) = subamostrar(&dados, 10, 42);
+/// let contagem = contar_rotulos(&reduzidos);
+/// assert_eq!((contagem["A"], contagem["B"]), (10, 10));
+/// assert_eq!(removidos.len(), 80);
+/// assert!(removidos.iter().all(|&i| dados[i].rotulo == "A"));
+///
+/// // a mesma semente descarta os mesmos pontos
+/// assert_eq!(subamostrar(&dados, 10, 42).1, removidos);
+/// ```
+pub fn subamostrar(treinamento: &[Ponto], alvo_por_classe: usize, semente: u64) -> (Vec<Ponto>, Vec<usize>) {
+    let limite = alvo_por_classe.max(1);
+    let mut rng = Rng::novo(semente);
+    let mut descartar = vec![false; treinamento.len()];
+
+    // As classes são percorridas em ordem alfabética para que a semente dê sempre o mesmo resultado
+    for (rotulo, quantidade) in contagem_ordenada(treinamento) {
+        if quantidade <= limite {
+            continue;
+        }
+        let indices_classe: Vec<usize> = treinamento.iter()
+            .enumerate()
+            .filter(|(_, ponto)| ponto.rotulo == rotulo)
+            .map(|(indice, _)| indice)
+            .collect();
+        for posicao in rng.amostrar_indices(quantidade, quantidade - limite) {
+            descartar[indices_classe[posicao]] = true;
+        }
+    }
+
+    let mut mantidos = Vec::with_capacity(treinamento.len());
+    let mut removidos = Vec::new();
+    for (indice, ponto) in treinamento.iter().enumerate() {
+        if descartar[indice] {
+            removidos.push(indice);
+        } else {
+            mantidos.push(ponto.clone());
+        }
+    }
+    (mantidos, removidos)
+}
+
+// ==================== BALANCEAMENTO POR SORTEIO ====================
+/// Sobreamostragem aleatória: repete pontos sorteados (com reposição) de cada classe
+/// minoritária até que todas fiquem com o mesmo número de pontos da classe majoritária.
+///
+/// Diferente do [`smote`], não cria pontos novos, só cópias dos que já existem; é mais
+/// simples, mas o KNN passa a ver os mesmos pontos várias vezes. O resultado tem todos os
+/// pontos originais mais as cópias, embaralhados (as cópias não ficam agrupadas no fim).
+/// A mesma `semente` dá sempre o mesmo resultado; dados vazios devolvem uma lista vazia.
+///
+/// ```
+/// use aprendizagem_knn::{contar_rotulos, sobreamostrar_minoritario, Ponto};
+///
+/// // 6 pontos de A, 3 de B e 1 de C
+/// let dados: Vec<Ponto> = (0..10)
+///     .map(|i| Ponto::novo(vec![i as f64], match i { 0..=5 => "A", 6..=8 => "B", _ => "C" }.to_string()))
+///     .collect();
+///
+/// let balanceados = sobreamostrar_minoritario(&dados, 42);
+/// let contagem = contar_rotulos(&balanceados);
+/// assert_eq!((contagem["A"], contagem["B"], contagem["C"]), (6, 6, 6));
+/// // As cópias são dos pontos da própria classe
+/// assert!(balanceados.iter().filter(|p| p.rotulo == "C").all(|p| p.caracteristicas == vec![9.0]));
+/// // Todos os originais continuam lá
+/// assert!(dados.iter().all(|original| balanceados.contains(original)));
+/// // E a ordem é embaralhada, mas repetível
+/// assert_ne!(balanceados[..10], dados[..]);
+/// assert_eq!(sobreamostrar_minoritario(&dados, 42), balanceados);
+/// ```
+pub fn sobreamostrar_minoritario(dados: &[Ponto], semente: u64) -> Vec<Ponto> {
+    let contagem = contagem_ordenada(dados);
+    let maximo = contagem.iter().map(|&(_, quantidade)| quantidade).max().unwrap_or(0);
+    let mut rng = Rng::novo(semente);
+
+    let mut resultado = dados.to_vec();
+    // As classes são percorridas em ordem alfabética para que a semente dê sempre o mesmo resultado
+    for (rotulo, quantidade) in contagem {
+        let da_classe: Vec<&Ponto> = dados.iter().filter(|ponto| ponto.rotulo == rotulo).collect();
+        for _ in quantidade..maximo {
+            resultado.push(da_classe[rng.proximo_usize(quantidade)].clone());
+        }
+    }
+    embaralhar(resultado, &mut rng)
+}
+
+/// Subamostragem da maioria: descarta pontos sorteados das classes maiores até que todas
+/// fiquem com o mesmo número de pontos da classe minoritária.
+///
+/// É o [`subamostrar`] com o alvo igual ao tamanho da menor classe, mas devolve só os
+/// pontos mantidos, embaralhados como em [`sobreamostrar_minoritario`]. Bom quando há
+/// muitos dados; com classes muito pequenas, quase tudo é jogado fora.
+///
+/// ```
+/// use aprendizagem_knn::{contar_rotulos, subamostrar_majoritario, Ponto};
+///
+/// // 6 pontos de A, 3 de B e 2 de C
+/// let dados: Vec<Ponto> = (0..11)
+///     .map(|i| Ponto::novo(vec![i as f64], match i { 0..=5 => "A", 6..=8 => "B", _ => "C" }.to_string()))
+///     .collect();
+///
+/// let balanceados = subamostrar_majoritario(&dados, 42);
+/// let contagem = contar_rotulos(&balanceados);
+/// assert_eq!((contagem["A"], contagem["B"], contagem["C"]), (2, 2, 2));
+/// // Só sobram pontos originais, sem repetição
+/// assert!(balanceados.iter().all(|ponto| dados.contains(ponto)));
+/// assert_eq!(subamostrar_majoritario(&dados, 42), balanceados);
+/// assert!(subamostrar_majoritario(&[], 42).is_empty());
+/// ```
+pub fn subamostrar_majoritario(dados: &[Ponto], semente: u64) -> Vec<Ponto> {
+    let Some(minimo) = contagem_ordenada(dados).iter().map(|&(_, quantidade)| quantidade).min() else {
+        return Vec::new();
+    };
+    let (mantidos, _) = subamostrar(dados, minimo, semente);
+    // Outro gerador (semente + 1) para a ordem, independente do sorteio dos descartados
+    embaralhar(mantidos, &mut Rng::novo(semente.wrapping_add(1)))
+}
+
+// Devolve os pontos numa ordem aleatória (Fisher-Yates, via amostrar_indices)
+fn embaralhar(pontos: Vec<Ponto>, rng: &mut Rng) -> Vec<Ponto> {
+    let ordem = rng.amostrar_indices(pontos.len(), pontos.len());
+    let mut pontos: Vec<Option<Ponto>> = pontos.into_iter().map(Some).collect();
+    ordem.into_iter().map(|indice| pontos[indice].take().expect("cada índice aparece uma vez")).collect()
+}
+
+// ==================== LIGAÇÕES DE TOMEK ====================
+// Uma ligação de Tomek é um par de pontos de classes diferentes em que cada um é o vizinho
+// mais próximo do outro (distância euclidiana). Esses pares ficam na fronteira entre as
+// classes, ou são ruído; tirar o ponto da classe majoritária de cada par deixa a fronteira
+// mais limpa sem perder nenhum ponto das classes menores.
+
+/// Encontra as ligações de Tomek que envolvem a classe majoritária (a de maior contagem;
+/// num empate, a primeira em ordem alfabética, como em
+/// [`classe_majoritaria`]).
+///
+/// Devolve os pares `(indice_majoritario, indice_minoritario)`, com os índices em `dados`,
+/// em ordem crescente do índice majoritário. Pares entre duas classes que não são a
+/// majoritária não entram. Com menos de dois pontos, não há ligações. A busca compara
+/// todos os pares de pontos (O(n²)).
+///
+/// ```
+/// use aprendizagem_knn::{encontrar_tomek_links, pontos};
+///
+/// // Dois grupos bem separados: nenhum ponto tem um vizinho mais próximo da outra classe
+/// let separaveis = pontos![
+///     [0.0, 0.0] => "A", [0.0, 1.0] => "A", [1.0, 0.0] => "A",
+///     [10.0, 10.0] => "B", [10.0, 11.0] => "B",
+/// ];
+/// assert!(encontrar_tomek_links(&separaveis).is_empty());
+///
+/// // O B em 5.2 e o A em 5.0 são o vizinho mais próximo um do outro
+/// let fronteira = pontos![[0.0] => "A", [1.0] => "A", [5.0] => "A", [5.2] => "B", [9.0] => "B"];
+/// assert_eq!(encontrar_tomek_links(&fronteira), vec![(2, 3)]);
+/// ```
+pub fn encontrar_tomek_links(dados: &[Ponto]) -> Vec<(usize, usize)> {
+    let Some(majoritaria) = classe_majoritaria(dados) else {
+        return Vec::new();
+    };
+    let vizinhos: Vec<Option<usize>> = (0..dados.len()).map(|indice| vizinho_mais_proximo(dados, indice)).collect();
+
+    let mut ligacoes = Vec::new();
+    for (indice, ponto) in dados.iter().enumerate() {
+        if ponto.rotulo != majoritaria {
+            continue;
+        }
+        if let Some(vizinho) = vizinhos[indice] {
+            // O vizinho é de outra classe e o vizinho mais próximo dele é este ponto
+            if dados[vizinho].rotulo != ponto.rotulo && vizinhos[vizinho] == Some(indice) {
+                ligacoes.push((indice, vizinho));
+            }
+        }
+    }
+    ligacoes
+}
+
+/// Remove o ponto da classe majoritária de cada ligação de Tomek (veja
+/// [`encontrar_tomek_links`]), mantendo a ordem dos demais pontos.
+///
+/// É uma limpeza, não um balanceamento: só sai um ponto por ligação, e as classes menores
+/// ficam intactas. Costuma ser usada depois do [`smote`] ou antes da subamostragem.
+///
+/// ```
+/// use aprendizagem_knn::{pontos, remover_tomek_majoritarios};
+///
+/// let dados = pontos![[0.0] => "A", [1.0] => "A", [5.0] => "A", [5.2] => "B", [9.0] => "B"];
+/// let limpos = remover_tomek_majoritarios(dados);
+/// let posicoes: Vec<f64> = limpos.iter().map(|p| p.caracteristicas[0]).collect();
+/// assert_eq!(posicoes, vec![0.0, 1.0, 5.2, 9.0]);
+/// ```
+pub fn remover_tomek_majoritarios(dados: Vec<Ponto>) -> Vec<Ponto> {
+    let mut remover = vec![false; dados.len()];
+    for (indice_majoritario, _) in encontrar_tomek_links(&dados) {
+        remover[indice_majoritario] = true;
+    }
+    dados.into_iter()
+        .zip(remover)
+        .filter_map(|(ponto, remover)| (!remover).then_some(ponto))
+        .collect()
+}
+
+// Índice do vizinho mais próximo de dados[indice] (o primeiro, num empate), sem contar ele mesmo
+fn vizinho_mais_proximo(dados: &[Ponto], indice: usize) -> Option<usize> {
+    let mut melhor: Option<(usize, f64)> = None;
+    for (outro, ponto) in dados.iter().enumerate() {
+        if outro == indice {
+            continue;
+        }
+        let distancia = distancia_euclidiana(&dados[indice], ponto);
+        if melhor.is_none_or(|(_, menor)| distancia < menor) {
+            melhor = Some((outro, distancia));
+        }
+    }
+    melhor.map(|(outro, _)| outro)
+}
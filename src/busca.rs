@@ -0,0 +1,416 @@
+//! Busca em grade: escolhe k, métrica e votação do KNN pela validação cruzada.
+
+use std::num::NonZeroUsize;
+use std::thread;
+
+use crate::distancia::DistanciaMetrica;
+use crate::erro::ErroKnn;
+use crate::knn::Votacao;
+use crate::ponto::Ponto;
+use crate::validacao::{avaliar_fold_com, dividir_em_folds, ResultadoCV};
+
+// ==================== GRADE DE CANDIDATOS ====================
+/// Os valores a testar na busca em grade: todas as combinações de k, métrica e votação.
+///
+/// # Exemplo
+///
+/// ```
+/// use aprendizagem_knn::{DistanciaMetrica, GradeBusca, Votacao};
+///
+/// let grade = GradeBusca {
+///     valores_k: vec![1, 3, 5],
+///     metricas: vec![DistanciaMetrica::Euclidiana, DistanciaMetrica::Manhattan],
+///     votacoes: vec![Votacao::Maioria],
+/// };
+/// assert_eq!(grade.configuracoes().len(), 6);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct GradeBusca {
+    /// Valores de k candidatos
+    pub valores_k: Vec<usize>,
+    /// Métricas de distância candidatas
+    pub metricas: Vec<DistanciaMetrica>,
+    /// Esquemas de votação candidatos
+    pub votacoes: Vec<Votacao>,
+}
+
+impl GradeBusca {
+    /// Grade que varia só o k, com distância euclidiana e votação por maioria (o [`knn`](fn@crate::knn)).
+    ///
+    /// ```
+    /// use aprendizagem_knn::GradeBusca;
+    ///
+    /// let grade = GradeBusca::de_k(vec![1, 3, 5, 7]);
+    /// assert_eq!(grade.configuracoes().len(), 4);
+    /// ```
+    pub fn de_k(valores_k: Vec<usize>) -> Self {
+        Self {
+            valores_k,
+            metricas: vec![DistanciaMetrica::Euclidiana],
+            votacoes: vec![Votacao::Maioria],
+        }
+    }
+
+    /// Todas as combinações, na ordem k, depois métrica, depois votação.
+    ///
+    /// ```
+    /// use aprendizagem_knn::{GradeBusca, Votacao};
+    ///
+    /// let mut grade = GradeBusca::de_k(vec![1, 3]);
+    /// grade.votacoes.push(Votacao::InversoDaDistancia);
+    /// let configuracoes = grade.configuracoes();
+    /// assert_eq!((configuracoes[1].k, configuracoes[1].votacao), (1, Votacao::InversoDaDistancia));
+    /// ```
+    pub fn configuracoes(&self) -> Vec<ConfiguracaoKnn> {
+        let mut configuracoes = Vec::new();
+        for &k in &self.valores_k {
+            for metrica in &self.metricas {
+                for &votacao in &self.votacoes {
+                    configuracoes.push(ConfiguracaoKnn { k, metrica: metrica.clone(), votacao });
+                }
+            }
+        }
+        configuracoes
+    }
+}
+
+/// Uma combinação de parâmetros do KNN.
+///
+/// ```
+/// use aprendizagem_knn::{knn_com_votacao, ponto, pontos, ConfiguracaoKnn, DistanciaMetrica, Votacao};
+///
+/// let configuracao = ConfiguracaoKnn { k: 1, metrica: DistanciaMetrica::Manhattan, votacao: Votacao::Maioria };
+/// let treinamento = pontos![[0.0] => "A", [5.0] => "B"];
+/// let rotulo = knn_com_votacao(&treinamento, &ponto!([4.0], "?"), configuracao.k, &configuracao.metrica, configuracao.votacao);
+/// assert_eq!(rotulo.unwrap(), "B");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfiguracaoKnn {
+    /// Número de vizinhos
+    pub k: usize,
+    /// Métrica de distância
+    pub metrica: DistanciaMetrica,
+    /// Esquema de votação
+    pub votacao: Votacao,
+}
+
+/// Desempenho de uma configuração na validação cruzada.
+///
+/// ```
+/// use aprendizagem_knn::{busca_em_grade, pontos, GradeBusca};
+///
+/// let dados = pontos![[0.0] => "A", [1.0] => "A", [9.0] => "B", [10.0] => "B"];
+/// let relatorio = busca_em_grade(&dados, &GradeBusca::de_k(vec![1]), 2, 0).unwrap();
+/// let resultado = &relatorio.resultados[0];
+/// assert_eq!(resultado.configuracao.k, 1);
+/// assert_eq!(resultado.validacao.acuracias_por_fold.len(), 2);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResultadoBusca {
+    /// A configuração avaliada
+    pub configuracao: ConfiguracaoKnn,
+    /// Acurácias de cada fold, com média e desvio padrão
+    pub validacao: ResultadoCV,
+}
+
+/// Resultado da busca em grade: a melhor configuração e a tabela com todas elas.
+///
+/// ```
+/// use aprendizagem_knn::{busca_em_grade, pontos, GradeBusca};
+///
+/// let dados = pontos![[0.0] => "A", [1.0] => "A", [9.0] => "B", [10.0] => "B"];
+/// let relatorio = busca_em_grade(&dados, &GradeBusca::de_k(vec![1, 3]), 2, 0).unwrap();
+/// for resultado in &relatorio.resultados {
+///     println!("k = {}: {:.2}", resultado.configuracao.k, resultado.validacao.media);
+/// }
+/// assert_eq!(relatorio.melhor.configuracao.k, 1);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct RelatorioBusca {
+    /// A configuração de maior acurácia média
+    pub melhor: ResultadoBusca,
+    /// Todas as configurações, na ordem de [`GradeBusca::configuracoes`]
+    pub resultados: Vec<ResultadoBusca>,
+}
+
+// ==================== BUSCA EM GRADE ====================
+/// Avalia cada combinação da `grade` com validação cruzada de `n_folds` folds e escolhe
+/// a de maior acurácia média.
+///
+/// Todas as configurações são avaliadas nos mesmos folds (sorteados com a `semente`), para
+/// que a comparação seja justa e a busca dê sempre o mesmo resultado. No empate vence o
+/// menor k (um modelo mais simples), e depois a configuração que vem antes na grade.
+/// As configurações são avaliadas em paralelo, uma thread por núcleo do processador.
+///
+/// É erro se os dados estiverem vazios, se a grade não tiver nenhuma combinação, se algum
+/// k for zero, se alguma métrica for inválida ou se `n_folds` não estiver entre 2 e o
+/// número de pontos.
+///
+/// ```
+/// use aprendizagem_knn::{busca_em_grade, GradeBusca, Ponto};
+///
+/// // Duas classes bem separadas em x, com um em cada cinco rótulos trocado (ruído).
+/// // Com k = 1, cada ponto com ruído engana os pontos vizinhos; um k maior o ignora.
+/// let dados: Vec<Ponto> = (0..40)
+///     .map(|i| {
+///         let classe_a = i < 20;
+///         let ruido = i % 5 == 2;
+///         let rotulo = if classe_a != ruido { "A" } else { "B" };
+///         Ponto::novo(vec![if classe_a { i as f64 } else { 100.0 + i as f64 }], rotulo.to_string())
+///     })
+///     .collect();
+///
+/// let relatorio = busca_em_grade(&dados, &GradeBusca::de_k(vec![1, 3, 5, 7, 9]), 5, 42).unwrap();
+/// assert_eq!(relatorio.resultados.len(), 5);
+/// assert!(relatorio.melhor.configuracao.k > 1);
+/// assert!(relatorio.melhor.validacao.media > relatorio.resultados[0].validacao.media);
+/// ```
+pub fn busca_em_grade(
+    dados: &[Ponto],
+    grade: &GradeBusca,
+    n_folds: usize,
+    semente: u64,
+) -> Result<RelatorioBusca, ErroKnn> {
+    if dados.is_empty() {
+        return Err(ErroKnn::ConjuntoVazio);
+    }
+    if n_folds < 2 || n_folds > dados.len() {
+        return Err(ErroKnn::ParametroInvalido(format!(
+            "n_folds deve estar entre 2 e o número de pontos ({}), recebido {}",
+            dados.len(),
+            n_folds
+        )));
+    }
+    if grade.valores_k.contains(&0) {
+        return Err(ErroKnn::ParametroInvalido("os valores de k devem ser maiores que zero".to_string()));
+    }
+    for metrica in &grade.metricas {
+        metrica.validar()?;
+    }
+    let configuracoes = grade.configuracoes();
+    if configuracoes.is_empty() {
+        return Err(ErroKnn::ParametroInvalido(
+            "a grade precisa de ao menos um k, uma métrica e uma votação".to_string(),
+        ));
+    }
+
+    let folds = dividir_em_folds(dados.len(), n_folds, semente);
+    let avaliar = |configuracao: &ConfiguracaoKnn| {
+        let acuracias = folds.iter()
+            .map(|fold| avaliar_fold_com(dados, fold, configuracao.k, &configuracao.metrica, configuracao.votacao).acuracia)
+            .collect();
+        ResultadoCV::das_acuracias(acuracias)
+    };
+
+    // thread::scope permite que as threads usem referências a `dados` e `folds`,
+    // já que todas terminam antes de o escopo acabar. A thread t avalia as configurações
+    // t, t + n_threads, t + 2*n_threads... e cada resultado volta para a sua posição,
+    // então a ordem (e o desempate) não depende de qual thread terminou primeiro.
+    let n_threads = thread::available_parallelism().map_or(1, NonZeroUsize::get).min(configuracoes.len());
+    let mut validacoes: Vec<Option<ResultadoCV>> = vec![None; configuracoes.len()];
+    thread::scope(|escopo| {
+        let tarefas: Vec<_> = (0..n_threads)
+            .map(|t| {
+                let configuracoes = &configuracoes;
+                let avaliar = &avaliar;
+                escopo.spawn(move || {
+                    (t..configuracoes.len())
+                        .step_by(n_threads)
+                        .map(|i| (i, avaliar(&configuracoes[i])))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        for tarefa in tarefas {
+            for (i, validacao) in tarefa.join().expect("uma thread da busca em grade falhou") {
+                validacoes[i] = Some(validacao);
+            }
+        }
+    });
+
+    let resultados: Vec<ResultadoBusca> = configuracoes.into_iter()
+        .zip(validacoes)
+        .map(|(configuracao, validacao)| ResultadoBusca {
+            configuracao,
+            validacao: validacao.expect("toda configuração é avaliada por uma thread"),
+        })
+        .collect();
+
+    // Maior média; no empate, menor k; depois, a primeira da grade
+    let melhor = resultados.iter()
+        .enumerate()
+        .max_by(|(i, a), (j, b)| {
+            a.validacao.media.total_cmp(&b.validacao.media)
+                .then(b.configuracao.k.cmp(&a.configuracao.k))
+                .then(j.cmp(i))
+        })
+        .map(|(_, resultado)| resultado.clone())
+        .expect("a grade tem ao menos uma configuração");
+
+    Ok(RelatorioBusca { melhor, resultados })
+}
+
+// ==================== ESCOLHA DE K PELA REGRA DE UM ERRO PADRÃO ====================
+/// A acurácia da validação cruzada para um valor de k.
+///
+/// ```
+/// use aprendizagem_knn::PontoCurvaK;
+///
+/// let ponto = PontoCurvaK { k: 3, media: 0.9, erro_padrao: 0.02 };
+/// assert!(ponto.media - ponto.erro_padrao > 0.85);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PontoCurvaK {
+    /// Número de vizinhos
+    pub k: usize,
+    /// Acurácia média nos folds
+    pub media: f64,
+    /// Erro padrão da média: o desvio padrão amostral das acurácias dividido pela raiz
+    /// do número de folds
+    pub erro_padrao: f64,
+}
+
+/// Resultado de [`escolher_k`]: o k escolhido pela regra de um erro padrão, o k de maior
+/// acurácia e a curva completa.
+///
+/// ```
+/// use aprendizagem_knn::{escolher_k, pontos};
+///
+/// let dados = pontos![[0.0] => "A", [1.0] => "A", [2.0] => "A", [9.0] => "B", [10.0] => "B", [11.0] => "B"];
+/// let selecao = escolher_k(&dados, None, 3, 0).unwrap();
+/// for ponto in &selecao.curva {
+///     println!("k = {}: {:.2} ± {:.2}", ponto.k, ponto.media, ponto.erro_padrao);
+/// }
+/// assert!(selecao.k_escolhido <= selecao.melhor_k);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelecaoK {
+    /// O menor k cuja acurácia está a até um erro padrão da melhor
+    pub k_escolhido: usize,
+    /// O k de maior acurácia média (no empate, o menor)
+    pub melhor_k: usize,
+    /// Acurácia de cada k candidato, na ordem em que foram informados
+    pub curva: Vec<PontoCurvaK>,
+}
+
+/// Os valores de k testados por padrão em [`escolher_k`]: os ímpares de 1 até a raiz de
+/// `total_dados` arredondada para cima (ímpares evitam empates na votação entre duas classes).
+///
+/// Sempre inclui o 1, mesmo para conjuntos vazios.
+///
+/// ```
+/// use aprendizagem_knn::candidatos_k_padrao;
+///
+/// // raiz de 40 ≈ 6.32, arredondada para 7
+/// assert_eq!(candidatos_k_padrao(40), vec![1, 3, 5, 7]);
+/// // raiz de 20 ≈ 4.47, arredondada para 5
+/// assert_eq!(candidatos_k_padrao(20), vec![1, 3, 5]);
+/// assert_eq!(candidatos_k_padrao(0), vec![1]);
+/// ```
+pub fn candidatos_k_padrao(total_dados: usize) -> Vec<usize> {
+    let limite = ((total_dados as f64).sqrt().ceil() as usize).max(1);
+    (1..=limite).step_by(2).collect()
+}
+
+/// Aplica a regra de um erro padrão a uma curva de acurácia por k: encontra o k de maior
+/// acurácia média e devolve o menor k cuja média está a até um erro padrão (o do melhor)
+/// dela.
+///
+/// A ideia é que diferenças menores que o erro padrão podem ser só ruído da validação
+/// cruzada; entre modelos estatisticamente empatados, ficamos com o de menor k. Devolve
+/// `None` se a curva estiver vazia.
+///
+/// ```
+/// use aprendizagem_knn::{selecionar_k_um_erro_padrao, PontoCurvaK};
+///
+/// let curva = [
+///     PontoCurvaK { k: 1, media: 0.80, erro_padrao: 0.02 },
+///     PontoCurvaK { k: 3, media: 0.90, erro_padrao: 0.03 },
+///     PontoCurvaK { k: 5, media: 0.87, erro_padrao: 0.02 },
+///     PontoCurvaK { k: 7, media: 0.92, erro_padrao: 0.04 },
+/// ];
+/// // O melhor é k = 7 (0.92); o limite é 0.92 - 0.04 = 0.88, e o menor k acima dele é 3
+/// assert_eq!(selecionar_k_um_erro_padrao(&curva), Some(3));
+///
+/// // Com um erro padrão menor, o limite sobe para 0.915 e só o próprio k = 7 serve
+/// let mut estreita = curva;
+/// estreita[3].erro_padrao = 0.005;
+/// assert_eq!(selecionar_k_um_erro_padrao(&estreita), Some(7));
+///
+/// assert_eq!(selecionar_k_um_erro_padrao(&[]), None);
+/// ```
+pub fn selecionar_k_um_erro_padrao(curva: &[PontoCurvaK]) -> Option<usize> {
+    let melhor = melhor_ponto(curva)?;
+    let limite = melhor.media - melhor.erro_padrao;
+    curva.iter().filter(|ponto| ponto.media >= limite).map(|ponto| ponto.k).min()
+}
+
+// Maior acurácia média; no empate, o menor k
+fn melhor_ponto(curva: &[PontoCurvaK]) -> Option<&PontoCurvaK> {
+    curva.iter().max_by(|a, b| a.media.total_cmp(&b.media).then(b.k.cmp(&a.k)))
+}
+
+/// Escolhe o k do KNN pelos dados, como alternativa à regra fixa de [`calcular_k`](crate::calcular_k):
+/// avalia cada k candidato com validação cruzada de `n_folds` folds (pela
+/// [`busca_em_grade`], com distância euclidiana e votação por maioria) e aplica a
+/// [`selecionar_k_um_erro_padrao`] à curva de acurácia.
+///
+/// Sem `candidatos`, usa [`candidatos_k_padrao`]. Com `n_folds` igual ao número de
+/// pontos, a validação vira leave-one-out. Os erros são os da [`busca_em_grade`], e
+/// também é erro passar uma lista de candidatos vazia.
+///
+/// ```
+/// use aprendizagem_knn::{escolher_k, Ponto};
+///
+/// // Duas classes bem separadas em x, com um em cada cinco rótulos trocado (ruído):
+/// // k = 1 erra perto de cada ruído, e qualquer k maior já os ignora
+/// let dados: Vec<Ponto> = (0..40)
+///     .map(|i| {
+///         let classe_a = i < 20;
+///         let ruido = i % 5 == 2;
+///         let rotulo = if classe_a != ruido { "A" } else { "B" };
+///         Ponto::novo(vec![if classe_a { i as f64 } else { 100.0 + i as f64 }], rotulo.to_string())
+///     })
+///     .collect();
+///
+/// let selecao = escolher_k(&dados, None, 5, 42).unwrap();
+/// let ks: Vec<usize> = selecao.curva.iter().map(|ponto| ponto.k).collect();
+/// assert_eq!(ks, vec![1, 3, 5, 7]);
+/// assert!(selecao.k_escolhido > 1);
+/// assert!(selecao.k_escolhido <= selecao.melhor_k);
+///
+/// // Candidatos próprios, com leave-one-out
+/// let selecao = escolher_k(&dados, Some(&[1, 9]), dados.len(), 0).unwrap();
+/// assert_eq!(selecao.k_escolhido, 9);
+///
+/// assert!(escolher_k(&dados, Some(&[]), 5, 0).is_err());
+/// ```
+pub fn escolher_k(
+    dados: &[Ponto],
+    candidatos: Option<&[usize]>,
+    n_folds: usize,
+    semente: u64,
+) -> Result<SelecaoK, ErroKnn> {
+    let valores_k = candidatos.map_or_else(|| candidatos_k_padrao(dados.len()), <[usize]>::to_vec);
+    if valores_k.is_empty() {
+        return Err(ErroKnn::ParametroInvalido("é preciso ao menos um valor de k candidato".to_string()));
+    }
+
+    let relatorio = busca_em_grade(dados, &GradeBusca::de_k(valores_k), n_folds, semente)?;
+    let curva: Vec<PontoCurvaK> = relatorio.resultados.iter()
+        .map(|resultado| {
+            // O desvio padrão guardado é o populacional; o amostral divide por n - 1, e o
+            // erro padrão ainda divide pela raiz de n: sobra o populacional / raiz(n - 1)
+            let n = resultado.validacao.acuracias_por_fold.len() as f64;
+            PontoCurvaK {
+                k: resultado.configuracao.k,
+                media: resultado.validacao.media,
+                erro_padrao: resultado.validacao.desvio_padrao / (n - 1.0).sqrt(),
+            }
+        })
+        .collect();
+
+    let melhor_k = melhor_ponto(&curva).expect("a curva tem ao menos um ponto").k;
+    let k_escolhido = selecionar_k_um_erro_padrao(&curva).expect("a curva tem ao menos um ponto");
+    Ok(SelecaoK { k_escolhido, melhor_k, curva })
+}
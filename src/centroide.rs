@@ -0,0 +1,166 @@
+//! Classificador pelo centroide mais próximo, uma linha de base rápida para o KNN.
+
+use std::collections::HashMap;
+
+use crate::aleatorio::obter_semente;
+use crate::distancia::DistanciaMetrica;
+use crate::erro::ErroKnn;
+use crate::knn::Votacao;
+use crate::ponto::Ponto;
+use crate::validacao::{avaliar_fold_com, dividir_em_folds, validar_parametros_cv};
+
+// ==================== CENTROIDE MAIS PRÓXIMO ====================
+/// Classificador que resume cada classe pelo seu centroide (a média das características
+/// dos seus pontos) e prevê a classe do centroide mais próximo.
+///
+/// O treinamento passa uma vez pelos dados (O(n)) e a previsão só compara o ponto com um
+/// centroide por classe, sem depender do tamanho do treinamento, enquanto o KNN compara
+/// com todos os pontos. Funciona bem quando as classes formam grupos compactos; com
+/// classes em forma de anel ou espalhadas em vários grupos, o centroide fica num lugar que
+/// não representa a classe, e o KNN é bem melhor. Por isso serve como linha de base: se o
+/// KNN não superar o centroide, a vizinhança não está ajudando.
+///
+/// # Exemplo
+///
+/// ```
+/// use aprendizagem_knn::{ponto, pontos, ClassificadorCentroide};
+///
+/// let treinamento = pontos![[0.0, 0.0] => "A", [2.0, 0.0] => "A", [10.0, 10.0] => "B", [12.0, 10.0] => "B"];
+/// let classificador = ClassificadorCentroide::treinar(&treinamento);
+/// assert_eq!(classificador.centroides["A"], vec![1.0, 0.0]);
+/// assert_eq!(classificador.centroides["B"], vec![11.0, 10.0]);
+/// assert_eq!(classificador.prever(&ponto!([4.0, 3.0], "?")), Ok("A".to_string()));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClassificadorCentroide {
+    /// Centroide (média das características) de cada classe, pelo rótulo
+    pub centroides: HashMap<String, Vec<f64>>,
+}
+
+impl ClassificadorCentroide {
+    /// Calcula o centroide de cada classe de `dados`. Com `dados` vazio, não há centroides.
+    ///
+    /// ```
+    /// use aprendizagem_knn::{pontos, ClassificadorCentroide};
+    ///
+    /// let classificador = ClassificadorCentroide::treinar(&pontos![[1.0] => "A", [2.0] => "A", [6.0] => "B"]);
+    /// assert_eq!(classificador.centroides.len(), 2);
+    /// assert_eq!(classificador.centroides["A"], vec![1.5]);
+    /// ```
+    pub fn treinar(dados: &[Ponto]) -> Self {
+        ClassificadorCentroide { centroides: centroides_das_classes(dados) }
+    }
+
+    /// Classe cujo centroide está mais perto de `ponto` (distância euclidiana).
+    ///
+    /// Num empate de distâncias vence o rótulo alfabeticamente menor, como no
+    /// [`knn`](fn@crate::knn). Um classificador sem nenhum centroide (treinado com dados
+    /// vazios) é [`ErroKnn::ConjuntoVazio`], como o treinamento vazio no knn.
+    ///
+    /// ```
+    /// use aprendizagem_knn::{ponto, pontos, ClassificadorCentroide, ErroKnn};
+    ///
+    /// let classificador = ClassificadorCentroide::treinar(&pontos![[0.0] => "B", [10.0] => "A"]);
+    /// assert_eq!(classificador.prever(&ponto!([9.0], "?")), Ok("A".to_string()));
+    /// // 5.0 está à mesma distância dos dois centroides
+    /// assert_eq!(classificador.prever(&ponto!([5.0], "?")), Ok("A".to_string()));
+    ///
+    /// let vazio = ClassificadorCentroide::treinar(&[]);
+    /// assert_eq!(vazio.prever(&ponto!([5.0], "?")), Err(ErroKnn::ConjuntoVazio));
+    /// ```
+    pub fn prever(&self, ponto: &Ponto) -> Result<String, ErroKnn> {
+        self.centroides.iter()
+            .map(|(rotulo, centroide)| (rotulo, distancia_quadrada(&ponto.caracteristicas, centroide)))
+            // total_cmp ordena as distâncias; no empate, o rótulo decide
+            .min_by(|a, b| a.1.total_cmp(&b.1).then_with(|| a.0.cmp(b.0)))
+            .map(|(rotulo, _)| rotulo.clone())
+            .ok_or(ErroKnn::ConjuntoVazio)
+    }
+}
+
+// Média das características de cada classe, numa única passada pelos pontos
+fn centroides_das_classes<'a>(pontos: impl IntoIterator<Item = &'a Ponto>) -> HashMap<String, Vec<f64>> {
+    let mut somas: HashMap<String, (Vec<f64>, usize)> = HashMap::new();
+    for ponto in pontos {
+        let (soma, quantidade) = somas
+            .entry(ponto.rotulo.clone())
+            .or_insert_with(|| (vec![0.0; ponto.caracteristicas.len()], 0));
+        for (acumulado, valor) in soma.iter_mut().zip(&ponto.caracteristicas) {
+            *acumulado += valor;
+        }
+        *quantidade += 1;
+    }
+    somas.into_iter()
+        .map(|(rotulo, (soma, quantidade))| {
+            (rotulo, soma.into_iter().map(|valor| valor / quantidade as f64).collect())
+        })
+        .collect()
+}
+
+// A raiz não muda qual centroide é o mais próximo, então a comparação usa o quadrado
+fn distancia_quadrada(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum()
+}
+
+/// Compara o classificador pelo centroide com o KNN de `k_vizinhos` vizinhos (distância
+/// euclidiana) na mesma validação cruzada de `k_folds` folds, e devolve
+/// `(acuracia_centroide, acuracia_knn)`, cada uma a média das acurácias dos folds.
+///
+/// Os dois são avaliados exatamente nos mesmos folds, sorteados com a semente global
+/// ([`obter_semente`]). Os erros são os de
+/// [`validacao_cruzada_k_fold`](crate::validacao_cruzada_k_fold).
+///
+/// ```
+/// use aprendizagem_knn::{comparar_com_knn, Ponto};
+///
+/// // Dois grupos separados por uma reta: os dois acertam tudo
+/// let separaveis: Vec<Ponto> = (0..40)
+///     .map(|i| {
+///         let (x, y) = ((i % 5) as f64, (i / 5 % 4) as f64);
+///         let (deslocamento, rotulo) = if i < 20 { (0.0, "A") } else { (20.0, "B") };
+///         Ponto::novo(vec![x + deslocamento, y], rotulo.to_string())
+///     })
+///     .collect();
+/// assert_eq!(comparar_com_knn(&separaveis, 5, 3), Ok((1.0, 1.0)));
+///
+/// // Um anel de "B" em volta de um grupo de "A": os dois centroides quase coincidem,
+/// // e só o KNN acerta
+/// let anel: Vec<Ponto> = (0..60)
+///     .map(|i| {
+///         let angulo = i as f64 * 0.7;
+///         let raio = if i % 2 == 0 { 1.0 } else { 10.0 };
+///         let rotulo = if i % 2 == 0 { "A" } else { "B" };
+///         Ponto::novo(vec![raio * angulo.cos(), raio * angulo.sin()], rotulo.to_string())
+///     })
+///     .collect();
+/// let (centroide, knn) = comparar_com_knn(&anel, 5, 3).unwrap();
+/// assert!(knn > centroide + 0.3, "centroide {} x knn {}", centroide, knn);
+///
+/// // Mais folds que pontos
+/// assert!(comparar_com_knn(&anel, 61, 3).is_err());
+/// ```
+pub fn comparar_com_knn(dados: &[Ponto], k_folds: usize, k_vizinhos: usize) -> Result<(f64, f64), ErroKnn> {
+    validar_parametros_cv(dados.len(), k_folds, k_vizinhos)?;
+
+    let folds = dividir_em_folds(dados.len(), k_folds, obter_semente());
+    let mut soma_centroide = 0.0;
+    let mut soma_knn = 0.0;
+    for fold in &folds {
+        let mut no_fold = vec![false; dados.len()];
+        for &indice in fold {
+            no_fold[indice] = true;
+        }
+        let treinamento = dados.iter().zip(&no_fold).filter(|(_, &esta)| !esta).map(|(ponto, _)| ponto);
+        let classificador = ClassificadorCentroide { centroides: centroides_das_classes(treinamento) };
+        let mut acertos = 0;
+        for &indice in fold {
+            if classificador.prever(&dados[indice])? == dados[indice].rotulo {
+                acertos += 1;
+            }
+        }
+        soma_centroide += acertos as f64 / fold.len() as f64;
+
+        soma_knn += avaliar_fold_com(dados, fold, k_vizinhos, &DistanciaMetrica::Euclidiana, Votacao::Maioria).acuracia;
+    }
+    Ok((soma_centroide / folds.len() as f64, soma_knn / folds.len() as f64))
+}
@@ -0,0 +1,359 @@
+//! Classificador KNN configurado uma vez (k, métrica, votação e normalização) e depois
+//! treinado e usado, sem repetir os parâmetros em cada chamada.
+
+use std::fmt;
+
+use crate::distancia::DistanciaMetrica;
+use crate::erro::ErroKnn;
+use crate::knn::{calcular_k, knn_com_votacao, Votacao};
+use crate::ponto::Ponto;
+use crate::preprocessamento::{Preprocessamento, Transformador};
+
+// ==================== CONSTRUTOR ====================
+// As funções livres (knn, knn_com_votacao, knn_preprocessado...) recebem o treinamento e
+// todos os parâmetros a cada chamada. O ClassificadorKnn guarda a configuração e o
+// treinamento já normalizado; o construtor abaixo monta a configuração passo a passo e a
+// valida uma única vez, em construir()
+
+/// Monta a configuração de um [`ClassificadorKnn`]; é criado por [`ClassificadorKnn::builder`].
+///
+/// Cada método troca um parâmetro e devolve o próprio construtor, para encadear as
+/// chamadas. O que não for escolhido fica no padrão: k sugerido por [`calcular_k`] com o
+/// tamanho do treinamento, distância euclidiana, votação por maioria e nenhuma
+/// normalização (ou seja, o mesmo que o [`knn`](fn@crate::knn)).
+///
+/// ```
+/// use aprendizagem_knn::{ClassificadorKnn, DistanciaMetrica, Preprocessamento, Votacao};
+///
+/// let classificador = ClassificadorKnn::builder()
+///     .k(5)
+///     .metrica(DistanciaMetrica::Manhattan)
+///     .votacao(Votacao::InversoDaDistancia)
+///     .normalizacao(Preprocessamento::ZScore)
+///     .construir()
+///     .unwrap();
+/// assert_eq!(classificador.k(), Some(5));
+/// assert_eq!(classificador.metrica(), &DistanciaMetrica::Manhattan);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConstrutorClassificadorKnn {
+    k: Option<usize>,
+    metrica: DistanciaMetrica,
+    votacao: Votacao,
+    normalizacao: Preprocessamento,
+}
+
+impl Default for ConstrutorClassificadorKnn {
+    fn default() -> Self {
+        Self {
+            k: None,
+            metrica: DistanciaMetrica::Euclidiana,
+            votacao: Votacao::Maioria,
+            normalizacao: Preprocessamento::Nenhum,
+        }
+    }
+}
+
+impl ConstrutorClassificadorKnn {
+    /// Número de vizinhos que votam. Sem ele, o k é o de [`calcular_k`] com o tamanho do
+    /// treinamento; um k maior que o treinamento faz todos os pontos votarem, como no
+    /// [`knn`](fn@crate::knn).
+    ///
+    /// ```
+    /// use aprendizagem_knn::ClassificadorKnn;
+    ///
+    /// assert_eq!(ClassificadorKnn::builder().k(3).construir().unwrap().k(), Some(3));
+    /// ```
+    pub fn k(mut self, k: usize) -> Self {
+        self.k = Some(k);
+        self
+    }
+
+    /// Métrica de distância entre os pontos (já normalizados).
+    ///
+    /// ```
+    /// use aprendizagem_knn::{ClassificadorKnn, DistanciaMetrica};
+    ///
+    /// let classificador = ClassificadorKnn::builder().metrica(DistanciaMetrica::Chebyshev).construir().unwrap();
+    /// assert_eq!(classificador.metrica(), &DistanciaMetrica::Chebyshev);
+    /// ```
+    pub fn metrica(mut self, metrica: DistanciaMetrica) -> Self {
+        self.metrica = metrica;
+        self
+    }
+
+    /// Como os k vizinhos votam.
+    ///
+    /// ```
+    /// use aprendizagem_knn::{ClassificadorKnn, Votacao};
+    ///
+    /// let classificador = ClassificadorKnn::builder().votacao(Votacao::InversoDaDistancia).construir().unwrap();
+    /// assert_eq!(classificador.votacao(), Votacao::InversoDaDistancia);
+    /// ```
+    pub fn votacao(mut self, votacao: Votacao) -> Self {
+        self.votacao = votacao;
+        self
+    }
+
+    /// Pré-processamento ajustado no treinamento e aplicado também a cada ponto classificado.
+    ///
+    /// ```
+    /// use aprendizagem_knn::{ClassificadorKnn, Preprocessamento};
+    ///
+    /// let classificador = ClassificadorKnn::builder().normalizacao(Preprocessamento::MinMax).construir().unwrap();
+    /// assert_eq!(classificador.normalizacao(), Preprocessamento::MinMax);
+    /// ```
+    pub fn normalizacao(mut self, normalizacao: Preprocessamento) -> Self {
+        self.normalizacao = normalizacao;
+        self
+    }
+
+    /// Valida a configuração e cria o classificador, ainda sem treinamento.
+    ///
+    /// É [`ErroKnn::ParametroInvalido`]:
+    ///
+    /// - `k = 0`;
+    /// - uma métrica com parâmetros inválidos (veja [`DistanciaMetrica::validar`]);
+    /// - `Preprocessamento::Pca(0)`, que não deixaria nenhuma característica;
+    /// - a distância euclidiana ponderada junto com o PCA: os pesos são de cada
+    ///   característica original, e depois do PCA elas viram componentes principais.
+    ///
+    /// ```
+    /// use aprendizagem_knn::{ClassificadorKnn, DistanciaMetrica, ErroKnn, Preprocessamento};
+    ///
+    /// let erro = ClassificadorKnn::builder().k(0).construir().unwrap_err();
+    /// assert!(matches!(erro, ErroKnn::ParametroInvalido(_)));
+    ///
+    /// let ponderada_com_pca = ClassificadorKnn::builder()
+    ///     .metrica(DistanciaMetrica::EuclidianaPonderada(vec![1.0, 2.0]))
+    ///     .normalizacao(Preprocessamento::Pca(1))
+    ///     .construir();
+    /// assert!(ponderada_com_pca.is_err());
+    /// ```
+    pub fn construir(self) -> Result<ClassificadorKnn, ErroKnn> {
+        if self.k == Some(0) {
+            return Err(ErroKnn::ParametroInvalido("k deve ser maior que zero".to_string()));
+        }
+        self.metrica.validar()?;
+        if self.normalizacao == Preprocessamento::Pca(0) {
+            return Err(ErroKnn::ParametroInvalido("o PCA precisa de ao menos um componente".to_string()));
+        }
+        if matches!(self.metrica, DistanciaMetrica::EuclidianaPonderada(_))
+            && matches!(self.normalizacao, Preprocessamento::Pca(_))
+        {
+            return Err(ErroKnn::ParametroInvalido(
+                "a distância euclidiana ponderada não combina com o PCA: os pesos são das características \
+                 originais, e o PCA as troca pelos componentes principais"
+                    .to_string(),
+            ));
+        }
+        Ok(ClassificadorKnn { configuracao: self, treinado: None })
+    }
+}
+
+// ==================== CLASSIFICADOR ====================
+// O treinamento guardado já passou pela normalização; o transformador ajustado fica junto
+// para normalizar do mesmo jeito cada ponto classificado depois
+struct Treinado {
+    transformador: Box<dyn Transformador>,
+    pontos: Vec<Ponto>,
+    dimensao: usize,
+}
+
+/// Classificador KNN com k, métrica, votação e normalização escolhidos uma vez.
+///
+/// É criado pelo construtor de [`ClassificadorKnn::builder`], recebe os dados em
+/// [`ClassificadorKnn::treinar`] e depois classifica quantos pontos forem preciso, com
+/// [`ClassificadorKnn::classificar`] ou [`ClassificadorKnn::classificar_lote`]. A
+/// normalização é ajustada só no treinamento, como em
+/// [`knn_preprocessado`](crate::knn_preprocessado). As funções livres, como o
+/// [`knn`](fn@crate::knn), continuam valendo para um uso rápido. (Não confundir com o
+/// [`KnnClassificador`](crate::KnnClassificador), que só classifica sob demanda.)
+///
+/// # Exemplo
+///
+/// ```
+/// use aprendizagem_knn::{ponto, pontos, ClassificadorKnn, ErroKnn, Preprocessamento};
+///
+/// let mut classificador =
+///     ClassificadorKnn::builder().k(1).normalizacao(Preprocessamento::MinMax).construir().unwrap();
+/// // Antes do treinamento não há o que consultar
+/// assert_eq!(classificador.classificar(&ponto!([0.0, 0.0], "?")), Err(ErroKnn::NaoTreinado));
+///
+/// // Sem normalização a segunda característica (na casa das centenas) decidiria tudo
+/// classificador.treinar(pontos![[0.0, 0.0] => "A", [1.0, 1000.0] => "B"]).unwrap();
+/// assert_eq!(classificador.classificar(&ponto!([0.9, 200.0], "?")), Ok("B".to_string()));
+/// ```
+pub struct ClassificadorKnn {
+    configuracao: ConstrutorClassificadorKnn,
+    treinado: Option<Treinado>,
+}
+
+// Box<dyn Transformador> não implementa Debug; o que importa ao depurar é a configuração
+// e o tamanho do treinamento
+impl fmt::Debug for ClassificadorKnn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ClassificadorKnn")
+            .field("configuracao", &self.configuracao)
+            .field("pontos_treinamento", &self.treinado.as_ref().map(|treinado| treinado.pontos.len()))
+            .finish()
+    }
+}
+
+impl ClassificadorKnn {
+    /// Começa a configuração de um classificador, com os parâmetros padrão (veja
+    /// [`ConstrutorClassificadorKnn`]).
+    ///
+    /// ```
+    /// use aprendizagem_knn::{ClassificadorKnn, DistanciaMetrica, Preprocessamento, Votacao};
+    ///
+    /// let padrao = ClassificadorKnn::builder().construir().unwrap();
+    /// assert_eq!(padrao.k(), None);
+    /// assert_eq!(padrao.metrica(), &DistanciaMetrica::Euclidiana);
+    /// assert_eq!(padrao.votacao(), Votacao::Maioria);
+    /// assert_eq!(padrao.normalizacao(), Preprocessamento::Nenhum);
+    /// assert!(!padrao.esta_treinado());
+    /// ```
+    pub fn builder() -> ConstrutorClassificadorKnn {
+        ConstrutorClassificadorKnn::default()
+    }
+
+    /// O k escolhido no construtor (`None`: o de [`calcular_k`] com o tamanho do treinamento).
+    ///
+    /// ```
+    /// use aprendizagem_knn::ClassificadorKnn;
+    ///
+    /// assert_eq!(ClassificadorKnn::builder().k(7).construir().unwrap().k(), Some(7));
+    /// ```
+    pub fn k(&self) -> Option<usize> {
+        self.configuracao.k
+    }
+
+    /// A métrica de distância escolhida.
+    ///
+    /// ```
+    /// use aprendizagem_knn::{ClassificadorKnn, DistanciaMetrica};
+    ///
+    /// assert_eq!(ClassificadorKnn::builder().construir().unwrap().metrica(), &DistanciaMetrica::Euclidiana);
+    /// ```
+    pub fn metrica(&self) -> &DistanciaMetrica {
+        &self.configuracao.metrica
+    }
+
+    /// O esquema de votação escolhido.
+    ///
+    /// ```
+    /// use aprendizagem_knn::{ClassificadorKnn, Votacao};
+    ///
+    /// assert_eq!(ClassificadorKnn::builder().construir().unwrap().votacao(), Votacao::Maioria);
+    /// ```
+    pub fn votacao(&self) -> Votacao {
+        self.configuracao.votacao
+    }
+
+    /// A normalização escolhida.
+    ///
+    /// ```
+    /// use aprendizagem_knn::{ClassificadorKnn, Preprocessamento};
+    ///
+    /// assert_eq!(ClassificadorKnn::builder().construir().unwrap().normalizacao(), Preprocessamento::Nenhum);
+    /// ```
+    pub fn normalizacao(&self) -> Preprocessamento {
+        self.configuracao.normalizacao
+    }
+
+    /// Se [`ClassificadorKnn::treinar`] já foi chamado com sucesso.
+    ///
+    /// ```
+    /// use aprendizagem_knn::{pontos, ClassificadorKnn};
+    ///
+    /// let mut classificador = ClassificadorKnn::builder().construir().unwrap();
+    /// assert!(!classificador.esta_treinado());
+    /// classificador.treinar(pontos![[0.0] => "A"]).unwrap();
+    /// assert!(classificador.esta_treinado());
+    /// ```
+    pub fn esta_treinado(&self) -> bool {
+        self.treinado.is_some()
+    }
+
+    /// Ajusta a normalização em `pontos` e guarda o treinamento normalizado. Treinar de
+    /// novo substitui o treinamento anterior.
+    ///
+    /// Um treinamento vazio é [`ErroKnn::ConjuntoVazio`]; pontos com números de
+    /// características diferentes, ou pesos da distância ponderada em número diferente das
+    /// características, são [`ErroKnn::DimensaoIncompativel`]; os erros do ajuste da
+    /// normalização (um PCA com mais componentes que características) são repassados. Se
+    /// der erro, o classificador continua como estava.
+    ///
+    /// ```
+    /// use aprendizagem_knn::{pontos, ClassificadorKnn, ErroKnn};
+    ///
+    /// let mut classificador = ClassificadorKnn::builder().construir().unwrap();
+    /// assert_eq!(classificador.treinar(Vec::new()), Err(ErroKnn::ConjuntoVazio));
+    /// let erro = classificador.treinar(pontos![[0.0, 1.0] => "A", [2.0] => "B"]).unwrap_err();
+    /// assert_eq!(erro, ErroKnn::DimensaoIncompativel { esperado: 2, obtido: 1 });
+    /// assert!(!classificador.esta_treinado());
+    /// ```
+    pub fn treinar(&mut self, pontos: Vec<Ponto>) -> Result<(), ErroKnn> {
+        let dimensao = pontos.first().ok_or(ErroKnn::ConjuntoVazio)?.caracteristicas.len();
+        if let Some(ponto) = pontos.iter().find(|ponto| ponto.caracteristicas.len() != dimensao) {
+            return Err(ErroKnn::DimensaoIncompativel { esperado: dimensao, obtido: ponto.caracteristicas.len() });
+        }
+        if let DistanciaMetrica::EuclidianaPonderada(pesos) = &self.configuracao.metrica {
+            if pesos.len() != dimensao {
+                return Err(ErroKnn::DimensaoIncompativel { esperado: dimensao, obtido: pesos.len() });
+            }
+        }
+
+        let transformador = self.configuracao.normalizacao.ajustar(&pontos)?;
+        let pontos = transformador.transformar(&pontos);
+        self.treinado = Some(Treinado { transformador, pontos, dimensao });
+        Ok(())
+    }
+
+    /// Classifica um ponto: normaliza-o como o treinamento e vota entre os k vizinhos.
+    ///
+    /// Antes de [`ClassificadorKnn::treinar`], o erro é [`ErroKnn::NaoTreinado`]; um ponto
+    /// com um número de características diferente do treinamento é
+    /// [`ErroKnn::DimensaoIncompativel`]. No empate de votos vence o rótulo
+    /// alfabeticamente menor, como no [`knn`](fn@crate::knn).
+    ///
+    /// ```
+    /// use aprendizagem_knn::{ponto, pontos, ClassificadorKnn, ErroKnn};
+    ///
+    /// let mut classificador = ClassificadorKnn::builder().k(3).construir().unwrap();
+    /// classificador.treinar(pontos![[0.0] => "A", [1.0] => "A", [5.0] => "B", [6.0] => "B"]).unwrap();
+    /// assert_eq!(classificador.classificar(&ponto!([0.5], "?")), Ok("A".to_string()));
+    /// let erro = classificador.classificar(&ponto!([0.5, 1.0], "?")).unwrap_err();
+    /// assert_eq!(erro, ErroKnn::DimensaoIncompativel { esperado: 1, obtido: 2 });
+    /// ```
+    pub fn classificar(&self, ponto: &Ponto) -> Result<String, ErroKnn> {
+        let treinado = self.treinado.as_ref().ok_or(ErroKnn::NaoTreinado)?;
+        if ponto.caracteristicas.len() != treinado.dimensao {
+            return Err(ErroKnn::DimensaoIncompativel {
+                esperado: treinado.dimensao,
+                obtido: ponto.caracteristicas.len(),
+            });
+        }
+        let k = self.configuracao.k.unwrap_or_else(|| calcular_k(treinado.pontos.len()));
+        let normalizado = treinado.transformador.transformar_ponto(ponto);
+        knn_com_votacao(&treinado.pontos, &normalizado, k, &self.configuracao.metrica, self.configuracao.votacao)
+    }
+
+    /// Classifica vários pontos, na ordem recebida; para no primeiro erro, que é o de
+    /// [`ClassificadorKnn::classificar`].
+    ///
+    /// ```
+    /// use aprendizagem_knn::{pontos, ClassificadorKnn, ErroKnn};
+    ///
+    /// let mut classificador = ClassificadorKnn::builder().k(1).construir().unwrap();
+    /// let testes = pontos![[0.4] => "?", [8.0] => "?"];
+    /// assert_eq!(classificador.classificar_lote(&testes), Err(ErroKnn::NaoTreinado));
+    ///
+    /// classificador.treinar(pontos![[0.0] => "A", [9.0] => "B"]).unwrap();
+    /// assert_eq!(classificador.classificar_lote(&testes), Ok(vec!["A".to_string(), "B".to_string()]));
+    /// ```
+    pub fn classificar_lote(&self, pontos: &[Ponto]) -> Result<Vec<String>, ErroKnn> {
+        pontos.iter().map(|ponto| self.classificar(ponto)).collect()
+    }
+}
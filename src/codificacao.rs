@@ -0,0 +1,261 @@
+//! Codificação one-hot de colunas categóricas.
+
+use serde::{Deserialize, Serialize};
+
+use crate::dados::{eh_valor_ausente, interpretar_valor};
+use crate::ponto::Ponto;
+
+// ==================== CODIFICAÇÃO ONE-HOT ====================
+/// Guarda, para cada coluna original, se ela é numérica (None) ou categórica
+/// (Some com as categorias vistas no treinamento, em ordem alfabética).
+///
+/// Uma coluna categórica com 3 categorias vira 3 características 0/1.
+///
+/// # Exemplo
+///
+/// ```
+/// use aprendizagem_knn::CodificadorOneHot;
+///
+/// let colunas = vec!["peso".to_string(), "cor".to_string()];
+/// let linha1 = vec!["1.5".to_string(), "red".to_string()];
+/// let linha2 = vec!["2.0".to_string(), "green".to_string()];
+/// let codificador = CodificadorOneHot::ajustar(&colunas, &[linha1.as_slice(), linha2.as_slice()], None);
+///
+/// assert_eq!(codificador.dimensao(), 3);
+/// assert_eq!(codificador.codificar(&linha1).unwrap(), vec![1.5, 0.0, 1.0]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CodificadorOneHot {
+    /// Nomes das colunas originais
+    pub colunas: Vec<String>,
+    /// Categorias de cada coluna (None para colunas numéricas)
+    pub categorias: Vec<Option<Vec<String>>>,
+}
+
+impl CodificadorOneHot {
+    /// Aprende as categorias de cada coluna a partir das linhas de treinamento.
+    ///
+    /// Sem `colunas_categoricas`, uma coluna é categórica quando algum valor presente
+    /// não é numérico.
+    ///
+    /// ```
+    /// use aprendizagem_knn::CodificadorOneHot;
+    ///
+    /// let colunas = vec!["nota".to_string()];
+    /// let linhas = [vec!["3".to_string()], vec!["1".to_string()]];
+    /// let campos: Vec<&[String]> = linhas.iter().map(|linha| linha.as_slice()).collect();
+    ///
+    /// // forçando a coluna numérica a ser tratada como categórica
+    /// let codificador = CodificadorOneHot::ajustar(&colunas, &campos, Some(&[0]));
+    /// assert_eq!(codificador.categorias[0], Some(vec!["1".to_string(), "3".to_string()]));
+    /// ```
+    pub fn ajustar(colunas: &[String], linhas: &[&[String]], colunas_categoricas: Option<&[usize]>) -> Self {
+        let categorias = (0..colunas.len())
+            .map(|coluna| {
+                let categorica = match colunas_categoricas {
+                    Some(indices) => indices.contains(&coluna),
+                    None => linhas.iter().any(|campos| {
+                        interpretar_valor(&campos[coluna]).is_none() && !eh_valor_ausente(&campos[coluna])
+                    }),
+                };
+                if !categorica {
+                    return None;
+                }
+
+                let mut valores: Vec<String> = linhas.iter()
+                    .map(|campos| campos[coluna].clone())
+                    .filter(|valor| !eh_valor_ausente(valor))
+                    .collect();
+                valores.sort();
+                valores.dedup();
+                Some(valores)
+            })
+            .collect();
+
+        Self { colunas: colunas.to_vec(), categorias }
+    }
+
+    /// Número de características depois da codificação.
+    ///
+    /// ```
+    /// use aprendizagem_knn::CodificadorOneHot;
+    ///
+    /// let codificador = CodificadorOneHot {
+    ///     colunas: vec!["x".to_string(), "cor".to_string()],
+    ///     categorias: vec![None, Some(vec!["a".to_string(), "b".to_string()])],
+    /// };
+    /// assert_eq!(codificador.dimensao(), 3);
+    /// ```
+    pub fn dimensao(&self) -> usize {
+        self.categorias.iter()
+            .map(|categorias| categorias.as_ref().map_or(1, |valores| valores.len()))
+            .sum()
+    }
+
+    /// Nome de cada característica codificada, como "cor=red".
+    ///
+    /// ```
+    /// use aprendizagem_knn::CodificadorOneHot;
+    ///
+    /// let codificador = CodificadorOneHot {
+    ///     colunas: vec!["x".to_string(), "cor".to_string()],
+    ///     categorias: vec![None, Some(vec!["a".to_string(), "b".to_string()])],
+    /// };
+    /// assert_eq!(codificador.nomes_caracteristicas(), vec!["x", "cor=a", "cor=b"]);
+    /// ```
+    pub fn nomes_caracteristicas(&self) -> Vec<String> {
+        let mut nomes = Vec::with_capacity(self.dimensao());
+        for (coluna, categorias) in self.colunas.iter().zip(&self.categorias) {
+            match categorias {
+                Some(valores) => nomes.extend(valores.iter().map(|valor| format!("{}={}", coluna, valor))),
+                None => nomes.push(coluna.clone()),
+            }
+        }
+        nomes
+    }
+
+    /// Codifica os campos de características de uma linha.
+    ///
+    /// Categoria nunca vista no treinamento (ou ausente) vira só zeros, com um aviso
+    /// (`log::warn!`) no caso da desconhecida; valor numérico inválido é erro.
+    ///
+    /// ```
+    /// use aprendizagem_knn::CodificadorOneHot;
+    ///
+    /// let codificador = CodificadorOneHot {
+    ///     colunas: vec!["x".to_string(), "cor".to_string()],
+    ///     categorias: vec![None, Some(vec!["a".to_string(), "b".to_string()])],
+    /// };
+    /// let nova = vec!["2".to_string(), "z".to_string()];
+    /// assert_eq!(codificador.codificar(&nova).unwrap(), vec![2.0, 0.0, 0.0]);
+    /// assert!(codificador.codificar(&["x".to_string(), "a".to_string()]).is_err());
+    /// ```
+    pub fn codificar(&self, campos: &[String]) -> Result<Vec<f64>, String> {
+        if campos.len() != self.colunas.len() {
+            return Err(format!("esperadas {} colunas, encontradas {}", self.colunas.len(), campos.len()));
+        }
+
+        let mut caracteristicas = Vec::with_capacity(self.dimensao());
+        for (coluna, (campo, categorias)) in campos.iter().zip(&self.categorias).enumerate() {
+            match categorias {
+                Some(valores) => {
+                    let posicao = valores.iter().position(|valor| valor == campo);
+                    if posicao.is_none() && !eh_valor_ausente(campo) {
+                        log::warn!(
+                            "categoria '{}' não vista no treinamento na coluna '{}'; codificada como zeros",
+                            campo, self.colunas[coluna]
+                        );
+                    }
+                    caracteristicas.extend((0..valores.len()).map(|i| if Some(i) == posicao { 1.0 } else { 0.0 }));
+                }
+                None => match interpretar_valor(campo) {
+                    Some(valor) => caracteristicas.push(valor),
+                    None if eh_valor_ausente(campo) => caracteristicas.push(f64::NAN),
+                    None => return Err(format!(
+                        "coluna {} ('{}'): valor numérico inválido '{}'",
+                        coluna + 1, self.colunas[coluna], campo
+                    )),
+                },
+            }
+        }
+        Ok(caracteristicas)
+    }
+}
+
+// ==================== ONE-HOT PARA LINHAS JÁ SEPARADAS ====================
+/// Categorias de cada coluna categórica escolhida: para cada índice de `indices_cat` (na
+/// mesma ordem), os valores distintos que a coluna `linha.1[indice]` assume em `dados`,
+/// em ordem alfabética.
+///
+/// Cada linha de `dados` é `(características numéricas, campos categóricos, rótulo)`.
+/// Guarde o vocabulário do treinamento e use [`aplicar_one_hot`] para codificar os pontos
+/// de teste com as mesmas colunas. Entra em pânico se algum índice não existir nas linhas.
+///
+/// ```
+/// use aprendizagem_knn::vocabulario_categorias;
+///
+/// let dados = vec![
+///     (vec![1.0], vec!["red".to_string(), "P".to_string()], "A".to_string()),
+///     (vec![2.0], vec!["blue".to_string(), "M".to_string()], "B".to_string()),
+///     (vec![3.0], vec!["red".to_string(), "G".to_string()], "B".to_string()),
+/// ];
+/// assert_eq!(vocabulario_categorias(&dados, &[0]), vec![vec!["blue", "red"]]);
+/// assert_eq!(vocabulario_categorias(&dados, &[1, 0])[0], vec!["G", "M", "P"]);
+/// ```
+pub fn vocabulario_categorias(dados: &[(Vec<f64>, Vec<String>, String)], indices_cat: &[usize]) -> Vec<Vec<String>> {
+    indices_cat.iter()
+        .map(|&indice| {
+            let mut valores: Vec<String> = dados.iter().map(|(_, categoricas, _)| categoricas[indice].clone()).collect();
+            valores.sort();
+            valores.dedup();
+            valores
+        })
+        .collect()
+}
+
+/// Transforma linhas com colunas numéricas e categóricas em pontos só com números.
+///
+/// As características de cada ponto são as numéricas da linha, seguidas de um bloco 0/1
+/// para cada coluna de `indices_cat` (uma característica por categoria, na ordem do
+/// vocabulário). Campos categóricos fora de `indices_cat` são ignorados. O vocabulário é
+/// aprendido dos próprios `dados` com [`vocabulario_categorias`].
+///
+/// ```
+/// use aprendizagem_knn::codificar_one_hot;
+///
+/// let dados = vec![
+///     (vec![1.0], vec!["red".to_string()], "A".to_string()),
+///     (vec![2.0], vec!["blue".to_string()], "B".to_string()),
+///     (vec![3.0], vec!["green".to_string()], "B".to_string()),
+/// ];
+/// let pontos = codificar_one_hot(&dados, &[0]);
+/// // blue, green, red
+/// assert_eq!(pontos[0].caracteristicas, vec![1.0, 0.0, 0.0, 1.0]);
+/// assert_eq!(pontos[2].caracteristicas, vec![3.0, 0.0, 1.0, 0.0]);
+/// assert_eq!(pontos[1].rotulo, "B");
+/// ```
+pub fn codificar_one_hot(dados: &[(Vec<f64>, Vec<String>, String)], indices_cat: &[usize]) -> Vec<Ponto> {
+    aplicar_one_hot(dados, indices_cat, &vocabulario_categorias(dados, indices_cat))
+}
+
+/// Codifica linhas com um vocabulário já aprendido (por exemplo, os pontos de teste com o
+/// vocabulário do treinamento), para que as características fiquem nas mesmas posições.
+///
+/// `vocabulario[i]` são as categorias da coluna `indices_cat[i]`. Uma categoria que não
+/// está no vocabulário vira um bloco só de zeros.
+///
+/// ```
+/// use aprendizagem_knn::{aplicar_one_hot, vocabulario_categorias};
+///
+/// let treino = vec![
+///     (vec![1.0], vec!["red".to_string()], "A".to_string()),
+///     (vec![2.0], vec!["blue".to_string()], "B".to_string()),
+/// ];
+/// let vocabulario = vocabulario_categorias(&treino, &[0]);
+///
+/// let teste = vec![
+///     (vec![5.0], vec!["red".to_string()], "?".to_string()),
+///     (vec![6.0], vec!["purple".to_string()], "?".to_string()),
+/// ];
+/// let pontos = aplicar_one_hot(&teste, &[0], &vocabulario);
+/// assert_eq!(pontos[0].caracteristicas, vec![5.0, 0.0, 1.0]);
+/// assert_eq!(pontos[1].caracteristicas, vec![6.0, 0.0, 0.0]);
+/// ```
+pub fn aplicar_one_hot(
+    dados: &[(Vec<f64>, Vec<String>, String)],
+    indices_cat: &[usize],
+    vocabulario: &[Vec<String>],
+) -> Vec<Ponto> {
+    assert_eq!(indices_cat.len(), vocabulario.len(), "o vocabulário deve ter uma lista de categorias por coluna");
+
+    dados.iter()
+        .map(|(numericas, categoricas, rotulo)| {
+            let mut caracteristicas = numericas.clone();
+            for (&indice, categorias) in indices_cat.iter().zip(vocabulario) {
+                let valor = &categoricas[indice];
+                caracteristicas.extend(categorias.iter().map(|categoria| if categoria == valor { 1.0 } else { 0.0 }));
+            }
+            Ponto::novo(caracteristicas, rotulo.clone())
+        })
+        .collect()
+}
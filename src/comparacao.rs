@@ -0,0 +1,242 @@
+//! Testes estatísticos para comparar dois classificadores: McNemar, nas previsões dos
+//! mesmos pontos, e t de Welch, nas acurácias de cada um.
+
+use crate::erro::ErroKnn;
+use crate::estatisticas::{erfc, p_valor_t_bilateral, EstatisticasIncrementais};
+
+// ==================== TESTE DE MCNEMAR ====================
+/// Até este número de pontos discordantes (b + c), o p-valor do McNemar é calculado pela
+/// distribuição binomial exata; acima dele, pela aproximação qui-quadrado.
+///
+/// ```
+/// use aprendizagem_knn::{mcnemar, LIMITE_MCNEMAR_EXATO};
+///
+/// // 30 pontos em que só A acerta: mais discordantes que o limite
+/// let reais = vec!["A".to_string(); 30];
+/// let teste = mcnemar(&reais, &reais, &vec!["B".to_string(); 30]).unwrap();
+/// assert!(30 > LIMITE_MCNEMAR_EXATO && !teste.exato);
+/// ```
+pub const LIMITE_MCNEMAR_EXATO: usize = 25;
+
+/// Resultado do teste de McNemar entre dois classificadores A e B.
+///
+/// É devolvido por [`mcnemar`].
+///
+/// ```
+/// use aprendizagem_knn::mcnemar;
+///
+/// let rotulos = |texto: &str| texto.chars().map(String::from).collect::<Vec<_>>();
+/// let reais = rotulos("AABBAB");
+/// // A erra o último ponto; B erra os dois primeiros
+/// let teste = mcnemar(&reais, &rotulos("AABBAA"), &rotulos("BBBBAB")).unwrap();
+/// assert_eq!(teste.tabela, [[3, 2], [1, 0]]);
+/// assert!(teste.exato);
+/// assert!(teste.p_valor > 0.05);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TesteMcNemar {
+    /// Tabela 2×2 de acertos: a linha diz se A acertou (0) ou errou (1), a coluna diz o
+    /// mesmo de B. `tabela[0][1]` (b: só A acertou) e `tabela[1][0]` (c: só B acertou)
+    /// são os pontos discordantes, os únicos que entram no teste.
+    pub tabela: [[usize; 2]; 2],
+    /// Estatística qui-quadrado com correção de continuidade: (|b - c| - 1)² / (b + c)
+    /// (0 quando não há pontos discordantes)
+    pub estatistica: f64,
+    /// Probabilidade de ver uma diferença entre b e c pelo menos tão grande se os dois
+    /// classificadores errassem igualmente (bilateral)
+    pub p_valor: f64,
+    /// Se o p-valor veio da binomial exata (b + c ≤ [`LIMITE_MCNEMAR_EXATO`])
+    pub exato: bool,
+}
+
+/// Teste de McNemar: diz se a diferença de acurácia entre duas configurações (outro k,
+/// outra métrica...) avaliadas nos mesmos pontos de teste é estatisticamente significativa.
+///
+/// Pontos em que os dois acertam ou os dois erram não dizem nada sobre qual é melhor; o
+/// teste olha só para os discordantes: b pontos em que só A acertou e c em que só B
+/// acertou. Se os dois fossem equivalentes, cada discordante teria 50% de chance de cair
+/// de cada lado, e o p-valor mede o quão improvável seria a divisão observada. Um p-valor
+/// pequeno (abaixo de 0.05, por exemplo) indica que a diferença não é só acaso.
+///
+/// Com poucos discordantes (b + c ≤ [`LIMITE_MCNEMAR_EXATO`]) o p-valor é o da binomial
+/// exata, 2 · P(X ≤ min(b, c)) com X ~ Binomial(b + c, 1/2), limitado a 1; com mais, usa
+/// a estatística qui-quadrado com 1 grau de liberdade.
+///
+/// É erro se as três listas não tiverem o mesmo tamanho ou estiverem vazias.
+///
+/// ```
+/// use aprendizagem_knn::mcnemar;
+///
+/// let rotulos = |texto: &str| texto.chars().map(String::from).collect::<Vec<_>>();
+/// let reais = rotulos("AAAABBBBAB");
+///
+/// // Classificadores idênticos: nenhum ponto discordante, p = 1
+/// let previstos = rotulos("AAABBBBAAB");
+/// let teste = mcnemar(&reais, &previstos, &previstos).unwrap();
+/// assert_eq!(teste.tabela[0][1] + teste.tabela[1][0], 0);
+/// assert_eq!(teste.p_valor, 1.0);
+///
+/// // B acerta tudo, A erra 7 pontos: b = 0, c = 7, p = 2 / 2⁷
+/// let a = rotulos("BBBBAAABAB");
+/// let teste = mcnemar(&reais, &a, &reais).unwrap();
+/// assert_eq!(teste.tabela, [[3, 0], [7, 0]]);
+/// assert!(teste.exato);
+/// assert!((teste.p_valor - 2.0 / 128.0).abs() < 1e-12);
+/// ```
+///
+/// Com muitos discordantes vale a aproximação qui-quadrado:
+///
+/// ```
+/// use aprendizagem_knn::mcnemar;
+///
+/// // 40 pontos em que só A acerta e 10 em que só B acerta: χ² = (30 - 1)² / 50 = 16.82
+/// let reais = vec!["A".to_string(); 50];
+/// let previstos_a: Vec<String> = (0..50).map(|i| if i < 40 { "A" } else { "B" }.to_string()).collect();
+/// let previstos_b: Vec<String> = (0..50).map(|i| if i < 40 { "B" } else { "A" }.to_string()).collect();
+/// let teste = mcnemar(&reais, &previstos_a, &previstos_b).unwrap();
+/// assert_eq!(teste.tabela, [[0, 40], [10, 0]]);
+/// assert!(!teste.exato);
+/// assert!((teste.estatistica - 16.82).abs() < 1e-12);
+/// assert!((teste.p_valor - 4.1098e-5).abs() < 1e-8);
+/// ```
+pub fn mcnemar(reais: &[String], previstos_a: &[String], previstos_b: &[String]) -> Result<TesteMcNemar, ErroKnn> {
+    if reais.len() != previstos_a.len() || reais.len() != previstos_b.len() {
+        return Err(ErroKnn::ParametroInvalido(format!(
+            "há {} rótulos reais, {} previsões de A e {} de B",
+            reais.len(),
+            previstos_a.len(),
+            previstos_b.len()
+        )));
+    }
+    if reais.is_empty() {
+        return Err(ErroKnn::ConjuntoVazio);
+    }
+
+    let mut tabela = [[0usize; 2]; 2];
+    for ((real, a), b) in reais.iter().zip(previstos_a).zip(previstos_b) {
+        // Índice 0 para acerto e 1 para erro
+        tabela[usize::from(a != real)][usize::from(b != real)] += 1;
+    }
+
+    let (b, c) = (tabela[0][1], tabela[1][0]);
+    let discordantes = b + c;
+    let estatistica = if discordantes == 0 {
+        0.0
+    } else {
+        (b.abs_diff(c) as f64 - 1.0).max(0.0).powi(2) / discordantes as f64
+    };
+    let exato = discordantes <= LIMITE_MCNEMAR_EXATO;
+    let p_valor = if exato {
+        p_valor_binomial_bilateral(b.min(c), discordantes)
+    } else {
+        // P(χ² com 1 grau de liberdade > x) = erfc(√(x / 2))
+        erfc((estatistica / 2.0).sqrt())
+    };
+
+    Ok(TesteMcNemar { tabela, estatistica, p_valor, exato })
+}
+
+// 2 · P(X ≤ menor) com X ~ Binomial(n, 1/2), limitado a 1 (com n = 0 o resultado é 1)
+fn p_valor_binomial_bilateral(menor: usize, n: usize) -> f64 {
+    let mut combinacao = 1.0; // C(n, 0)
+    let mut soma = 0.0;
+    for i in 0..=menor {
+        soma += combinacao;
+        combinacao = combinacao * (n - i) as f64 / (i + 1) as f64;
+    }
+    (2.0 * soma * 0.5f64.powi(n as i32)).min(1.0)
+}
+
+// ==================== TESTE T DE WELCH ====================
+/// Teste t de Welch: as médias de duas amostras (por exemplo, as acurácias por fold de
+/// duas configurações do KNN na validação cruzada) são diferentes? Devolve
+/// `(estatistica_t, p_valor)`.
+///
+/// Ao contrário do teste t de Student, não supõe que as duas amostras tenham a mesma
+/// variância. A estatística é t = (média_a - média_b) / √(s²_a / n_a + s²_b / n_b), com
+/// as variâncias amostrais, e os graus de liberdade vêm da fórmula de Welch-Satterthwaite
+/// (em geral não inteiros). O p-valor é bilateral: a probabilidade de um |t| pelo menos
+/// tão grande se as médias fossem iguais. t positivo quer dizer média de A maior.
+///
+/// Sobre a precisão:
+/// - A distribuição t é calculada pela função beta incompleta, numa expansão em fração
+///   contínua; o p-valor tem cerca de 12 dígitos corretos, bem mais do que o teste precisa.
+/// - Os graus de liberdade de Welch-Satterthwaite já são uma aproximação, e o teste supõe
+///   amostras independentes com distribuição perto da normal; com poucos valores (como 5
+///   folds), o p-valor é só uma indicação.
+/// - As acurácias dos folds de uma mesma validação cruzada não são independentes (os
+///   conjuntos de treinamento se sobrepõem), o que deixa o p-valor otimista. Para duas
+///   configurações avaliadas nos mesmos pontos, o [`mcnemar`] é mais apropriado.
+///
+/// Se as duas amostras forem constantes, t é 0 com p-valor 1 quando as médias são iguais,
+/// e ±infinito com p-valor 0 quando são diferentes. Entra em pânico se alguma amostra
+/// tiver menos de 2 valores.
+///
+/// ```
+/// use aprendizagem_knn::teste_t_welsh;
+///
+/// let acuracias_k3 = [0.81, 0.84, 0.79, 0.86, 0.83];
+/// let acuracias_k1 = [0.78, 0.76, 0.80, 0.75, 0.79];
+/// let (t, p_valor) = teste_t_welsh(&acuracias_k3, &acuracias_k1);
+/// assert!((t - 3.28266082149306).abs() < 1e-12);
+/// assert!((p_valor - 0.0121949259050019).abs() < 1e-12);
+///
+/// // Amostras de tamanhos diferentes
+/// let (t, p_valor) = teste_t_welsh(&[1.0, 2.0, 3.0, 4.0], &[2.0, 4.0, 6.0, 8.0, 10.0, 12.0]);
+/// assert!((t + 2.71360210119987).abs() < 1e-12);
+/// assert!((p_valor - 0.0318244437808415).abs() < 1e-12);
+/// ```
+pub fn teste_t_welsh(amostras_a: &[f64], amostras_b: &[f64]) -> (f64, f64) {
+    assert!(
+        amostras_a.len() >= 2 && amostras_b.len() >= 2,
+        "cada amostra precisa de ao menos 2 valores (recebidos {} e {})",
+        amostras_a.len(),
+        amostras_b.len()
+    );
+
+    let (media_a, variancia_a) = media_e_variancia_amostral(amostras_a);
+    let (media_b, variancia_b) = media_e_variancia_amostral(amostras_b);
+    let (n_a, n_b) = (amostras_a.len() as f64, amostras_b.len() as f64);
+    // Variância de cada média: s² / n
+    let (erro_a, erro_b) = (variancia_a / n_a, variancia_b / n_b);
+    let erro_padrao = (erro_a + erro_b).sqrt();
+    let diferenca = media_a - media_b;
+
+    if erro_padrao == 0.0 {
+        return if diferenca == 0.0 { (0.0, 1.0) } else { (diferenca.signum() * f64::INFINITY, 0.0) };
+    }
+
+    let t = diferenca / erro_padrao;
+    let graus = (erro_a + erro_b).powi(2) / (erro_a.powi(2) / (n_a - 1.0) + erro_b.powi(2) / (n_b - 1.0));
+    (t, p_valor_t_bilateral(t, graus))
+}
+
+// Média e variância amostral (dividida por n - 1)
+fn media_e_variancia_amostral(amostras: &[f64]) -> (f64, f64) {
+    let mut estatisticas = EstatisticasIncrementais::nova(1);
+    for &valor in amostras {
+        estatisticas.atualizar(&[valor]);
+    }
+    (estatisticas.media[0], estatisticas.m2[0] / (estatisticas.n - 1) as f64)
+}
+
+/// Atalho para o [`teste_t_welsh`]: `true` se o p-valor for menor que `alfa` (o nível de
+/// significância, em geral 0.05), ou seja, se a diferença entre as médias dificilmente
+/// seria só acaso.
+///
+/// ```
+/// use aprendizagem_knn::diferenca_significativa;
+///
+/// let acuracias_k3 = [0.81, 0.84, 0.79, 0.86, 0.83];
+/// let acuracias_k1 = [0.78, 0.76, 0.80, 0.75, 0.79];
+/// assert!(diferenca_significativa(&acuracias_k3, &acuracias_k1, 0.05));
+/// // p ≈ 0.012: não chega a 1%
+/// assert!(!diferenca_significativa(&acuracias_k3, &acuracias_k1, 0.01));
+///
+/// // Médias quase iguais
+/// let acuracias_k5 = [0.79, 0.83, 0.77, 0.80, 0.82];
+/// assert!(!diferenca_significativa(&[0.80, 0.82, 0.78, 0.81, 0.79], &acuracias_k5, 0.05));
+/// ```
+pub fn diferenca_significativa(amostras_a: &[f64], amostras_b: &[f64], alfa: f64) -> bool {
+    teste_t_welsh(amostras_a, amostras_b).1 < alfa
+}
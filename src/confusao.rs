@@ -0,0 +1,237 @@
+//! Matriz de confusão: quantas vezes cada classe real foi prevista como cada classe.
+
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fmt;
+use std::io::Write;
+
+use csv::Writer;
+
+use crate::erro::ErroKnn;
+
+// ==================== MATRIZ DE CONFUSÃO ====================
+/// Matriz de confusão: a linha é a classe real, a coluna é a classe prevista e cada
+/// célula conta quantos pontos tiveram aquele par (real, previsto).
+///
+/// A diagonal são os acertos; fora dela, cada célula mostra qual classe foi confundida
+/// com qual. As classes são todas as que aparecem nos rótulos reais ou nos previstos
+/// (em ordem alfabética), então uma classe que só aparece de um dos lados ganha uma linha
+/// e uma coluna mesmo assim.
+///
+/// # Exemplo
+///
+/// ```
+/// use aprendizagem_knn::MatrizConfusao;
+///
+/// let reais: Vec<String> = ["A", "A", "B", "B"].iter().map(|r| r.to_string()).collect();
+/// let previstos: Vec<String> = ["A", "B", "B", "B"].iter().map(|r| r.to_string()).collect();
+/// let matriz = MatrizConfusao::nova(&reais, &previstos).unwrap();
+///
+/// assert_eq!(matriz.contagens, vec![vec![1, 1], vec![0, 2]]);
+/// println!("{}", matriz);
+/// // real \ previsto  A  B
+/// // A                1  1
+/// // B                0  2
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatrizConfusao {
+    /// Classes, em ordem alfabética; a posição de cada uma é a sua linha e a sua coluna
+    pub rotulos: Vec<String>,
+    /// `contagens[i][j]`: pontos da classe `rotulos[i]` previstos como `rotulos[j]`
+    pub contagens: Vec<Vec<usize>>,
+}
+
+impl MatrizConfusao {
+    /// Monta a matriz a partir dos rótulos reais e previstos, na mesma ordem.
+    ///
+    /// É erro se as duas listas tiverem tamanhos diferentes.
+    ///
+    /// ```
+    /// use aprendizagem_knn::MatrizConfusao;
+    ///
+    /// let rotulos = |lista: &[&str]| lista.iter().map(|r| r.to_string()).collect::<Vec<_>>();
+    ///
+    /// // 3 classes
+    /// let reais = rotulos(&["A", "A", "A", "B", "B", "C", "C", "C"]);
+    /// let previstos = rotulos(&["A", "A", "B", "B", "C", "C", "C", "A"]);
+    /// let matriz = MatrizConfusao::nova(&reais, &previstos).unwrap();
+    /// assert_eq!(matriz.contagens, vec![vec![2, 1, 0], vec![0, 1, 1], vec![1, 0, 2]]);
+    ///
+    /// // "D" só aparece nas previsões, mas ganha linha e coluna
+    /// let matriz = MatrizConfusao::nova(&rotulos(&["A", "B"]), &rotulos(&["A", "D"])).unwrap();
+    /// assert_eq!(matriz.rotulos, vec!["A", "B", "D"]);
+    /// assert_eq!(matriz.contagem("B", "D"), 1);
+    /// assert_eq!(matriz.total_real("D"), 0);
+    ///
+    /// assert!(MatrizConfusao::nova(&rotulos(&["A", "B"]), &rotulos(&["A"])).is_err());
+    /// ```
+    pub fn nova(reais: &[String], previstos: &[String]) -> Result<Self, ErroKnn> {
+        if reais.len() != previstos.len() {
+            return Err(ErroKnn::ParametroInvalido(format!(
+                "há {} rótulos reais e {} previstos",
+                reais.len(),
+                previstos.len()
+            )));
+        }
+
+        // BTreeSet mantém os rótulos únicos e já ordenados
+        let rotulos: Vec<String> = reais.iter().chain(previstos).cloned().collect::<BTreeSet<_>>().into_iter().collect();
+        let mut contagens = vec![vec![0; rotulos.len()]; rotulos.len()];
+        for (real, previsto) in reais.iter().zip(previstos) {
+            // Os rótulos estão ordenados, então a busca binária encontra a posição de cada um
+            let i = rotulos.binary_search(real).expect("todo rótulo real está na lista");
+            let j = rotulos.binary_search(previsto).expect("todo rótulo previsto está na lista");
+            contagens[i][j] += 1;
+        }
+
+        Ok(Self { rotulos, contagens })
+    }
+
+    // Posição de um rótulo nas linhas e colunas (None se ele não aparece na matriz)
+    fn posicao(&self, rotulo: &str) -> Option<usize> {
+        self.rotulos.iter().position(|r| r == rotulo)
+    }
+
+    /// Quantos pontos da classe `real` foram previstos como `previsto`
+    /// (0 se alguma das classes não aparece na matriz).
+    ///
+    /// ```
+    /// use aprendizagem_knn::MatrizConfusao;
+    ///
+    /// let reais: Vec<String> = ["A", "A", "B"].iter().map(|r| r.to_string()).collect();
+    /// let previstos: Vec<String> = ["B", "B", "B"].iter().map(|r| r.to_string()).collect();
+    /// let matriz = MatrizConfusao::nova(&reais, &previstos).unwrap();
+    /// assert_eq!(matriz.contagem("A", "B"), 2);
+    /// assert_eq!(matriz.contagem("A", "Z"), 0);
+    /// ```
+    pub fn contagem(&self, real: &str, previsto: &str) -> usize {
+        match (self.posicao(real), self.posicao(previsto)) {
+            (Some(i), Some(j)) => self.contagens[i][j],
+            _ => 0,
+        }
+    }
+
+    /// Quantos pontos pertencem de fato à classe (soma da linha).
+    ///
+    /// ```
+    /// use aprendizagem_knn::MatrizConfusao;
+    ///
+    /// let reais: Vec<String> = ["A", "A", "B"].iter().map(|r| r.to_string()).collect();
+    /// let previstos: Vec<String> = ["A", "B", "B"].iter().map(|r| r.to_string()).collect();
+    /// assert_eq!(MatrizConfusao::nova(&reais, &previstos).unwrap().total_real("A"), 2);
+    /// ```
+    pub fn total_real(&self, rotulo: &str) -> usize {
+        self.posicao(rotulo).map_or(0, |i| self.contagens[i].iter().sum())
+    }
+
+    /// Quantos pontos foram previstos como a classe (soma da coluna).
+    ///
+    /// ```
+    /// use aprendizagem_knn::MatrizConfusao;
+    ///
+    /// let reais: Vec<String> = ["A", "A", "B"].iter().map(|r| r.to_string()).collect();
+    /// let previstos: Vec<String> = ["A", "B", "B"].iter().map(|r| r.to_string()).collect();
+    /// assert_eq!(MatrizConfusao::nova(&reais, &previstos).unwrap().total_previsto("B"), 2);
+    /// ```
+    pub fn total_previsto(&self, rotulo: &str) -> usize {
+        self.posicao(rotulo).map_or(0, |j| self.contagens.iter().map(|linha| linha[j]).sum())
+    }
+
+    /// Número total de pontos na matriz.
+    ///
+    /// ```
+    /// use aprendizagem_knn::MatrizConfusao;
+    ///
+    /// let rotulos: Vec<String> = ["A", "B", "B"].iter().map(|r| r.to_string()).collect();
+    /// assert_eq!(MatrizConfusao::nova(&rotulos, &rotulos).unwrap().total(), 3);
+    /// ```
+    pub fn total(&self) -> usize {
+        self.contagens.iter().flatten().sum()
+    }
+
+    /// Fração de acertos: a soma da diagonal dividida pelo total (0 para a matriz vazia).
+    ///
+    /// ```
+    /// use aprendizagem_knn::MatrizConfusao;
+    ///
+    /// let reais: Vec<String> = ["A", "A", "B", "B"].iter().map(|r| r.to_string()).collect();
+    /// let previstos: Vec<String> = ["A", "B", "B", "B"].iter().map(|r| r.to_string()).collect();
+    /// assert_eq!(MatrizConfusao::nova(&reais, &previstos).unwrap().acuracia(), 0.75);
+    /// ```
+    pub fn acuracia(&self) -> f64 {
+        let total = self.total();
+        if total == 0 {
+            return 0.0;
+        }
+        let acertos: usize = (0..self.rotulos.len()).map(|i| self.contagens[i][i]).sum();
+        acertos as f64 / total as f64
+    }
+
+    /// Grava a matriz em CSV: um cabeçalho com as classes previstas e uma linha por classe real.
+    ///
+    /// ```
+    /// use aprendizagem_knn::MatrizConfusao;
+    ///
+    /// let reais: Vec<String> = ["A", "B"].iter().map(|r| r.to_string()).collect();
+    /// let previstos: Vec<String> = ["A", "A"].iter().map(|r| r.to_string()).collect();
+    /// let mut saida = Vec::new();
+    /// MatrizConfusao::nova(&reais, &previstos).unwrap().escrever_csv(&mut saida).unwrap();
+    /// assert_eq!(String::from_utf8(saida).unwrap(), "real \\ previsto,A,B\nA,1,0\nB,1,0\n");
+    /// ```
+    pub fn escrever_csv<W: Write>(&self, destino: W) -> Result<(), Box<dyn Error>> {
+        let mut escritor = Writer::from_writer(destino);
+        escritor.write_record(std::iter::once(CANTO_DA_TABELA).chain(self.rotulos.iter().map(String::as_str)))?;
+        for (rotulo, linha) in self.rotulos.iter().zip(&self.contagens) {
+            escritor.write_record(std::iter::once(rotulo.clone()).chain(linha.iter().map(usize::to_string)))?;
+        }
+        escritor.flush()?;
+        Ok(())
+    }
+
+    /// Salva a matriz em um arquivo CSV (veja [`MatrizConfusao::escrever_csv`]).
+    ///
+    /// ```
+    /// use aprendizagem_knn::MatrizConfusao;
+    ///
+    /// let caminho = std::env::temp_dir().join("aprendizagem_knn_doc_confusao.csv");
+    /// let rotulos: Vec<String> = ["A", "B"].iter().map(|r| r.to_string()).collect();
+    /// MatrizConfusao::nova(&rotulos, &rotulos).unwrap().salvar_csv(caminho.to_str().unwrap()).unwrap();
+    /// assert!(std::fs::read_to_string(&caminho).unwrap().starts_with("real \\ previsto,A,B"));
+    /// ```
+    pub fn salvar_csv(&self, caminho_arquivo: &str) -> Result<(), Box<dyn Error>> {
+        self.escrever_csv(std::fs::File::create(caminho_arquivo)?)
+    }
+}
+
+// Texto do canto superior esquerdo, que explica o que são as linhas e as colunas
+const CANTO_DA_TABELA: &str = "real \\ previsto";
+
+// Display formata a matriz como uma tabela alinhada para o terminal: a primeira coluna
+// tem a largura do maior nome de classe e as demais a do maior entre o nome e os números
+impl fmt::Display for MatrizConfusao {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let largura_primeira = self.rotulos.iter()
+            .map(|rotulo| rotulo.chars().count())
+            .chain(std::iter::once(CANTO_DA_TABELA.chars().count()))
+            .max()
+            .unwrap_or(0);
+        let larguras: Vec<usize> = (0..self.rotulos.len())
+            .map(|j| {
+                let maior_numero = self.contagens.iter().map(|linha| linha[j].to_string().len()).max().unwrap_or(1);
+                self.rotulos[j].chars().count().max(maior_numero)
+            })
+            .collect();
+
+        write!(f, "{:<largura$}", CANTO_DA_TABELA, largura = largura_primeira)?;
+        for (rotulo, largura) in self.rotulos.iter().zip(&larguras) {
+            write!(f, "  {:>largura$}", rotulo, largura = largura)?;
+        }
+        for (rotulo, linha) in self.rotulos.iter().zip(&self.contagens) {
+            write!(f, "\n{:<largura$}", rotulo, largura = largura_primeira)?;
+            for (contagem, largura) in linha.iter().zip(&larguras) {
+                write!(f, "  {:>largura$}", contagem, largura = largura)?;
+            }
+        }
+        Ok(())
+    }
+}
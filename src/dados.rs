@@ -0,0 +1,1298 @@
+//! Leitura dos conjuntos de dados a partir de arquivos CSV.
+
+use csv::{ByteRecord, Reader, ReaderBuilder, WriterBuilder}; // Biblioteca externa para manipulação de arquivos CSV
+use flate2::bufread::GzDecoder;    // Descompressão de arquivos gzip enquanto são lidos
+use memmap2::Mmap;                 // Mapeamento de arquivos na memória (mmap)
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+
+use crate::codificacao::CodificadorOneHot;
+use crate::erro::ErroKnn;
+use crate::ponto::{Ponto, PontoGenerico};
+
+// ==================== FUNÇÕES DE ENTRADA/SAÍDA ====================
+// Result é um tipo que representa sucesso (Ok) ou erro (Err)
+// ErroKnn diz qual foi o problema, para que quem chama possa tratar cada caso com match
+/// Carrega um CSV com cabeçalho em que a última coluna é o rótulo e todas as anteriores
+/// são características numéricas (quantas forem).
+///
+/// Se o caminho terminar em `.gz`, ou o arquivo começar com a assinatura do gzip (os bytes
+/// `1f 8b`), ele é descomprimido enquanto é lido, sem precisar de um passo separado; isso
+/// vale para todas as funções que leem CSV de um arquivo, de treinamento ou de teste.
+///
+/// Os erros são [`ErroKnn`], e a mensagem sempre cita o arquivo:
+/// - um arquivo que não existe é [`ErroKnn::ArquivoNaoEncontrado`];
+/// - uma linha com número de colunas diferente do cabeçalho, com um valor que não é um
+///   número finito (texto, `NaN`, `inf`) ou com o rótulo vazio é [`ErroKnn::LinhaInvalida`],
+///   com o número da linha (contando o cabeçalho como a linha 1), a coluna e o texto lido;
+/// - um arquivo sem nenhuma linha de dados é [`ErroKnn::ConjuntoVazio`];
+/// - os outros problemas (sem permissão de leitura, cabeçalho sem colunas, gzip
+///   corrompido ou truncado) são [`ErroKnn::Arquivo`].
+///
+/// A leitura para no primeiro problema; para receber todos de uma vez, use
+/// [`OpcoesCsv::coletar_erros`]. Para um rótulo em outra coluna, veja
+/// [`carregar_dados_do_csv_com_rotulo`]; para valores ausentes, que viram `NaN` de
+/// propósito, [`carregar_dados_do_csv_com_ausentes`].
+///
+/// ```
+/// use aprendizagem_knn::{carregar_dados_do_csv, ErroKnn};
+///
+/// let dados = carregar_dados_do_csv("src/dados.csv").unwrap();
+/// assert_eq!(dados.len(), 215);
+/// assert_eq!(dados[0].caracteristicas.len(), 2);
+///
+/// // Uma única característica
+/// let caminho = std::env::temp_dir().join("aprendizagem_knn_doc_1_coluna.csv");
+/// std::fs::write(&caminho, "x,classe\n1.5,A\n-2,B\n").unwrap();
+/// let dados = carregar_dados_do_csv(caminho.to_str().unwrap()).unwrap();
+/// assert_eq!(dados[1].caracteristicas, vec![-2.0]);
+/// assert_eq!(dados[1].rotulo, "B");
+///
+/// // Quatro características, como no conjunto Iris
+/// let caminho = std::env::temp_dir().join("aprendizagem_knn_doc_4_colunas.csv");
+/// std::fs::write(&caminho, "sepala_c,sepala_l,petala_c,petala_l,especie\n5.1,3.5,1.4,0.2,setosa\n").unwrap();
+/// let dados = carregar_dados_do_csv(caminho.to_str().unwrap()).unwrap();
+/// assert_eq!(dados[0].caracteristicas, vec![5.1, 3.5, 1.4, 0.2]);
+/// assert_eq!(dados[0].rotulo, "setosa");
+///
+/// // Trinta características
+/// let cabecalho: Vec<String> = (0..30).map(|i| format!("c{}", i)).chain(["classe".to_string()]).collect();
+/// let linha: Vec<String> = (0..30).map(|i| i.to_string()).chain(["X".to_string()]).collect();
+/// let caminho = std::env::temp_dir().join("aprendizagem_knn_doc_30_colunas.csv");
+/// std::fs::write(&caminho, format!("{}\n{}\n", cabecalho.join(","), linha.join(","))).unwrap();
+/// let dados = carregar_dados_do_csv(caminho.to_str().unwrap()).unwrap();
+/// assert_eq!(dados[0].caracteristicas.len(), 30);
+/// assert_eq!(dados[0].caracteristicas[29], 29.0);
+///
+/// // A linha 3 tem uma coluna a menos
+/// let caminho = std::env::temp_dir().join("aprendizagem_knn_doc_irregular.csv");
+/// std::fs::write(&caminho, "x,y,classe\n1,2,A\n3,B\n").unwrap();
+/// let erro = carregar_dados_do_csv(caminho.to_str().unwrap()).unwrap_err();
+/// assert!(matches!(erro, ErroKnn::LinhaInvalida { linha: 3, .. }), "{:?}", erro);
+/// assert!(erro.to_string().ends_with("linha 3: tem 2 colunas, mas o cabeçalho tem 3: [\"3\", \"B\"]"), "{}", erro);
+///
+/// // Um NaN escrito no arquivo não passa: depois ele quebraria a comparação das distâncias
+/// std::fs::write(&caminho, "x,y,classe\n1,2,A\n3,NaN,B\n").unwrap();
+/// let erro = carregar_dados_do_csv(caminho.to_str().unwrap()).unwrap_err();
+/// assert!(erro.to_string().contains("linha 3: coluna 2 ('y'): valor não finito 'NaN'"), "{}", erro);
+/// ```
+pub fn carregar_dados_do_csv(caminho_arquivo: &str) -> Result<Vec<Ponto>, ErroKnn> {
+    carregar_dados_de_leitor(abrir_arquivo(caminho_arquivo)?, caminho_arquivo)
+}
+
+/// Como [`carregar_dados_do_csv`], mas lendo o CSV de qualquer fonte de bytes ([`Read`]):
+/// a entrada padrão (`std::io::stdin()`), uma conexão de rede, um `Cursor` com os dados em
+/// memória... `origem` identifica a fonte nas mensagens de erro (`"<stdin>"`, por exemplo).
+///
+/// O leitor é consumido aos poucos, sem precisar caber inteiro na memória. Uma entrada
+/// sem nenhuma linha de dados (vazia, como um Ctrl-D logo no começo, ou só com o
+/// cabeçalho) é [`ErroKnn::ConjuntoVazio`], em vez de um conjunto vazio que só daria
+/// problema depois, no knn.
+///
+/// ```
+/// use std::io::Cursor;
+///
+/// use aprendizagem_knn::{carregar_dados_de_leitor, ErroKnn};
+///
+/// let dados = carregar_dados_de_leitor(Cursor::new("x,y,classe\n1,2,A\n3,4,B\n"), "<memória>").unwrap();
+/// assert_eq!(dados[1].caracteristicas, vec![3.0, 4.0]);
+/// assert_eq!(dados[1].rotulo, "B");
+///
+/// let erro = carregar_dados_de_leitor(Cursor::new(""), "<memória>").unwrap_err();
+/// assert_eq!(erro, ErroKnn::ConjuntoVazio);
+/// let erro = carregar_dados_de_leitor(Cursor::new("x,classe\n"), "<memória>").unwrap_err();
+/// assert_eq!(erro, ErroKnn::ConjuntoVazio);
+///
+/// let erro = carregar_dados_de_leitor(Cursor::new("x,classe\n1,A\nabc,B\n"), "<memória>").unwrap_err();
+/// assert_eq!(
+///     erro,
+///     ErroKnn::LinhaInvalida {
+///         origem: "<memória>".to_string(),
+///         linha: 3,
+///         motivo: "coluna 1 ('x'): valor numérico inválido 'abc'".to_string(),
+///     }
+/// );
+/// ```
+pub fn carregar_dados_de_leitor<R: Read>(leitor: R, origem: &str) -> Result<Vec<Ponto>, ErroKnn> {
+    let (_, pontos) = carregar_dados_de_leitor_com_opcoes(leitor, origem, &OpcoesCsv::default())?;
+    Ok(pontos) // Retorna sucesso com os pontos
+}
+
+/// Como [`carregar_dados_do_csv`], mas com o rótulo na coluna `coluna_rotulo` (contando
+/// a partir de 0); todas as outras colunas, na ordem do arquivo, são as características.
+///
+/// É erro ([`ErroKnn::ParametroInvalido`]) se `coluna_rotulo` não existir no cabeçalho; os
+/// outros erros são os de [`carregar_dados_do_csv`].
+///
+/// ```
+/// use aprendizagem_knn::{carregar_dados_do_csv_com_rotulo, ErroKnn};
+///
+/// let caminho = std::env::temp_dir().join("aprendizagem_knn_doc_rotulo_primeiro.csv");
+/// std::fs::write(&caminho, "classe,x,y\nA,1,2\nB,3,4\n").unwrap();
+///
+/// let dados = carregar_dados_do_csv_com_rotulo(caminho.to_str().unwrap(), 0).unwrap();
+/// assert_eq!(dados[1].caracteristicas, vec![3.0, 4.0]);
+/// assert_eq!(dados[1].rotulo, "B");
+///
+/// let erro = carregar_dados_do_csv_com_rotulo(caminho.to_str().unwrap(), 3).unwrap_err();
+/// assert!(matches!(erro, ErroKnn::ParametroInvalido(_)));
+/// ```
+pub fn carregar_dados_do_csv_com_rotulo(
+    caminho_arquivo: &str,
+    coluna_rotulo: usize,
+) -> Result<Vec<Ponto>, ErroKnn> {
+    let mut registros = ler_registros_csv(caminho_arquivo)?;
+    exigir_linhas(&registros.linhas)?;
+    mover_rotulo_para_o_fim(&mut registros, caminho_arquivo, coluna_rotulo)?;
+    converter_registros(&registros, caminho_arquivo, false)
+}
+
+/// Como [`carregar_dados_do_csv`], mas convertendo cada rótulo com `map_rotulo` enquanto
+/// o arquivo é lido, para obter pontos com rótulos de outro tipo (números, um `enum`
+/// próprio...; veja [`PontoGenerico`]).
+///
+/// `map_rotulo` recebe o texto do rótulo e devolve o novo rótulo, ou uma mensagem se o
+/// texto não for válido; a mensagem vira [`ErroKnn::LinhaInvalida`], com o número da linha
+/// e o rótulo lido. Os outros erros são os de [`carregar_dados_do_csv`].
+///
+/// ```
+/// use aprendizagem_knn::{carregar_dados_do_csv_mapeando_rotulos, ErroKnn};
+///
+/// let caminho = std::env::temp_dir().join("aprendizagem_knn_doc_rotulos_numericos.csv");
+/// let caminho = caminho.to_str().unwrap();
+/// std::fs::write(caminho, "x,classe\n1.5,0\n2.5,2\n").unwrap();
+///
+/// let numero = |rotulo: &str| rotulo.parse::<u32>().map_err(|erro| erro.to_string());
+/// let dados = carregar_dados_do_csv_mapeando_rotulos(caminho, numero).unwrap();
+/// assert_eq!(dados[1].rotulo, 2u32);
+///
+/// std::fs::write(caminho, "x,classe\n1.5,0\n2.5,dois\n").unwrap();
+/// let erro = carregar_dados_do_csv_mapeando_rotulos(caminho, numero).unwrap_err();
+/// assert!(matches!(erro, ErroKnn::LinhaInvalida { linha: 3, .. }), "{:?}", erro);
+/// assert!(erro.to_string().contains("rótulo 'dois'"), "{}", erro);
+/// ```
+pub fn carregar_dados_do_csv_mapeando_rotulos<S, F>(
+    caminho_arquivo: &str,
+    mut map_rotulo: F,
+) -> Result<Vec<PontoGenerico<S>>, ErroKnn>
+where
+    F: FnMut(&str) -> Result<S, String>,
+{
+    let registros = ler_registros_csv(caminho_arquivo)?;
+    exigir_linhas(&registros.linhas)?;
+    let pontos = converter_registros(&registros, caminho_arquivo, false)?;
+    // converter_registros gera um ponto por linha, na ordem, então os números das linhas
+    // vêm dos registros
+    pontos.into_iter()
+        .zip(&registros.linhas)
+        .map(|(ponto, (linha, _))| {
+            let rotulo = map_rotulo(&ponto.rotulo).map_err(|motivo| {
+                linha_invalida(caminho_arquivo, *linha, format!("rótulo '{}': {}", ponto.rotulo, motivo))
+            })?;
+            Ok(PontoGenerico::novo(ponto.caracteristicas, rotulo))
+        })
+        .collect()
+}
+
+// Leva a coluna do rótulo para a última posição (no cabeçalho e em cada linha), para que
+// o resto da leitura possa sempre tratar a última coluna como o rótulo
+fn mover_rotulo_para_o_fim(
+    registros: &mut RegistrosCsv,
+    caminho_arquivo: &str,
+    coluna_rotulo: usize,
+) -> Result<(), ErroKnn> {
+    if coluna_rotulo >= registros.cabecalho.len() {
+        return Err(ErroKnn::ParametroInvalido(format!(
+            "{}: a coluna do rótulo é {}, mas o cabeçalho só tem as colunas de 0 a {}",
+            caminho_arquivo,
+            coluna_rotulo,
+            registros.cabecalho.len() - 1
+        )));
+    }
+    let rotulo = registros.cabecalho.remove(coluna_rotulo);
+    registros.cabecalho.push(rotulo);
+    for (_, campos) in &mut registros.linhas {
+        let rotulo = campos.remove(coluna_rotulo);
+        campos.push(rotulo);
+    }
+    Ok(())
+}
+
+/// Carrega um CSV comprimido com gzip (`.csv.gz`), com ou sem cabeçalho, no formato de
+/// [`carregar_dados_do_csv`]: a última coluna é o rótulo e as anteriores as características.
+///
+/// A descompressão acontece aos poucos, enquanto as linhas são lidas. Um arquivo sem `.gz`
+/// no final e sem a assinatura do gzip é lido como um CSV comum, então a função serve para
+/// os dois casos. Sem cabeçalho, a primeira linha já é um ponto. Os erros são os de
+/// [`carregar_dados_do_csv`]: um arquivo sem nenhuma linha de dados, por exemplo, é
+/// [`ErroKnn::ConjuntoVazio`].
+///
+/// ```
+/// use std::io::Write;
+/// use aprendizagem_knn::carregar_dados_do_csv_gz;
+/// use flate2::{write::GzEncoder, Compression};
+///
+/// let caminho = std::env::temp_dir().join("aprendizagem_knn_doc_dados.csv.gz");
+/// let mut compressor = GzEncoder::new(std::fs::File::create(&caminho).unwrap(), Compression::default());
+/// compressor.write_all(b"1.0,2.0,A\n3.0,4.0,B\n").unwrap();
+/// compressor.finish().unwrap();
+///
+/// let dados = carregar_dados_do_csv_gz(caminho.to_str().unwrap(), false).unwrap();
+/// assert_eq!(dados.len(), 2);
+/// assert_eq!(dados[1].caracteristicas, vec![3.0, 4.0]);
+/// assert_eq!(dados[1].rotulo, "B");
+/// ```
+pub fn carregar_dados_do_csv_gz(caminho: &str, tem_cabecalho: bool) -> Result<Vec<Ponto>, ErroKnn> {
+    let leitor = ReaderBuilder::new()
+        .flexible(true)
+        .has_headers(tem_cabecalho)
+        .from_reader(abrir_arquivo(caminho)?);
+    let registros = ler_registros(leitor, caminho, true)?;
+    exigir_linhas(&registros.linhas)?;
+    converter_registros(&registros, caminho, false)
+}
+
+/// Carrega um CSV grande mapeando o arquivo na memória (mmap), no formato de
+/// [`carregar_dados_do_csv`]: a última coluna é o rótulo e as anteriores as características.
+///
+/// Em vez de copiar o arquivo para a memória do programa, o sistema operacional expõe o
+/// conteúdo do arquivo como uma fatia de bytes, e cada linha é convertida direto dela em
+/// um [`Ponto`], sem guardar o texto das linhas. Assim só os pontos ocupam memória, o que
+/// permite carregar arquivos maiores que a memória disponível para o texto. Para quem
+/// chama, o resultado é o mesmo de [`carregar_dados_do_csv`], com os mesmos erros (uma
+/// linha inválida é [`ErroKnn::LinhaInvalida`], com o número da linha, e um arquivo sem
+/// nenhuma linha de dados é [`ErroKnn::ConjuntoVazio`]).
+///
+/// Arquivos gzip (pela extensão `.gz` ou pela assinatura) não podem ser mapeados, porque os
+/// bytes estão comprimidos, e são recusados; use [`carregar_dados_do_csv`] para eles. Sem
+/// cabeçalho, a primeira linha já é um ponto.
+///
+/// ```
+/// use aprendizagem_knn::{carregar_dados_do_csv, carregar_dados_mmap};
+///
+/// let dados = carregar_dados_mmap("src/dados.csv", true).unwrap();
+/// assert_eq!(dados, carregar_dados_do_csv("src/dados.csv").unwrap());
+///
+/// let caminho = std::env::temp_dir().join("aprendizagem_knn_doc_mmap.csv");
+/// std::fs::write(&caminho, "1.0,2.0,A\n3.0,x,B\n").unwrap();
+/// let erro = carregar_dados_mmap(caminho.to_str().unwrap(), false).unwrap_err().to_string();
+/// assert!(erro.contains("linha 2: coluna 2 ('coluna 2')"), "{}", erro);
+/// ```
+pub fn carregar_dados_mmap(caminho: &str, tem_cabecalho: bool) -> Result<Vec<Ponto>, ErroKnn> {
+    let erro_arquivo = |mensagem: String| ErroKnn::Arquivo(format!("{}: {}", caminho, mensagem));
+    if caminho.ends_with(".gz") {
+        return Err(erro_arquivo("arquivos .gz não podem ser mapeados na memória".to_string()));
+    }
+    let arquivo = File::open(caminho).map_err(|erro| ErroKnn::de_io(caminho, &erro))?;
+
+    // O mapeamento é só de leitura (Mmap, não MmapMut): escrever na fatia não compila, e o
+    // arquivo nunca é alterado por aqui. As páginas do arquivo só são lidas do disco quando
+    // acessadas, e o cache de páginas do sistema operacional decide quanto fica na memória
+    // (páginas já lidas podem ser descartadas e relidas se a memória ficar escassa).
+    // SAFETY: o mapeamento vale enquanto `mapa` existir; o único risco é outro processo
+    // alterar ou truncar o arquivo durante a leitura, o que mudaria os bytes lidos (ou
+    // encerraria o programa, se truncado). Um conjunto de dados sendo carregado não deve
+    // estar sendo escrito ao mesmo tempo.
+    let mapa = unsafe { Mmap::map(&arquivo) }.map_err(|erro| ErroKnn::de_io(caminho, &erro))?;
+    if mapa.starts_with(&ASSINATURA_GZIP) {
+        return Err(erro_arquivo("arquivos gzip não podem ser mapeados na memória".to_string()));
+    }
+
+    let erro_csv = |erro: csv::Error| erro_do_csv(erro, caminho);
+    let mut leitor = ReaderBuilder::new().flexible(true).has_headers(tem_cabecalho).from_reader(&mapa[..]);
+    let cabecalho: Vec<String> = if tem_cabecalho {
+        leitor.headers().map_err(erro_csv)?.iter().map(|coluna| coluna.trim().to_string()).collect()
+    } else {
+        (1..=leitor.byte_headers().map_err(erro_csv)?.len()).map(|numero| format!("coluna {}", numero)).collect()
+    };
+    exigir_linhas(&cabecalho)?;
+    if cabecalho.len() < 2 {
+        return Err(erro_arquivo("o cabeçalho precisa de ao menos uma característica e o rótulo".to_string()));
+    }
+    let dimensao = cabecalho.len() - 1;
+
+    // Um único ByteRecord é reaproveitado em todas as linhas: os campos continuam sendo
+    // bytes do mapeamento até virarem números
+    let mut pontos = Vec::new();
+    let mut registro = ByteRecord::new();
+    while leitor.read_byte_record(&mut registro).map_err(erro_csv)? {
+        let linha = registro.position().map_or(0, |posicao| posicao.line() as usize);
+        if registro.len() != cabecalho.len() {
+            let campos: Vec<_> = registro.iter().map(String::from_utf8_lossy).collect();
+            return Err(linha_invalida(caminho, linha, motivo_colunas(&campos, cabecalho.len())));
+        }
+        let texto = |coluna: usize| String::from_utf8_lossy(&registro[coluna]);
+
+        let mut caracteristicas = Vec::with_capacity(dimensao);
+        for coluna in 0..dimensao {
+            let valor = std::str::from_utf8(&registro[coluna]).ok().and_then(interpretar_valor).ok_or_else(|| {
+                linha_invalida(
+                    caminho,
+                    linha,
+                    format!(
+                        "coluna {} ('{}'): {}",
+                        coluna + 1, cabecalho[coluna], motivo_valor_invalido(texto(coluna).trim())
+                    ),
+                )
+            })?;
+            caracteristicas.push(valor);
+        }
+        let rotulo = texto(dimensao).trim().to_string();
+        if rotulo.is_empty() {
+            return Err(linha_invalida(caminho, linha, motivo_rotulo_vazio(dimensao, &cabecalho)));
+        }
+        pontos.push(Ponto::novo(caracteristicas, rotulo));
+    }
+    exigir_linhas(&pontos)?;
+    Ok(pontos)
+}
+
+// Células vazias, "NA", "NaN" e "?" representam valores ausentes
+pub(crate) fn eh_valor_ausente(campo: &str) -> bool {
+    let campo = campo.trim();
+    campo.is_empty() || campo.eq_ignore_ascii_case("NA") || campo.eq_ignore_ascii_case("NaN") || campo == "?"
+}
+
+// Converte o texto de uma célula em número (None se não for um número finito: "NaN" e
+// "inf" são aceitos pelo parse do Rust, mas um NaN nas características quebraria a
+// ordenação das distâncias lá na frente)
+pub(crate) fn interpretar_valor(campo: &str) -> Option<f64> {
+    campo.trim().parse::<f64>().ok().filter(|valor| valor.is_finite())
+}
+
+// Explica por que o texto de uma célula não virou característica, citando o texto lido
+pub(crate) fn motivo_valor_invalido(campo: &str) -> String {
+    if campo.trim().parse::<f64>().is_ok() {
+        format!("valor não finito '{}' (NaN e infinito não são aceitos)", campo)
+    } else {
+        format!("valor numérico inválido '{}'", campo)
+    }
+}
+
+// Motivo de erro para um rótulo vazio na coluna `coluna` (contando a partir de 0)
+fn motivo_rotulo_vazio(coluna: usize, cabecalho: &[String]) -> String {
+    format!("coluna {} ('{}'): o rótulo está vazio", coluna + 1, cabecalho[coluna])
+}
+
+// Guarda os erros das linhas durante a leitura. Parando no primeiro (o padrão), registrar
+// devolve o erro na hora, e o ? o propaga; coletando, ele é guardado, a linha é pulada e
+// a leitura continua até o fim, quando concluir devolve todos juntos
+#[derive(Default)]
+struct ColetorErros {
+    coletar: bool,
+    erros: Vec<ErroKnn>,
+}
+
+impl ColetorErros {
+    fn registrar(&mut self, erro: ErroKnn) -> Result<(), ErroKnn> {
+        if self.coletar {
+            self.erros.push(erro);
+            Ok(())
+        } else {
+            Err(erro)
+        }
+    }
+
+    // As linhas com colunas a mais ou a menos são vistas antes das outras (na leitura dos
+    // registros); a ordenação estável devolve tudo na ordem do arquivo
+    fn concluir(mut self) -> Result<(), ErroKnn> {
+        if self.erros.is_empty() {
+            return Ok(());
+        }
+        self.erros.sort_by_key(|erro| match erro {
+            ErroKnn::LinhaInvalida { linha, .. } => *linha,
+            _ => 0,
+        });
+        Err(ErroKnn::VariosErros(self.erros))
+    }
+}
+
+// Conteúdo bruto (texto) de um CSV com cabeçalho. Cada linha guarda o seu número
+// no arquivo, para que os erros possam apontar exatamente onde está o problema.
+pub(crate) struct RegistrosCsv {
+    pub(crate) cabecalho: Vec<String>,
+    pub(crate) linhas: Vec<(usize, Vec<String>)>,
+}
+
+// Erro de uma linha de `origem`, com o número dela
+fn linha_invalida(origem: &str, linha: usize, motivo: String) -> ErroKnn {
+    ErroKnn::LinhaInvalida { origem: origem.to_string(), linha, motivo }
+}
+
+// Lê o cabeçalho e as linhas de um CSV em que a última coluna é o rótulo,
+// conferindo se todas as linhas têm o mesmo número de colunas do cabeçalho
+pub(crate) fn ler_registros_csv(caminho_arquivo: &str) -> Result<RegistrosCsv, ErroKnn> {
+    // flexible(true) deixa linhas com número diferente de colunas passarem,
+    // para que possamos gerar uma mensagem de erro mais clara
+    let leitor = ReaderBuilder::new().flexible(true).from_reader(abrir_arquivo(caminho_arquivo)?);
+    ler_registros(leitor, caminho_arquivo, true)
+}
+
+// Os dois primeiros bytes de todo arquivo gzip
+const ASSINATURA_GZIP: [u8; 2] = [0x1f, 0x8b];
+
+// Abre o arquivo para leitura; se o nome terminar em ".gz" ou o arquivo começar com a
+// assinatura do gzip, os bytes passam por um descompressor gzip, que descomprime aos
+// poucos, conforme o leitor de CSV pede mais dados (o arquivo descomprimido nunca fica
+// inteiro na memória). Box<dyn Read> deixa as duas fontes terem o mesmo tipo
+fn abrir_arquivo(caminho_arquivo: &str) -> Result<Box<dyn Read>, ErroKnn> {
+    let erro_arquivo = |erro: io::Error| ErroKnn::de_io(caminho_arquivo, &erro);
+    let arquivo = File::open(caminho_arquivo).map_err(erro_arquivo)?;
+    let leitor = descomprimir_se_gzip(BufReader::new(arquivo), caminho_arquivo, caminho_arquivo.ends_with(".gz"))
+        .map_err(erro_arquivo)?;
+    Ok(leitor)
+}
+
+// Passa os bytes por um descompressor gzip se `gzip` for verdadeiro (pelo nome da origem)
+// ou se eles começarem com a assinatura do gzip; serve para arquivos e downloads
+pub(crate) fn descomprimir_se_gzip<R: BufRead + 'static>(
+    mut leitor: R,
+    origem: &str,
+    gzip: bool,
+) -> io::Result<Box<dyn Read>> {
+    // fill_buf espia o começo dos dados sem consumir nada
+    if gzip || leitor.fill_buf()?.starts_with(&ASSINATURA_GZIP) {
+        Ok(Box::new(LeitorGzip { descompressor: GzDecoder::new(leitor), caminho: origem.to_string() }))
+    } else {
+        Ok(Box::new(leitor))
+    }
+}
+
+// Descompressor que acrescenta a origem (caminho ou URL) aos erros: um gzip corrompido ou
+// truncado aparece como erro de E/S do arquivo, e não como uma linha de CSV mal formada
+struct LeitorGzip<R> {
+    descompressor: GzDecoder<R>,
+    caminho: String,
+}
+
+impl<R: BufRead> Read for LeitorGzip<R> {
+    fn read(&mut self, destino: &mut [u8]) -> io::Result<usize> {
+        self.descompressor.read(destino).map_err(|erro| {
+            io::Error::new(erro.kind(), format!("{}: gzip inválido ou corrompido: {}", self.caminho, erro))
+        })
+    }
+}
+
+// O leitor e o escritor de CSV embrulham os erros de E/S num csv::Error; aqui eles voltam
+// a ser o io::Error original (o do LeitorGzip já cita o arquivo) antes de virar um ErroKnn
+fn erro_do_csv(erro: csv::Error, origem: &str) -> ErroKnn {
+    if erro.is_io_error() {
+        ErroKnn::de_io(origem, &io::Error::from(erro))
+    } else {
+        ErroKnn::Arquivo(format!("{}: {}", origem, erro))
+    }
+}
+
+// Nome usado no lugar do caminho do arquivo quando o CSV vem de um texto
+pub(crate) const ORIGEM_TEXTO: &str = "<texto>";
+
+// Mesmo que ler_registros_csv, mas com o conteúdo do CSV já em memória (por exemplo,
+// um arquivo enviado pelo navegador). As mensagens de erro citam a origem "<texto>".
+pub(crate) fn ler_registros_de_texto(conteudo_csv: &str) -> Result<RegistrosCsv, ErroKnn> {
+    let leitor = ReaderBuilder::new().flexible(true).from_reader(conteudo_csv.as_bytes());
+    ler_registros(leitor, ORIGEM_TEXTO, true)
+}
+
+// Leitura comum a arquivos e textos. `origem` identifica de onde veio o CSV nas mensagens de erro.
+// Read é o trait de qualquer fonte de bytes (arquivo, fatia de memória, stdin...)
+// Sem `com_rotulo`, todas as colunas são características (um CSV de pontos a classificar).
+// Se o leitor foi criado sem cabeçalho, as colunas ganham os nomes "coluna 1", "coluna 2"...
+fn ler_registros<R: Read>(leitor: Reader<R>, caminho_arquivo: &str, com_rotulo: bool) -> Result<RegistrosCsv, ErroKnn> {
+    ler_registros_com(leitor, caminho_arquivo, com_rotulo, &mut ColetorErros::default())
+}
+
+// Motivo de erro de uma linha com número de colunas diferente do cabeçalho. Os campos lidos
+// aparecem como lista (["3.0", "B"]), o que mostra onde cada um termina seja qual for o delimitador
+fn motivo_colunas<T: AsRef<str>>(campos: &[T], colunas_cabecalho: usize) -> String {
+    let texto: Vec<&str> = campos.iter().map(AsRef::as_ref).collect();
+    format!("tem {} colunas, mas o cabeçalho tem {}: {:?}", campos.len(), colunas_cabecalho, texto)
+}
+
+// Como ler_registros, mas as linhas com o número errado de colunas vão para o `coletor`
+// (que pode parar a leitura ou só guardar o erro e pular a linha)
+fn ler_registros_com<R: Read>(
+    mut leitor: Reader<R>,
+    caminho_arquivo: &str,
+    com_rotulo: bool,
+    coletor: &mut ColetorErros,
+) -> Result<RegistrosCsv, ErroKnn> {
+    let erro_csv = |erro: csv::Error| erro_do_csv(erro, caminho_arquivo);
+    let cabecalho: Vec<String> = if leitor.has_headers() {
+        leitor.headers().map_err(erro_csv)?.iter().map(|coluna| coluna.trim().to_string()).collect()
+    } else {
+        // Sem cabeçalho, headers() só espia a primeira linha, que continua sendo um registro
+        (1..=leitor.headers().map_err(erro_csv)?.len()).map(|numero| format!("coluna {}", numero)).collect()
+    };
+
+    // Sem nem o cabeçalho, o arquivo está vazio
+    exigir_linhas(&cabecalho)?;
+    if com_rotulo && cabecalho.len() < 2 {
+        return Err(ErroKnn::Arquivo(format!(
+            "{}: o cabeçalho precisa de ao menos uma característica e o rótulo",
+            caminho_arquivo
+        )));
+    }
+
+    let mut linhas = Vec::new();
+    for resultado in leitor.records() {
+        let registro = resultado.map_err(erro_csv)?;
+        let linha = registro.position().map_or(0, |posicao| posicao.line() as usize);
+
+        if registro.len() != cabecalho.len() {
+            let campos: Vec<&str> = registro.iter().collect();
+            coletor.registrar(linha_invalida(caminho_arquivo, linha, motivo_colunas(&campos, cabecalho.len())))?;
+            continue;
+        }
+
+        linhas.push((linha, registro.iter().map(|campo| campo.trim().to_string()).collect()));
+    }
+
+    Ok(RegistrosCsv { cabecalho, linhas })
+}
+
+// Um arquivo sem nenhuma linha de dados (vazio ou só com o cabeçalho) é ConjuntoVazio em
+// todas as funções de leitura, em vez de um Vec vazio que só daria problema depois, no knn
+fn exigir_linhas<T>(linhas: &[T]) -> Result<(), ErroKnn> {
+    if linhas.is_empty() {
+        return Err(ErroKnn::ConjuntoVazio);
+    }
+    Ok(())
+}
+
+// Converte os campos de características de uma linha em números.
+// Com aceitar_ausentes, valores ausentes viram f64::NAN; qualquer outro texto
+// não numérico é erro, citando arquivo, linha e coluna.
+pub(crate) fn converter_caracteristicas(
+    campos: &[String],
+    cabecalho: &[String],
+    caminho_arquivo: &str,
+    linha: usize,
+    aceitar_ausentes: bool,
+) -> Result<Vec<f64>, ErroKnn> {
+    let mut caracteristicas = Vec::with_capacity(campos.len());
+    for (coluna, campo) in campos.iter().enumerate() {
+        let valor = match interpretar_valor(campo) {
+            Some(valor) => valor,
+            None if aceitar_ausentes && eh_valor_ausente(campo) => f64::NAN,
+            None => return Err(linha_invalida(
+                caminho_arquivo,
+                linha,
+                format!("coluna {} ('{}'): {}", coluna + 1, cabecalho[coluna], motivo_valor_invalido(campo)),
+            )),
+        };
+        caracteristicas.push(valor);
+    }
+    Ok(caracteristicas)
+}
+
+// Lê um CSV com cabeçalho, em que todas as colunas menos a última são características
+// e a última é o rótulo. Devolve os nomes das colunas junto com os pontos.
+pub(crate) fn ler_csv_com_cabecalho(
+    caminho_arquivo: &str,
+    aceitar_ausentes: bool,
+) -> Result<(Vec<String>, Vec<Ponto>), ErroKnn> {
+    let registros = ler_registros_csv(caminho_arquivo)?;
+    let pontos = converter_registros(&registros, caminho_arquivo, aceitar_ausentes)?;
+    Ok((registros.cabecalho, pontos))
+}
+
+// Converte as linhas já lidas em pontos (todas as colunas menos a última são características)
+fn converter_registros(
+    registros: &RegistrosCsv,
+    caminho_arquivo: &str,
+    aceitar_ausentes: bool,
+) -> Result<Vec<Ponto>, ErroKnn> {
+    let dimensao = registros.cabecalho.len() - 1;
+
+    let mut pontos = Vec::with_capacity(registros.linhas.len());
+    for (linha, campos) in &registros.linhas {
+        let caracteristicas = converter_caracteristicas(
+            &campos[..dimensao],
+            &registros.cabecalho,
+            caminho_arquivo,
+            *linha,
+            aceitar_ausentes,
+        )?;
+        if campos[dimensao].is_empty() {
+            return Err(linha_invalida(caminho_arquivo, *linha, motivo_rotulo_vazio(dimensao, &registros.cabecalho)));
+        }
+        pontos.push(Ponto::novo(caracteristicas, campos[dimensao].clone()));
+    }
+
+    Ok(pontos)
+}
+
+/// Carrega um CSV que pode conter valores ausentes (células vazias, "NA", "NaN" ou "?").
+///
+/// Os ausentes ficam como `f64::NAN` e precisam ser tratados por uma
+/// [`PoliticaAusentes`](crate::PoliticaAusentes) antes do KNN, já que NaN não pode
+/// ser comparado como distância.
+///
+/// ```
+/// use aprendizagem_knn::carregar_dados_do_csv_com_ausentes;
+///
+/// let caminho = std::env::temp_dir().join("aprendizagem_knn_doc_ausentes.csv");
+/// std::fs::write(&caminho, "x,y,classe\n1.0,NA,A\n,2.0,B\n").unwrap();
+///
+/// let dados = carregar_dados_do_csv_com_ausentes(caminho.to_str().unwrap()).unwrap();
+/// assert!(dados[0].caracteristicas[1].is_nan());
+/// assert!(dados[1].caracteristicas[0].is_nan());
+/// ```
+pub fn carregar_dados_do_csv_com_ausentes(caminho_arquivo: &str) -> Result<Vec<Ponto>, ErroKnn> {
+    let (_, pontos) = ler_csv_com_cabecalho(caminho_arquivo, true)?;
+    Ok(pontos)
+}
+
+/// Carrega um CSV a partir do seu conteúdo em texto, sem passar por um arquivo
+/// (útil quando o CSV chega pela rede ou pelo navegador).
+///
+/// O formato é o de [`carregar_dados_do_csv_com_ausentes`]: cabeçalho, todas as colunas
+/// menos a última como características e a última como rótulo; ausentes viram `f64::NAN`.
+///
+/// ```
+/// use aprendizagem_knn::carregar_dados_de_texto_csv;
+///
+/// let dados = carregar_dados_de_texto_csv("x,y,z,classe\n1.0,2.0,3.0,A\n4.0,NA,6.0,B\n").unwrap();
+/// assert_eq!(dados[0].caracteristicas, vec![1.0, 2.0, 3.0]);
+/// assert!(dados[1].caracteristicas[1].is_nan());
+/// assert_eq!(dados[1].rotulo, "B");
+/// ```
+pub fn carregar_dados_de_texto_csv(conteudo_csv: &str) -> Result<Vec<Ponto>, ErroKnn> {
+    let registros = ler_registros_de_texto(conteudo_csv)?;
+    converter_registros(&registros, ORIGEM_TEXTO, true)
+}
+
+/// Carrega um CSV com colunas categóricas (texto, como "red"/"green"/"blue"),
+/// expandindo cada uma em colunas 0/1 (one-hot).
+///
+/// Sem `colunas_categoricas`, uma coluna é considerada categórica quando algum valor
+/// não é numérico. Devolve também o codificador, que deve ser usado nos pontos de teste
+/// para que eles recebam exatamente a mesma codificação.
+///
+/// ```
+/// use aprendizagem_knn::carregar_dados_do_csv_categorico;
+///
+/// let caminho = std::env::temp_dir().join("aprendizagem_knn_doc_categorico.csv");
+/// std::fs::write(&caminho, "tamanho,cor,classe\n1.0,red,A\n2.0,blue,B\n").unwrap();
+///
+/// let (dados, codificador) = carregar_dados_do_csv_categorico(caminho.to_str().unwrap(), None).unwrap();
+/// assert_eq!(codificador.nomes_caracteristicas(), vec!["tamanho", "cor=blue", "cor=red"]);
+/// assert_eq!(dados[0].caracteristicas, vec![1.0, 0.0, 1.0]);
+/// ```
+pub fn carregar_dados_do_csv_categorico(
+    caminho_arquivo: &str,
+    colunas_categoricas: Option<&[usize]>,
+) -> Result<(Vec<Ponto>, CodificadorOneHot), ErroKnn> {
+    let registros = ler_registros_csv(caminho_arquivo)?;
+    codificar_registros(&registros, caminho_arquivo, colunas_categoricas)
+}
+
+// Ajusta o codificador one-hot nas linhas já lidas e codifica cada uma delas
+pub(crate) fn codificar_registros(
+    registros: &RegistrosCsv,
+    caminho_arquivo: &str,
+    colunas_categoricas: Option<&[usize]>,
+) -> Result<(Vec<Ponto>, CodificadorOneHot), ErroKnn> {
+    let dimensao = registros.cabecalho.len() - 1;
+
+    let campos: Vec<&[String]> = registros.linhas.iter().map(|(_, campos)| &campos[..dimensao]).collect();
+    let codificador = CodificadorOneHot::ajustar(&registros.cabecalho[..dimensao], &campos, colunas_categoricas);
+
+    let mut pontos = Vec::with_capacity(registros.linhas.len());
+    for (linha, campos) in &registros.linhas {
+        let caracteristicas = codificador.codificar(&campos[..dimensao])
+            .map_err(|motivo| linha_invalida(caminho_arquivo, *linha, motivo))?;
+        pontos.push(Ponto::novo(caracteristicas, campos[dimensao].clone()));
+    }
+
+    Ok((pontos, codificador))
+}
+
+// ==================== OPÇÕES DE LEITURA ====================
+/// Como ler um CSV em [`carregar_dados_do_csv_com_opcoes`]: o separador das colunas, as
+/// aspas, se há cabeçalho e quais colunas são o rótulo e as características, pelo nome.
+///
+/// O padrão ([`OpcoesCsv::default`]) é o formato de [`carregar_dados_do_csv`]: separado
+/// por vírgulas, com cabeçalho, o rótulo na última coluna e todas as outras como
+/// características.
+///
+/// # Exemplo
+///
+/// ```
+/// use aprendizagem_knn::OpcoesCsv;
+///
+/// let opcoes = OpcoesCsv { rotulo: Some("species".to_string()), ..OpcoesCsv::default() };
+/// assert!(opcoes.tem_cabecalho);
+/// assert_eq!(opcoes.delimitador, Some(b','));
+/// assert_eq!(opcoes.caracteristicas, None);
+///
+/// // Arquivo separado por tabulações (TSV)
+/// let tsv = OpcoesCsv { delimitador: Some(b'\t'), ..OpcoesCsv::default() };
+/// assert_eq!(tsv.aspas, b'"');
+///
+/// // Excel em português: ponto e vírgula e vírgula decimal
+/// let excel = OpcoesCsv { delimitador: Some(b';'), virgula_decimal: Some(true), ..OpcoesCsv::default() };
+/// assert_ne!(excel, OpcoesCsv::default());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpcoesCsv {
+    /// Se a primeira linha tem os nomes das colunas. Sem cabeçalho, as colunas se chamam
+    /// "coluna 1", "coluna 2"... e a primeira linha já é um ponto
+    pub tem_cabecalho: bool,
+    /// Caractere que separa as colunas: `b','`, `b';'` (Excel em português), `b'\t'`...
+    /// `None`: escolhido pela primeira linha do arquivo, com [`detectar_delimitador`]
+    pub delimitador: Option<u8>,
+    /// Caractere que envolve um campo com o delimitador no meio (`"a, b"`); dentro do
+    /// campo, a própria aspa aparece dobrada (`"diz ""oi"""`)
+    pub aspas: u8,
+    /// Se os números usam vírgula decimal e ponto de milhar (`1.234,56`), como nos
+    /// arquivos do Excel em português (veja [`interpretar_virgula_decimal`]).
+    /// `None`: só quando o delimitador é `;`, o separador usado nesses arquivos
+    pub virgula_decimal: Option<bool>,
+    /// Nome da coluna do rótulo (`None`: a última coluna)
+    pub rotulo: Option<String>,
+    /// Nomes das colunas de características, na ordem em que devem ficar no ponto.
+    /// `None`: todas as outras colunas; se o rótulo foi escolhido pelo nome, as colunas
+    /// com algum valor não numérico (como um identificador em texto) são ignoradas
+    pub caracteristicas: Option<Vec<String>>,
+    /// Se a leitura continua depois de uma linha inválida, para mostrar todos os problemas
+    /// do arquivo de uma vez em [`ErroKnn::VariosErros`] (na ordem das linhas). `false`:
+    /// para na primeira, com o erro dela
+    pub coletar_erros: bool,
+}
+
+// Default permite escrever OpcoesCsv { rotulo: ..., ..OpcoesCsv::default() }
+/// ```
+/// use aprendizagem_knn::OpcoesCsv;
+///
+/// assert_eq!(OpcoesCsv::default().rotulo, None);
+/// ```
+impl Default for OpcoesCsv {
+    fn default() -> Self {
+        Self {
+            tem_cabecalho: true,
+            delimitador: Some(b','),
+            aspas: b'"',
+            virgula_decimal: Some(false),
+            rotulo: None,
+            caracteristicas: None,
+            coletar_erros: false,
+        }
+    }
+}
+
+// Separadores reconhecidos por detectar_delimitador; no empate vence o que vem antes
+const DELIMITADORES_CANDIDATOS: [u8; 4] = [b',', b';', b'\t', b'|'];
+
+/// Escolhe o separador de colunas de um CSV pela sua primeira linha: entre vírgula,
+/// ponto e vírgula, tabulação e barra vertical, o que aparece mais vezes fora das aspas.
+///
+/// No empate vence o que vem antes nessa lista; se nenhum aparece (um CSV de uma coluna
+/// só), a vírgula. Como só olha uma linha, pode errar em arquivos incomuns; nesses casos,
+/// informe o delimitador em [`OpcoesCsv`].
+///
+/// ```
+/// use aprendizagem_knn::detectar_delimitador;
+///
+/// assert_eq!(detectar_delimitador("x,y,classe", b'"'), b',');
+/// assert_eq!(detectar_delimitador("x;y;classe", b'"'), b';');
+/// assert_eq!(detectar_delimitador("x\ty\tclasse", b'"'), b'\t');
+/// // As vírgulas dentro das aspas não contam
+/// assert_eq!(detectar_delimitador("\"altura, cm\";\"peso, kg\";classe", b'"'), b';');
+/// assert_eq!(detectar_delimitador("classe", b'"'), b',');
+/// ```
+pub fn detectar_delimitador(primeira_linha: &str, aspas: u8) -> u8 {
+    let mut contagens = [0usize; DELIMITADORES_CANDIDATOS.len()];
+    let mut dentro_das_aspas = false;
+    for byte in primeira_linha.bytes() {
+        if byte == aspas {
+            // Uma aspa dobrada ("") entra e sai das aspas em seguida, sem mudar nada
+            dentro_das_aspas = !dentro_das_aspas;
+        } else if !dentro_das_aspas {
+            if let Some(posicao) = DELIMITADORES_CANDIDATOS.iter().position(|&candidato| candidato == byte) {
+                contagens[posicao] += 1;
+            }
+        }
+    }
+    // max_by_key devolve o último dos máximos; percorrer ao contrário faz o primeiro vencer
+    DELIMITADORES_CANDIDATOS.iter()
+        .zip(contagens)
+        .rev()
+        .filter(|&(_, contagem)| contagem > 0)
+        .max_by_key(|&(_, contagem)| contagem)
+        .map_or(b',', |(&delimitador, _)| delimitador)
+}
+
+/// Converte um número escrito com vírgula decimal, como `4,5` ou `-1.234,56` (ponto como
+/// separador de milhar), em `f64`.
+///
+/// Os pontos só são aceitos separando grupos de três dígitos na parte inteira. Um valor
+/// que parece escrito com ponto decimal (`4.5`) é erro, e `1.234` sem vírgula também: pode
+/// ser mil duzentos e trinta e quatro ou um vírgula dois três quatro, e um número errado
+/// sem aviso seria pior do que parar. O erro traz o texto original.
+///
+/// ```
+/// use aprendizagem_knn::interpretar_virgula_decimal;
+///
+/// assert_eq!(interpretar_virgula_decimal("4,5"), Ok(4.5));
+/// assert_eq!(interpretar_virgula_decimal("-0,25"), Ok(-0.25));
+/// assert_eq!(interpretar_virgula_decimal("1.234,56"), Ok(1234.56));
+/// assert_eq!(interpretar_virgula_decimal("1.234.567"), Ok(1234567.0));
+/// assert_eq!(interpretar_virgula_decimal(" 12 "), Ok(12.0));
+///
+/// assert!(interpretar_virgula_decimal("4.5").unwrap_err().contains("'4.5'"));
+/// assert!(interpretar_virgula_decimal("1.234").unwrap_err().contains("ambíguo"));
+/// assert!(interpretar_virgula_decimal("12.34,5").is_err());
+/// assert!(interpretar_virgula_decimal("1,2,3").is_err());
+/// assert!(interpretar_virgula_decimal("abc").is_err());
+/// ```
+pub fn interpretar_virgula_decimal(campo: &str) -> Result<f64, String> {
+    let texto = campo.trim();
+    let invalido = || format!("'{}' não é um número com vírgula decimal (como 1.234,56)", campo);
+
+    let (negativo, corpo) = match texto.strip_prefix('-') {
+        Some(resto) => (true, resto),
+        None => (false, texto.strip_prefix('+').unwrap_or(texto)),
+    };
+    let (inteira, fracao) = match corpo.split_once(',') {
+        Some((inteira, fracao)) => (inteira, Some(fracao)),
+        None => (corpo, None),
+    };
+    // Uma segunda vírgula fica na fração e é recusada aqui
+    let so_digitos = |parte: &str| parte.bytes().all(|byte| byte.is_ascii_digit());
+    if fracao.is_some_and(|fracao| fracao.is_empty() || !so_digitos(fracao)) {
+        return Err(invalido());
+    }
+    if inteira.is_empty() && fracao.is_none() {
+        return Err(invalido());
+    }
+
+    // Parte inteira: "1234" ou grupos de milhar "1.234.567"
+    let grupos: Vec<&str> = inteira.split('.').collect();
+    if grupos.iter().any(|grupo| !so_digitos(grupo)) {
+        return Err(invalido());
+    }
+    if grupos.len() > 1 {
+        let milhar_valido = (1..=3).contains(&grupos[0].len()) && grupos[1..].iter().all(|grupo| grupo.len() == 3);
+        if !milhar_valido {
+            return Err(format!("'{}' parece usar ponto decimal, mas a vírgula decimal foi pedida", campo));
+        }
+        if grupos.len() == 2 && fracao.is_none() {
+            return Err(format!(
+                "'{}' é ambíguo: pode ser {}{} (ponto de milhar) ou {} (ponto decimal)",
+                campo, if negativo { "-" } else { "" }, grupos.concat(), texto
+            ));
+        }
+    }
+
+    // Com dígitos demais, o número passa do maior f64 e vira infinito
+    let valor: f64 = format!("{}.{}", grupos.concat(), fracao.unwrap_or("0")).parse().map_err(|_| invalido())?;
+    if !valor.is_finite() {
+        return Err(format!("'{}' é grande demais para um número", campo));
+    }
+    Ok(if negativo { -valor } else { valor })
+}
+
+/// Carrega um CSV escolhendo as colunas pelo nome, conforme as [`OpcoesCsv`]. Devolve os
+/// nomes das colunas usadas como características (na ordem das características de cada
+/// ponto) e os pontos.
+///
+/// Uma coluna pedida que não existe é erro ([`ErroKnn::ParametroInvalido`]), com o nome
+/// dela; um valor não numérico numa característica escolhida também
+/// ([`ErroKnn::LinhaInvalida`]), com o nome da coluna e o número da linha. Os outros erros
+/// e a descompressão dos arquivos `.gz` são os de [`carregar_dados_do_csv`].
+///
+/// Campos entre aspas podem conter o delimitador e aspas dobradas, como gravam o Excel e
+/// as planilhas em geral.
+///
+/// ```
+/// use aprendizagem_knn::{carregar_dados_do_csv_com_opcoes, ErroKnn, OpcoesCsv};
+///
+/// let caminho = std::env::temp_dir().join("aprendizagem_knn_doc_opcoes.csv");
+/// let caminho = caminho.to_str().unwrap();
+/// std::fs::write(caminho, "id,sepal,species,petal\nf1,5.1,setosa,1.4\nf2,6.3,virginica,6.0\n").unwrap();
+///
+/// // Rótulo no meio, escolhido pelo nome; a coluna "id" (texto) é ignorada
+/// let opcoes = OpcoesCsv { rotulo: Some("species".to_string()), ..OpcoesCsv::default() };
+/// let (colunas, dados) = carregar_dados_do_csv_com_opcoes(caminho, &opcoes).unwrap();
+/// assert_eq!(colunas, vec!["sepal", "petal"]);
+/// assert_eq!(dados[1].caracteristicas, vec![6.3, 6.0]);
+/// assert_eq!(dados[1].rotulo, "virginica");
+///
+/// // Só algumas características, na ordem pedida
+/// let opcoes = OpcoesCsv { caracteristicas: Some(vec!["petal".to_string()]), ..opcoes };
+/// let (colunas, dados) = carregar_dados_do_csv_com_opcoes(caminho, &opcoes).unwrap();
+/// assert_eq!(colunas, vec!["petal"]);
+/// assert_eq!(dados[0].caracteristicas, vec![1.4]);
+///
+/// // Pedir uma coluna de texto como característica é erro, com o nome e a linha
+/// let opcoes = OpcoesCsv { caracteristicas: Some(vec!["id".to_string()]), ..opcoes };
+/// let erro = carregar_dados_do_csv_com_opcoes(caminho, &opcoes).unwrap_err().to_string();
+/// assert!(erro.contains("'id'") && erro.contains("linha 2"), "{}", erro);
+///
+/// // Uma coluna que não existe também
+/// let opcoes = OpcoesCsv { rotulo: Some("classe".to_string()), ..OpcoesCsv::default() };
+/// let erro = carregar_dados_do_csv_com_opcoes(caminho, &opcoes).unwrap_err();
+/// assert!(matches!(&erro, ErroKnn::ParametroInvalido(motivo) if motivo.contains("'classe'")), "{:?}", erro);
+///
+/// // Sem cabeçalho: posições, como em carregar_dados_do_csv
+/// std::fs::write(caminho, "5.1,1.4,setosa\n6.3,6.0,virginica\n").unwrap();
+/// let opcoes = OpcoesCsv { tem_cabecalho: false, ..OpcoesCsv::default() };
+/// let (colunas, dados) = carregar_dados_do_csv_com_opcoes(caminho, &opcoes).unwrap();
+/// assert_eq!(colunas, vec!["coluna 1", "coluna 2"]);
+/// assert_eq!(dados.len(), 2);
+/// assert_eq!(dados[0].rotulo, "setosa");
+///
+/// // Separado por ponto e vírgula, com o delimitador e aspas dentro de um campo entre aspas
+/// std::fs::write(caminho, "x;classe\n1.5;\"setosa; a \"\"comum\"\"\"\n").unwrap();
+/// let opcoes = OpcoesCsv { delimitador: Some(b';'), ..OpcoesCsv::default() };
+/// let (_, dados) = carregar_dados_do_csv_com_opcoes(caminho, &opcoes).unwrap();
+/// assert_eq!(dados[0].caracteristicas, vec![1.5]);
+/// assert_eq!(dados[0].rotulo, "setosa; a \"comum\"");
+///
+/// // Com delimitador None, ele é detectado pela primeira linha
+/// let opcoes = OpcoesCsv { delimitador: None, ..OpcoesCsv::default() };
+/// assert_eq!(carregar_dados_do_csv_com_opcoes(caminho, &opcoes).unwrap().1, dados);
+/// ```
+pub fn carregar_dados_do_csv_com_opcoes(
+    caminho_arquivo: &str,
+    opcoes: &OpcoesCsv,
+) -> Result<(Vec<String>, Vec<Ponto>), ErroKnn> {
+    carregar_dados_de_leitor_com_opcoes(abrir_arquivo(caminho_arquivo)?, caminho_arquivo, opcoes)
+}
+
+/// Como [`carregar_dados_do_csv_com_opcoes`], mas lendo de qualquer fonte de bytes, como
+/// em [`carregar_dados_de_leitor`] (inclusive o erro [`ErroKnn::ConjuntoVazio`] para uma
+/// entrada sem linhas de dados).
+///
+/// ```
+/// use std::io::Cursor;
+///
+/// use aprendizagem_knn::{carregar_dados_de_leitor_com_opcoes, OpcoesCsv};
+///
+/// // Delimitador detectado pela primeira linha, que continua sendo lida como cabeçalho
+/// let opcoes = OpcoesCsv { delimitador: None, virgula_decimal: None, ..OpcoesCsv::default() };
+/// let entrada = Cursor::new("altura;classe\n1,5;A\n");
+/// let (colunas, dados) = carregar_dados_de_leitor_com_opcoes(entrada, "<stdin>", &opcoes).unwrap();
+/// assert_eq!(colunas, vec!["altura"]);
+/// assert_eq!(dados[0].caracteristicas, vec![1.5]);
+/// ```
+pub fn carregar_dados_de_leitor_com_opcoes<R: Read>(
+    leitor: R,
+    origem: &str,
+    opcoes: &OpcoesCsv,
+) -> Result<(Vec<String>, Vec<Ponto>), ErroKnn> {
+    let (leitor, delimitador) = leitor_com_opcoes(leitor, origem, opcoes)?;
+    // Uma entrada vazia, em que nem o cabeçalho chegou (por exemplo, Ctrl-D no terminal),
+    // já é ConjuntoVazio em ler_registros_com
+    let mut coletor = ColetorErros { coletar: opcoes.coletar_erros, ..ColetorErros::default() };
+    let registros = ler_registros_com(leitor, origem, true, &mut coletor)?;
+    if registros.linhas.is_empty() {
+        // Todas as linhas eram inválidas: os erros delas explicam melhor que "vazio"
+        coletor.concluir()?;
+        return Err(ErroKnn::ConjuntoVazio);
+    }
+
+    let converter = conversor_de_numeros(opcoes, delimitador);
+
+    // Posição de uma coluna pelo nome, com uma mensagem que lista as colunas existentes
+    let posicao = |nome: &str| {
+        registros.cabecalho.iter().position(|coluna| coluna == nome).ok_or_else(|| {
+            ErroKnn::ParametroInvalido(format!(
+                "{}: a coluna '{}' não existe (colunas: {})",
+                origem,
+                nome,
+                registros.cabecalho.join(", ")
+            ))
+        })
+    };
+
+    let coluna_rotulo = match &opcoes.rotulo {
+        Some(nome) => posicao(nome)?,
+        None => registros.cabecalho.len() - 1,
+    };
+    let colunas_caracteristicas: Vec<usize> = match &opcoes.caracteristicas {
+        Some(nomes) => {
+            let colunas = nomes.iter().map(|nome| posicao(nome)).collect::<Result<Vec<_>, _>>()?;
+            if colunas.contains(&coluna_rotulo) {
+                return Err(ErroKnn::ParametroInvalido(format!(
+                    "{}: a coluna '{}' é o rótulo e não pode ser também uma característica",
+                    origem, registros.cabecalho[coluna_rotulo]
+                )));
+            }
+            colunas
+        }
+        // Rótulo pelo nome: as outras colunas em que todos os valores são números
+        None if opcoes.rotulo.is_some() => (0..registros.cabecalho.len())
+            .filter(|&coluna| coluna != coluna_rotulo)
+            .filter(|&coluna| registros.linhas.iter().all(|(_, campos)| converter(&campos[coluna]).is_ok()))
+            .collect(),
+        None => (0..registros.cabecalho.len()).filter(|&coluna| coluna != coluna_rotulo).collect(),
+    };
+    if colunas_caracteristicas.is_empty() {
+        return Err(ErroKnn::Arquivo(format!("{}: nenhuma coluna numérica para usar como característica", origem)));
+    }
+
+    // Cada linha gera no máximo um erro (o primeiro problema dela); coletando, a linha é pulada
+    let mut pontos = Vec::with_capacity(registros.linhas.len());
+    'linhas: for (linha, campos) in &registros.linhas {
+        let mut caracteristicas = Vec::with_capacity(colunas_caracteristicas.len());
+        for &coluna in &colunas_caracteristicas {
+            match converter(&campos[coluna]) {
+                Ok(valor) => caracteristicas.push(valor),
+                Err(motivo) => {
+                    let motivo = format!("coluna {} ('{}'): {}", coluna + 1, registros.cabecalho[coluna], motivo);
+                    coletor.registrar(linha_invalida(origem, *linha, motivo))?;
+                    continue 'linhas;
+                }
+            }
+        }
+        if campos[coluna_rotulo].is_empty() {
+            let motivo = motivo_rotulo_vazio(coluna_rotulo, &registros.cabecalho);
+            coletor.registrar(linha_invalida(origem, *linha, motivo))?;
+            continue;
+        }
+        pontos.push(Ponto::novo(caracteristicas, campos[coluna_rotulo].clone()));
+    }
+    coletor.concluir()?;
+
+    let nomes = colunas_caracteristicas.iter().map(|&coluna| registros.cabecalho[coluna].clone()).collect();
+    Ok((nomes, pontos))
+}
+
+// Monta o leitor de CSV com o delimitador, as aspas e o cabeçalho das `opcoes`. Sem
+// delimitador nas opções, ele é detectado pela primeira linha; devolve também o escolhido
+fn leitor_com_opcoes<R: Read>(
+    leitor: R,
+    origem: &str,
+    opcoes: &OpcoesCsv,
+) -> Result<(Reader<impl Read>, u8), ErroKnn> {
+    let mut leitor = BufReader::new(leitor);
+    let mut primeira_linha = String::new();
+    let delimitador = match opcoes.delimitador {
+        Some(delimitador) => delimitador,
+        None => {
+            leitor.read_line(&mut primeira_linha).map_err(|erro| ErroKnn::de_io(origem, &erro))?;
+            detectar_delimitador(&primeira_linha, opcoes.aspas)
+        }
+    };
+    // A primeira linha, se foi lida para detectar o delimitador, volta para a frente do
+    // resto; chain junta as duas fontes sem precisar voltar atrás no leitor (o stdin não volta)
+    let leitor = io::Cursor::new(primeira_linha.into_bytes()).chain(leitor);
+    let leitor = ReaderBuilder::new()
+        .flexible(true)
+        .has_headers(opcoes.tem_cabecalho)
+        .delimiter(delimitador)
+        .quote(opcoes.aspas)
+        .from_reader(leitor);
+    Ok((leitor, delimitador))
+}
+
+// Converte um campo em número conforme a vírgula decimal das `opcoes` (que, sem escolha
+// explícita, vale quando o delimitador é `;`). Com vírgula decimal o erro explica o problema
+// (ambíguo, ponto decimal...); sem ela, a mensagem só cita o valor, como nas outras funções
+fn conversor_de_numeros(opcoes: &OpcoesCsv, delimitador: u8) -> impl Fn(&str) -> Result<f64, String> {
+    let virgula_decimal = opcoes.virgula_decimal.unwrap_or(delimitador == b';');
+    move |campo: &str| {
+        if virgula_decimal {
+            interpretar_virgula_decimal(campo)
+        } else {
+            interpretar_valor(campo).ok_or_else(|| motivo_valor_invalido(campo))
+        }
+    }
+}
+
+// ==================== CLASSIFICAÇÃO DE UM CSV DE TESTE ====================
+/// Carrega um CSV sem rótulo, em que todas as colunas são características (os pontos a
+/// classificar). Os pontos vêm na ordem do arquivo, com o rótulo vazio.
+///
+/// Uma linha com número de colunas diferente do cabeçalho ou com um valor não numérico é
+/// [`ErroKnn::LinhaInvalida`], que cita o arquivo e o número da linha.
+///
+/// ```
+/// use aprendizagem_knn::{carregar_pontos_sem_rotulo, ErroKnn};
+///
+/// let caminho = std::env::temp_dir().join("aprendizagem_knn_doc_sem_rotulo.csv");
+/// std::fs::write(&caminho, "x,y\n1.0,2.0\n3.5,-1\n").unwrap();
+/// let pontos = carregar_pontos_sem_rotulo(caminho.to_str().unwrap()).unwrap();
+/// assert_eq!(pontos[1].caracteristicas, vec![3.5, -1.0]);
+/// assert_eq!(pontos[1].rotulo, "");
+///
+/// std::fs::write(&caminho, "x,y\n1.0,2.0\n3.5,abc\n").unwrap();
+/// let erro = carregar_pontos_sem_rotulo(caminho.to_str().unwrap()).unwrap_err();
+/// assert!(matches!(erro, ErroKnn::LinhaInvalida { linha: 3, .. }), "{:?}", erro);
+/// ```
+pub fn carregar_pontos_sem_rotulo(caminho_arquivo: &str) -> Result<Vec<Ponto>, ErroKnn> {
+    let registros = ler_registros_sem_rotulo(abrir_arquivo(caminho_arquivo)?, caminho_arquivo)?;
+    converter_sem_rotulo(&registros, caminho_arquivo)
+}
+
+fn ler_registros_sem_rotulo<R: Read>(leitor: R, origem: &str) -> Result<RegistrosCsv, ErroKnn> {
+    let leitor = ReaderBuilder::new().flexible(true).from_reader(leitor);
+    ler_registros(leitor, origem, false)
+}
+
+fn converter_sem_rotulo(registros: &RegistrosCsv, caminho_arquivo: &str) -> Result<Vec<Ponto>, ErroKnn> {
+    registros.linhas.iter()
+        .map(|(linha, campos)| {
+            let caracteristicas =
+                converter_caracteristicas(campos, &registros.cabecalho, caminho_arquivo, *linha, false)?;
+            Ok(Ponto::novo(caracteristicas, String::new()))
+        })
+        .collect()
+}
+
+/// Classifica todas as linhas de um CSV de teste sem rótulo e grava o resultado em outro CSV.
+///
+/// O teste é lido com as mesmas `opcoes` do treinamento: o delimitador (ou a detecção dele),
+/// as aspas, o cabeçalho e a vírgula decimal (os campos `rotulo`, `caracteristicas` e
+/// `coletar_erros` não se aplicam a um arquivo sem rótulo). O CSV de saída usa o mesmo
+/// delimitador e as mesmas aspas, repete as colunas do teste exatamente como estavam
+/// escritas e acrescenta a coluna `rotulo_previsto` (e `confianca`, com `incluir_confianca`,
+/// escrita com vírgula decimal quando o teste usa vírgula decimal), na mesma ordem das linhas
+/// de entrada. `classificar` recebe cada linha como um [`Ponto`] sem rótulo e devolve o
+/// rótulo e a confiança, ou um erro, como faz o [`knn_com_confianca`](crate::knn_com_confianca);
+/// assim quem chama escolhe k, métrica e normalização. O teste precisa ter
+/// `n_caracteristicas` colunas, as mesmas do treinamento (sem o rótulo); se não tiver, o
+/// erro é [`ErroKnn::DimensaoIncompativel`].
+///
+/// Devolve o número de linhas classificadas. Os erros de leitura citam o número da linha
+/// (veja [`carregar_pontos_sem_rotulo`]), e o primeiro erro de `classificar` interrompe
+/// tudo; o arquivo de saída só é criado depois de o teste ser lido e classificado sem erros.
+///
+/// ```
+/// use aprendizagem_knn::{classificar_csv, knn_com_confianca, pontos, DistanciaMetrica, ErroKnn, OpcoesCsv};
+///
+/// let treinamento = pontos![[0.0, 0.0] => "A", [1.0, 0.0] => "A", [9.0, 9.0] => "B"];
+/// let pasta = std::env::temp_dir();
+/// let teste = pasta.join("aprendizagem_knn_doc_teste.csv");
+/// let saida = pasta.join("aprendizagem_knn_doc_previsoes.csv");
+/// let (teste, saida) = (teste.to_str().unwrap(), saida.to_str().unwrap());
+/// std::fs::write(teste, "x,y\n8.5,9\n0.2,0.1\n").unwrap();
+///
+/// let classificar = |ponto: &_| knn_com_confianca(&treinamento, ponto, 1, &DistanciaMetrica::Euclidiana);
+/// let opcoes = OpcoesCsv::default();
+/// assert_eq!(classificar_csv(teste, saida, &opcoes, 2, classificar, true).unwrap(), 2);
+/// assert_eq!(
+///     std::fs::read_to_string(saida).unwrap(),
+///     "x,y,rotulo_previsto,confianca\n8.5,9,B,1\n0.2,0.1,A,1\n"
+/// );
+///
+/// // Excel em português: ponto e vírgula e vírgula decimal, na leitura e na saída
+/// std::fs::write(teste, "x;y\n8,5;9\n0,2;0,1\n").unwrap();
+/// let excel = OpcoesCsv { delimitador: Some(b';'), virgula_decimal: Some(true), ..OpcoesCsv::default() };
+/// assert_eq!(classificar_csv(teste, saida, &excel, 2, classificar, false).unwrap(), 2);
+/// assert_eq!(std::fs::read_to_string(saida).unwrap(), "x;y;rotulo_previsto\n8,5;9;B\n0,2;0,1;A\n");
+///
+/// // O treinamento tem 3 características, mas o teste só tem 2 colunas
+/// assert_eq!(
+///     classificar_csv(teste, saida, &excel, 3, classificar, true),
+///     Err(ErroKnn::DimensaoIncompativel { esperado: 3, obtido: 2 })
+/// );
+/// ```
+pub fn classificar_csv<F>(
+    caminho_teste: &str,
+    caminho_saida: &str,
+    opcoes: &OpcoesCsv,
+    n_caracteristicas: usize,
+    classificar: F,
+    incluir_confianca: bool,
+) -> Result<usize, ErroKnn>
+where
+    F: Fn(&Ponto) -> Result<(String, f64), ErroKnn>,
+{
+    let leitor = abrir_arquivo(caminho_teste)?;
+    let (n, confianca) = (n_caracteristicas, incluir_confianca);
+    classificar_csv_de_leitor(leitor, caminho_teste, caminho_saida, opcoes, n, classificar, confianca)
+}
+
+/// Como [`classificar_csv`], mas lendo o CSV de teste de qualquer fonte de bytes (a
+/// entrada padrão, por exemplo); `origem` identifica a fonte nas mensagens de erro. Um
+/// teste sem nenhuma linha de dados é [`ErroKnn::ConjuntoVazio`].
+///
+/// ```
+/// use std::io::Cursor;
+///
+/// use aprendizagem_knn::{classificar_csv_de_leitor, ErroKnn, OpcoesCsv, Ponto};
+///
+/// let saida = std::env::temp_dir().join("aprendizagem_knn_doc_previsoes_leitor.csv");
+/// let saida = saida.to_str().unwrap();
+/// // Um classificador de brinquedo: limiar em 4.5
+/// let classificar = |ponto: &Ponto| {
+///     let rotulo = if ponto.caracteristicas[0] < 4.5 { "A" } else { "B" };
+///     Ok((rotulo.to_string(), 1.0))
+/// };
+/// let opcoes = OpcoesCsv::default();
+///
+/// let total = classificar_csv_de_leitor(Cursor::new("x\n8\n1\n"), "<stdin>", saida, &opcoes, 1, classificar, false);
+/// assert_eq!(total, Ok(2));
+/// assert_eq!(std::fs::read_to_string(saida).unwrap(), "x,rotulo_previsto\n8,B\n1,A\n");
+///
+/// let erro = classificar_csv_de_leitor(Cursor::new("x\n"), "<stdin>", saida, &opcoes, 1, classificar, false);
+/// assert_eq!(erro, Err(ErroKnn::ConjuntoVazio));
+/// ```
+pub fn classificar_csv_de_leitor<R: Read, F>(
+    leitor: R,
+    origem: &str,
+    caminho_saida: &str,
+    opcoes: &OpcoesCsv,
+    n_caracteristicas: usize,
+    classificar: F,
+    incluir_confianca: bool,
+) -> Result<usize, ErroKnn>
+where
+    F: Fn(&Ponto) -> Result<(String, f64), ErroKnn>,
+{
+    let (leitor, delimitador) = leitor_com_opcoes(leitor, origem, opcoes)?;
+    let registros = ler_registros(leitor, origem, false)?;
+    exigir_linhas(&registros.linhas)?;
+    if registros.cabecalho.len() != n_caracteristicas {
+        return Err(ErroKnn::DimensaoIncompativel { esperado: n_caracteristicas, obtido: registros.cabecalho.len() });
+    }
+
+    let converter = conversor_de_numeros(opcoes, delimitador);
+    let mut pontos = Vec::with_capacity(registros.linhas.len());
+    for (linha, campos) in &registros.linhas {
+        let caracteristicas = campos.iter()
+            .enumerate()
+            .map(|(coluna, campo)| {
+                converter(campo).map_err(|motivo| {
+                    let motivo = format!("coluna {} ('{}'): {}", coluna + 1, registros.cabecalho[coluna], motivo);
+                    linha_invalida(origem, *linha, motivo)
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        pontos.push(Ponto::novo(caracteristicas, String::new()));
+    }
+    let previsoes = pontos.iter().map(classificar).collect::<Result<Vec<_>, ErroKnn>>()?;
+
+    let erro_saida = |erro: csv::Error| erro_do_csv(erro, caminho_saida);
+    let mut escritor = WriterBuilder::new()
+        .delimiter(delimitador)
+        .quote(opcoes.aspas)
+        .from_path(caminho_saida)
+        .map_err(erro_saida)?;
+    // Sem cabeçalho no teste, a saída também não tem (os nomes "coluna 1"... são só internos)
+    if opcoes.tem_cabecalho {
+        let mut cabecalho = registros.cabecalho.clone();
+        cabecalho.push("rotulo_previsto".to_string());
+        if incluir_confianca {
+            cabecalho.push("confianca".to_string());
+        }
+        escritor.write_record(&cabecalho).map_err(erro_saida)?;
+    }
+
+    // Cada linha sai com o texto original das características, seguido da previsão
+    let virgula_decimal = opcoes.virgula_decimal.unwrap_or(delimitador == b';');
+    for ((_, campos), (rotulo, confianca)) in registros.linhas.iter().zip(previsoes) {
+        let mut registro = campos.clone();
+        registro.push(rotulo);
+        if incluir_confianca {
+            let confianca = confianca.to_string();
+            registro.push(if virgula_decimal { confianca.replace('.', ",") } else { confianca });
+        }
+        escritor.write_record(&registro).map_err(erro_saida)?;
+    }
+    escritor.flush().map_err(|erro| ErroKnn::de_io(caminho_saida, &erro))?;
+    Ok(pontos.len())
+}
@@ -0,0 +1,332 @@
+//! Leitura de conjuntos de dados no formato ARFF, o formato de arquivo do Weka.
+
+use std::fs;
+
+use crate::erro::ErroKnn;
+use crate::ponto::Ponto;
+
+// ==================== FORMATO ARFF ====================
+// Um arquivo ARFF tem um cabeçalho, que declara os atributos (colunas) e seus tipos, e a
+// seção @data, com uma linha por exemplo e os valores separados por vírgula:
+//
+//     % comentário
+//     @relation flores
+//     @attribute comprimento numeric
+//     @attribute largura     real
+//     @attribute classe      {setosa, versicolor, 'virginica azul'}
+//     @data
+//     5.1, 3.5, setosa
+//     6.3, ?,   'virginica azul'
+//
+// O `?` marca um valor ausente. As palavras-chave não diferenciam maiúsculas de minúsculas.
+
+// Tipo de um atributo declarado no cabeçalho
+#[derive(Debug, Clone, PartialEq)]
+enum TipoAtributo {
+    // numeric, real ou integer
+    Numerico,
+    // {valor1, valor2, ...}
+    Nominal(Vec<String>),
+    // string, date, relational
+    Outro(String),
+}
+
+#[derive(Debug, Clone)]
+struct Atributo {
+    nome: String,
+    tipo: TipoAtributo,
+    // Linha em que foi declarado, para as mensagens de erro
+    linha: usize,
+}
+
+/// Carrega um arquivo ARFF (o formato do Weka), usando a classe como rótulo e os demais
+/// atributos como características.
+///
+/// A classe é o atributo chamado `classe`, se informado, ou o último atributo declarado
+/// (a convenção do Weka). Ela pode ser de qualquer tipo e seus valores viram rótulos como
+/// estão escritos; se for nominal, cada valor precisa estar entre os declarados.
+///
+/// Todos os outros atributos precisam ser numéricos (`numeric`, `real` ou `integer`).
+/// Atributos nominais, `string` ou `date` fora da classe são recusados com um erro que
+/// cita o atributo, em vez de serem codificados de algum jeito que o knn não saberia
+/// medir; converta-os antes (por exemplo com [`CodificadorOneHot`](crate::CodificadorOneHot)
+/// sobre um CSV) ou remova-os do arquivo.
+///
+/// Na seção `@data`, o que vem depois de `%` (fora de aspas) é comentário, valores podem
+/// estar entre aspas simples ou duplas, e `?` é um valor ausente: vira `NaN` nas
+/// características (use [`Imputador`](crate::Imputador) ou
+/// [`knn_com_ausentes`](crate::knn_com_ausentes) depois) e é um erro na classe. O formato
+/// esparso (linhas entre `{}`) não é aceito.
+///
+/// Devolve [`ErroKnn::Arquivo`], citando a linha, para cabeçalhos mal formados (tipo
+/// desconhecido, lista nominal sem `}`, atributo repetido, falta do `@data`), linhas com o
+/// número errado de valores, números inválidos e valores da classe não declarados.
+///
+/// ```
+/// use aprendizagem_knn::carregar_dados_do_arff;
+///
+/// let caminho = std::env::temp_dir().join("aprendizagem_knn_doc_dados.arff");
+/// std::fs::write(
+///     &caminho,
+///     "@relation exemplo\n@attribute x numeric\n@attribute classe {A, B}\n@data\n1.5, A\n?, B\n",
+/// )
+/// .unwrap();
+///
+/// let dados = carregar_dados_do_arff(caminho.to_str().unwrap(), None).unwrap();
+/// assert_eq!(dados[0].caracteristicas, vec![1.5]);
+/// assert_eq!(dados[0].rotulo, "A");
+/// assert!(dados[1].caracteristicas[0].is_nan());
+/// ```
+pub fn carregar_dados_do_arff(caminho: &str, classe: Option<&str>) -> Result<Vec<Ponto>, ErroKnn> {
+    let conteudo = fs::read_to_string(caminho).map_err(|erro| ErroKnn::de_io(caminho, &erro))?;
+    ler_arff(&conteudo, caminho, classe)
+}
+
+/// Como [`carregar_dados_do_arff`], mas com o conteúdo já em memória (as mensagens de erro
+/// citam a origem `<texto>`).
+///
+/// ```
+/// use aprendizagem_knn::carregar_dados_de_texto_arff;
+///
+/// let texto = "\
+/// % flores de exemplo
+/// @RELATION flores
+/// @ATTRIBUTE especie {'setosa comum', versicolor}
+/// @ATTRIBUTE 'comprimento da pétala' REAL
+/// @DATA
+/// 'setosa comum', 1.4 % a classe não precisa ser a última
+/// versicolor, 4.7
+/// ";
+/// let dados = carregar_dados_de_texto_arff(texto, Some("especie")).unwrap();
+/// assert_eq!(dados[0].rotulo, "setosa comum");
+/// assert_eq!(dados[1].caracteristicas, vec![4.7]);
+///
+/// // Atributo nominal fora da classe é recusado
+/// let texto = "@attribute cor {azul, verde}\n@attribute x numeric\n@attribute c {A}\n@data\nazul, 1, A\n";
+/// let erro = carregar_dados_de_texto_arff(texto, None).unwrap_err().to_string();
+/// assert!(erro.contains("'cor'") && erro.contains("nominal"), "{}", erro);
+///
+/// // Valor da classe que não foi declarado
+/// let texto = "@attribute x numeric\n@attribute c {A, B}\n@data\n1, C\n";
+/// let erro = carregar_dados_de_texto_arff(texto, None).unwrap_err().to_string();
+/// assert!(erro.contains("linha 4") && erro.contains("'C'"), "{}", erro);
+/// ```
+pub fn carregar_dados_de_texto_arff(conteudo: &str, classe: Option<&str>) -> Result<Vec<Ponto>, ErroKnn> {
+    ler_arff(conteudo, "<texto>", classe)
+}
+
+fn ler_arff(conteudo: &str, origem: &str, classe: Option<&str>) -> Result<Vec<Ponto>, ErroKnn> {
+    let erro = |linha: usize, mensagem: String| ErroKnn::Arquivo(format!("{}: linha {}: {}", origem, linha, mensagem));
+    let mut linhas = conteudo.lines().enumerate().map(|(numero, linha)| (numero + 1, linha));
+
+    // ---------- Cabeçalho ----------
+    let mut atributos: Vec<Atributo> = Vec::new();
+    let mut achou_dados = false;
+    for (numero, linha) in linhas.by_ref() {
+        let linha = linha.trim();
+        if linha.is_empty() || linha.starts_with('%') {
+            continue;
+        }
+        let (palavra, resto) = linha.split_once(char::is_whitespace).unwrap_or((linha, ""));
+        match palavra.to_lowercase().as_str() {
+            "@relation" => {}
+            "@attribute" => {
+                let atributo = ler_atributo(resto, numero).map_err(|mensagem| erro(numero, mensagem))?;
+                if let Some(anterior) = atributos.iter().find(|anterior| anterior.nome == atributo.nome) {
+                    return Err(erro(
+                        numero,
+                        format!("o atributo '{}' já foi declarado na linha {}", atributo.nome, anterior.linha),
+                    ));
+                }
+                atributos.push(atributo);
+            }
+            "@data" => {
+                achou_dados = true;
+                break;
+            }
+            _ => {
+                return Err(erro(numero, format!("esperava @relation, @attribute ou @data, mas encontrou '{}'", linha)));
+            }
+        }
+    }
+    if !achou_dados {
+        return Err(ErroKnn::Arquivo(format!("{}: o arquivo não tem a seção @data", origem)));
+    }
+    if atributos.len() < 2 {
+        return Err(ErroKnn::Arquivo(format!(
+            "{}: são precisos ao menos dois atributos (uma característica e a classe), mas há {}",
+            origem,
+            atributos.len()
+        )));
+    }
+
+    let indice_classe = match classe {
+        None => atributos.len() - 1,
+        Some(nome) => atributos.iter().position(|atributo| atributo.nome == nome).ok_or_else(|| {
+            ErroKnn::Arquivo(format!("{}: não há nenhum atributo chamado '{}' para ser a classe", origem, nome))
+        })?,
+    };
+    for (indice, atributo) in atributos.iter().enumerate() {
+        if indice == indice_classe {
+            continue;
+        }
+        let tipo = match &atributo.tipo {
+            TipoAtributo::Numerico => continue,
+            TipoAtributo::Nominal(_) => "nominal",
+            TipoAtributo::Outro(tipo) => tipo.as_str(),
+        };
+        return Err(erro(
+            atributo.linha,
+            format!(
+                "o atributo '{}' é {}, mas só atributos numéricos podem ser características do knn \
+                 (converta-o em números ou remova-o)",
+                atributo.nome, tipo
+            ),
+        ));
+    }
+
+    // ---------- Dados ----------
+    let mut dados = Vec::new();
+    for (numero, linha) in linhas {
+        let linha = linha.trim();
+        if linha.is_empty() || linha.starts_with('%') {
+            continue;
+        }
+        if linha.starts_with('{') {
+            return Err(erro(numero, "o formato ARFF esparso ({índice valor, ...}) não é aceito".to_string()));
+        }
+        let valores = dividir_valores(linha, ',').map_err(|mensagem| erro(numero, mensagem))?;
+        if valores.len() != atributos.len() {
+            return Err(erro(
+                numero,
+                format!("a linha tem {} valores, mas há {} atributos", valores.len(), atributos.len()),
+            ));
+        }
+
+        let mut caracteristicas = Vec::with_capacity(atributos.len() - 1);
+        let mut rotulo = String::new();
+        for (indice, ((valor, entre_aspas), atributo)) in valores.into_iter().zip(&atributos).enumerate() {
+            // Só o ? sem aspas é ausente; '?' entre aspas é o texto "?"
+            let ausente = valor == "?" && !entre_aspas;
+            if indice == indice_classe {
+                if ausente {
+                    return Err(erro(numero, format!("a classe ('{}') está ausente", atributo.nome)));
+                }
+                if let TipoAtributo::Nominal(permitidos) = &atributo.tipo {
+                    if !permitidos.contains(&valor) {
+                        return Err(erro(
+                            numero,
+                            format!(
+                                "o valor '{}' não está entre os declarados para '{}' {{{}}}",
+                                valor,
+                                atributo.nome,
+                                permitidos.join(", ")
+                            ),
+                        ));
+                    }
+                }
+                rotulo = valor;
+            } else if ausente {
+                caracteristicas.push(f64::NAN);
+            } else {
+                let numero_lido = valor.parse::<f64>().map_err(|_| {
+                    erro(numero, format!("atributo '{}': valor numérico inválido '{}'", atributo.nome, valor))
+                })?;
+                caracteristicas.push(numero_lido);
+            }
+        }
+        dados.push(Ponto::novo(caracteristicas, rotulo));
+    }
+    Ok(dados)
+}
+
+// Lê o que vem depois de "@attribute": o nome (que pode estar entre aspas) e o tipo
+fn ler_atributo(declaracao: &str, linha: usize) -> Result<Atributo, String> {
+    let declaracao = declaracao.trim();
+    let (nome, tipo) = match declaracao.chars().next() {
+        Some(aspa @ ('\'' | '"')) => {
+            let fim = declaracao[1..]
+                .find(aspa)
+                .ok_or_else(|| format!("o nome do atributo em '{}' não fecha as aspas", declaracao))?;
+            (declaracao[1..=fim].to_string(), &declaracao[fim + 2..])
+        }
+        Some(_) => {
+            let (nome, tipo) = declaracao.split_once(char::is_whitespace).unwrap_or((declaracao, ""));
+            (nome.to_string(), tipo)
+        }
+        None => return Err("@attribute sem nome nem tipo".to_string()),
+    };
+
+    let tipo = tipo.trim();
+    let tipo = if let Some(lista) = tipo.strip_prefix('{') {
+        let lista = lista
+            .strip_suffix('}')
+            .ok_or_else(|| format!("a lista de valores do atributo '{}' não fecha com '}}'", nome))?;
+        let valores: Vec<String> = dividir_valores(lista, ',')?.into_iter().map(|(valor, _)| valor).collect();
+        if valores.iter().any(String::is_empty) {
+            return Err(format!("a lista de valores do atributo '{}' tem um valor vazio", nome));
+        }
+        TipoAtributo::Nominal(valores)
+    } else {
+        let palavra = tipo.split_whitespace().next().unwrap_or("").to_lowercase();
+        match palavra.as_str() {
+            "numeric" | "real" | "integer" => TipoAtributo::Numerico,
+            "string" | "date" | "relational" => TipoAtributo::Outro(palavra),
+            "" => return Err(format!("o atributo '{}' não tem tipo", nome)),
+            _ => return Err(format!("tipo desconhecido '{}' para o atributo '{}'", tipo, nome)),
+        }
+    };
+    Ok(Atributo { nome, tipo, linha })
+}
+
+// Separa os valores de uma linha pelo `separador`, respeitando aspas simples ou duplas (com
+// \ para escapar a aspa dentro delas) e parando no % de um comentário. Cada valor vem sem
+// espaços nas pontas e com a indicação de se estava entre aspas.
+fn dividir_valores(linha: &str, separador: char) -> Result<Vec<(String, bool)>, String> {
+    let mut valores = Vec::new();
+    let mut atual = String::new();
+    let mut entre_aspas = false;
+    let mut caracteres = linha.chars();
+    loop {
+        match caracteres.next() {
+            Some(aspa @ ('\'' | '"')) if atual.trim().is_empty() => {
+                // Lê até a aspa que fecha
+                let mut texto = String::new();
+                loop {
+                    match caracteres.next() {
+                        Some('\\') => texto.extend(caracteres.next()),
+                        Some(c) if c == aspa => break,
+                        Some(c) => texto.push(c),
+                        None => return Err(format!("aspas sem fechar em '{}'", linha)),
+                    }
+                }
+                atual = texto;
+                entre_aspas = true;
+                // Depois das aspas, só espaços até o separador
+                let resto: String = caracteres.clone().take_while(|&c| c != separador && c != '%').collect();
+                if !resto.trim().is_empty() {
+                    return Err(format!("texto '{}' depois das aspas em '{}'", resto.trim(), linha));
+                }
+            }
+            Some(c) if c == separador => {
+                valores.push(terminar_valor(&mut atual, &mut entre_aspas));
+            }
+            Some('%') | None => {
+                if !valores.is_empty() || entre_aspas || !atual.trim().is_empty() {
+                    valores.push(terminar_valor(&mut atual, &mut entre_aspas));
+                }
+                return Ok(valores);
+            }
+            Some(c) if entre_aspas => {
+                // Os espaços depois das aspas já foram verificados
+                debug_assert!(c.is_whitespace());
+            }
+            Some(c) => atual.push(c),
+        }
+    }
+}
+
+fn terminar_valor(atual: &mut String, entre_aspas: &mut bool) -> (String, bool) {
+    let valor = if *entre_aspas { std::mem::take(atual) } else { std::mem::take(atual).trim().to_string() };
+    (valor, std::mem::replace(entre_aspas, false))
+}
@@ -0,0 +1,83 @@
+//! Download de conjuntos de dados de uma URL http:// ou https:// (feature `http`).
+//!
+//! Compile com `cargo build --features http`. O corpo da resposta é lido aos poucos, como
+//! um arquivo: o CSV nunca precisa ser gravado em disco nem ficar inteiro na memória.
+
+use std::io::BufReader;
+
+use crate::dados::{carregar_dados_de_leitor_com_opcoes, descomprimir_se_gzip, OpcoesCsv};
+use crate::erro::ErroKnn;
+use crate::ponto::Ponto;
+
+// ==================== DOWNLOAD HTTP ====================
+// A resposta do servidor é só mais uma fonte de bytes (io::Read): passa pela mesma
+// detecção de gzip dos arquivos e pelo mesmo leitor de CSV, então as regras e as
+// mensagens de erro são as de carregar_dados_do_csv, com a URL no lugar do caminho.
+
+/// Baixa um CSV de `url` (`http://` ou `https://`) e o carrega como
+/// [`carregar_dados_do_csv`](crate::carregar_dados_do_csv): a última coluna é o rótulo e
+/// as anteriores as características.
+///
+/// Veja [`carregar_dados_de_url_com_opcoes`] para o gzip e os erros.
+///
+/// ```
+/// use aprendizagem_knn::carregar_dados_de_url;
+///
+/// // Um servidor local, só para o exemplo
+/// let servidor = tiny_http::Server::http("127.0.0.1:0").unwrap();
+/// let url = format!("http://{}/dados.csv", servidor.server_addr().to_ip().unwrap());
+/// std::thread::spawn(move || {
+///     let pedido = servidor.recv().unwrap();
+///     pedido.respond(tiny_http::Response::from_string("x,y,classe\n1,2,A\n3,4,B\n")).unwrap();
+/// });
+///
+/// let dados = carregar_dados_de_url(&url).unwrap();
+/// assert_eq!(dados[1].caracteristicas, vec![3.0, 4.0]);
+/// assert_eq!(dados[1].rotulo, "B");
+/// ```
+pub fn carregar_dados_de_url(url: &str) -> Result<Vec<Ponto>, ErroKnn> {
+    let (_, pontos) = carregar_dados_de_url_com_opcoes(url, &OpcoesCsv::default())?;
+    Ok(pontos)
+}
+
+/// Como [`carregar_dados_de_url`], com as opções de
+/// [`carregar_dados_do_csv_com_opcoes`](crate::carregar_dados_do_csv_com_opcoes)
+/// (delimitador, aspas, cabeçalho, coluna do rótulo...).
+///
+/// Um arquivo comprimido com gzip (URL terminada em `.gz`, ignorando a query string, ou
+/// corpo começando com a assinatura do gzip) é descomprimido durante o download, assim
+/// como uma resposta com `Content-Encoding: gzip`.
+///
+/// Falha de conexão ou de DNS, um status HTTP de erro (4xx ou 5xx, com o código) e gzip
+/// corrompido são [`ErroKnn::Arquivo`], com a URL na mensagem. Os erros do CSV são os de
+/// um arquivo, com a URL no lugar do caminho: uma linha inválida é
+/// [`ErroKnn::LinhaInvalida`] e uma resposta sem linhas de dados é
+/// [`ErroKnn::ConjuntoVazio`].
+///
+/// ```
+/// use aprendizagem_knn::{carregar_dados_de_url_com_opcoes, ErroKnn, OpcoesCsv};
+///
+/// // Nada escuta na porta 1: a conexão é recusada
+/// let erro = carregar_dados_de_url_com_opcoes("http://127.0.0.1:1/dados.csv", &OpcoesCsv::default()).unwrap_err();
+/// assert!(matches!(erro, ErroKnn::Arquivo(_)));
+/// assert!(erro.to_string().contains("http://127.0.0.1:1/dados.csv"), "{}", erro);
+/// ```
+pub fn carregar_dados_de_url_com_opcoes(
+    url: &str,
+    opcoes: &OpcoesCsv,
+) -> Result<(Vec<String>, Vec<Ponto>), ErroKnn> {
+    let erro = |mensagem: String| ErroKnn::Arquivo(format!("{}: {}", url, mensagem));
+    let resposta = ureq::get(url).call().map_err(|falha| match falha {
+        ureq::Error::StatusCode(codigo) => erro(format!("o servidor respondeu com o status HTTP {}", codigo)),
+        outra => erro(format!("falha no download: {}", outra)),
+    })?;
+
+    // O ".gz" é procurado no caminho da URL, sem a query string (?...) e o fragmento (#...)
+    let caminho = url.split(['?', '#']).next().unwrap_or(url);
+    let leitor = BufReader::new(resposta.into_body().into_reader());
+    let leitor =
+        descomprimir_se_gzip(leitor, url, caminho.ends_with(".gz")).map_err(|falha| ErroKnn::de_io(url, &falha))?;
+
+    // Uma conexão que cai no meio do download aparece como erro de E/S da leitura, já com a URL
+    carregar_dados_de_leitor_com_opcoes(leitor, url, opcoes)
+}
@@ -0,0 +1,314 @@
+//! Leitura de conjuntos de dados e gravação de previsões em JSON.
+
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::distancia::DistanciaMetrica;
+use crate::erro::ErroKnn;
+use crate::knn::{erro_sem_vizinhos, k_vizinhos_mais_proximos, validar_entrada, votar_por_maioria};
+use crate::ponto::Ponto;
+
+// ==================== LEITURA DE JSON ====================
+/// Carrega um conjunto de dados em JSON: um array de objetos no formato do [`Ponto`],
+/// `{"caracteristicas": [4.5, 8.0], "rotulo": "A"}`.
+///
+/// Devolve [`ErroKnn::Arquivo`] se o arquivo não puder ser lido, se não for JSON válido,
+/// se não for um array, ou se algum elemento não for um ponto (falta um campo, uma
+/// característica não é número...) ou tiver um número de características diferente do
+/// primeiro. Nesses dois últimos casos a mensagem cita a posição do elemento no array,
+/// contando a partir de 0.
+///
+/// ```
+/// use aprendizagem_knn::carregar_dados_do_json;
+///
+/// let caminho = std::env::temp_dir().join("aprendizagem_knn_doc_dados.json");
+/// std::fs::write(&caminho, r#"[
+///     {"caracteristicas": [4.5, 8.0], "rotulo": "A"},
+///     {"caracteristicas": [1.0, -2.0], "rotulo": "B"}
+/// ]"#).unwrap();
+/// let dados = carregar_dados_do_json(caminho.to_str().unwrap()).unwrap();
+/// assert_eq!(dados[1].caracteristicas, vec![1.0, -2.0]);
+/// assert_eq!(dados[1].rotulo, "B");
+///
+/// // O elemento 1 não tem rótulo
+/// std::fs::write(&caminho, r#"[{"caracteristicas": [1], "rotulo": "A"}, {"caracteristicas": [2]}]"#).unwrap();
+/// let erro = carregar_dados_do_json(caminho.to_str().unwrap()).unwrap_err().to_string();
+/// assert!(erro.contains("elemento 1") && erro.contains("rotulo"), "{}", erro);
+///
+/// // O elemento 2 tem uma característica a mais
+/// std::fs::write(&caminho, r#"[
+///     {"caracteristicas": [1, 2], "rotulo": "A"},
+///     {"caracteristicas": [3, 4], "rotulo": "A"},
+///     {"caracteristicas": [5, 6, 7], "rotulo": "B"}
+/// ]"#).unwrap();
+/// let erro = carregar_dados_do_json(caminho.to_str().unwrap()).unwrap_err().to_string();
+/// assert!(erro.contains("elemento 2 tem 3 características"), "{}", erro);
+/// ```
+pub fn carregar_dados_do_json(caminho: &str) -> Result<Vec<Ponto>, ErroKnn> {
+    let erro_arquivo = |mensagem: String| ErroKnn::Arquivo(format!("{}: {}", caminho, mensagem));
+    let conteudo = fs::read_to_string(caminho).map_err(|erro| ErroKnn::de_io(caminho, &erro))?;
+
+    // Primeiro o JSON genérico e depois cada elemento, para que o erro diga qual elemento está errado
+    let valor: Value = serde_json::from_str(&conteudo).map_err(|erro| erro_arquivo(erro.to_string()))?;
+    let Value::Array(elementos) = valor else {
+        return Err(erro_arquivo("o conteúdo deve ser um array de pontos".to_string()));
+    };
+
+    let mut pontos: Vec<Ponto> = Vec::with_capacity(elementos.len());
+    for (indice, elemento) in elementos.into_iter().enumerate() {
+        let ponto: Ponto = serde_json::from_value(elemento)
+            .map_err(|erro| erro_arquivo(format!("elemento {}: {}", indice, erro)))?;
+        if let Some(primeiro) = pontos.first() {
+            if ponto.caracteristicas.len() != primeiro.caracteristicas.len() {
+                return Err(erro_arquivo(format!(
+                    "elemento {} tem {} características, mas o elemento 0 tem {}",
+                    indice,
+                    ponto.caracteristicas.len(),
+                    primeiro.caracteristicas.len()
+                )));
+            }
+        }
+        pontos.push(ponto);
+    }
+    Ok(pontos)
+}
+
+// ==================== PREVISÕES EM JSON ====================
+/// Um vizinho usado em uma [`Previsao`]: a posição no treinamento, o rótulo e a distância.
+///
+/// ```
+/// use aprendizagem_knn::VizinhoPrevisao;
+///
+/// let vizinho = VizinhoPrevisao { indice: 3, rotulo: "A".to_string(), distancia: 0.5 };
+/// let json = serde_json::to_string(&vizinho).unwrap();
+/// assert_eq!(json, r#"{"indice":3,"rotulo":"A","distancia":0.5}"#);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VizinhoPrevisao {
+    /// Posição do vizinho no conjunto de treinamento
+    pub indice: usize,
+    /// Rótulo do vizinho
+    pub rotulo: String,
+    /// Distância euclidiana até o ponto classificado
+    pub distancia: f64,
+}
+
+/// A previsão do KNN para um ponto, no formato gravado em JSON.
+///
+/// Os campos têm os mesmos nomes do log de [`knn_com_log`](crate::knn_com_log), para que
+/// as mesmas ferramentas leiam os dois. `vizinhos` só aparece no JSON quando foi pedido.
+///
+/// ```
+/// use aprendizagem_knn::{pontos, prever_para_json};
+///
+/// let treinamento = pontos![[0.0] => "A", [1.0] => "A", [9.0] => "B"];
+/// let previsao = &prever_para_json(&treinamento, &pontos![[2.0] => "?"], 3, false).unwrap()[0];
+/// assert_eq!(previsao.rotulo_previsto, "A");
+/// assert_eq!(
+///     serde_json::to_string(previsao).unwrap(),
+///     r#"{"caracteristicas":[2.0],"rotulo_previsto":"A","confianca":0.6666666666666666}"#
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Previsao {
+    /// Características do ponto classificado
+    pub caracteristicas: Vec<f64>,
+    /// Rótulo escolhido pela maioria dos vizinhos
+    pub rotulo_previsto: String,
+    /// Fração dos vizinhos que votou no rótulo previsto
+    pub confianca: f64,
+    /// Os k vizinhos, do mais próximo ao mais distante (`None` se não foram pedidos)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vizinhos: Option<Vec<VizinhoPrevisao>>,
+}
+
+/// Como gravar uma lista de previsões em JSON.
+///
+/// ```
+/// use aprendizagem_knn::{escrever_previsoes_json, pontos, prever_para_json, FormatoJson};
+///
+/// let treinamento = pontos![[0.0] => "A", [9.0] => "B"];
+/// let previsoes = prever_para_json(&treinamento, &pontos![[1.0] => "?", [8.0] => "?"], 1, false).unwrap();
+///
+/// let mut linhas = Vec::new();
+/// escrever_previsoes_json(&previsoes, &mut linhas, FormatoJson::Linhas).unwrap();
+/// assert_eq!(String::from_utf8(linhas).unwrap().lines().count(), 2);
+///
+/// let mut array = Vec::new();
+/// escrever_previsoes_json(&previsoes, &mut array, FormatoJson::Array).unwrap();
+/// assert!(String::from_utf8(array).unwrap().trim_start().starts_with('['));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormatoJson {
+    /// Um objeto por linha (JSON Lines), bom para processar aos poucos ou acrescentar linhas
+    Linhas,
+    /// Um único array com todos os objetos
+    Array,
+}
+
+/// Classifica cada ponto de teste com o KNN (distância euclidiana, como o
+/// [`knn`](fn@crate::knn)) e devolve as previsões, na ordem dos pontos, prontas para
+/// serem gravadas com [`escrever_previsoes_json`].
+///
+/// Com `incluir_vizinhos`, cada previsão leva também os `k` vizinhos usados. Os erros são
+/// os do [`knn`](fn@crate::knn): treinamento vazio é [`ErroKnn::ConjuntoVazio`] e `k`
+/// igual a 0 é [`ErroKnn::KInvalido`].
+///
+/// ```
+/// use aprendizagem_knn::{prever_para_json, pontos, ErroKnn};
+///
+/// let treinamento = pontos![[0.0] => "A", [1.0] => "A", [9.0] => "B"];
+/// let previsoes = prever_para_json(&treinamento, &pontos![[0.4] => "?"], 3, true).unwrap();
+/// assert_eq!(previsoes[0].rotulo_previsto, "A");
+/// assert_eq!(previsoes[0].confianca, 2.0 / 3.0);
+/// let vizinhos = previsoes[0].vizinhos.as_ref().unwrap();
+/// assert_eq!((vizinhos[0].indice, vizinhos[0].distancia), (0, 0.4));
+///
+/// assert_eq!(prever_para_json(&treinamento, &pontos![[0.4] => "?"], 3, false).unwrap()[0].vizinhos, None);
+///
+/// assert_eq!(prever_para_json(&[], &pontos![[0.4] => "?"], 3, false), Err(ErroKnn::ConjuntoVazio));
+/// assert_eq!(
+///     prever_para_json(&treinamento, &pontos![[0.4] => "?"], 0, false),
+///     Err(ErroKnn::KInvalido { k: 0, maximo: 3 })
+/// );
+/// ```
+pub fn prever_para_json(
+    treinamento: &[Ponto],
+    pontos_teste: &[Ponto],
+    k: usize,
+    incluir_vizinhos: bool,
+) -> Result<Vec<Previsao>, ErroKnn> {
+    validar_entrada(treinamento, k)?;
+    pontos_teste.iter()
+        .map(|ponto| {
+            let vizinhos = k_vizinhos_mais_proximos(treinamento, ponto, k, &DistanciaMetrica::Euclidiana);
+            if vizinhos.is_empty() {
+                return Err(erro_sem_vizinhos(&ponto.caracteristicas));
+            }
+            let rotulo_previsto = votar_por_maioria(vizinhos.iter().map(|vizinho| vizinho.rotulo.clone()));
+            let votos = vizinhos.iter().filter(|vizinho| vizinho.rotulo == rotulo_previsto).count();
+            Ok(Previsao {
+                caracteristicas: ponto.caracteristicas.clone(),
+                confianca: votos as f64 / vizinhos.len() as f64,
+                rotulo_previsto,
+                vizinhos: incluir_vizinhos.then(|| {
+                    vizinhos.into_iter()
+                        .map(|vizinho| VizinhoPrevisao {
+                            indice: vizinho.indice,
+                            rotulo: vizinho.rotulo,
+                            distancia: vizinho.distancia,
+                        })
+                        .collect()
+                }),
+            })
+        })
+        .collect()
+}
+
+/// Grava as previsões em JSON, uma por linha ou todas em um array (veja [`FormatoJson`]).
+///
+/// JSON não tem NaN nem infinito: características com esses valores são gravadas como
+/// `null` e não podem ser lidas de volta.
+///
+/// ```
+/// use aprendizagem_knn::{escrever_previsoes_json, prever_para_json, pontos, FormatoJson};
+///
+/// let treinamento = pontos![[0.0] => "A", [9.0] => "B"];
+/// let previsoes = prever_para_json(&treinamento, &pontos![[1.0] => "?", [8.0] => "?"], 1, false).unwrap();
+///
+/// let mut linhas = Vec::new();
+/// escrever_previsoes_json(&previsoes, &mut linhas, FormatoJson::Linhas).unwrap();
+/// assert_eq!(
+///     String::from_utf8(linhas).unwrap(),
+///     "{\"caracteristicas\":[1.0],\"rotulo_previsto\":\"A\",\"confianca\":1.0}\n\
+///      {\"caracteristicas\":[8.0],\"rotulo_previsto\":\"B\",\"confianca\":1.0}\n"
+/// );
+///
+/// let mut array = Vec::new();
+/// escrever_previsoes_json(&previsoes, &mut array, FormatoJson::Array).unwrap();
+/// assert!(String::from_utf8(array).unwrap().starts_with("[\n  {"));
+/// ```
+pub fn escrever_previsoes_json<W: Write>(
+    previsoes: &[Previsao],
+    mut destino: W,
+    formato: FormatoJson,
+) -> Result<(), Box<dyn Error>> {
+    match formato {
+        FormatoJson::Linhas => {
+            for previsao in previsoes {
+                serde_json::to_writer(&mut destino, previsao)?;
+                destino.write_all(b"\n")?;
+            }
+        }
+        FormatoJson::Array => {
+            serde_json::to_writer_pretty(&mut destino, previsoes)?;
+            destino.write_all(b"\n")?;
+        }
+    }
+    destino.flush()?;
+    Ok(())
+}
+
+/// Salva as previsões em um arquivo JSON (veja [`escrever_previsoes_json`]).
+///
+/// ```
+/// use aprendizagem_knn::{
+///     carregar_previsoes_json, prever_para_json, pontos, salvar_previsoes_json, FormatoJson,
+/// };
+///
+/// let caminho = std::env::temp_dir().join("aprendizagem_knn_doc_previsoes.jsonl");
+/// let previsoes = prever_para_json(&pontos![[0.0] => "A"], &pontos![[1.0] => "?"], 1, true).unwrap();
+/// salvar_previsoes_json(&previsoes, caminho.to_str().unwrap(), FormatoJson::Linhas).unwrap();
+/// assert_eq!(carregar_previsoes_json(caminho.to_str().unwrap()).unwrap(), previsoes);
+/// ```
+pub fn salvar_previsoes_json(
+    previsoes: &[Previsao],
+    caminho: &str,
+    formato: FormatoJson,
+) -> Result<(), Box<dyn Error>> {
+    escrever_previsoes_json(previsoes, BufWriter::new(File::create(caminho)?), formato)
+}
+
+/// Lê previsões gravadas por [`escrever_previsoes_json`], em qualquer um dos dois
+/// formatos: se o conteúdo começa com `[`, é um array; senão, um objeto por linha não vazia.
+///
+/// Devolve [`ErroKnn::Arquivo`] se o arquivo não puder ser lido ou se alguma previsão
+/// for inválida, citando a posição no array (a partir de 0) ou o número da linha (a
+/// partir de 1).
+///
+/// ```
+/// use aprendizagem_knn::carregar_previsoes_json;
+///
+/// let caminho = std::env::temp_dir().join("aprendizagem_knn_doc_previsoes_invalidas.jsonl");
+/// let conteudo = "{\"caracteristicas\":[1.0],\"rotulo_previsto\":\"A\",\"confianca\":1.0}\n\
+///                 {\"caracteristicas\":[2.0]}\n";
+/// std::fs::write(&caminho, conteudo).unwrap();
+/// let erro = carregar_previsoes_json(caminho.to_str().unwrap()).unwrap_err().to_string();
+/// assert!(erro.contains("linha 2"), "{}", erro);
+/// ```
+pub fn carregar_previsoes_json(caminho: &str) -> Result<Vec<Previsao>, ErroKnn> {
+    let erro_arquivo = |mensagem: String| ErroKnn::Arquivo(format!("{}: {}", caminho, mensagem));
+    let conteudo = fs::read_to_string(caminho).map_err(|erro| ErroKnn::de_io(caminho, &erro))?;
+
+    if conteudo.trim_start().starts_with('[') {
+        let elementos: Vec<Value> = serde_json::from_str(&conteudo).map_err(|erro| erro_arquivo(erro.to_string()))?;
+        elementos.into_iter()
+            .enumerate()
+            .map(|(indice, elemento)| {
+                serde_json::from_value(elemento).map_err(|erro| erro_arquivo(format!("elemento {}: {}", indice, erro)))
+            })
+            .collect()
+    } else {
+        conteudo.lines()
+            .enumerate()
+            .filter(|(_, linha)| !linha.trim().is_empty())
+            .map(|(numero, linha)| {
+                serde_json::from_str(linha).map_err(|erro| erro_arquivo(format!("linha {}: {}", numero + 1, erro)))
+            })
+            .collect()
+    }
+}
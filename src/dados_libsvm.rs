@@ -0,0 +1,122 @@
+//! Leitura de conjuntos de dados no formato esparso do LIBSVM/SVMlight.
+
+use std::fs;
+
+use crate::erro::ErroKnn;
+use crate::ponto::Ponto;
+
+// ==================== FORMATO LIBSVM ====================
+// Cada linha é um ponto: o rótulo seguido dos pares índice:valor das características que
+// não são zero, por exemplo "+1 1:0.5 3:2.0". Os índices começam em 1, e as
+// características que não aparecem valem 0. Muitos conjuntos de dados públicos de
+// benchmark são distribuídos assim, porque o arquivo fica pequeno quando quase tudo é zero.
+
+/// Carrega um arquivo no formato LIBSVM (`rótulo índice:valor índice:valor ...`),
+/// transformando cada linha em um ponto denso: as características ausentes viram 0.
+///
+/// O número de características é `dimensao`, se informado, ou o maior índice encontrado
+/// no arquivo. Os índices começam em 1 e podem vir fora de ordem; o que vem depois de
+/// `#` é comentário, linhas em branco são ignoradas e pares `qid:...` (do SVMlight)
+/// também. O rótulo é guardado exatamente como escrito (`+1` e `1` são rótulos diferentes).
+///
+/// Devolve [`ErroKnn::Arquivo`], citando o número da linha, para um par mal formado, um
+/// índice 0, repetido ou maior que `dimensao`, ou um valor que não é número.
+///
+/// ```
+/// use aprendizagem_knn::carregar_dados_do_libsvm;
+///
+/// let caminho = std::env::temp_dir().join("aprendizagem_knn_doc_dados.libsvm");
+/// std::fs::write(&caminho, "+1 1:0.5 3:2\n-1 2:1.5\n").unwrap();
+///
+/// let dados = carregar_dados_do_libsvm(caminho.to_str().unwrap(), None).unwrap();
+/// assert_eq!(dados[0].caracteristicas, vec![0.5, 0.0, 2.0]);
+/// assert_eq!(dados[1].caracteristicas, vec![0.0, 1.5, 0.0]);
+/// assert_eq!(dados[1].rotulo, "-1");
+///
+/// // Com a dimensão informada, as características a mais também valem 0
+/// let dados = carregar_dados_do_libsvm(caminho.to_str().unwrap(), Some(5)).unwrap();
+/// assert_eq!(dados[1].caracteristicas, vec![0.0, 1.5, 0.0, 0.0, 0.0]);
+/// ```
+pub fn carregar_dados_do_libsvm(caminho: &str, dimensao: Option<usize>) -> Result<Vec<Ponto>, ErroKnn> {
+    let conteudo = fs::read_to_string(caminho).map_err(|erro| ErroKnn::de_io(caminho, &erro))?;
+    ler_libsvm(&conteudo, caminho, dimensao)
+}
+
+/// Como [`carregar_dados_do_libsvm`], mas com o conteúdo já em memória (as mensagens de
+/// erro citam a origem `<texto>`).
+///
+/// ```
+/// use aprendizagem_knn::carregar_dados_de_texto_libsvm;
+///
+/// let texto = "# comentário\n\n2 3:1.0 1:4.0  # índices fora de ordem\n1 qid:7 2:-1\n";
+/// let dados = carregar_dados_de_texto_libsvm(texto, None).unwrap();
+/// assert_eq!(dados.len(), 2);
+/// assert_eq!(dados[0].caracteristicas, vec![4.0, 0.0, 1.0]);
+/// assert_eq!(dados[1].caracteristicas, vec![0.0, -1.0, 0.0]);
+///
+/// let erro = carregar_dados_de_texto_libsvm("1 1:2\n1 0:3\n", None).unwrap_err().to_string();
+/// assert!(erro.contains("linha 2") && erro.contains("começam em 1"), "{}", erro);
+/// let erro = carregar_dados_de_texto_libsvm("1 1:2 1:3\n", None).unwrap_err().to_string();
+/// assert!(erro.contains("linha 1") && erro.contains("repetido"), "{}", erro);
+/// let erro = carregar_dados_de_texto_libsvm("1 4:2\n", Some(3)).unwrap_err().to_string();
+/// assert!(erro.contains("maior que a dimensão 3"), "{}", erro);
+/// assert!(carregar_dados_de_texto_libsvm("1 2:abc\n", None).is_err());
+/// assert!(carregar_dados_de_texto_libsvm("1 2\n", None).is_err());
+/// ```
+pub fn carregar_dados_de_texto_libsvm(conteudo: &str, dimensao: Option<usize>) -> Result<Vec<Ponto>, ErroKnn> {
+    ler_libsvm(conteudo, "<texto>", dimensao)
+}
+
+// Um ponto ainda esparso: o rótulo e os pares (índice a partir de 0, valor)
+type PontoEsparso = (String, Vec<(usize, f64)>);
+
+fn ler_libsvm(conteudo: &str, origem: &str, dimensao: Option<usize>) -> Result<Vec<Ponto>, ErroKnn> {
+    // Primeira passada: lê os pares de cada linha, para saber o maior índice
+    let mut esparsos: Vec<PontoEsparso> = Vec::new();
+    let mut maior_indice = 0;
+    for (numero, linha) in conteudo.lines().enumerate() {
+        let erro_linha = |mensagem: String| ErroKnn::Arquivo(format!("{}: linha {}: {}", origem, numero + 1, mensagem));
+        let sem_comentario = linha.split('#').next().unwrap_or("");
+        let mut partes = sem_comentario.split_whitespace();
+        let Some(rotulo) = partes.next() else {
+            continue;
+        };
+
+        let mut pares: Vec<(usize, f64)> = Vec::new();
+        for par in partes {
+            let (indice, valor) = par.split_once(':')
+                .ok_or_else(|| erro_linha(format!("'{}' não está no formato índice:valor", par)))?;
+            if indice == "qid" {
+                continue;
+            }
+            let indice: usize = indice.parse()
+                .map_err(|_| erro_linha(format!("índice inválido '{}' em '{}'", indice, par)))?;
+            if indice == 0 {
+                return Err(erro_linha(format!("'{}': os índices começam em 1", par)));
+            }
+            if let Some(limite) = dimensao.filter(|&limite| indice > limite) {
+                return Err(erro_linha(format!("o índice {} é maior que a dimensão {}", indice, limite)));
+            }
+            let valor: f64 = valor.parse()
+                .map_err(|_| erro_linha(format!("valor numérico inválido '{}' em '{}'", valor, par)))?;
+            if pares.iter().any(|&(existente, _)| existente == indice - 1) {
+                return Err(erro_linha(format!("o índice {} está repetido", indice)));
+            }
+            maior_indice = maior_indice.max(indice);
+            pares.push((indice - 1, valor));
+        }
+        esparsos.push((rotulo.to_string(), pares));
+    }
+
+    // Segunda passada: todos os pontos com o mesmo número de características
+    let dimensao = dimensao.unwrap_or(maior_indice);
+    Ok(esparsos.into_iter()
+        .map(|(rotulo, pares)| {
+            let mut caracteristicas = vec![0.0; dimensao];
+            for (indice, valor) in pares {
+                caracteristicas[indice] = valor;
+            }
+            Ponto::novo(caracteristicas, rotulo)
+        })
+        .collect())
+}
@@ -0,0 +1,230 @@
+//! Leitura e gravação de conjuntos de dados em Apache Parquet (feature `parquet`).
+//!
+//! Compile com `cargo build --features parquet`. O arquivo é lido e gravado em lotes
+//! (record batches do Arrow), então só um pedaço dele fica na memória de cada vez, além
+//! dos pontos.
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::sync::Arc;
+
+use arrow_array::cast::AsArray;
+use arrow_array::types::Float64Type;
+use arrow_array::{Array, ArrayRef, Float64Array, RecordBatch, StringArray};
+use arrow_schema::{DataType, Field, Schema};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+
+use crate::erro::ErroKnn;
+use crate::ponto::Ponto;
+
+// ==================== FORMATO PARQUET ====================
+// Parquet guarda a tabela por colunas, cada uma com um tipo (inteiro, decimal, texto...).
+// A coluna de rótulo precisa ser de texto e todas as outras numéricas: cada linha da
+// tabela vira um Ponto com as colunas numéricas, na ordem do arquivo, como características.
+
+/// Carrega um arquivo Parquet, usando a coluna `coluna_rotulo` (de texto) como rótulo e
+/// todas as demais (numéricas: inteiros, decimais ou ponto flutuante) como características,
+/// na ordem em que aparecem no arquivo.
+///
+/// Os tipos são conferidos no esquema antes de ler os dados, e o arquivo é lido em lotes.
+/// Um valor nulo numa característica vira `NaN` (veja [`Imputador`](crate::Imputador));
+/// um rótulo nulo é erro. Arquivos comprimidos com Snappy são aceitos.
+///
+/// Um arquivo que não existe é [`ErroKnn::ArquivoNaoEncontrado`]. Devolve
+/// [`ErroKnn::Arquivo`] se o arquivo não for Parquet, se a coluna de rótulo não existir ou
+/// não for de texto, se outra coluna não for numérica (a mensagem
+/// cita a coluna e o tipo) ou se algum rótulo for nulo (citando a linha).
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use aprendizagem_knn::carregar_dados_do_parquet;
+/// use arrow_array::{ArrayRef, Float64Array, Int32Array, RecordBatch, StringArray};
+/// use parquet::arrow::ArrowWriter;
+///
+/// let lote = RecordBatch::try_from_iter([
+///     ("altura", Arc::new(Float64Array::from(vec![1.5, 2.5])) as ArrayRef),
+///     ("classe", Arc::new(StringArray::from(vec!["A", "B"])) as ArrayRef),
+///     ("idade", Arc::new(Int32Array::from(vec![10, 20])) as ArrayRef),
+/// ])
+/// .unwrap();
+/// let caminho = std::env::temp_dir().join("aprendizagem_knn_doc_dados.parquet");
+/// let mut escritor = ArrowWriter::try_new(std::fs::File::create(&caminho).unwrap(), lote.schema(), None).unwrap();
+/// escritor.write(&lote).unwrap();
+/// escritor.close().unwrap();
+///
+/// let dados = carregar_dados_do_parquet(caminho.to_str().unwrap(), "classe").unwrap();
+/// assert_eq!(dados[1].caracteristicas, vec![2.5, 20.0]);
+/// assert_eq!(dados[1].rotulo, "B");
+///
+/// let erro = carregar_dados_do_parquet(caminho.to_str().unwrap(), "idade").unwrap_err().to_string();
+/// assert!(erro.contains("'idade'") && erro.contains("Int32"), "{}", erro);
+/// ```
+pub fn carregar_dados_do_parquet(caminho: &str, coluna_rotulo: &str) -> Result<Vec<Ponto>, ErroKnn> {
+    let erro = |mensagem: String| ErroKnn::Arquivo(format!("{}: {}", caminho, mensagem));
+    let arquivo = File::open(caminho).map_err(|e| ErroKnn::de_io(caminho, &e))?;
+    let construtor = ParquetRecordBatchReaderBuilder::try_new(arquivo)
+        .map_err(|e| erro(format!("não foi possível ler como Parquet: {}", e)))?;
+
+    // Confere os tipos no esquema, antes de ler qualquer dado
+    let esquema = construtor.schema().clone();
+    let indice_rotulo = esquema
+        .index_of(coluna_rotulo)
+        .map_err(|_| erro(format!("não há nenhuma coluna chamada '{}' para o rótulo", coluna_rotulo)))?;
+    let tipo_rotulo = esquema.field(indice_rotulo).data_type();
+    if !eh_texto(tipo_rotulo) {
+        return Err(erro(format!(
+            "a coluna de rótulo '{}' é do tipo {}, mas precisa ser de texto (Utf8)",
+            coluna_rotulo, tipo_rotulo
+        )));
+    }
+    let mut indices_caracteristicas = Vec::new();
+    for (indice, campo) in esquema.fields().iter().enumerate() {
+        if indice == indice_rotulo {
+            continue;
+        }
+        if !campo.data_type().is_numeric() {
+            return Err(erro(format!(
+                "a coluna '{}' é do tipo {}, mas as características precisam ser numéricas",
+                campo.name(),
+                campo.data_type()
+            )));
+        }
+        indices_caracteristicas.push(indice);
+    }
+    if indices_caracteristicas.is_empty() {
+        return Err(erro("o arquivo não tem nenhuma coluna além do rótulo".to_string()));
+    }
+
+    let leitor = construtor.build().map_err(|e| erro(e.to_string()))?;
+    let mut dados = Vec::new();
+    for lote in leitor {
+        let lote = lote.map_err(|e| erro(e.to_string()))?;
+        // cast converte qualquer tipo numérico para f64 e qualquer texto (inclusive
+        // dicionário) para Utf8, já conferidos acima
+        let colunas: Vec<_> = indices_caracteristicas
+            .iter()
+            .map(|&indice| arrow_cast::cast(lote.column(indice), &DataType::Float64))
+            .collect::<Result<_, _>>()
+            .map_err(|e| erro(e.to_string()))?;
+        let colunas: Vec<_> = colunas.iter().map(|coluna| coluna.as_primitive::<Float64Type>()).collect();
+        let rotulos = arrow_cast::cast(lote.column(indice_rotulo), &DataType::Utf8).map_err(|e| erro(e.to_string()))?;
+        let rotulos = rotulos.as_string::<i32>();
+
+        for linha in 0..lote.num_rows() {
+            if rotulos.is_null(linha) {
+                return Err(erro(format!("linha {}: o rótulo ('{}') é nulo", dados.len() + 1, coluna_rotulo)));
+            }
+            let caracteristicas = colunas
+                .iter()
+                .map(|coluna| if coluna.is_null(linha) { f64::NAN } else { coluna.value(linha) })
+                .collect();
+            dados.push(Ponto::novo(caracteristicas, rotulos.value(linha).to_string()));
+        }
+    }
+    Ok(dados)
+}
+
+// Texto em qualquer uma das representações do Arrow, inclusive como dicionário
+fn eh_texto(tipo: &DataType) -> bool {
+    match tipo {
+        DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View => true,
+        DataType::Dictionary(_, valores) => eh_texto(valores),
+        _ => false,
+    }
+}
+
+// ==================== GRAVAÇÃO EM PARQUET ====================
+// O caminho inverso da leitura: as características viram colunas Float64 e o rótulo uma
+// coluna de texto, e o arquivo pode ser lido de volta por carregar_dados_do_parquet.
+
+// Nome da coluna de rótulo nos arquivos gravados por exportar_pontos_parquet
+const COLUNA_ROTULO_PARQUET: &str = "rotulo";
+
+// Linhas por lote (record batch) na gravação: cada lote é montado na memória e gravado
+const LINHAS_POR_LOTE: usize = 8192;
+
+/// Grava pontos num arquivo Parquet, para abrir em outras ferramentas (pandas, Polars,
+/// Spark, DuckDB...) sem passar por CSV.
+///
+/// Cada característica vira uma coluna `Float64`, com o nome correspondente em
+/// `nomes_features`, e o rótulo vira a coluna `Utf8` `"rotulo"`, a última. Serve tanto
+/// para o treinamento quanto para previsões (pontos com o rótulo previsto). O arquivo é
+/// gravado em lotes e comprimido com Snappy, e pode ser lido de volta com
+/// [`carregar_dados_do_parquet`] usando `"rotulo"` como coluna de rótulo. Um `NaN` é
+/// gravado como está (não como nulo).
+///
+/// Erros:
+/// - [`ErroKnn::DimensaoIncompativel`] se algum ponto não tiver uma característica para
+///   cada nome;
+/// - [`ErroKnn::ParametroInvalido`] se `nomes_features` estiver vazio, tiver nomes
+///   repetidos ou usar o nome `"rotulo"`;
+/// - [`ErroKnn::Arquivo`] se o arquivo não puder ser criado ou gravado.
+///
+/// ```
+/// use aprendizagem_knn::{carregar_dados_do_parquet, exportar_pontos_parquet, pontos, ErroKnn};
+///
+/// let dados = pontos![[5.1, 3.5] => "setosa", [7.0, 3.2] => "versicolor"];
+/// let nomes = vec!["comprimento".to_string(), "largura".to_string()];
+/// let caminho = std::env::temp_dir().join("aprendizagem_knn_doc_exportar.parquet");
+/// let caminho = caminho.to_str().unwrap();
+///
+/// exportar_pontos_parquet(&dados, &nomes, caminho).unwrap();
+/// assert_eq!(carregar_dados_do_parquet(caminho, "rotulo").unwrap(), dados);
+///
+/// // Um nome para cada característica
+/// let erro = exportar_pontos_parquet(&dados, &nomes[..1], caminho).unwrap_err();
+/// assert_eq!(erro, ErroKnn::DimensaoIncompativel { esperado: 1, obtido: 2 });
+/// ```
+pub fn exportar_pontos_parquet(dados: &[Ponto], nomes_features: &[String], caminho: &str) -> Result<(), ErroKnn> {
+    if nomes_features.is_empty() {
+        return Err(ErroKnn::ParametroInvalido("é preciso ao menos um nome de característica".to_string()));
+    }
+    let mut vistos = HashSet::new();
+    for nome in nomes_features {
+        if nome == COLUNA_ROTULO_PARQUET {
+            return Err(ErroKnn::ParametroInvalido(format!(
+                "'{}' é o nome da coluna de rótulo e não pode ser o de uma característica",
+                COLUNA_ROTULO_PARQUET
+            )));
+        }
+        if !vistos.insert(nome) {
+            return Err(ErroKnn::ParametroInvalido(format!("a característica '{}' aparece duas vezes", nome)));
+        }
+    }
+    // Confere todos os pontos antes de criar o arquivo, para não deixar um arquivo pela metade
+    if let Some(ponto) = dados.iter().find(|ponto| ponto.caracteristicas.len() != nomes_features.len()) {
+        return Err(ErroKnn::DimensaoIncompativel {
+            esperado: nomes_features.len(),
+            obtido: ponto.caracteristicas.len(),
+        });
+    }
+
+    let erro = |mensagem: String| ErroKnn::Arquivo(format!("{}: {}", caminho, mensagem));
+    let mut campos: Vec<Field> = nomes_features.iter().map(|nome| Field::new(nome, DataType::Float64, false)).collect();
+    campos.push(Field::new(COLUNA_ROTULO_PARQUET, DataType::Utf8, false));
+    let esquema = Arc::new(Schema::new(campos));
+
+    let arquivo = File::create(caminho).map_err(|e| ErroKnn::de_io(caminho, &e))?;
+    let propriedades = WriterProperties::builder().set_compression(Compression::SNAPPY).build();
+    let mut escritor =
+        ArrowWriter::try_new(arquivo, esquema.clone(), Some(propriedades)).map_err(|e| erro(e.to_string()))?;
+
+    // Parquet é por colunas: cada lote monta uma coluna por característica, mais a do rótulo
+    for lote in dados.chunks(LINHAS_POR_LOTE) {
+        let mut colunas: Vec<ArrayRef> = (0..nomes_features.len())
+            .map(|coluna| {
+                let valores = lote.iter().map(|ponto| ponto.caracteristicas[coluna]);
+                Arc::new(Float64Array::from_iter_values(valores)) as ArrayRef
+            })
+            .collect();
+        colunas.push(Arc::new(StringArray::from_iter_values(lote.iter().map(|ponto| &ponto.rotulo))));
+        let lote = RecordBatch::try_new(esquema.clone(), colunas).map_err(|e| erro(e.to_string()))?;
+        escritor.write(&lote).map_err(|e| erro(e.to_string()))?;
+    }
+    escritor.close().map_err(|e| erro(e.to_string()))?;
+    Ok(())
+}
@@ -0,0 +1,152 @@
+//! Leitura de conjuntos de dados de um banco SQLite (feature `sqlite`).
+//!
+//! Compile com `cargo build --features sqlite`. O SQLite vem junto (feature `bundled` do
+//! rusqlite), então não é preciso tê-lo instalado no sistema.
+
+use rusqlite::types::ValueRef;
+use rusqlite::{Connection, OpenFlags};
+
+use crate::dados::{eh_valor_ausente, interpretar_valor};
+use crate::erro::ErroKnn;
+use crate::ponto::Ponto;
+
+// ==================== CONSULTA SQLITE ====================
+// O resultado da consulta é tratado como a tabela de um CSV: cada linha vira um Ponto, a
+// coluna de rótulo vira o rótulo e as demais, na ordem do SELECT, as características.
+// No SQLite o tipo é de cada valor (não da coluna), por isso a conferência é feita valor
+// a valor: inteiros e reais são usados direto, e textos são lidos como no CSV.
+
+/// Abre o banco em `caminho_db` (só para leitura), executa `consulta_sql` e converte o
+/// resultado em pontos: a coluna `coluna_rotulo` é o rótulo e todas as outras colunas do
+/// resultado, na ordem do `SELECT`, são as características.
+///
+/// Veja [`carregar_dados_de_conexao_sqlite`] para as regras de conversão e os erros.
+///
+/// ```
+/// use aprendizagem_knn::carregar_dados_do_sqlite;
+///
+/// let caminho = std::env::temp_dir().join("aprendizagem_knn_doc_dados.sqlite");
+/// let _ = std::fs::remove_file(&caminho);
+/// let conexao = rusqlite::Connection::open(&caminho).unwrap();
+/// conexao
+///     .execute_batch("CREATE TABLE flores (x REAL, y REAL, especie TEXT);
+///                     INSERT INTO flores VALUES (1.0, 2.0, 'A'), (8.0, 9.0, 'B');")
+///     .unwrap();
+///
+/// let dados = carregar_dados_do_sqlite(
+///     caminho.to_str().unwrap(),
+///     "SELECT x, y, especie FROM flores ORDER BY x",
+///     "especie",
+/// )
+/// .unwrap();
+/// assert_eq!(dados[1].caracteristicas, vec![8.0, 9.0]);
+/// assert_eq!(dados[1].rotulo, "B");
+/// ```
+pub fn carregar_dados_do_sqlite(
+    caminho_db: &str,
+    consulta_sql: &str,
+    coluna_rotulo: &str,
+) -> Result<Vec<Ponto>, ErroKnn> {
+    let conexao = Connection::open_with_flags(caminho_db, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|erro| ErroKnn::Arquivo(format!("{}: {}", caminho_db, erro)))?;
+    ler_consulta(&conexao, caminho_db, consulta_sql, coluna_rotulo)
+}
+
+/// Como [`carregar_dados_do_sqlite`], mas com uma conexão já aberta (útil para um banco em
+/// memória ou para consultas em uma transação).
+///
+/// Os valores das características seguem a mesma política de ausentes do CSV
+/// ([`carregar_dados_do_csv_com_ausentes`](crate::carregar_dados_do_csv_com_ausentes)):
+/// `NULL`, texto vazio, `"NA"` e `"?"` viram `f64::NAN`. Inteiros e reais são usados
+/// direto, e texto com um número (`'4.5'`) é convertido. Qualquer outro texto, ou um BLOB,
+/// é erro citando a linha do resultado e o nome da coluna.
+///
+/// O rótulo pode ser texto ou inteiro (a classe `3` vira o rótulo `"3"`); `NULL`, real ou
+/// BLOB na coluna de rótulo é erro. Também é erro se a consulta for inválida, se não
+/// tiver a coluna de rótulo ou se não tiver nenhuma outra coluna.
+///
+/// ```
+/// use aprendizagem_knn::carregar_dados_de_conexao_sqlite;
+/// use rusqlite::Connection;
+///
+/// let conexao = Connection::open_in_memory().unwrap();
+/// conexao
+///     .execute_batch("CREATE TABLE t (x, classe); INSERT INTO t VALUES (1, 'A'), (NULL, 'B'), ('abc', 'C');")
+///     .unwrap();
+///
+/// let dados = carregar_dados_de_conexao_sqlite(&conexao, "SELECT x, classe FROM t LIMIT 2", "classe").unwrap();
+/// assert_eq!(dados[0].caracteristicas, vec![1.0]);
+/// assert!(dados[1].caracteristicas[0].is_nan());
+///
+/// let erro = carregar_dados_de_conexao_sqlite(&conexao, "SELECT x, classe FROM t", "classe").unwrap_err();
+/// assert!(erro.to_string().contains("linha 3, coluna 'x'"), "{}", erro);
+/// ```
+pub fn carregar_dados_de_conexao_sqlite(
+    conexao: &Connection,
+    consulta_sql: &str,
+    coluna_rotulo: &str,
+) -> Result<Vec<Ponto>, ErroKnn> {
+    ler_consulta(conexao, "<conexão>", consulta_sql, coluna_rotulo)
+}
+
+fn ler_consulta(
+    conexao: &Connection,
+    origem: &str,
+    consulta_sql: &str,
+    coluna_rotulo: &str,
+) -> Result<Vec<Ponto>, ErroKnn> {
+    let erro = |mensagem: String| ErroKnn::Arquivo(format!("{}: {}", origem, mensagem));
+    let mut comando = conexao
+        .prepare(consulta_sql)
+        .map_err(|e| erro(format!("consulta inválida: {}", e)))?;
+    let colunas: Vec<String> = comando.column_names().into_iter().map(String::from).collect();
+    let indice_rotulo = colunas
+        .iter()
+        .position(|coluna| coluna == coluna_rotulo)
+        .ok_or_else(|| {
+            erro(format!("a consulta não tem a coluna de rótulo '{}' (tem {:?})", coluna_rotulo, colunas))
+        })?;
+    if colunas.len() < 2 {
+        return Err(erro("a consulta não tem nenhuma coluna além do rótulo".to_string()));
+    }
+
+    let mut linhas = comando.query([]).map_err(|e| erro(e.to_string()))?;
+    let mut dados = Vec::new();
+    while let Some(linha) = linhas.next().map_err(|e| erro(e.to_string()))? {
+        let numero = dados.len() + 1;
+        let mut caracteristicas = Vec::with_capacity(colunas.len() - 1);
+        let mut rotulo = String::new();
+        for (indice, coluna) in colunas.iter().enumerate() {
+            let valor = linha.get_ref(indice).map_err(|e| erro(e.to_string()))?;
+            let erro_valor = |mensagem: String| erro(format!("linha {}, coluna '{}': {}", numero, coluna, mensagem));
+            if indice == indice_rotulo {
+                rotulo = match valor {
+                    ValueRef::Text(texto) => String::from_utf8_lossy(texto).into_owned(),
+                    ValueRef::Integer(inteiro) => inteiro.to_string(),
+                    ValueRef::Null => return Err(erro_valor("o rótulo é NULL".to_string())),
+                    _ => {
+                        let tipo = valor.data_type();
+                        return Err(erro_valor(format!("o rótulo precisa ser texto ou inteiro, não {:?}", tipo)));
+                    }
+                };
+                continue;
+            }
+            caracteristicas.push(match valor {
+                ValueRef::Integer(inteiro) => inteiro as f64,
+                ValueRef::Real(real) => real,
+                ValueRef::Null => f64::NAN,
+                ValueRef::Text(texto) => {
+                    let texto = String::from_utf8_lossy(texto);
+                    match interpretar_valor(&texto) {
+                        Some(numero) => numero,
+                        None if eh_valor_ausente(&texto) => f64::NAN,
+                        None => return Err(erro_valor(format!("valor numérico inválido '{}'", texto))),
+                    }
+                }
+                ValueRef::Blob(_) => return Err(erro_valor("um BLOB não pode ser característica".to_string())),
+            });
+        }
+        dados.push(Ponto::novo(caracteristicas, rotulo));
+    }
+    Ok(dados)
+}
@@ -0,0 +1,31 @@
+//! Logger das mensagens de diagnóstico, usado pela linha de comando (feature `cli`).
+//!
+//! A biblioteca escreve seus avisos com as macros do crate `log` (`log::warn!`,
+//! `log::info!`), que não mostram nada até que algum logger seja instalado. Assim quem
+//! usa a biblioteca decide para onde as mensagens vão (ou se são ignoradas), ligando o
+//! logger que preferir; o binário usa [`inicializar_logger`].
+
+// ==================== LOGGER ====================
+/// Instala um logger do `env_logger` que escreve no stderr as mensagens a partir do `nivel`
+/// (`"error"`, `"warn"`, `"info"`, `"debug"` ou `"trace"`; também aceita filtros por módulo
+/// como `"aprendizagem_knn=debug"`).
+///
+/// A variável de ambiente `RUST_LOG`, se definida, tem prioridade sobre o `nivel`. Se já
+/// houver um logger instalado (por exemplo, o do programa que usa a biblioteca), a função
+/// não faz nada: o logger só pode ser definido uma vez por processo.
+///
+/// ```
+/// use aprendizagem_knn::inicializar_logger;
+///
+/// inicializar_logger("warn");
+/// assert_eq!(log::max_level(), log::LevelFilter::Warn);
+///
+/// // A segunda chamada não troca o logger já instalado
+/// inicializar_logger("trace");
+/// assert_eq!(log::max_level(), log::LevelFilter::Warn);
+/// ```
+pub fn inicializar_logger(nivel: &str) {
+    // try_init usa log::set_boxed_logger, que falha (sem pânico) se outro logger já existe
+    let ambiente = env_logger::Env::default().default_filter_or(nivel);
+    let _ = env_logger::Builder::from_env(ambiente).try_init();
+}
@@ -0,0 +1,675 @@
+//! Funções de distância entre pontos e a escolha da métrica usada pelo KNN.
+
+use std::str::FromStr;
+
+use crate::erro::ErroKnn;
+use crate::ponto::{Ponto, PontoGenerico};
+
+// ==================== FUNÇÃO DE DISTÂNCIA ====================
+// fn define uma função "solta" (não associada a uma estrutura)
+// &Ponto indica uma referência a um Ponto (sem transferir propriedade)
+/// Distância euclidiana ("em linha reta"): √Σ(ai - bi)².
+///
+/// Só as características entram na conta, então os pontos podem ter rótulos de qualquer
+/// tipo (veja [`PontoGenerico`]).
+///
+/// # Exemplo
+///
+/// ```
+/// use aprendizagem_knn::{distancia_euclidiana, ponto};
+///
+/// let a = ponto!([0.0, 0.0], "A");
+/// let b = ponto!([3.0, 4.0], "B");
+/// assert_eq!(distancia_euclidiana(&a, &b), 5.0);
+/// ```
+pub fn distancia_euclidiana<R>(ponto1: &PontoGenerico<R>, ponto2: &PontoGenerico<R>) -> f64 {
+    ponto1.caracteristicas.iter()     // iter() cria um iterador sobre as características
+        .zip(ponto2.caracteristicas.iter()) // zip combina dois iteradores em pares
+        .map(|(a, b)| (a - b).powi(2))     // map transforma cada par em sua diferença ao quadrado
+        .sum::<f64>()                       // soma todos os valores (anotação de tipo explícita)
+        .sqrt()                             // calcula a raiz quadrada
+}
+
+/// Distância de Manhattan: soma das diferenças absolutas (como andar por quarteirões).
+///
+/// ```
+/// use aprendizagem_knn::{distancia_manhattan, ponto};
+///
+/// assert_eq!(distancia_manhattan(&ponto!([0.0, 0.0], "A"), &ponto!([3.0, 4.0], "B")), 7.0);
+/// ```
+pub fn distancia_manhattan(ponto1: &Ponto, ponto2: &Ponto) -> f64 {
+    ponto1.caracteristicas.iter()
+        .zip(ponto2.caracteristicas.iter())
+        .map(|(a, b)| (a - b).abs())
+        .sum()
+}
+
+/// Distância de Chebyshev: a maior diferença absoluta entre as características.
+///
+/// ```
+/// use aprendizagem_knn::{distancia_chebyshev, ponto};
+///
+/// assert_eq!(distancia_chebyshev(&ponto!([0.0, 0.0], "A"), &ponto!([3.0, 4.0], "B")), 4.0);
+/// ```
+pub fn distancia_chebyshev(ponto1: &Ponto, ponto2: &Ponto) -> f64 {
+    ponto1.caracteristicas.iter()
+        .zip(ponto2.caracteristicas.iter())
+        .map(|(a, b)| (a - b).abs())
+        .fold(0.0, f64::max)
+}
+
+/// Distância de Minkowski: (Σ|ai - bi|^p)^(1/p).
+///
+/// Generaliza as anteriores: p = 1 é Manhattan, p = 2 é Euclidiana e,
+/// no limite p → ∞, vira Chebyshev (tratado como caso especial).
+/// Devolve erro se p não for maior que zero.
+///
+/// ```
+/// use aprendizagem_knn::{distancia_minkowski, ponto};
+///
+/// let a = ponto!([0.0, 0.0], "A");
+/// let b = ponto!([3.0, 4.0], "B");
+/// assert_eq!(distancia_minkowski(&a, &b, 1.0).unwrap(), 7.0);
+/// assert_eq!(distancia_minkowski(&a, &b, f64::INFINITY).unwrap(), 4.0);
+/// assert!(distancia_minkowski(&a, &b, 0.0).is_err());
+/// ```
+pub fn distancia_minkowski(ponto1: &Ponto, ponto2: &Ponto, p: f64) -> Result<f64, ErroKnn> {
+    validar_p_minkowski(p)?;
+    if p == f64::INFINITY {
+        return Ok(distancia_chebyshev(ponto1, ponto2));
+    }
+
+    let soma = ponto1.caracteristicas.iter()
+        .zip(ponto2.caracteristicas.iter())
+        .map(|(a, b)| (a - b).abs().powf(p))
+        .sum::<f64>();
+    Ok(soma.powf(1.0 / p))
+}
+
+/// Distância de correlação: 1 - r, em que r é o coeficiente de correlação de Pearson.
+///
+/// Compara o "formato" dos vetores e ignora diferenças de escala e deslocamento:
+/// 0 = perfeitamente correlacionados, 1 = sem correlação, 2 = correlação negativa perfeita.
+/// Se algum vetor tiver variância zero (todas as características iguais) a correlação
+/// não está definida, e devolvemos 1.0 como distância máxima "neutra".
+///
+/// ```
+/// use aprendizagem_knn::{distancia_correlacao_pearson, ponto};
+///
+/// let a = ponto!([1.0, 2.0, 3.0], "A");
+/// let b = ponto!([10.0, 20.0, 30.0], "B");
+/// assert!(distancia_correlacao_pearson(&a, &b).abs() < 1e-12);
+/// ```
+pub fn distancia_correlacao_pearson(ponto1: &Ponto, ponto2: &Ponto) -> f64 {
+    let n = ponto1.caracteristicas.len().min(ponto2.caracteristicas.len());
+    if n == 0 {
+        return 1.0;
+    }
+    let a = &ponto1.caracteristicas[..n];
+    let b = &ponto2.caracteristicas[..n];
+
+    let media_a = a.iter().sum::<f64>() / n as f64;
+    let media_b = b.iter().sum::<f64>() / n as f64;
+
+    let mut covariancia = 0.0;
+    let mut variancia_a = 0.0;
+    let mut variancia_b = 0.0;
+    for (x, y) in a.iter().zip(b) {
+        covariancia += (x - media_a) * (y - media_b);
+        variancia_a += (x - media_a).powi(2);
+        variancia_b += (y - media_b).powi(2);
+    }
+
+    if variancia_a == 0.0 || variancia_b == 0.0 {
+        return 1.0;
+    }
+
+    // clamp protege contra erros de arredondamento que levariam r para fora de [-1, 1]
+    let r = (covariancia / (variancia_a * variancia_b).sqrt()).clamp(-1.0, 1.0);
+    1.0 - r
+}
+
+/// Distância de Canberra: Σ |ai - bi| / (|ai| + |bi|).
+///
+/// Cada termo fica entre 0 e 1, então pequenas mudanças perto de zero pesam muito
+/// e grandes diferenças absolutas pesam pouco (útil para rankings e proporções).
+/// Quando ai e bi são ambos zero o termo seria 0/0; nesse caso ele contribui com 0.
+///
+/// ```
+/// use aprendizagem_knn::{distancia_canberra, ponto};
+///
+/// assert_eq!(distancia_canberra(&ponto!([0.0, 1.0], "A"), &ponto!([0.0, 3.0], "B")), 0.5);
+/// ```
+pub fn distancia_canberra(ponto1: &Ponto, ponto2: &Ponto) -> f64 {
+    ponto1.caracteristicas.iter()
+        .zip(ponto2.caracteristicas.iter())
+        .map(|(a, b)| {
+            let denominador = a.abs() + b.abs();
+            if denominador == 0.0 { 0.0 } else { (a - b).abs() / denominador }
+        })
+        .sum()
+}
+
+/// Distância euclidiana parcial: usa apenas as dimensões presentes (não NaN) nos dois pontos.
+///
+/// O resultado é reescalado pela fração de dimensões usadas, para que pontos com dados
+/// faltando não pareçam artificialmente próximos.
+/// Sem nenhuma dimensão em comum, a distância é infinita.
+///
+/// ```
+/// use aprendizagem_knn::{distancia_euclidiana_parcial, ponto};
+///
+/// let a = ponto!([0.0, f64::NAN], "A");
+/// let b = ponto!([3.0, 1.0], "B");
+/// // só a primeira dimensão é usada: √(9 * 2 / 1)
+/// assert_eq!(distancia_euclidiana_parcial(&a, &b), 18f64.sqrt());
+/// ```
+pub fn distancia_euclidiana_parcial(ponto1: &Ponto, ponto2: &Ponto) -> f64 {
+    let total = ponto1.caracteristicas.len().min(ponto2.caracteristicas.len());
+    let mut usadas = 0;
+    let mut soma = 0.0;
+    for (a, b) in ponto1.caracteristicas.iter().zip(ponto2.caracteristicas.iter()) {
+        if !a.is_nan() && !b.is_nan() {
+            usadas += 1;
+            soma += (a - b).powi(2);
+        }
+    }
+
+    if usadas == 0 {
+        return f64::INFINITY;
+    }
+    (soma * total as f64 / usadas as f64).sqrt()
+}
+
+/// Distância euclidiana ponderada: √Σ wi (ai - bi)².
+///
+/// Cada peso diz o quanto a característica importa: peso 0 ignora a característica
+/// e peso 2 conta a diferença ao quadrado em dobro. Os pesos podem ser escolhidos à mão
+/// ou aprendidos dos dados com [`ajustar_pesos_informacao_mutua`](crate::ajustar_pesos_informacao_mutua).
+///
+/// ```
+/// use aprendizagem_knn::{distancia_euclidiana_ponderada, ponto};
+///
+/// let a = ponto!([0.0, 0.0], "A");
+/// let b = ponto!([3.0, 4.0], "B");
+/// assert_eq!(distancia_euclidiana_ponderada(&a, &b, &[1.0, 0.0]), 3.0);
+/// assert_eq!(distancia_euclidiana_ponderada(&a, &b, &[1.0, 1.0]), 5.0);
+/// ```
+pub fn distancia_euclidiana_ponderada(ponto1: &Ponto, ponto2: &Ponto, pesos: &[f64]) -> f64 {
+    ponto1.caracteristicas.iter()
+        .zip(ponto2.caracteristicas.iter())
+        .zip(pesos)
+        .map(|((a, b), peso)| peso * (a - b).powi(2))
+        .sum::<f64>()
+        .sqrt()
+}
+
+// ==================== DISTÂNCIA PARA SÉRIES TEMPORAIS (DTW) ====================
+// Dynamic Time Warping: quando as características são uma série temporal, duas séries
+// parecidas mas "fora de fase" (uma atrasada em relação à outra) ficam longe na distância
+// euclidiana, que compara sempre a posição i com a posição i. O DTW procura o alinhamento
+// entre as duas séries (quem casa com quem) de menor custo total, permitindo que um valor
+// de uma série case com vários valores seguidos da outra.
+//
+// Programação dinâmica: D[i][j] é o menor custo para alinhar os i primeiros valores de `a`
+// com os j primeiros de `b`:
+//     D[i][j] = |a[i-1] - b[j-1]| + min(D[i-1][j], D[i][j-1], D[i-1][j-1])
+// com D[0][0] = 0 e o resto da primeira linha e da primeira coluna infinito.
+
+/// Distância DTW (Dynamic Time Warping) entre duas séries temporais: o custo
+/// (Σ |ai - bj| ao longo do caminho) do melhor alinhamento entre elas.
+///
+/// Séries iguais mas deslocadas no tempo ficam próximas, ao contrário da distância
+/// euclidiana. As séries podem ter tamanhos diferentes; se só uma delas for vazia,
+/// a distância é infinita. Custa O(n * m) operações; veja [`distancia_dtw_com_janela`]
+/// para uma versão mais rápida e [`caminho_dtw`] para o alinhamento encontrado.
+///
+/// ```
+/// use aprendizagem_knn::{distancia_dtw, distancia_euclidiana, ponto};
+///
+/// let serie = ponto!([0.0, 1.0, 2.0, 1.0, 0.0, 0.0], "A");
+/// let atrasada = ponto!([0.0, 0.0, 1.0, 2.0, 1.0, 0.0], "A");
+/// let diferente = ponto!([2.0, 2.0, 0.0, 0.0, 2.0, 2.0], "B");
+///
+/// assert_eq!(distancia_dtw(&serie, &serie), 0.0);
+/// assert_eq!(distancia_dtw(&serie, &atrasada), 0.0); // o atraso é absorvido pelo alinhamento
+/// assert!(distancia_euclidiana(&serie, &atrasada) > 0.0);
+/// assert!(distancia_dtw(&serie, &atrasada) < distancia_dtw(&serie, &diferente));
+/// ```
+pub fn distancia_dtw(ponto1: &Ponto, ponto2: &Ponto) -> f64 {
+    let (a, b) = (&ponto1.caracteristicas, &ponto2.caracteristicas);
+    // Uma janela do tamanho da maior série não restringe nada
+    custo_dtw(a, b, a.len().max(b.len()))
+}
+
+/// DTW com a restrição de Sakoe-Chiba: a posição i de uma série só pode casar com as
+/// posições j da outra em que |i - j| <= `janela`.
+///
+/// Além de impedir alinhamentos absurdos (o começo de uma série casando com o fim da
+/// outra), só as células dentro da faixa são calculadas, o que reduz o custo para
+/// O(n * janela). Se as séries tiverem tamanhos diferentes, a janela é aumentada para
+/// pelo menos a diferença entre eles, senão não haveria caminho até o fim das duas.
+/// Com `janela` 0 e séries do mesmo tamanho, é a distância de Manhattan.
+///
+/// ```
+/// use aprendizagem_knn::{distancia_dtw, distancia_dtw_com_janela, distancia_manhattan, ponto};
+///
+/// let a = ponto!([0.0, 1.0, 2.0, 1.0, 0.0, 0.0], "A");
+/// let b = ponto!([0.0, 0.0, 1.0, 2.0, 1.0, 0.0], "B");
+///
+/// assert_eq!(distancia_dtw_com_janela(&a, &b, 1), distancia_dtw(&a, &b));
+/// assert_eq!(distancia_dtw_com_janela(&a, &b, 0), distancia_manhattan(&a, &b));
+/// ```
+pub fn distancia_dtw_com_janela(ponto1: &Ponto, ponto2: &Ponto, janela: usize) -> f64 {
+    custo_dtw(&ponto1.caracteristicas, &ponto2.caracteristicas, janela)
+}
+
+/// O alinhamento de menor custo encontrado pelo DTW, como pares (i, j) de posições das
+/// duas séries, do começo ao fim.
+///
+/// Cada par diz que o valor i da primeira série foi casado com o valor j da segunda; um
+/// índice repetido em pares seguidos mostra onde uma série "esperou" pela outra.
+/// Séries vazias não têm caminho.
+///
+/// ```
+/// use aprendizagem_knn::{caminho_dtw, ponto};
+///
+/// let a = ponto!([0.0, 1.0, 2.0], "A");
+/// let b = ponto!([0.0, 0.0, 1.0, 2.0], "B");
+/// // o primeiro valor de `a` casa com os dois zeros do começo de `b`
+/// assert_eq!(caminho_dtw(&a, &b), vec![(0, 0), (0, 1), (1, 2), (2, 3)]);
+/// ```
+pub fn caminho_dtw(ponto1: &Ponto, ponto2: &Ponto) -> Vec<(usize, usize)> {
+    let (a, b) = (&ponto1.caracteristicas, &ponto2.caracteristicas);
+    let (n, m) = (a.len(), b.len());
+    if n == 0 || m == 0 {
+        return Vec::new();
+    }
+
+    // Aqui a matriz de custos inteira fica na memória, pois é ela que guarda o caminho
+    let mut custos = vec![vec![f64::INFINITY; m + 1]; n + 1];
+    custos[0][0] = 0.0;
+    for i in 1..=n {
+        for j in 1..=m {
+            let melhor_anterior = custos[i - 1][j - 1].min(custos[i - 1][j]).min(custos[i][j - 1]);
+            custos[i][j] = (a[i - 1] - b[j - 1]).abs() + melhor_anterior;
+        }
+    }
+
+    // Refaz o caminho de trás para frente, sempre pela célula anterior mais barata
+    // (no empate, a diagonal, que avança nas duas séries)
+    let mut caminho = vec![(n - 1, m - 1)];
+    let (mut i, mut j) = (n, m);
+    while (i, j) != (1, 1) {
+        (i, j) = if i == 1 {
+            (i, j - 1)
+        } else if j == 1 {
+            (i - 1, j)
+        } else {
+            let diagonal = custos[i - 1][j - 1];
+            if diagonal <= custos[i - 1][j] && diagonal <= custos[i][j - 1] {
+                (i - 1, j - 1)
+            } else if custos[i - 1][j] <= custos[i][j - 1] {
+                (i - 1, j)
+            } else {
+                (i, j - 1)
+            }
+        };
+        caminho.push((i - 1, j - 1));
+    }
+    caminho.reverse();
+    caminho
+}
+
+// Custo do DTW guardando só duas linhas da matriz (a anterior e a atual), já que
+// D[i][j] depende apenas da linha i-1 e da célula à esquerda.
+// Em cada linha i só as colunas j com |i - j| <= janela são calculadas.
+fn custo_dtw(a: &[f64], b: &[f64], janela: usize) -> f64 {
+    let (n, m) = (a.len(), b.len());
+    if n == 0 || m == 0 {
+        return if n == m { 0.0 } else { f64::INFINITY };
+    }
+    let janela = janela.max(n.abs_diff(m));
+
+    let mut anterior = vec![f64::INFINITY; m + 1];
+    let mut atual = vec![f64::INFINITY; m + 1];
+    anterior[0] = 0.0;
+
+    for i in 1..=n {
+        let inicio = i.saturating_sub(janela).max(1);
+        let fim = (i + janela).min(m);
+
+        // As células logo antes e logo depois da faixa podem guardar valores de duas linhas
+        // atrás (os vetores são reaproveitados); como estão fora da faixa, valem infinito
+        atual[inicio - 1] = f64::INFINITY;
+        if fim < m {
+            atual[fim + 1] = f64::INFINITY;
+        }
+        for j in inicio..=fim {
+            let melhor_anterior = anterior[j - 1].min(anterior[j]).min(atual[j - 1]);
+            atual[j] = (a[i - 1] - b[j - 1]).abs() + melhor_anterior;
+        }
+        std::mem::swap(&mut anterior, &mut atual);
+    }
+    anterior[m]
+}
+
+// ==================== DISTÂNCIA COM SIMD ====================
+// SIMD (Single Instruction, Multiple Data): uma única instrução do processador opera
+// sobre vários números ao mesmo tempo. Com AVX, um registrador de 256 bits guarda
+// 4 valores f64, então subtração, quadrado e soma andam de 4 em 4 características.
+
+/// Distância euclidiana calculada de 4 em 4 características com instruções SIMD.
+///
+/// Em processadores x86_64 com AVX usa as instruções AVX diretamente; nos demais
+/// soma em blocos de 4 acumuladores independentes, formato que o compilador consegue
+/// vetorizar com o SIMD disponível (SSE2, NEON...). Como a ordem das somas muda, o
+/// resultado pode diferir de [`distancia_euclidiana`] nos últimos dígitos.
+///
+/// ```
+/// use aprendizagem_knn::{distancia_euclidiana, distancia_euclidiana_simd, Ponto};
+///
+/// let a = Ponto::novo((0..10).map(|i| i as f64).collect(), "A".to_string());
+/// let b = Ponto::novo(vec![0.0; 10], "B".to_string());
+/// let diferenca = distancia_euclidiana_simd(&a, &b) - distancia_euclidiana(&a, &b);
+/// assert!(diferenca.abs() < 1e-12);
+/// ```
+pub fn distancia_euclidiana_simd(ponto1: &Ponto, ponto2: &Ponto) -> f64 {
+    let a = &ponto1.caracteristicas;
+    let b = &ponto2.caracteristicas;
+
+    #[cfg(target_arch = "x86_64")]
+    {
+        if is_x86_feature_detected!("avx") {
+            // SAFETY: a função só usa instruções AVX, e acabamos de confirmar que o processador as tem
+            return unsafe { soma_quadrados_avx(a, b) }.sqrt();
+        }
+    }
+
+    soma_quadrados_em_blocos(a, b).sqrt()
+}
+
+/// Escolhe, em tempo de execução, a implementação mais rápida da distância euclidiana:
+/// [`distancia_euclidiana_simd`] se o processador tiver AVX e a versão escalar
+/// [`distancia_euclidiana`] caso contrário.
+///
+/// ```
+/// use aprendizagem_knn::{distancia_euclidiana_auto, ponto};
+///
+/// let a = ponto!([0.0, 0.0, 0.0, 0.0, 0.0], "A");
+/// let b = ponto!([3.0, 4.0, 0.0, 0.0, 0.0], "B");
+/// assert_eq!(distancia_euclidiana_auto(&a, &b), 5.0);
+/// ```
+pub fn distancia_euclidiana_auto(ponto1: &Ponto, ponto2: &Ponto) -> f64 {
+    if simd_disponivel() {
+        distancia_euclidiana_simd(ponto1, ponto2)
+    } else {
+        distancia_euclidiana(ponto1, ponto2)
+    }
+}
+
+/// Diz se o processador atual tem as instruções SIMD usadas por [`distancia_euclidiana_simd`].
+///
+/// A detecção é feita uma vez e guardada pela biblioteca padrão, então a chamada é barata.
+///
+/// ```
+/// use aprendizagem_knn::simd_disponivel;
+///
+/// println!("AVX disponível: {}", simd_disponivel());
+/// ```
+pub fn simd_disponivel() -> bool {
+    #[cfg(target_arch = "x86_64")]
+    {
+        is_x86_feature_detected!("avx")
+    }
+    #[cfg(not(target_arch = "x86_64"))]
+    {
+        false
+    }
+}
+
+// Σ (ai - bi)² com intrinsics AVX: 4 diferenças ao quadrado por instrução.
+// #[target_feature] deixa o compilador gerar AVX só dentro desta função, por isso
+// ela é unsafe: quem chama precisa garantir que o processador suporta AVX.
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx")]
+unsafe fn soma_quadrados_avx(a: &[f64], b: &[f64]) -> f64 {
+    use std::arch::x86_64::{
+        _mm256_add_pd, _mm256_loadu_pd, _mm256_mul_pd, _mm256_setzero_pd, _mm256_storeu_pd, _mm256_sub_pd,
+    };
+
+    let n = a.len().min(b.len());
+    let blocos = n / 4;
+    let mut acumulador = _mm256_setzero_pd();
+    for i in 0..blocos {
+        // loadu aceita endereços não alinhados a 32 bytes, como os de um Vec<f64> qualquer
+        let va = _mm256_loadu_pd(a.as_ptr().add(i * 4));
+        let vb = _mm256_loadu_pd(b.as_ptr().add(i * 4));
+        let diferenca = _mm256_sub_pd(va, vb);
+        acumulador = _mm256_add_pd(acumulador, _mm256_mul_pd(diferenca, diferenca));
+    }
+
+    let mut parciais = [0.0; 4];
+    _mm256_storeu_pd(parciais.as_mut_ptr(), acumulador);
+
+    // As características que sobram (n não múltiplo de 4) são somadas uma a uma
+    let resto: f64 = a[blocos * 4..n].iter().zip(&b[blocos * 4..n]).map(|(x, y)| (x - y).powi(2)).sum();
+    parciais.iter().sum::<f64>() + resto
+}
+
+// Versão portátil: 4 somas parciais independentes, que o compilador pode
+// manter em registradores SIMD e atualizar com uma instrução só
+fn soma_quadrados_em_blocos(a: &[f64], b: &[f64]) -> f64 {
+    let n = a.len().min(b.len());
+    let mut parciais = [0.0; 4];
+    for (bloco_a, bloco_b) in a[..n].chunks_exact(4).zip(b[..n].chunks_exact(4)) {
+        for i in 0..4 {
+            parciais[i] += (bloco_a[i] - bloco_b[i]).powi(2);
+        }
+    }
+
+    let inicio_resto = n - n % 4;
+    let resto: f64 = a[inicio_resto..n].iter().zip(&b[inicio_resto..n]).map(|(x, y)| (x - y).powi(2)).sum();
+    parciais.iter().sum::<f64>() + resto
+}
+
+// O parâmetro p precisa ser positivo (NaN também é rejeitado)
+fn validar_p_minkowski(p: f64) -> Result<(), ErroKnn> {
+    if p.is_nan() || p <= 0.0 {
+        return Err(ErroKnn::ParametroInvalido(format!(
+            "p da distância de Minkowski deve ser maior que zero (recebido {})",
+            p
+        )));
+    }
+    Ok(())
+}
+
+// Os pesos precisam ser finitos e não negativos (um peso negativo deixaria a soma negativa)
+fn validar_pesos(pesos: &[f64]) -> Result<(), ErroKnn> {
+    if let Some(peso) = pesos.iter().find(|peso| !peso.is_finite() || **peso < 0.0) {
+        return Err(ErroKnn::ParametroInvalido(format!(
+            "os pesos da distância ponderada devem ser finitos e não negativos (recebido {})",
+            peso
+        )));
+    }
+    Ok(())
+}
+
+// ==================== MÉTRICAS DE DISTÂNCIA ====================
+// enum permite escolher a métrica usada pelo KNN sem mudar o algoritmo
+/// Métrica de distância usada pelo KNN.
+///
+/// # Exemplo
+///
+/// ```
+/// use aprendizagem_knn::{ponto, DistanciaMetrica};
+///
+/// let a = ponto!([0.0, 0.0], "A");
+/// let b = ponto!([3.0, 4.0], "B");
+/// assert_eq!(DistanciaMetrica::Euclidiana.calcular(&a, &b), 5.0);
+/// assert_eq!(DistanciaMetrica::Manhattan.calcular(&a, &b), 7.0);
+///
+/// // Uma distância própria: a diferença só na primeira característica
+/// let so_x = DistanciaMetrica::Personalizada(|a, b| (a.caracteristicas[0] - b.caracteristicas[0]).abs());
+/// assert_eq!(so_x.calcular(&a, &b), 3.0);
+/// ```
+// O compilador avisa que comparar ponteiros de função (na Personalizada) não é confiável,
+// já que a mesma função pode ter dois endereços; para o PartialEq derivado isso basta
+#[allow(unpredictable_function_pointer_comparisons)]
+#[derive(Debug, Clone, PartialEq)]
+pub enum DistanciaMetrica {
+    /// Ver [`distancia_euclidiana`]
+    Euclidiana,
+    /// Ignora valores ausentes (NaN); ver [`distancia_euclidiana_parcial`]
+    EuclidianaParcial,
+    /// Ver [`distancia_manhattan`]
+    Manhattan,
+    /// Ver [`distancia_chebyshev`]
+    Chebyshev,
+    /// Guarda o parâmetro p; ver [`distancia_minkowski`]
+    Minkowski(f64),
+    /// Ver [`distancia_correlacao_pearson`]
+    CorrelacaoPearson,
+    /// Ver [`distancia_canberra`]
+    Canberra,
+    /// Guarda um peso por característica; ver [`distancia_euclidiana_ponderada`]
+    EuclidianaPonderada(Vec<f64>),
+    /// Para séries temporais; ver [`distancia_dtw`]
+    Dtw,
+    /// DTW restrito a uma faixa de largura `janela`; ver [`distancia_dtw_com_janela`]
+    DtwComJanela(usize),
+    /// Uma distância escrita por quem usa a biblioteca: uma função comum, ou um closure que
+    /// não captura nada. Se ela devolver NaN para um par, o KNN ignora esse ponto (veja
+    /// [`Vizinho`](crate::Vizinho)). Não tem forma em texto, e duas métricas
+    /// personalizadas só são iguais (`==`) se apontarem para a mesma função
+    Personalizada(fn(&Ponto, &Ponto) -> f64),
+}
+
+impl DistanciaMetrica {
+    /// Cria a métrica de Minkowski já validando o parâmetro p.
+    ///
+    /// ```
+    /// use aprendizagem_knn::DistanciaMetrica;
+    ///
+    /// assert_eq!(DistanciaMetrica::minkowski(3.0), Ok(DistanciaMetrica::Minkowski(3.0)));
+    /// assert!(DistanciaMetrica::minkowski(f64::NAN).is_err());
+    /// ```
+    pub fn minkowski(p: f64) -> Result<Self, ErroKnn> {
+        let metrica = DistanciaMetrica::Minkowski(p);
+        metrica.validar()?;
+        Ok(metrica)
+    }
+
+    /// Cria a métrica euclidiana ponderada já validando os pesos.
+    ///
+    /// ```
+    /// use aprendizagem_knn::DistanciaMetrica;
+    ///
+    /// assert!(DistanciaMetrica::euclidiana_ponderada(vec![0.5, 2.0]).is_ok());
+    /// assert!(DistanciaMetrica::euclidiana_ponderada(vec![1.0, -1.0]).is_err());
+    /// ```
+    pub fn euclidiana_ponderada(pesos: Vec<f64>) -> Result<Self, ErroKnn> {
+        let metrica = DistanciaMetrica::EuclidianaPonderada(pesos);
+        metrica.validar()?;
+        Ok(metrica)
+    }
+
+    /// Verifica se os parâmetros da métrica são válidos.
+    ///
+    /// ```
+    /// use aprendizagem_knn::DistanciaMetrica;
+    ///
+    /// assert!(DistanciaMetrica::Euclidiana.validar().is_ok());
+    /// assert!(DistanciaMetrica::Minkowski(-2.0).validar().is_err());
+    /// ```
+    pub fn validar(&self) -> Result<(), ErroKnn> {
+        match self {
+            DistanciaMetrica::Minkowski(p) => validar_p_minkowski(*p),
+            DistanciaMetrica::EuclidianaPonderada(pesos) => validar_pesos(pesos),
+            _ => Ok(()),
+        }
+    }
+
+    // match escolhe a função de distância correspondente a cada variante
+    /// Calcula a distância entre dois pontos com esta métrica.
+    ///
+    /// Entra em pânico se a métrica for `Minkowski` com p inválido
+    /// (crie-a com [`DistanciaMetrica::minkowski`] para validar antes).
+    ///
+    /// ```
+    /// use aprendizagem_knn::{ponto, DistanciaMetrica};
+    ///
+    /// let a = ponto!([1.0, 5.0], "A");
+    /// let b = ponto!([4.0, 1.0], "B");
+    /// assert_eq!(DistanciaMetrica::Chebyshev.calcular(&a, &b), 4.0);
+    /// ```
+    pub fn calcular(&self, ponto1: &Ponto, ponto2: &Ponto) -> f64 {
+        match self {
+            DistanciaMetrica::Euclidiana => distancia_euclidiana(ponto1, ponto2),
+            DistanciaMetrica::EuclidianaParcial => distancia_euclidiana_parcial(ponto1, ponto2),
+            DistanciaMetrica::Manhattan => distancia_manhattan(ponto1, ponto2),
+            DistanciaMetrica::Chebyshev => distancia_chebyshev(ponto1, ponto2),
+            DistanciaMetrica::Minkowski(p) => distancia_minkowski(ponto1, ponto2, *p)
+                .expect("métrica de Minkowski com p inválido (crie-a com DistanciaMetrica::minkowski)"),
+            DistanciaMetrica::CorrelacaoPearson => distancia_correlacao_pearson(ponto1, ponto2),
+            DistanciaMetrica::Canberra => distancia_canberra(ponto1, ponto2),
+            DistanciaMetrica::EuclidianaPonderada(pesos) => distancia_euclidiana_ponderada(ponto1, ponto2, pesos),
+            DistanciaMetrica::Dtw => distancia_dtw(ponto1, ponto2),
+            DistanciaMetrica::DtwComJanela(janela) => distancia_dtw_com_janela(ponto1, ponto2, *janela),
+            DistanciaMetrica::Personalizada(distancia) => distancia(ponto1, ponto2),
+        }
+    }
+}
+
+// FromStr permite escrever a métrica como texto (na linha de comando, por exemplo)
+// e convertê-la com "manhattan".parse::<DistanciaMetrica>()
+/// ```
+/// use aprendizagem_knn::DistanciaMetrica;
+///
+/// assert_eq!("Manhattan".parse(), Ok(DistanciaMetrica::Manhattan));
+/// assert_eq!("minkowski:3".parse(), Ok(DistanciaMetrica::Minkowski(3.0)));
+/// assert_eq!("dtw:2".parse(), Ok(DistanciaMetrica::DtwComJanela(2)));
+/// assert!("minkowski:-1".parse::<DistanciaMetrica>().is_err());
+/// assert!("hamming".parse::<DistanciaMetrica>().is_err());
+/// ```
+impl FromStr for DistanciaMetrica {
+    type Err = ErroKnn;
+
+    /// Aceita (sem diferenciar maiúsculas) `euclidiana`, `euclidiana_parcial`, `manhattan`,
+    /// `chebyshev`, `minkowski:p`, `pearson`, `canberra`, `dtw` e `dtw:janela`. A
+    /// euclidiana ponderada não tem forma em texto, já que precisa de um peso por
+    /// característica, e a personalizada também não, já que é código.
+    fn from_str(texto: &str) -> Result<Self, Self::Err> {
+        let texto = texto.trim().to_lowercase();
+        // "minkowski:3" vira ("minkowski", Some("3"))
+        let (nome, parametro) = match texto.split_once(':') {
+            Some((nome, parametro)) => (nome, Some(parametro.trim())),
+            None => (texto.as_str(), None),
+        };
+        let invalido = ErroKnn::ParametroInvalido;
+
+        match (nome, parametro) {
+            ("euclidiana", None) => Ok(DistanciaMetrica::Euclidiana),
+            ("euclidiana_parcial", None) => Ok(DistanciaMetrica::EuclidianaParcial),
+            ("manhattan", None) => Ok(DistanciaMetrica::Manhattan),
+            ("chebyshev", None) => Ok(DistanciaMetrica::Chebyshev),
+            ("pearson", None) => Ok(DistanciaMetrica::CorrelacaoPearson),
+            ("canberra", None) => Ok(DistanciaMetrica::Canberra),
+            ("dtw", None) => Ok(DistanciaMetrica::Dtw),
+            ("minkowski", Some(p)) => {
+                let p = p.parse()
+                    .map_err(|_| invalido(format!("o p de Minkowski deve ser um número, recebido '{}'", p)))?;
+                DistanciaMetrica::minkowski(p)
+            }
+            ("dtw", Some(janela)) => janela.parse().map(DistanciaMetrica::DtwComJanela).map_err(|_| {
+                invalido(format!("a janela do DTW deve ser um inteiro não negativo, recebido '{}'", janela))
+            }),
+            ("minkowski", None) => Err(invalido("a métrica de Minkowski precisa do p: use minkowski:p".to_string())),
+            _ => Err(invalido(format!(
+                "métrica desconhecida '{}' (use euclidiana, euclidiana_parcial, manhattan, chebyshev, minkowski:p, \
+                 pearson, canberra, dtw ou dtw:janela)",
+                texto
+            ))),
+        }
+    }
+}
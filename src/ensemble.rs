@@ -0,0 +1,132 @@
+//! Conjunto (ensemble) de classificadores KNN com valores de k diferentes.
+
+use crate::distancia::DistanciaMetrica;
+use crate::erro::ErroKnn;
+use crate::knn::{erro_sem_vizinhos, k_vizinhos_mais_proximos, validar_entrada, votar_por_maioria};
+use crate::ponto::Ponto;
+
+// ==================== ENSEMBLE DE KNN ====================
+/// Vários classificadores KNN, um para cada valor de k, que votam juntos.
+///
+/// Um único k deixa o resultado sensível a essa escolha: perto da fronteira entre as
+/// classes, ou perto de um rótulo com ruído, k = 3 e k = 5 podem discordar. No ensemble
+/// cada k dá o seu rótulo (com [`knn`](fn@crate::knn)) e vence o rótulo com mais votos,
+/// o que suaviza as escolhas ruins de um k isolado.
+///
+/// # Exemplo
+///
+/// ```
+/// use aprendizagem_knn::{ponto, pontos, EnsembleKnn};
+///
+/// let treinamento = pontos![
+///     [0.0] => "A", [1.0] => "A", [2.0] => "B",
+///     [3.0] => "B", [4.0] => "B", [10.0] => "A",
+/// ];
+/// let ensemble = EnsembleKnn { k_valores: vec![1, 3, 5] };
+/// // k = 1 vê só o 2.0 ("B"); k = 3 vê 2.0, 1.0 e 3.0 ("B"); k = 5 vê três "B" e dois "A"
+/// assert_eq!(ensemble.prever(&treinamento, &ponto!([1.8], "?")), Ok("B".to_string()));
+/// ```
+///
+/// Com dados ruidosos, a acurácia de cada k varia bastante, e o ensemble fica acima da
+/// média deles sem que seja preciso saber de antemão qual k é o melhor:
+///
+/// ```
+/// use aprendizagem_knn::{knn, EnsembleKnn, Ponto, Rng};
+///
+/// let k_valores = vec![1, 3, 5, 7, 9];
+/// let ensemble = EnsembleKnn { k_valores: k_valores.clone() };
+/// // Teste sem ruído: "A" abaixo de 50, "B" acima
+/// let teste: Vec<Ponto> = (0..100)
+///     .map(|i| Ponto::novo(vec![i as f64 + 0.5], if i < 50 { "A" } else { "B" }.to_string()))
+///     .collect();
+/// let acuracia = |prever: &dyn Fn(&Ponto) -> String| {
+///     teste.iter().filter(|ponto| prever(ponto) == ponto.rotulo).count() as f64 / teste.len() as f64
+/// };
+///
+/// for semente in 0..3 {
+///     // 100 pontos de treinamento com 20% dos rótulos trocados
+///     let mut rng = Rng::novo(semente);
+///     let treinamento: Vec<Ponto> = (0..100)
+///         .map(|_| {
+///             let x = rng.proximo_f64() * 100.0;
+///             let ruido = rng.proximo_f64() < 0.2;
+///             Ponto::novo(vec![x], if (x < 50.0) != ruido { "A" } else { "B" }.to_string())
+///         })
+///         .collect();
+///
+///     let por_k: Vec<f64> = k_valores.iter().map(|&k| acuracia(&|p| knn(&treinamento, p, k).unwrap())).collect();
+///     let media_por_k = por_k.iter().sum::<f64>() / por_k.len() as f64;
+///     let do_ensemble = acuracia(&|p| ensemble.prever(&treinamento, p).unwrap());
+///     assert!(do_ensemble >= media_por_k);
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnsembleKnn {
+    /// Os valores de k, um por classificador (repetir um valor dá mais peso a ele)
+    pub k_valores: Vec<usize>,
+}
+
+impl EnsembleKnn {
+    /// Classifica o ponto pela maioria dos rótulos previstos por cada k.
+    ///
+    /// No empate de votos vence o rótulo alfabeticamente menor, como no [`knn`](fn@crate::knn).
+    /// Os erros são os do [`knn`](fn@crate::knn) para cada k (treinamento vazio é
+    /// [`ErroKnn::ConjuntoVazio`] e um k igual a 0 é [`ErroKnn::KInvalido`]); `k_valores`
+    /// vazio é [`ErroKnn::ParametroInvalido`].
+    ///
+    /// ```
+    /// use aprendizagem_knn::{knn, ponto, pontos, EnsembleKnn, ErroKnn};
+    ///
+    /// let treinamento = pontos![[0.0] => "A", [1.0] => "B", [2.0] => "B"];
+    /// let ponto_teste = ponto!([0.2], "?");
+    /// // Com um único k, o ensemble é o próprio knn
+    /// let ensemble = EnsembleKnn { k_valores: vec![3] };
+    /// assert_eq!(ensemble.prever(&treinamento, &ponto_teste), knn(&treinamento, &ponto_teste, 3));
+    ///
+    /// assert_eq!(ensemble.prever(&[], &ponto_teste), Err(ErroKnn::ConjuntoVazio));
+    /// let com_zero = EnsembleKnn { k_valores: vec![1, 0] };
+    /// assert_eq!(com_zero.prever(&treinamento, &ponto_teste), Err(ErroKnn::KInvalido { k: 0, maximo: 3 }));
+    /// assert!(EnsembleKnn { k_valores: vec![] }.prever(&treinamento, &ponto_teste).is_err());
+    /// ```
+    pub fn prever(&self, treinamento: &[Ponto], ponto_teste: &Ponto) -> Result<String, ErroKnn> {
+        Ok(self.prever_com_confianca(treinamento, ponto_teste)?.0)
+    }
+
+    /// Como [`EnsembleKnn::prever`], mas devolve também a confiança: a fração dos
+    /// classificadores que previu o rótulo vencedor (1.0 quando todos concordam).
+    ///
+    /// ```
+    /// use aprendizagem_knn::{ponto, pontos, EnsembleKnn};
+    ///
+    /// let treinamento = pontos![[0.0] => "A", [1.0] => "B", [2.0] => "B", [3.0] => "B"];
+    /// let ensemble = EnsembleKnn { k_valores: vec![1, 3, 5, 7] };
+    ///
+    /// // k = 1 diz "A"; k = 3, 5 e 7 dizem "B"
+    /// assert_eq!(ensemble.prever_com_confianca(&treinamento, &ponto!([0.1], "?")), Ok(("B".to_string(), 0.75)));
+    /// // Longe de "A", todos concordam
+    /// assert_eq!(ensemble.prever_com_confianca(&treinamento, &ponto!([2.5], "?")), Ok(("B".to_string(), 1.0)));
+    /// ```
+    pub fn prever_com_confianca(&self, treinamento: &[Ponto], ponto_teste: &Ponto) -> Result<(String, f64), ErroKnn> {
+        let Some(&maior_k) = self.k_valores.iter().max() else {
+            return Err(ErroKnn::ParametroInvalido("o ensemble precisa de ao menos um valor de k".to_string()));
+        };
+        for &k in &self.k_valores {
+            validar_entrada(treinamento, k)?;
+        }
+
+        // Os vizinhos do maior k, do mais próximo para o mais distante, já contêm os de
+        // todos os outros: os k primeiros são exatamente os que o knn usaria com esse k
+        let vizinhos = k_vizinhos_mais_proximos(treinamento, ponto_teste, maior_k, &DistanciaMetrica::Euclidiana);
+        if vizinhos.is_empty() {
+            return Err(erro_sem_vizinhos(&ponto_teste.caracteristicas));
+        }
+
+        let previsoes: Vec<String> = self.k_valores.iter()
+            .map(|&k| votar_por_maioria(vizinhos.iter().take(k).map(|vizinho| vizinho.rotulo.clone())))
+            .collect();
+        let vencedor = votar_por_maioria(previsoes.iter().cloned());
+        let votos = previsoes.iter().filter(|&rotulo| *rotulo == vencedor).count();
+        let confianca = votos as f64 / previsoes.len() as f64;
+        Ok((vencedor, confianca))
+    }
+}
@@ -0,0 +1,113 @@
+//! Erros devolvidos pelas funções da biblioteca.
+
+use std::io;
+
+// ==================== ERROS ====================
+// thiserror gera as implementações de Display (a partir de cada #[error("...")]) e do
+// trait Error, o que permite usar o tipo com ? junto de Box<dyn Error>. Os campos são
+// sempre texto e números, e não o erro original, para que ErroKnn continue podendo ser
+// clonado e comparado com == nos testes.
+/// Erros que as funções do KNN podem devolver.
+///
+/// Cada causa tem a sua variante, para que quem usa a biblioteca possa reagir a ela com
+/// `match` em vez de procurar palavras na mensagem. As mensagens dizem onde está o
+/// problema (arquivo e linha, quando há um) e, se possível, como resolvê-lo.
+///
+/// # Exemplo
+///
+/// ```
+/// use aprendizagem_knn::{carregar_dados_do_csv, DistanciaMetrica, ErroKnn};
+///
+/// let erro = DistanciaMetrica::minkowski(-1.0).unwrap_err();
+/// assert!(matches!(erro, ErroKnn::ParametroInvalido(_)));
+/// assert_eq!(ErroKnn::ConjuntoVazio.to_string(), "o conjunto de dados está vazio");
+///
+/// match carregar_dados_do_csv("nao_existe.csv") {
+///     Err(ErroKnn::ArquivoNaoEncontrado(caminho)) => assert_eq!(caminho, "nao_existe.csv"),
+///     outro => panic!("esperava ArquivoNaoEncontrado, veio {:?}", outro),
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum ErroKnn {
+    /// Um parâmetro recebeu um valor fora do domínio permitido
+    #[error("parâmetro inválido: {0}")]
+    ParametroInvalido(String),
+    /// A operação precisa de ao menos um ponto
+    #[error("o conjunto de dados está vazio")]
+    ConjuntoVazio,
+    /// O arquivo (o caminho guardado aqui) não existe
+    #[error("{0}: arquivo não encontrado (confira o caminho; um caminho relativo parte da pasta atual)")]
+    ArquivoNaoEncontrado(String),
+    /// Uma linha do arquivo tem conteúdo inválido: colunas a mais ou a menos, um valor
+    /// que não é número...
+    #[error("{origem}: linha {linha}: {motivo}")]
+    LinhaInvalida {
+        /// Caminho do arquivo (ou outra origem, como `"<stdin>"` ou uma URL)
+        origem: String,
+        /// Número da linha no arquivo, contando a partir de 1 (o cabeçalho é a linha 1)
+        linha: usize,
+        /// O que há de errado na linha
+        motivo: String,
+    },
+    /// Um arquivo não pôde ser lido ou tem conteúdo inválido fora das linhas de dados
+    /// (permissão negada, cabeçalho sem colunas, gzip corrompido...); a mensagem diz qual
+    #[error("erro de arquivo: {0}")]
+    Arquivo(String),
+    /// Os pontos têm um número de características diferente do esperado (por exemplo, um
+    /// teste com colunas a mais que o treinamento)
+    #[error(
+        "dimensão incompatível: eram esperadas {esperado} característica(s), mas vieram {obtido} \
+         (use as mesmas colunas do treinamento, sem o rótulo)"
+    )]
+    DimensaoIncompativel {
+        /// Número de características do treinamento
+        esperado: usize,
+        /// Número de características recebido
+        obtido: usize,
+    },
+    /// Um vetor necessário para o cálculo está vazio ou não tem nenhum valor útil
+    /// (por exemplo, pesos todos zero); a mensagem diz qual
+    #[error("vetor vazio: {0}")]
+    VetorVazio(String),
+    /// Um número de itens a escolher (vizinhos, características...) está fora de `1..=maximo`
+    #[error("k = {k} inválido: deve estar entre 1 e {maximo}")]
+    KInvalido {
+        /// Valor recebido
+        k: usize,
+        /// Maior valor permitido
+        maximo: usize,
+    },
+    /// Um classificador foi usado para classificar antes de ser treinado (veja
+    /// [`ClassificadorKnn::treinar`](crate::ClassificadorKnn::treinar))
+    #[error("o classificador ainda não foi treinado: chame treinar antes de classificar")]
+    NaoTreinado,
+    /// Todos os problemas encontrados numa leitura que não para no primeiro (veja
+    /// [`OpcoesCsv::coletar_erros`](crate::OpcoesCsv::coletar_erros)), na ordem do arquivo
+    #[error("{}", listar_erros(.0))]
+    VariosErros(Vec<ErroKnn>),
+}
+
+// Mensagem de VariosErros: a quantidade e um problema por linha
+fn listar_erros(erros: &[ErroKnn]) -> String {
+    let mut mensagem = format!("{} problema(s) nos dados:", erros.len());
+    for erro in erros {
+        mensagem.push_str(&format!("\n  - {}", erro));
+    }
+    mensagem
+}
+
+impl ErroKnn {
+    // Erro de E/S ao abrir ou ler `origem`: um arquivo que não existe tem a sua própria
+    // variante; os outros (permissão negada, um diretório, gzip corrompido...) viram
+    // Arquivo, com a origem no começo da mensagem se ela ainda não estiver lá
+    pub(crate) fn de_io(origem: &str, erro: &io::Error) -> Self {
+        let mensagem = erro.to_string();
+        if erro.kind() == io::ErrorKind::NotFound {
+            ErroKnn::ArquivoNaoEncontrado(origem.to_string())
+        } else if mensagem.starts_with(origem) {
+            ErroKnn::Arquivo(mensagem)
+        } else {
+            ErroKnn::Arquivo(format!("{}: {}", origem, mensagem))
+        }
+    }
+}
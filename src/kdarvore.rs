@@ -0,0 +1,203 @@
+//! Árvore k-d: encontra os vizinhos mais próximos sem medir a distância até todos os pontos.
+
+use crate::distancia::distancia_euclidiana;
+use crate::erro::ErroKnn;
+use crate::knn::{validar_entrada, votar_por_maioria, Vizinho};
+use crate::ponto::Ponto;
+
+// ==================== ÁRVORE K-D ====================
+// Cada nó guarda um ponto e divide o espaço em dois pelo valor desse ponto em uma
+// característica (o eixo): à esquerda ficam os pontos com valor menor ou igual, à
+// direita os maiores. O eixo muda a cada nível (0, 1, 2, ..., 0, 1, ...).
+#[derive(Debug, Clone)]
+struct No {
+    // Posição do ponto em `pontos`
+    indice: usize,
+    eixo: usize,
+    esquerda: Option<usize>,
+    direita: Option<usize>,
+}
+
+/// Árvore k-d com os pontos de treinamento, para buscar os k vizinhos mais próximos
+/// (distância euclidiana) sem comparar o ponto de consulta com todos os outros.
+///
+/// A busca desce até a região do ponto de consulta e só visita os outros ramos quando o
+/// plano de corte está mais perto do que o pior vizinho encontrado até ali. Em poucas
+/// dimensões isso descarta a maior parte dos pontos; em muitas dimensões (dezenas de
+/// características), quase todos os ramos precisam ser visitados e a força bruta do
+/// [`knn`](fn@crate::knn) acaba sendo tão rápida quanto (ou mais).
+///
+/// O resultado é sempre o mesmo do [`knn`](fn@crate::knn), inclusive nos empates de
+/// distância (vence o ponto que vem antes nos dados). Características NaN não são aceitas.
+///
+/// # Exemplo
+///
+/// ```
+/// use aprendizagem_knn::{knn, ponto, pontos, KdArvore};
+///
+/// let treinamento = pontos![
+///     [1.0, 1.0] => "A", [1.5, 2.0] => "A", [2.0, 1.0] => "A",
+///     [8.0, 8.0] => "B", [9.0, 8.5] => "B", [8.5, 9.0] => "B",
+/// ];
+/// let arvore = KdArvore::construir(&treinamento);
+/// let consulta = ponto!([7.0, 7.5], "?");
+///
+/// let vizinhos = arvore.buscar_k_vizinhos(&consulta, 2);
+/// assert_eq!(vizinhos[0].rotulo, "B");
+/// assert_eq!(arvore.classificar(&consulta, 3), knn(&treinamento, &consulta, 3));
+/// ```
+#[derive(Debug, Clone)]
+pub struct KdArvore {
+    pontos: Vec<Ponto>,
+    nos: Vec<No>,
+    raiz: Option<usize>,
+}
+
+impl KdArvore {
+    /// Monta a árvore com uma cópia dos pontos. Em cada nível, o ponto do nó é a mediana
+    /// no eixo daquele nível, o que deixa a árvore balanceada (profundidade perto de log₂ n).
+    ///
+    /// ```
+    /// use aprendizagem_knn::{pontos, KdArvore};
+    ///
+    /// let arvore = KdArvore::construir(&pontos![[0.0] => "A", [5.0] => "B"]);
+    /// assert_eq!(arvore.len(), 2);
+    /// assert!(KdArvore::construir(&[]).is_empty());
+    /// ```
+    pub fn construir(dados: &[Ponto]) -> Self {
+        let dimensao = dados.first().map_or(0, |ponto| ponto.caracteristicas.len());
+        let mut arvore = Self { pontos: dados.to_vec(), nos: Vec::with_capacity(dados.len()), raiz: None };
+        let mut indices: Vec<usize> = (0..dados.len()).collect();
+        if dimensao > 0 {
+            arvore.raiz = arvore.construir_no(&mut indices, 0, dimensao);
+        }
+        arvore
+    }
+
+    // Monta a subárvore com os pontos de `indices` e devolve a posição do seu nó raiz
+    fn construir_no(&mut self, indices: &mut [usize], profundidade: usize, dimensao: usize) -> Option<usize> {
+        if indices.is_empty() {
+            return None;
+        }
+        let eixo = profundidade % dimensao;
+        let meio = indices.len() / 2;
+        // select_nth_unstable_by põe a mediana na posição `meio`, os menores antes e os
+        // maiores depois, sem ordenar tudo
+        let pontos = &self.pontos;
+        indices.select_nth_unstable_by(meio, |&a, &b| {
+            pontos[a].caracteristicas[eixo].total_cmp(&pontos[b].caracteristicas[eixo])
+        });
+
+        let posicao = self.nos.len();
+        self.nos.push(No { indice: indices[meio], eixo, esquerda: None, direita: None });
+        let (antes, depois) = indices.split_at_mut(meio);
+        let esquerda = self.construir_no(antes, profundidade + 1, dimensao);
+        let direita = self.construir_no(&mut depois[1..], profundidade + 1, dimensao);
+        self.nos[posicao].esquerda = esquerda;
+        self.nos[posicao].direita = direita;
+        Some(posicao)
+    }
+
+    /// Número de pontos na árvore.
+    ///
+    /// ```
+    /// use aprendizagem_knn::{pontos, KdArvore};
+    ///
+    /// assert_eq!(KdArvore::construir(&pontos![[1.0] => "A"]).len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.pontos.len()
+    }
+
+    /// `true` se a árvore não tiver nenhum ponto.
+    ///
+    /// ```
+    /// use aprendizagem_knn::KdArvore;
+    ///
+    /// assert!(KdArvore::construir(&[]).is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.pontos.is_empty()
+    }
+
+    /// Os `k` vizinhos mais próximos do ponto de consulta, do mais próximo para o mais
+    /// distante (todos os pontos, se a árvore tiver menos de `k`).
+    ///
+    /// O `indice` de cada vizinho é a posição do ponto no conjunto passado a
+    /// [`construir`](KdArvore::construir). Em distâncias iguais vem primeiro o menor índice,
+    /// como no [`knn`](fn@crate::knn), então os dois escolhem os mesmos vizinhos.
+    ///
+    /// ```
+    /// use aprendizagem_knn::{ponto, pontos, KdArvore};
+    ///
+    /// let arvore = KdArvore::construir(&pontos![[0.0, 0.0] => "A", [3.0, 4.0] => "B", [1.0, 0.0] => "C"]);
+    /// let vizinhos = arvore.buscar_k_vizinhos(&ponto!([0.0, 0.0], "?"), 2);
+    /// assert_eq!(vizinhos.iter().map(|v| v.rotulo.as_str()).collect::<Vec<_>>(), vec!["A", "C"]);
+    /// assert_eq!((vizinhos[1].distancia, vizinhos[1].indice), (1.0, 2));
+    /// ```
+    pub fn buscar_k_vizinhos(&self, ponto_consulta: &Ponto, k: usize) -> Vec<Vizinho> {
+        let mut melhores = Vec::with_capacity(k + 1);
+        if k > 0 {
+            if let Some(raiz) = self.raiz {
+                self.buscar(raiz, ponto_consulta, k, &mut melhores);
+            }
+        }
+        melhores.into_iter()
+            .map(|(distancia, indice)| Vizinho::novo(distancia, self.pontos[indice].rotulo.clone(), indice))
+            .collect()
+    }
+
+    // Visita a subárvore de `posicao`, mantendo em `melhores` os k pares (distância, índice)
+    // mais próximos, em ordem crescente (no empate de distância, o menor índice primeiro)
+    fn buscar(&self, posicao: usize, consulta: &Ponto, k: usize, melhores: &mut Vec<(f64, usize)>) {
+        let no = &self.nos[posicao];
+        let ponto = &self.pontos[no.indice];
+        let candidato = (distancia_euclidiana(consulta, ponto), no.indice);
+        if melhores.len() < k || comparar(&candidato, melhores.last().expect("a lista está cheia")).is_lt() {
+            let lugar = melhores.partition_point(|melhor| comparar(melhor, &candidato).is_lt());
+            melhores.insert(lugar, candidato);
+            melhores.truncate(k);
+        }
+
+        // Primeiro o lado do plano em que a consulta está, que tem mais chance de ter os vizinhos
+        let diferenca = consulta.caracteristicas[no.eixo] - ponto.caracteristicas[no.eixo];
+        let (perto, longe) = if diferenca <= 0.0 { (no.esquerda, no.direita) } else { (no.direita, no.esquerda) };
+        if let Some(filho) = perto {
+            self.buscar(filho, consulta, k, melhores);
+        }
+        // Qualquer ponto do outro lado está a pelo menos |diferenca| da consulta; se isso já
+        // passa do pior vizinho guardado, o lado inteiro pode ser ignorado. A comparação
+        // estrita mantém os empates, para que o desempate por índice seja o mesmo do knn
+        if let Some(filho) = longe {
+            let pior = melhores.last().map_or(f64::INFINITY, |&(distancia, _)| distancia);
+            if melhores.len() < k || diferenca.abs() <= pior {
+                self.buscar(filho, consulta, k, melhores);
+            }
+        }
+    }
+
+    /// Classifica o ponto pela maioria dos rótulos dos `k` vizinhos encontrados na árvore,
+    /// como o [`knn`](fn@crate::knn) (no empate, vence o rótulo alfabeticamente menor).
+    ///
+    /// Os erros são os do [`knn`](fn@crate::knn): uma árvore vazia é
+    /// [`ErroKnn::ConjuntoVazio`] e `k` igual a 0 é [`ErroKnn::KInvalido`].
+    ///
+    /// ```
+    /// use aprendizagem_knn::{ponto, pontos, ErroKnn, KdArvore};
+    ///
+    /// let arvore = KdArvore::construir(&pontos![[0.0] => "A", [1.0] => "A", [9.0] => "B"]);
+    /// assert_eq!(arvore.classificar(&ponto!([2.0], "?"), 3), Ok("A".to_string()));
+    /// assert_eq!(arvore.classificar(&ponto!([2.0], "?"), 0), Err(ErroKnn::KInvalido { k: 0, maximo: 3 }));
+    /// assert_eq!(KdArvore::construir(&[]).classificar(&ponto!([2.0], "?"), 3), Err(ErroKnn::ConjuntoVazio));
+    /// ```
+    pub fn classificar(&self, ponto_consulta: &Ponto, k: usize) -> Result<String, ErroKnn> {
+        validar_entrada(&self.pontos, k)?;
+        let vizinhos = self.buscar_k_vizinhos(ponto_consulta, k);
+        Ok(votar_por_maioria(vizinhos.into_iter().map(|vizinho| vizinho.rotulo)))
+    }
+}
+
+// Ordem dos candidatos: menor distância primeiro e, no empate, o menor índice
+fn comparar(a: &(f64, usize), b: &(f64, usize)) -> std::cmp::Ordering {
+    a.0.total_cmp(&b.0).then(a.1.cmp(&b.1))
+}
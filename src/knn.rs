@@ -0,0 +1,789 @@
+//! O algoritmo KNN: busca dos k vizinhos mais próximos e votação por maioria.
+
+use std::cmp::Ordering;           // Módulo padrão para definir como comparar elementos
+use std::collections::BinaryHeap; // Estrutura de dados de fila de prioridade (heap)
+use std::collections::HashMap;    // Dicionário chave-valor (usado para contar rótulos)
+use std::hash::Hash;              // Trait dos tipos que podem ser chave de um HashMap
+
+use crate::aleatorio::Rng;
+use crate::distancia::{distancia_euclidiana, DistanciaMetrica};
+use crate::erro::ErroKnn;
+use crate::ponto::{Ponto, PontoGenerico};
+
+// ==================== ESTRUTURA AUXILIAR PARA VIZINHOS ====================
+/// Um candidato a vizinho: a distância até o ponto de teste, o rótulo do ponto de
+/// treinamento (de qualquer tipo `R`, como no [`PontoGenerico`]) e a posição (índice)
+/// dele no treinamento.
+///
+/// A ordenação é invertida (menor distância = maior prioridade), para que um
+/// [`BinaryHeap`] de vizinhos devolva primeiro o mais próximo. Ela usa [`f64::total_cmp`],
+/// que ordena qualquer `f64`: uma distância infinita fica depois de todas as finitas, e
+/// uma NaN depois das infinitas, sem pânico.
+///
+/// Na mesma distância, vem primeiro o menor índice. Assim, quando vários pontos estão
+/// exatamente à mesma distância do ponto de teste, os escolhidos entre os k vizinhos são
+/// sempre os que aparecem antes no treinamento, e não os que o heap devolver por acaso: a
+/// mesma entrada dá sempre os mesmos vizinhos e a mesma previsão, no [`knn`](fn@knn), no
+/// [`knn_batch`] e na [`KdArvore`](crate::KdArvore), que desempatam do mesmo jeito.
+///
+/// Na busca dos vizinhos do KNN, os pontos à distância NaN (de características NaN ou de
+/// uma [`DistanciaMetrica::Personalizada`] que não vale para o par) nem entram no heap:
+/// são ignorados, com um aviso no log (`log::warn!`). Os infinitos entram e votam como os
+/// mais distantes de todos.
+///
+/// # Exemplo
+///
+/// ```
+/// use std::collections::BinaryHeap;
+/// use aprendizagem_knn::Vizinho;
+///
+/// let mut heap = BinaryHeap::new();
+/// heap.push(Vizinho::novo(2.0, "B".to_string(), 0));
+/// heap.push(Vizinho::novo(0.5, "A".to_string(), 1));
+/// assert_eq!(heap.pop().unwrap().rotulo, "A");
+///
+/// heap.push(Vizinho::novo(f64::NAN, "C".to_string(), 2));
+/// heap.push(Vizinho::novo(f64::INFINITY, "D".to_string(), 3));
+/// // Empata com o B, mas tem o índice maior
+/// heap.push(Vizinho::novo(2.0, "E".to_string(), 4));
+/// let ordem: Vec<String> = std::iter::from_fn(|| heap.pop()).map(|vizinho| vizinho.rotulo).collect();
+/// assert_eq!(ordem, vec!["B", "E", "D", "C"]);
+/// ```
+#[derive(Debug)]
+pub struct VizinhoGenerico<R> {
+    /// Distância até o ponto de teste
+    pub distancia: f64,
+    /// Classe deste vizinho
+    pub rotulo: R,
+    /// Posição do ponto no conjunto de treinamento (desempata distâncias iguais)
+    pub indice: usize,
+}
+
+/// Um vizinho com o rótulo em texto, como o [`Ponto`]; é o que as funções da biblioteca
+/// devolvem.
+///
+/// ```
+/// use aprendizagem_knn::{Vizinho, VizinhoGenerico};
+///
+/// let vizinho: Vizinho = VizinhoGenerico::novo(0.5, "A".to_string(), 0);
+/// assert_eq!(vizinho.rotulo, "A");
+/// ```
+pub type Vizinho = VizinhoGenerico<String>;
+
+impl<R> VizinhoGenerico<R> {
+    /// Cria um vizinho a partir da distância, do rótulo e da posição no treinamento.
+    ///
+    /// ```
+    /// use aprendizagem_knn::Vizinho;
+    ///
+    /// let vizinho = Vizinho::novo(1.5, "Classe A".to_string(), 7);
+    /// assert_eq!((vizinho.distancia, vizinho.indice), (1.5, 7));
+    /// ```
+    pub fn novo(distancia: f64, rotulo: R, indice: usize) -> Self {
+        Self { distancia, rotulo, indice }
+    }
+}
+
+// ==================== IMPLEMENTAÇÃO DE ORDENAÇÃO ====================
+// Em Rust, para usar uma estrutura em uma coleção ordenada (como BinaryHeap),
+// precisamos implementar traits (interfaces) de comparação. A ordem só olha a distância
+// e o índice, então vale para qualquer tipo de rótulo (impl<R>)
+
+// Ord é usado para definir uma ordenação total (todos elementos são comparáveis)
+impl<R> Ord for VizinhoGenerico<R> {
+    fn cmp(&self, outro: &Self) -> Ordering {
+        // partial_cmp para f64 retorna None quando há um NaN; total_cmp sempre tem resposta
+        // (os NaN positivos ficam depois do infinito)
+        // Invertemos a ordem para ter um heap de mínimo (menor distância = maior prioridade);
+        // no empate, o menor índice tem a maior prioridade
+        outro.distancia.total_cmp(&self.distancia).then_with(|| outro.indice.cmp(&self.indice))
+    }
+}
+
+// PartialOrd é necessário para tipos que podem ser parcialmente ordenados
+impl<R> PartialOrd for VizinhoGenerico<R> {
+    fn partial_cmp(&self, outro: &Self) -> Option<Ordering> {
+        Some(self.cmp(outro))
+    }
+}
+
+// PartialEq define quando dois elementos são iguais; segue o Ord, para que um NaN seja
+// igual a si mesmo como o Eq exige
+impl<R> PartialEq for VizinhoGenerico<R> {
+    fn eq(&self, outro: &Self) -> bool {
+        self.cmp(outro) == Ordering::Equal
+    }
+}
+
+// Eq é um trait marcador que indica que a igualdade é uma relação de equivalência
+impl<R> Eq for VizinhoGenerico<R> {}
+
+// ==================== ALGORITMO KNN ====================
+// &[Ponto] é uma fatia (slice) de Pontos - uma visão de um array
+// usize é o tipo usado para índices e tamanhos em Rust
+/// Classifica `ponto_teste` pela classe mais frequente entre os `k` vizinhos mais
+/// próximos (distância euclidiana).
+///
+/// Em caso de empate na votação vence o rótulo alfabeticamente menor (o menor pelo `Ord`
+/// do tipo do rótulo). Se `k` passar do número de pontos de treinamento, todos eles votam.
+///
+/// O rótulo pode ser de qualquer tipo `Clone + Eq + Hash + Ord`: com [`Ponto`] é uma
+/// `String`, mas números ou um `enum` próprio também servem (veja [`PontoGenerico`]). As
+/// outras funções, como [`knn_com_metrica`], usam rótulos em texto.
+///
+/// Um treinamento vazio é o erro [`ErroKnn::ConjuntoVazio`], e `k` igual a 0 é
+/// [`ErroKnn::KInvalido`]: sem vizinhos não há votos. Os pontos à distância NaN ficam de
+/// fora (veja [`Vizinho`]); se forem todos, o erro é [`ErroKnn::VetorVazio`].
+///
+/// # Exemplo
+///
+/// ```
+/// use aprendizagem_knn::{knn, ponto, pontos, ErroKnn};
+///
+/// let treinamento = pontos![
+///     [1.0, 1.0] => "A",
+///     [1.5, 2.0] => "A",
+///     [8.0, 8.0] => "B",
+///     [9.0, 8.5] => "B",
+/// ];
+/// assert_eq!(knn(&treinamento, &ponto!([1.2, 1.4], "?"), 3).unwrap(), "A");
+///
+/// assert_eq!(knn(&[], &ponto!([1.2, 1.4], "?"), 3), Err(ErroKnn::ConjuntoVazio));
+/// assert_eq!(knn(&treinamento, &ponto!([1.2, 1.4], "?"), 0), Err(ErroKnn::KInvalido { k: 0, maximo: 4 }));
+///
+/// // Rótulos numéricos, sem nenhuma String
+/// use aprendizagem_knn::PontoGenerico;
+///
+/// let numeros = vec![PontoGenerico::novo(vec![0.0], 0u8), PontoGenerico::novo(vec![9.0], 1u8)];
+/// assert_eq!(knn(&numeros, &PontoGenerico::novo(vec![7.0], 0), 1), Ok(1));
+/// ```
+pub fn knn<R: Clone + Eq + Hash + Ord>(
+    treinamento: &[PontoGenerico<R>],
+    ponto_teste: &PontoGenerico<R>,
+    k: usize,
+) -> Result<R, ErroKnn> {
+    prever_com(treinamento, ponto_teste, k, distancia_euclidiana, Votacao::Maioria)
+}
+
+/// Mesmo algoritmo de [`knn`], mas com a métrica de distância escolhida pelo usuário.
+///
+/// Os erros são os de [`knn`]; uma métrica com parâmetros inválidos (veja
+/// [`DistanciaMetrica::validar`]) é [`ErroKnn::ParametroInvalido`], antes de qualquer distância.
+///
+/// ```
+/// use aprendizagem_knn::{knn_com_metrica, ponto, pontos, DistanciaMetrica, ErroKnn};
+///
+/// let treinamento = pontos![[0.0, 0.0] => "A", [5.0, 5.0] => "B"];
+/// let rotulo = knn_com_metrica(&treinamento, &ponto!([4.0, 4.5], "?"), 1, &DistanciaMetrica::Manhattan);
+/// assert_eq!(rotulo.unwrap(), "B");
+///
+/// let erro = knn_com_metrica(&treinamento, &ponto!([4.0, 4.5], "?"), 1, &DistanciaMetrica::Minkowski(-1.0));
+/// assert!(matches!(erro, Err(ErroKnn::ParametroInvalido(_))));
+/// ```
+pub fn knn_com_metrica(
+    treinamento: &[Ponto],
+    ponto_teste: &Ponto,
+    k: usize,
+    metrica: &DistanciaMetrica,
+) -> Result<String, ErroKnn> {
+    prever(treinamento, ponto_teste, k, metrica, Votacao::Maioria)
+}
+
+// Classificação das funções públicas com a métrica escolhida. A métrica é conferida
+// antes da busca: DistanciaMetrica::calcular entra em pânico com um p de Minkowski inválido
+fn prever(
+    treinamento: &[Ponto],
+    ponto_teste: &Ponto,
+    k: usize,
+    metrica: &DistanciaMetrica,
+    votacao: Votacao,
+) -> Result<String, ErroKnn> {
+    metrica.validar()?;
+    prever_com(treinamento, ponto_teste, k, |a: &Ponto, b: &Ponto| metrica.calcular(a, b), votacao)
+}
+
+// Classificação com qualquer tipo de rótulo e qualquer função de distância: confere as
+// entradas e, depois da busca, se sobrou algum vizinho (as distâncias NaN são descartadas)
+fn prever_com<R, D>(
+    treinamento: &[PontoGenerico<R>],
+    ponto_teste: &PontoGenerico<R>,
+    k: usize,
+    distancia: D,
+    votacao: Votacao,
+) -> Result<R, ErroKnn>
+where
+    R: Clone + Eq + Hash + Ord,
+    D: Fn(&PontoGenerico<R>, &PontoGenerico<R>) -> f64,
+{
+    validar_entrada(treinamento, k)?;
+    let vizinhos = k_vizinhos_com(treinamento, ponto_teste, k, distancia);
+    votar_entre(vizinhos, votacao).ok_or_else(|| erro_sem_vizinhos(&ponto_teste.caracteristicas))
+}
+
+// Sem nenhum vizinho depois da busca: a distância até todos os pontos de treinamento deu NaN
+pub(crate) fn erro_sem_vizinhos(caracteristicas: &[f64]) -> ErroKnn {
+    ErroKnn::VetorVazio(format!(
+        "nenhum vizinho para {:?}: a distância até todos os pontos de treinamento deu NaN",
+        caracteristicas
+    ))
+}
+
+// Confere as entradas das funções públicas antes da busca: sem pontos de treinamento não
+// há vizinhos, e com k = 0 não há votos. Um k maior que o treinamento não é erro: votam
+// todos os pontos, como sempre foi
+pub(crate) fn validar_entrada<R>(treinamento: &[PontoGenerico<R>], k: usize) -> Result<(), ErroKnn> {
+    if treinamento.is_empty() {
+        return Err(ErroKnn::ConjuntoVazio);
+    }
+    if k == 0 {
+        return Err(ErroKnn::KInvalido { k, maximo: treinamento.len() });
+    }
+    Ok(())
+}
+
+// Busca dos k vizinhos mais próximos, do mais próximo para o mais distante.
+// Aceita qualquer coleção iterável de referências a pontos (IntoIterator), o que permite,
+// por exemplo, deixar um ponto de fora sem precisar copiar o conjunto de treinamento.
+// Cada vizinho guarda a sua posição na coleção, para quem precisa do próprio ponto
+// (como o SMOTE) e não só do rótulo. Em distâncias iguais vem primeiro a menor posição
+// (veja o Ord de Vizinho), qualquer que seja a ordem em que o heap guarda os empates.
+pub(crate) fn k_vizinhos_mais_proximos<'a, I>(
+    treinamento: I,
+    ponto_teste: &Ponto,
+    k: usize,
+    metrica: &DistanciaMetrica,
+) -> Vec<Vizinho>
+where
+    I: IntoIterator<Item = &'a Ponto>,
+{
+    k_vizinhos_com(treinamento, ponto_teste, k, |a: &Ponto, b: &Ponto| metrica.calcular(a, b))
+}
+
+// A mesma busca para qualquer tipo de rótulo, com a distância dada por uma função
+fn k_vizinhos_com<'a, R, I, D>(
+    treinamento: I,
+    ponto_teste: &PontoGenerico<R>,
+    k: usize,
+    distancia: D,
+) -> Vec<VizinhoGenerico<R>>
+where
+    R: Clone + 'a,
+    I: IntoIterator<Item = &'a PontoGenerico<R>>,
+    D: Fn(&PontoGenerico<R>, &PontoGenerico<R>) -> f64,
+{
+    // BinaryHeap é uma fila de prioridade que mantém o menor elemento no topo
+    let mut heap = BinaryHeap::new();
+
+    // Calcular distâncias e adicionar ao heap; um NaN não é perto nem longe, então o
+    // ponto fica de fora (e é contado para o aviso)
+    let mut ignorados = 0;
+    for (indice, ponto_treinamento) in treinamento.into_iter().enumerate() {
+        let distancia = distancia(ponto_teste, ponto_treinamento);
+        if distancia.is_nan() {
+            ignorados += 1;
+            continue;
+        }
+        heap.push(VizinhoGenerico::novo(distancia, ponto_treinamento.rotulo.clone(), indice));
+    }
+    if ignorados > 0 {
+        log::warn!(
+            "{} ponto(s) de treinamento ignorado(s): a distância até {:?} deu NaN",
+            ignorados, ponto_teste.caracteristicas
+        );
+    }
+
+    // Coletar os k vizinhos mais próximos
+    let mut vizinhos = Vec::with_capacity(k);
+    for _ in 0..k {
+        // if let é usado para desempacotar Option de forma segura
+        if let Some(vizinho) = heap.pop() {
+            vizinhos.push(vizinho);
+        }
+    }
+    vizinhos
+}
+
+// Núcleo do KNN: busca os vizinhos e faz a votação entre os seus rótulos
+pub(crate) fn knn_sobre<'a, I>(treinamento: I, ponto_teste: &Ponto, k: usize, metrica: &DistanciaMetrica) -> String
+where
+    I: IntoIterator<Item = &'a Ponto>,
+{
+    knn_sobre_com_votacao(treinamento, ponto_teste, k, metrica, Votacao::Maioria)
+}
+
+// Mesmo núcleo, com o esquema de votação escolhido
+pub(crate) fn knn_sobre_com_votacao<'a, I>(
+    treinamento: I,
+    ponto_teste: &Ponto,
+    k: usize,
+    metrica: &DistanciaMetrica,
+    votacao: Votacao,
+) -> String
+where
+    I: IntoIterator<Item = &'a Ponto>,
+{
+    votar(k_vizinhos_mais_proximos(treinamento, ponto_teste, k, metrica), votacao)
+}
+
+// Votação entre os vizinhos encontrados, com o esquema escolhido
+fn votar(vizinhos: Vec<Vizinho>, votacao: Votacao) -> String {
+    votar_entre(vizinhos, votacao).unwrap_or_default()
+}
+
+// A mesma votação para qualquer tipo de rótulo; sem vizinhos não há vencedor (None)
+fn votar_entre<R: Eq + Hash + Ord>(vizinhos: Vec<VizinhoGenerico<R>>, votacao: Votacao) -> Option<R> {
+    match votacao {
+        Votacao::Maioria => maioria(vizinhos.into_iter().map(|vizinho| vizinho.rotulo)),
+        Votacao::InversoDaDistancia => maior_peso_pelo_inverso_da_distancia(vizinhos),
+    }
+}
+
+// Votação do KNN: o rótulo mais frequente entre os vizinhos.
+// Fica separada da busca para que quem encontra os vizinhos de outra forma
+// (como a validação leave-one-out) vote exatamente como o knn.
+// Sem nenhum rótulo, devolve o rótulo vazio; as funções públicas conferem as entradas
+// antes (em prever), então isso não chega a quem usa a biblioteca
+pub(crate) fn votar_por_maioria<I: IntoIterator<Item = String>>(rotulos: I) -> String {
+    maioria(rotulos).unwrap_or_default()
+}
+
+// O rótulo mais frequente, para qualquer tipo de rótulo; None se não houver nenhum
+fn maioria<R: Eq + Hash + Ord, I: IntoIterator<Item = R>>(rotulos: I) -> Option<R> {
+    // Contar frequência dos rótulos usando HashMap
+    let mut contador_rotulos = HashMap::new();
+    for rotulo in rotulos {
+        // entry API fornece uma maneira elegante de inserir ou atualizar valores
+        *contador_rotulos.entry(rotulo).or_insert(0) += 1;
+    }
+
+    // Encontrar o rótulo mais frequente
+    // A ordem de iteração do HashMap é aleatória, então em caso de empate
+    // escolhemos o rótulo alfabeticamente menor para que o resultado seja sempre o mesmo
+    contador_rotulos.into_iter()
+        .max_by(|(rotulo_a, count_a), (rotulo_b, count_b)| {
+            count_a.cmp(count_b).then_with(|| rotulo_b.cmp(rotulo_a))
+        })                               // Encontra entrada com maior contagem
+        .map(|(rotulo, _)| rotulo)       // Extrai apenas o rótulo
+}
+
+// Votação ponderada: cada vizinho vota com peso 1/distância, então os mais próximos
+// influenciam mais. Um vizinho à distância zero teria peso infinito; se houver algum,
+// só os vizinhos idênticos ao ponto de teste votam (por maioria entre eles).
+// Sem vizinhos, não há vencedor, como na maioria.
+fn maior_peso_pelo_inverso_da_distancia<R: Eq + Hash + Ord>(vizinhos: Vec<VizinhoGenerico<R>>) -> Option<R> {
+    if vizinhos.iter().any(|vizinho| vizinho.distancia == 0.0) {
+        return maioria(vizinhos.into_iter().filter(|vizinho| vizinho.distancia == 0.0).map(|vizinho| vizinho.rotulo));
+    }
+
+    let mut pesos: HashMap<R, f64> = HashMap::new();
+    for vizinho in vizinhos {
+        *pesos.entry(vizinho.rotulo).or_insert(0.0) += 1.0 / vizinho.distancia;
+    }
+    // No empate de pesos vence, como na votação por maioria, o rótulo alfabeticamente menor
+    pesos.into_iter()
+        .max_by(|(rotulo_a, peso_a), (rotulo_b, peso_b)| {
+            peso_a.total_cmp(peso_b).then_with(|| rotulo_b.cmp(rotulo_a))
+        })
+        .map(|(rotulo, _)| rotulo)
+}
+
+// ==================== PREVISÃO SOB DEMANDA (ITERADOR) ====================
+/// Iterador que classifica os pontos de teste um a um, conforme são pedidos.
+///
+/// Diferente de [`knn_batch`], os pontos de teste não precisam estar todos em um `Vec`:
+/// cada chamada a `next()` pega o próximo ponto do iterador de origem (que pode estar
+/// lendo de um arquivo, da rede ou de um sensor), roda o [`knn`] e devolve o ponto junto
+/// com o resultado do [`knn`] para ele. A memória extra não cresce com o número de pontos
+/// de teste.
+///
+/// É criado por [`KnnClassificador::prever_iter`].
+///
+/// ```
+/// use aprendizagem_knn::{pontos, KnnClassificador};
+///
+/// let treinamento = pontos![[0.0] => "A", [9.0] => "B"];
+/// let previsoes = KnnClassificador::prever_iter(&treinamento, pontos![[1.0] => "?", [8.0] => "?"], 1).unwrap();
+/// let rotulos: Result<Vec<String>, _> = previsoes.map(|(_, rotulo)| rotulo).collect();
+/// assert_eq!(rotulos.unwrap(), vec!["A", "B"]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct KnnIterador<'a, I: Iterator<Item = Ponto>> {
+    treinamento: &'a [Ponto],
+    pontos_teste: I,
+    k: usize,
+}
+
+impl<I: Iterator<Item = Ponto>> Iterator for KnnIterador<'_, I> {
+    // (ponto de teste, rótulo previsto ou o erro do knn para esse ponto)
+    type Item = (Ponto, Result<String, ErroKnn>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let ponto_teste = self.pontos_teste.next()?;
+        // As entradas foram conferidas em prever_iter; o que ainda pode falhar é um ponto
+        // à distância NaN de todo o treinamento, que não tem vizinhos
+        let rotulo = knn(self.treinamento, &ponto_teste, self.k);
+        Some((ponto_teste, rotulo))
+    }
+
+    // Um item de saída para cada ponto de teste, então o tamanho é o da origem
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.pontos_teste.size_hint()
+    }
+}
+
+/// Ponto de entrada para classificar com o KNN sem juntar os pontos de teste antes.
+///
+/// Não guarda nada: é só o lugar de [`KnnClassificador::prever_iter`].
+///
+/// ```
+/// use aprendizagem_knn::{ponto, pontos, KnnClassificador};
+///
+/// let treinamento = pontos![[0.0] => "A", [9.0] => "B"];
+/// let mut previsoes = KnnClassificador::prever_iter(&treinamento, [ponto!([7.0], "?")], 1).unwrap();
+/// assert_eq!(previsoes.next().unwrap().1, Ok("B".to_string()));
+/// assert!(previsoes.next().is_none());
+/// ```
+pub struct KnnClassificador;
+
+impl KnnClassificador {
+    /// Cria um [`KnnIterador`] que classifica os pontos de `pontos_teste` com os `k`
+    /// vizinhos mais próximos em `treinamento`, um de cada vez e só quando o próximo
+    /// resultado é pedido.
+    ///
+    /// O treinamento vazio e `k` igual a 0 são os erros de [`knn`], devolvidos já na
+    /// criação, antes de qualquer ponto ser classificado. Um ponto à distância NaN de todo
+    /// o treinamento vem com o erro [`ErroKnn::VetorVazio`] no lugar do rótulo, e a
+    /// iteração continua com o próximo.
+    ///
+    /// ```
+    /// use aprendizagem_knn::{knn_batch, ponto, pontos, ErroKnn, KnnClassificador, Ponto};
+    ///
+    /// let treinamento = pontos![[0.0] => "A", [1.0] => "A", [9.0] => "B", [10.0] => "B"];
+    ///
+    /// // Os pontos de teste são gerados sob demanda: nenhum Vec de teste é criado
+    /// let fluxo = (0..1_000_000).map(|i| Ponto::novo(vec![(i % 11) as f64], String::new()));
+    /// let mut previsoes = KnnClassificador::prever_iter(&treinamento, fluxo, 3).unwrap();
+    ///
+    /// let (ponto, rotulo) = previsoes.next().unwrap();
+    /// assert_eq!((ponto.caracteristicas[0], rotulo.unwrap().as_str()), (0.0, "A"));
+    /// // Só os pontos pedidos são classificados
+    /// let proximos: Vec<String> = previsoes.by_ref().take(9).map(|(_, rotulo)| rotulo.unwrap()).collect();
+    /// assert_eq!(proximos, vec!["A", "A", "A", "A", "A", "B", "B", "B", "B"]);
+    /// assert_eq!(previsoes.size_hint(), (1_000_000 - 10, Some(1_000_000 - 10)));
+    ///
+    /// // Com os mesmos pontos, o resultado é o de knn_batch
+    /// let testes = pontos![[2.0] => "?", [8.0] => "?"];
+    /// let rotulos: Result<Vec<String>, ErroKnn> = KnnClassificador::prever_iter(&treinamento, testes.clone(), 3)
+    ///     .unwrap()
+    ///     .map(|(_, rotulo)| rotulo)
+    ///     .collect();
+    /// assert_eq!(rotulos, knn_batch(&treinamento, &testes, 3));
+    ///
+    /// // Um ponto com NaN não tem vizinhos: vem o erro, e os outros pontos seguem
+    /// let testes = pontos![[f64::NAN] => "?", [9.5] => "?"];
+    /// let mut previsoes = KnnClassificador::prever_iter(&treinamento, testes, 1).unwrap();
+    /// assert!(matches!(previsoes.next().unwrap().1, Err(ErroKnn::VetorVazio(_))));
+    /// assert_eq!(previsoes.next().unwrap().1, Ok("B".to_string()));
+    /// ```
+    pub fn prever_iter<I>(
+        treinamento: &[Ponto],
+        pontos_teste: I,
+        k: usize,
+    ) -> Result<KnnIterador<'_, I::IntoIter>, ErroKnn>
+    where
+        I: IntoIterator<Item = Ponto>,
+    {
+        validar_entrada(treinamento, k)?;
+        Ok(KnnIterador { treinamento, pontos_teste: pontos_teste.into_iter(), k })
+    }
+}
+
+// ==================== ESQUEMAS DE VOTAÇÃO ====================
+/// Como os k vizinhos decidem a classe do ponto de teste.
+///
+/// # Exemplo
+///
+/// ```
+/// use aprendizagem_knn::{knn_com_votacao, ponto, pontos, DistanciaMetrica, Votacao};
+///
+/// // um A bem perto e dois B mais longe
+/// let treinamento = pontos![[1.0] => "A", [4.0] => "B", [5.0] => "B"];
+/// let teste = ponto!([0.0], "?");
+/// let euclidiana = DistanciaMetrica::Euclidiana;
+/// assert_eq!(knn_com_votacao(&treinamento, &teste, 3, &euclidiana, Votacao::Maioria).unwrap(), "B");
+/// assert_eq!(knn_com_votacao(&treinamento, &teste, 3, &euclidiana, Votacao::InversoDaDistancia).unwrap(), "A");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Votacao {
+    /// Cada vizinho vale um voto; vence a classe mais frequente (a votação do [`knn`])
+    Maioria,
+    /// Cada vizinho vota com peso 1/distância, então os mais próximos pesam mais.
+    /// Se algum vizinho estiver à distância zero, só os vizinhos nessa distância votam
+    InversoDaDistancia,
+}
+
+/// KNN com a métrica e o esquema de votação escolhidos.
+///
+/// Em qualquer esquema, no empate vence o rótulo alfabeticamente menor. Os erros são os
+/// de [`knn_com_metrica`].
+///
+/// ```
+/// use aprendizagem_knn::{knn, knn_com_votacao, ponto, pontos, DistanciaMetrica, Votacao};
+///
+/// let treinamento = pontos![[0.0] => "A", [1.0] => "A", [9.0] => "B"];
+/// let teste = ponto!([2.0], "?");
+/// let rotulo = knn_com_votacao(&treinamento, &teste, 3, &DistanciaMetrica::Euclidiana, Votacao::Maioria);
+/// assert_eq!(rotulo, knn(&treinamento, &teste, 3));
+/// ```
+pub fn knn_com_votacao(
+    treinamento: &[Ponto],
+    ponto_teste: &Ponto,
+    k: usize,
+    metrica: &DistanciaMetrica,
+    votacao: Votacao,
+) -> Result<String, ErroKnn> {
+    prever(treinamento, ponto_teste, k, metrica, votacao)
+}
+
+/// KNN que devolve, junto com o rótulo previsto, a confiança da previsão: a fração dos
+/// k vizinhos que têm o rótulo vencedor (a votação é a do [`knn`], por maioria).
+///
+/// Uma confiança de 1.0 quer dizer que todos os vizinhos concordam; perto de 1 dividido
+/// pelo número de classes, o ponto está numa região em que as classes se misturam. Se o
+/// treinamento tiver menos de `k` pontos, a fração é sobre todos eles (os pontos à
+/// distância NaN não contam). Os erros são os de [`knn_com_metrica`].
+///
+/// ```
+/// use aprendizagem_knn::{knn_com_confianca, ponto, pontos, DistanciaMetrica, ErroKnn};
+///
+/// let treinamento = pontos![[0.0] => "A", [1.0] => "A", [2.0] => "B", [9.0] => "B"];
+/// let euclidiana = DistanciaMetrica::Euclidiana;
+/// // Vizinhos de 0.5: 0.0 (A), 1.0 (A) e 2.0 (B)
+/// let (rotulo, confianca) = knn_com_confianca(&treinamento, &ponto!([0.5], "?"), 3, &euclidiana).unwrap();
+/// assert_eq!((rotulo.as_str(), confianca), ("A", 2.0 / 3.0));
+/// assert_eq!(knn_com_confianca(&treinamento, &ponto!([0.5], "?"), 2, &euclidiana).unwrap().1, 1.0);
+///
+/// assert_eq!(knn_com_confianca(&[], &ponto!([0.5], "?"), 3, &euclidiana), Err(ErroKnn::ConjuntoVazio));
+/// ```
+pub fn knn_com_confianca(
+    treinamento: &[Ponto],
+    ponto_teste: &Ponto,
+    k: usize,
+    metrica: &DistanciaMetrica,
+) -> Result<(String, f64), ErroKnn> {
+    metrica.validar()?;
+    validar_entrada(treinamento, k)?;
+    let vizinhos = k_vizinhos_mais_proximos(treinamento, ponto_teste, k, metrica);
+    let rotulo = maioria(vizinhos.iter().map(|vizinho| vizinho.rotulo.clone()))
+        .ok_or_else(|| erro_sem_vizinhos(&ponto_teste.caracteristicas))?;
+    let votos = vizinhos.iter().filter(|vizinho| vizinho.rotulo == rotulo).count();
+    let confianca = votos as f64 / vizinhos.len() as f64;
+    Ok((rotulo, confianca))
+}
+
+/// Classifica vários pontos de teste de uma vez, mantendo a ordem da entrada.
+///
+/// Os erros são os de [`knn`]; o primeiro ponto com erro interrompe o lote.
+///
+/// ```
+/// use aprendizagem_knn::{knn_batch, pontos};
+///
+/// let treinamento = pontos![[0.0] => "A", [10.0] => "B"];
+/// let testes = pontos![[9.0] => "?", [1.0] => "?"];
+/// assert_eq!(knn_batch(&treinamento, &testes, 1).unwrap(), vec!["B", "A"]);
+/// ```
+pub fn knn_batch(treinamento: &[Ponto], pontos_teste: &[Ponto], k: usize) -> Result<Vec<String>, ErroKnn> {
+    knn_batch_silencioso(treinamento, pontos_teste, k)
+}
+
+/// Como [`knn_batch`], mas chama `on_progresso(i, total)` depois de classificar cada ponto,
+/// com `i` indo de 1 até `total` (o número de pontos de teste).
+///
+/// Serve para atualizar uma barra de progresso ou escrever em um log durante lotes longos.
+/// O callback roda na mesma thread da classificação, entre um ponto e o próximo, então
+/// não deve ficar bloqueado por muito tempo (esperando rede ou disco, por exemplo): todo
+/// o tempo gasto nele atrasa o lote inteiro. Ele também não consegue interromper o lote;
+/// para permitir cancelamento, divida os pontos em pedaços e confira uma flag compartilhada
+/// (um `AtomicBool`, por exemplo) entre uma chamada e outra.
+///
+/// Os erros são os de [`knn`]; o lote para no primeiro ponto com erro, sem chamar o
+/// callback para ele.
+///
+/// ```
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use aprendizagem_knn::{knn_batch, knn_batch_com_progresso, pontos};
+///
+/// let treinamento = pontos![[0.0] => "A", [10.0] => "B"];
+/// let testes = pontos![[9.0] => "?", [1.0] => "?", [8.0] => "?"];
+///
+/// let concluidos = AtomicUsize::new(0);
+/// let previstos = knn_batch_com_progresso(&treinamento, &testes, 1, |i, total| {
+///     assert_eq!(total, 3);
+///     concluidos.store(i, Ordering::Relaxed);
+/// });
+/// assert_eq!(previstos, knn_batch(&treinamento, &testes, 1));
+/// assert_eq!(previstos.unwrap().len(), 3);
+/// assert_eq!(concluidos.load(Ordering::Relaxed), 3);
+/// ```
+pub fn knn_batch_com_progresso<F: Fn(usize, usize)>(
+    treinamento: &[Ponto],
+    pontos_teste: &[Ponto],
+    k: usize,
+    on_progresso: F,
+) -> Result<Vec<String>, ErroKnn> {
+    let total = pontos_teste.len();
+    pontos_teste.iter()
+        .enumerate()
+        .map(|(i, ponto_teste)| {
+            let rotulo = knn(treinamento, ponto_teste, k)?;
+            on_progresso(i + 1, total);
+            Ok(rotulo)
+        })
+        .collect()
+}
+
+/// [`knn_batch_com_progresso`] sem acompanhamento: o callback não faz nada.
+///
+/// ```
+/// use aprendizagem_knn::{knn_batch_silencioso, pontos};
+///
+/// let treinamento = pontos![[0.0] => "A", [10.0] => "B"];
+/// assert_eq!(knn_batch_silencioso(&treinamento, &pontos![[2.0] => "?"], 1).unwrap(), vec!["A"]);
+/// ```
+pub fn knn_batch_silencioso(
+    treinamento: &[Ponto],
+    pontos_teste: &[Ponto],
+    k: usize,
+) -> Result<Vec<String>, ErroKnn> {
+    knn_batch_com_progresso(treinamento, pontos_teste, k, |_, _| {})
+}
+
+/// KNN aproximado para conjuntos muito grandes: sorteia (fracao * n) pontos de
+/// treinamento, sem reposição, e roda o KNN só sobre essa subamostra.
+///
+/// É uma aproximação de Monte Carlo: quanto mais perto de 1.0 estiver `fracao`,
+/// mais o resultado se aproxima do KNN completo (com 1.0 ele é idêntico),
+/// e quanto menor, mais rápido e mais sujeito à sorte do sorteio.
+/// Uma `fracao` fora de (0.0, 1.0] é [`ErroKnn::ParametroInvalido`]; os outros erros são
+/// os de [`knn`], com a subamostra no lugar do treinamento.
+///
+/// ```
+/// use aprendizagem_knn::{knn, knn_subamostrado, ponto, pontos};
+///
+/// let treinamento = pontos![[0.0] => "A", [1.0] => "A", [9.0] => "B", [10.0] => "B"];
+/// let teste = ponto!([0.5], "?");
+/// assert_eq!(knn_subamostrado(&treinamento, &teste, 3, 1.0, 7), knn(&treinamento, &teste, 3));
+/// assert!(knn_subamostrado(&treinamento, &teste, 3, 0.0, 7).is_err());
+/// ```
+pub fn knn_subamostrado(
+    treinamento: &[Ponto],
+    ponto_teste: &Ponto,
+    k: usize,
+    fracao: f64,
+    semente: u64,
+) -> Result<String, ErroKnn> {
+    if !(fracao > 0.0 && fracao <= 1.0) {
+        return Err(ErroKnn::ParametroInvalido(format!("fracao deve estar em (0.0, 1.0] (recebido {})", fracao)));
+    }
+
+    let quantidade = (fracao * treinamento.len() as f64).ceil() as usize;
+    let mut indices = Rng::novo(semente).amostrar_indices(treinamento.len(), quantidade);
+    // Mantém a ordem original dos pontos, para que empates sejam resolvidos como no KNN completo
+    indices.sort_unstable();
+
+    let subamostra: Vec<Ponto> = indices.iter().map(|&i| treinamento[i].clone()).collect();
+    knn(&subamostra, ponto_teste, k)
+}
+
+// Esta função calcula o valor de "k" para o algoritmo KNN
+// com base no tamanho do conjunto de dados de treinamento.
+// Ela usa a heurística de calcular a raiz quadrada do
+// total de dados e arredondar o resultado para cima.
+/// Sugere um valor de k: a raiz quadrada do número de pontos, arredondada para cima.
+///
+/// ```
+/// use aprendizagem_knn::calcular_k;
+///
+/// assert_eq!(calcular_k(215), 15);
+/// assert_eq!(calcular_k(16), 4);
+/// ```
+pub fn calcular_k(total_dados: usize) -> usize {
+    // Converte o tamanho do conjunto de dados (usize) em um número de ponto flutuante (f64)
+    // para poder calcular a raiz quadrada.
+    (total_dados as f64)
+        // Calcula a raiz quadrada do total de dados.
+        .sqrt()
+        // Arredonda o resultado para cima, para o próximo número inteiro.
+        .ceil()
+        // Converte o resultado (f64) de volta para usize, que é o tipo esperado pelo algoritmo KNN.
+        as usize
+}
+
+/// Versão mais cuidadosa de [`calcular_k`]: continua sugerindo ⌈√n⌉, mas
+///
+/// - nunca devolve 0 (com `total_dados` 0 ou 1, k = 1);
+/// - nunca passa do número de pontos de treinamento;
+/// - em problemas binários (`n_classes` = `Some(2)`), usa sempre um k ímpar (tirando 1 de
+///   um k par), para que a votação entre as duas classes não possa empatar.
+///
+/// Com mais de duas classes um k ímpar não evita empates, então ele fica como está.
+/// [`calcular_k`] foi mantida sem mudanças para não alterar o resultado de quem já a usa.
+///
+/// ```
+/// use aprendizagem_knn::calcular_k_v2;
+///
+/// // Binário: sempre ímpar
+/// let binario: Vec<usize> = [0, 1, 2, 4, 100, 10_000].iter().map(|&n| calcular_k_v2(n, Some(2))).collect();
+/// assert_eq!(binario, vec![1, 1, 1, 1, 9, 99]);
+///
+/// // 5 classes (ou número de classes desconhecido): ⌈√n⌉, entre 1 e n
+/// let cinco: Vec<usize> = [0, 1, 2, 4, 100, 10_000].iter().map(|&n| calcular_k_v2(n, Some(5))).collect();
+/// assert_eq!(cinco, vec![1, 1, 2, 2, 10, 100]);
+/// assert_eq!(calcular_k_v2(100, None), 10);
+/// ```
+pub fn calcular_k_v2(total_dados: usize, n_classes: Option<usize>) -> usize {
+    // ⌈√n⌉ nunca passa de n para n ≥ 1; o max garante k ≥ 1 também para n = 0
+    let k = calcular_k(total_dados).clamp(1, total_dados.max(1));
+    if n_classes == Some(2) && k.is_multiple_of(2) {
+        k - 1
+    } else {
+        k
+    }
+}
+
+/// Sugere um k para um ponto de teste específico, de acordo com a densidade dos dados ao
+/// redor dele: conta os pontos de treinamento a uma distância euclidiana de no máximo
+/// `raio_estimativa` do ponto e usa a raiz quadrada dessa contagem, arredondada para cima.
+///
+/// Numa região densa o k fica grande e a votação, mais estável; numa região esparsa ele
+/// fica pequeno, e a previsão segue os poucos vizinhos que estão de fato perto. Se nenhum
+/// ponto estiver dentro do raio (inclusive com um raio negativo ou NaN), volta para o k
+/// global de [`calcular_k`] com o tamanho do treinamento todo.
+///
+/// ```
+/// use aprendizagem_knn::{calcular_k_local, ponto, pontos};
+///
+/// let treinamento = pontos![
+///     [0.0] => "A", [0.1] => "A", [0.2] => "A", [0.3] => "A", [0.4] => "A",
+///     [0.5] => "A", [0.6] => "A", [0.7] => "A", [0.8] => "A",
+///     [10.0] => "B",
+/// ];
+/// // 9 pontos no raio: k = 3
+/// assert_eq!(calcular_k_local(&treinamento, &ponto!([0.4], "?"), 1.0), 3);
+/// // Só o próprio B por perto: k = 1
+/// assert_eq!(calcular_k_local(&treinamento, &ponto!([10.0], "?"), 1.0), 1);
+/// // Ninguém no raio: ⌈√10⌉ = 4
+/// assert_eq!(calcular_k_local(&treinamento, &ponto!([5.0], "?"), 1.0), 4);
+/// ```
+pub fn calcular_k_local(treinamento: &[Ponto], ponto_teste: &Ponto, raio_estimativa: f64) -> usize {
+    let contagem = treinamento
+        .iter()
+        .filter(|ponto| distancia_euclidiana(ponto, ponto_teste) <= raio_estimativa)
+        .count();
+    if contagem == 0 {
+        calcular_k(treinamento.len())
+    } else {
+        // Com contagem ≥ 1, ⌈√contagem⌉ já é pelo menos 1
+        calcular_k(contagem)
+    }
+}
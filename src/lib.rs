@@ -0,0 +1,198 @@
+//! Implementação didática do algoritmo K-Nearest Neighbors (KNN).
+//!
+//! A biblioteca reúne tudo o que o binário `aprendizagemKNN` usa: a estrutura [`Ponto`],
+//! as funções de distância, o classificador [`knn()`](fn@knn), o pré-processamento das
+//! características, a leitura de CSV e a avaliação do modelo.
+//!
+//! # Exemplo
+//!
+//! ```
+//! use aprendizagem_knn::{calcular_k, carregar_dados_do_csv, knn, ponto};
+//!
+//! let dados = carregar_dados_do_csv("src/dados.csv").unwrap();
+//! let k = calcular_k(dados.len());
+//! let rotulo = knn(&dados, &ponto!([4.5, 8.0], "Desconhecido"), k).unwrap();
+//! println!("Classe prevista: {}", rotulo);
+//! ```
+
+// Todo item público precisa de documentação (/// ou //!)
+#![warn(missing_docs)]
+
+// ==================== MACROS PARA CRIAR PONTOS ====================
+// macro_rules! cria macros: código que gera código em tempo de compilação.
+// As macros ficam antes dos módulos para que eles também possam usá-las.
+// #[macro_export] deixa a macro disponível na raiz da biblioteca (aprendizagem_knn::ponto!).
+/// Cria um [`Ponto`]: `ponto!([1.0, 2.0], "A")` vira `Ponto::novo(vec![1.0, 2.0], String::from("A"))`.
+///
+/// `String::from` aceita tanto `&str` quanto `String` como rótulo.
+///
+/// ```
+/// use aprendizagem_knn::ponto;
+///
+/// let ponto = ponto!([1.0, 2.0], "A");
+/// assert_eq!(ponto.caracteristicas, vec![1.0, 2.0]);
+/// assert_eq!(ponto.rotulo, "A");
+/// ```
+#[macro_export]
+macro_rules! ponto {
+    ([$($valor:expr),* $(,)?], $rotulo:expr) => {
+        $crate::Ponto::novo(vec![$($valor),*], ::std::string::String::from($rotulo))
+    };
+}
+
+/// Cria um `Vec<Ponto>`: `pontos!([1.0, 2.0] => "A", [3.0, 4.0] => "B")`.
+///
+/// ```
+/// use aprendizagem_knn::pontos;
+///
+/// let dados = pontos![[1.0, 2.0] => "A", [3.0, 4.0] => "B"];
+/// assert_eq!(dados.len(), 2);
+/// assert_eq!(dados[1].rotulo, "B");
+/// ```
+#[macro_export]
+macro_rules! pontos {
+    ($([$($valor:expr),* $(,)?] => $rotulo:expr),* $(,)?) => {
+        vec![$($crate::ponto!([$($valor),*], $rotulo)),*]
+    };
+}
+
+// ==================== MÓDULOS ====================
+pub mod agrupamento;
+pub mod aleatorio;
+pub mod analise;
+pub mod anomalia;
+pub mod aprendizado_ativo;
+pub mod avaliacao;
+pub mod balanceamento;
+pub mod busca;
+pub mod centroide;
+pub mod classificador;
+pub mod codificacao;
+pub mod comparacao;
+pub mod confusao;
+pub mod dados;
+pub mod dados_arff;
+#[cfg(feature = "http")]
+pub mod dados_http;
+pub mod dados_json;
+pub mod dados_libsvm;
+#[cfg(feature = "parquet")]
+pub mod dados_parquet;
+#[cfg(feature = "sqlite")]
+pub mod dados_sqlite;
+#[cfg(feature = "cli")]
+pub mod diagnostico;
+pub mod distancia;
+pub mod ensemble;
+pub mod erro;
+pub mod estatisticas;
+pub mod kdarvore;
+pub mod knn;
+pub mod limpeza;
+pub mod matriz_distancias;
+pub mod metricas;
+pub mod modelo;
+pub mod pca;
+pub mod ponderacao;
+pub mod ponto;
+pub mod preprocessamento;
+pub mod regioes;
+pub mod registro;
+#[cfg(feature = "cli")]
+pub mod terminal;
+pub mod validacao;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+// Reexporta os itens públicos na raiz, para que quem usa a biblioteca possa
+// escrever aprendizagem_knn::knn em vez de aprendizagem_knn::knn::knn
+pub use agrupamento::{calcular_centroides, calcular_inercia, kmeans};
+pub use aleatorio::{definir_semente, obter_semente, Rng, SementeGlobal, SEMENTE_PADRAO};
+pub use analise::{
+    classe_majoritaria, classe_minoritaria, contar_rotulos, estatisticas_separabilidade, imprimir_distribuicao,
+    SeparabilidadeClasses,
+};
+pub use aprendizado_ativo::{selecionar_para_rotular, simulacao_aprendizado_ativo};
+pub use anomalia::{
+    area_pr, comparar_scores_anomalia, curva_precisao_recall_anomalia, detectar_anomalias, pontuacao_anomalia,
+    pontuacao_anomalia_canberra,
+};
+pub use avaliacao::{bootstrap_acuracia, bootstrap_previsoes, IntervaloBootstrap};
+pub use balanceamento::{
+    encontrar_tomek_links, remover_tomek_majoritarios, smote, sobreamostrar_minoritario, subamostrar,
+    subamostrar_majoritario,
+};
+pub use busca::{
+    busca_em_grade, candidatos_k_padrao, escolher_k, selecionar_k_um_erro_padrao, ConfiguracaoKnn, GradeBusca,
+    PontoCurvaK, RelatorioBusca, ResultadoBusca, SelecaoK,
+};
+pub use centroide::{comparar_com_knn, ClassificadorCentroide};
+pub use classificador::{ClassificadorKnn, ConstrutorClassificadorKnn};
+pub use codificacao::{aplicar_one_hot, codificar_one_hot, vocabulario_categorias, CodificadorOneHot};
+pub use comparacao::{diferenca_significativa, mcnemar, teste_t_welsh, TesteMcNemar, LIMITE_MCNEMAR_EXATO};
+pub use confusao::MatrizConfusao;
+pub use dados::{
+    carregar_dados_de_leitor, carregar_dados_de_leitor_com_opcoes, carregar_dados_de_texto_csv, carregar_dados_do_csv,
+    carregar_dados_do_csv_categorico, carregar_dados_do_csv_com_ausentes, carregar_dados_do_csv_com_opcoes,
+    carregar_dados_do_csv_com_rotulo, carregar_dados_do_csv_gz, carregar_dados_do_csv_mapeando_rotulos,
+    carregar_dados_mmap, carregar_pontos_sem_rotulo, classificar_csv, classificar_csv_de_leitor, detectar_delimitador,
+    interpretar_virgula_decimal, OpcoesCsv,
+};
+#[cfg(feature = "http")]
+pub use dados_http::{carregar_dados_de_url, carregar_dados_de_url_com_opcoes};
+pub use dados_json::{
+    carregar_dados_do_json, carregar_previsoes_json, escrever_previsoes_json, prever_para_json, salvar_previsoes_json,
+    FormatoJson, Previsao, VizinhoPrevisao,
+};
+pub use dados_arff::{carregar_dados_de_texto_arff, carregar_dados_do_arff};
+pub use dados_libsvm::{carregar_dados_de_texto_libsvm, carregar_dados_do_libsvm};
+#[cfg(feature = "parquet")]
+pub use dados_parquet::{carregar_dados_do_parquet, exportar_pontos_parquet};
+#[cfg(feature = "sqlite")]
+pub use dados_sqlite::{carregar_dados_de_conexao_sqlite, carregar_dados_do_sqlite};
+#[cfg(feature = "cli")]
+pub use diagnostico::inicializar_logger;
+pub use distancia::{
+    caminho_dtw, distancia_canberra, distancia_chebyshev, distancia_correlacao_pearson, distancia_dtw,
+    distancia_dtw_com_janela, distancia_euclidiana, distancia_euclidiana_auto, distancia_euclidiana_parcial,
+    distancia_euclidiana_ponderada, distancia_euclidiana_simd, distancia_manhattan, distancia_minkowski,
+    simd_disponivel, DistanciaMetrica,
+};
+pub use ensemble::EnsembleKnn;
+pub use erro::ErroKnn;
+pub use estatisticas::{percentil, EstatisticasIncrementais};
+pub use kdarvore::KdArvore;
+pub use knn::{
+    calcular_k, calcular_k_local, calcular_k_v2, knn, knn_batch, knn_batch_com_progresso, knn_batch_silencioso,
+    knn_com_confianca, knn_com_metrica, knn_com_votacao, knn_subamostrado, KnnClassificador, KnnIterador, Votacao,
+    Vizinho, VizinhoGenerico,
+};
+pub use limpeza::{deduplicar, enn, enn_com_removidos, ResultadoDeduplicacao};
+pub use matriz_distancias::MatrizDistanciasLazy;
+pub use metricas::{
+    acuracia_balanceada, acuracia_ponderada, auc_roc, curva_roc, f1_ponderado, log_loss, relatorio_classificacao,
+    CurvaRoc, MediasMetricas, MetricasClasse, PontoRoc, RelatorioClassificacao, EPSILON_LOG_LOSS,
+};
+pub use modelo::Modelo;
+pub use pca::PCA;
+pub use ponderacao::{
+    ajustar_pesos_informacao_mutua, informacao_mutua, knn_ponderado_por_informacao_mutua, rankear_features_por_mi,
+};
+pub use ponto::{Ponto, PontoGenerico};
+pub use preprocessamento::{
+    imprimir_recomendacoes_normalizacao, knn_com_ausentes, knn_preprocessado, rankear_features_por_variancia,
+    recomendar_normalizacao, selecionar_top_n_features, selecionar_variancia_acumulada,
+    tabela_recomendacoes_normalizacao, CadeiaTransformadores, ColunaDescartada, EscalonadorRobusto,
+    EstrategiaImputacao, Imputador, MetodoNormalizacao, NormalizadorMinMax, Padronizador, PoliticaAusentes,
+    Preprocessamento, RecomendacaoNormalizacao, SeletorVariancia, SemTransformacao, Transformador,
+};
+pub use regioes::{exportar_regioes_csv, exportar_regioes_decisao};
+pub use registro::{carregar_log, knn_com_log};
+#[cfg(feature = "cli")]
+pub use terminal::{limpar_se_terminal, limpar_terminal};
+pub use validacao::{
+    curva_aprendizado, curva_aprendizado_por_tamanho, dividir_treino_teste, loocv, loocv_com_limite, validacao_cruzada,
+    validacao_cruzada_com_estratificacao, validacao_cruzada_estratificada, validacao_cruzada_estratificada_repetida,
+    validacao_cruzada_k_fold, validacao_cruzada_k_fold_csv, validacao_cruzada_repetida, AvaliacaoFold,
+    CurvaAprendizado, FoldCV, PontoCurvaAprendizado, RelatorioCV, RelatorioCVRepetida, ResultadoCV, ResumoMetrica,
+};
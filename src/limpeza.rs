@@ -0,0 +1,145 @@
+//! Limpeza do conjunto de treinamento: remoção de ruído e de pontos repetidos.
+
+use std::collections::HashMap;
+
+use crate::distancia::{distancia_euclidiana, DistanciaMetrica};
+use crate::knn::knn_sobre;
+use crate::ponto::Ponto;
+
+// ==================== LIMPEZA DO CONJUNTO DE TREINAMENTO ====================
+/// Edited Nearest Neighbor (ENN): remove os pontos que são classificados errado
+/// pelos seus próprios k vizinhos (deixando o próprio ponto de fora).
+///
+/// Esses pontos costumam ser ruído ou rótulos errados perto da fronteira entre classes.
+/// O processo se repete até nenhum ponto ser removido.
+/// Devolve (pontos mantidos, pontos removidos).
+///
+/// ```
+/// use aprendizagem_knn::{enn_com_removidos, pontos};
+///
+/// let dados = pontos![
+///     [0.0] => "A", [0.1] => "A", [0.2] => "B", [0.3] => "A",
+///     [5.0] => "B", [5.1] => "B", [5.2] => "B",
+/// ];
+/// let (mantidos, removidos) = enn_com_removidos(&dados, 3);
+/// assert_eq!(mantidos.len(), 6);
+/// assert_eq!(removidos[0].caracteristicas, vec![0.2]);
+/// ```
+pub fn enn_com_removidos(dados: &[Ponto], k: usize) -> (Vec<Ponto>, Vec<Ponto>) {
+    let mut mantidos = dados.to_vec();
+    let mut removidos = Vec::new();
+
+    // Com menos de 2 pontos não há vizinhos para votar
+    while mantidos.len() > 1 {
+        let mal_classificados: Vec<bool> = (0..mantidos.len())
+            .map(|i| {
+                let outros = mantidos.iter()
+                    .enumerate()
+                    .filter(|(j, _)| *j != i)
+                    .map(|(_, ponto)| ponto);
+                knn_sobre(outros, &mantidos[i], k, &DistanciaMetrica::Euclidiana) != mantidos[i].rotulo
+            })
+            .collect();
+
+        if !mal_classificados.contains(&true) {
+            break;
+        }
+
+        // Todos os pontos da rodada são avaliados antes de qualquer remoção
+        let (ruins, bons): (Vec<_>, Vec<_>) = mantidos.into_iter()
+            .zip(mal_classificados)
+            .partition(|(_, mal_classificado)| *mal_classificado);
+        removidos.extend(ruins.into_iter().map(|(ponto, _)| ponto));
+        mantidos = bons.into_iter().map(|(ponto, _)| ponto).collect();
+    }
+
+    (mantidos, removidos)
+}
+
+/// Mesma limpeza de [`enn_com_removidos`], devolvendo só o conjunto resultante.
+///
+/// ```
+/// use aprendizagem_knn::{enn, pontos};
+///
+/// let dados = pontos![[0.0] => "A", [0.1] => "A", [0.2] => "B", [0.3] => "A"];
+/// assert!(enn(&dados, 3).iter().all(|ponto| ponto.rotulo == "A"));
+/// ```
+pub fn enn(dados: &[Ponto], k: usize) -> Vec<Ponto> {
+    enn_com_removidos(dados, k).0
+}
+
+/// Resultado da remoção de duplicatas feita por [`deduplicar`].
+///
+/// ```
+/// use aprendizagem_knn::{deduplicar, pontos};
+///
+/// let dados = pontos![[1.0] => "A", [1.0] => "A", [1.0] => "B"];
+/// let resultado = deduplicar(&dados, 0.0);
+/// assert_eq!(resultado.pontos.len(), 2);
+/// assert_eq!(resultado.removidos, 1);
+/// assert_eq!(resultado.conflitos, vec![(0, 2)]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ResultadoDeduplicacao {
+    /// Pontos mantidos, na ordem original
+    pub pontos: Vec<Ponto>,
+    /// Quantos pontos foram descartados
+    pub removidos: usize,
+    /// Pares (índice mantido, índice conflitante) no conjunto original com as mesmas
+    /// características (dentro de epsilon), mas rótulos diferentes: problema de qualidade
+    /// dos dados que vale a pena investigar. Os dois pontos são mantidos.
+    pub conflitos: Vec<(usize, usize)>,
+}
+
+/// Remove pontos repetidos: um ponto é descartado quando suas características estão
+/// a no máximo `epsilon` (distância euclidiana) de um ponto já mantido com o mesmo rótulo.
+///
+/// Duplicatas ganham votos extras no KNN, então removê-las deixa a votação mais justa.
+/// Com epsilon = 0 a comparação é exata e usa hashing dos bits dos números, em O(n).
+///
+/// ```
+/// use aprendizagem_knn::{deduplicar, pontos};
+///
+/// let dados = pontos![[1.0, 2.0] => "A", [1.001, 2.0] => "A", [9.0, 9.0] => "B"];
+/// assert_eq!(deduplicar(&dados, 0.0).removidos, 0);
+/// assert_eq!(deduplicar(&dados, 0.01).removidos, 1);
+/// ```
+pub fn deduplicar(treinamento: &[Ponto], epsilon: f64) -> ResultadoDeduplicacao {
+    let mut mantidos: Vec<usize> = Vec::new();
+    let mut conflitos = Vec::new();
+
+    if epsilon == 0.0 {
+        // Chave: os bits de cada característica (0.0 e -0.0 são tratados como iguais)
+        let mut vistos: HashMap<Vec<u64>, Vec<usize>> = HashMap::new();
+        for (indice, ponto) in treinamento.iter().enumerate() {
+            let chave = ponto.caracteristicas.iter()
+                .map(|&valor| if valor == 0.0 { 0 } else { valor.to_bits() })
+                .collect();
+            let iguais = vistos.entry(chave).or_default();
+            if iguais.iter().any(|&j| treinamento[j].rotulo == ponto.rotulo) {
+                continue;
+            }
+            conflitos.extend(iguais.iter().map(|&j| (j, indice)));
+            iguais.push(indice);
+            mantidos.push(indice);
+        }
+    } else {
+        for (indice, ponto) in treinamento.iter().enumerate() {
+            let proximos: Vec<usize> = mantidos.iter()
+                .copied()
+                .filter(|&j| distancia_euclidiana(&treinamento[j], ponto) <= epsilon)
+                .collect();
+            if proximos.iter().any(|&j| treinamento[j].rotulo == ponto.rotulo) {
+                continue;
+            }
+            conflitos.extend(proximos.iter().map(|&j| (j, indice)));
+            mantidos.push(indice);
+        }
+    }
+
+    ResultadoDeduplicacao {
+        removidos: treinamento.len() - mantidos.len(),
+        pontos: mantidos.iter().map(|&i| treinamento[i].clone()).collect(),
+        conflitos,
+    }
+}
@@ -1,183 +1,355 @@
+// O binário é só uma interface de linha de comando: todo o algoritmo
+// fica na biblioteca (src/lib.rs), que também pode ser usada por outros projetos
+
 // ==================== IMPORTAÇÃO DE BIBLIOTECAS ====================
-use csv::ReaderBuilder;        // Biblioteca externa para manipulação de arquivos CSV
-use serde::Deserialize;        // Biblioteca para converter (deserializar) dados de forma automática
-use std::cmp::Ordering;        // Módulo padrão para definir como comparar elementos
-use std::collections::BinaryHeap; // Estrutura de dados de fila de prioridade (heap)
+use aprendizagem_knn::{
+    calcular_k, carregar_dados_de_leitor_com_opcoes, carregar_dados_do_csv, carregar_dados_do_csv_com_opcoes,
+    carregar_dados_do_json, carregar_dados_do_libsvm, classificar_csv, classificar_csv_de_leitor, escolher_k,
+    imprimir_distribuicao, inicializar_logger, knn, knn_com_confianca, knn_com_metrica, limpar_terminal,
+    obter_semente, validacao_cruzada, DistanciaMetrica, ErroKnn, Modelo, OpcoesCsv, Ponto, Preprocessamento,
+};
+use clap::error::ErrorKind;    // Tipos de erro do clap, para relatar erros nos argumentos
+use clap::{CommandFactory, Parser, Subcommand};
 use std::error::Error;         // Trait para tratamento padronizado de erros
-use std::process::Command;     // Módulo para executar comandos do sistema operacional
-
-// ==================== ESTRUTURA DE DADOS PRINCIPAIS ====================
-// #[derive] são atributos em Rust que adicionam funcionalidades às estruturas
-// Debug: permite imprimir a estrutura para debug
-// Clone: permite criar cópias da estrutura
-// Deserialize: permite converter dados externos (como CSV) para esta estrutura
-#[derive(Debug, Clone, Deserialize)]
-struct Ponto {
-    caracteristicas: Vec<f64>, // Vec<f64> é um vetor dinâmico de números decimais
-    rotulo: String,            // String é o tipo de texto em Rust
-}
+use std::io;                   // Entrada padrão (stdin), para ler o CSV de um pipe
+use std::process::ExitCode;    // Código de saída do programa
 
-// impl em Rust define a implementação de métodos para uma estrutura
-// Similar a métodos de classe em outras linguagens
-impl Ponto {
-    // fn define uma função em Rust
-    // -> indica o tipo de retorno da função
-    // Self refere-se ao tipo atual (Ponto)
-    fn novo(caracteristicas: Vec<f64>, rotulo: String) -> Self {
-        Self { caracteristicas, rotulo } // Sintaxe curta quando o nome do campo e da variável são iguais
-    }
-}
+// ==================== ARGUMENTOS DA LINHA DE COMANDO ====================
+// #[derive(Parser)] gera a leitura dos argumentos e o texto do --help a partir da struct;
+// os comentários /// de cada campo viram a descrição de cada opção
+/// Classifica um ponto com o algoritmo KNN (K vizinhos mais próximos).
+///
+/// Sem subcomando, classifica o --ponto (ou cada linha do --teste) com os dados do --dados.
+/// Os subcomandos treinam e usam um modelo salvo, avaliam o KNN e resumem um CSV; veja
+/// `aprendizagemKNN <SUBCOMANDO> --help`.
+#[derive(Parser, Debug)]
+#[command(version, args_conflicts_with_subcommands = true)]
+struct Argumentos {
+    #[command(subcommand)]
+    comando: Option<Comando>,
+
+    /// Arquivo CSV com os dados de treinamento (características e rótulo na última coluna),
+    /// JSON (.json) com um array de {"caracteristicas": [...], "rotulo": "..."} ou
+    /// LIBSVM/SVMlight (.libsvm ou .svm), com linhas como "+1 1:0.5 3:2.0".
+    /// Com a feature http, também pode ser a URL (http:// ou https://) de um CSV
+    #[arg(long, value_name = "CAMINHO", default_value = "src/dados.csv")]
+    dados: String,
+
+    /// Lê o CSV de treinamento da entrada padrão (por exemplo, `cat dados.csv | knn --stdin`),
+    /// em vez do --dados; as opções de CSV abaixo valem do mesmo jeito
+    #[arg(long, conflicts_with = "dados")]
+    stdin: bool,
+
+    /// Separador das colunas do --dados e do --teste: um caractere (',', ';', '|'...) ou "tab"
+    #[arg(long, value_name = "CARACTERE", value_parser = ler_caractere, default_value = ",")]
+    delimitador: u8,
+
+    /// Escolhe o separador do --dados e do --teste pela primeira linha de cada um (vírgula,
+    /// ponto e vírgula, tabulação ou '|'), em vez de usar o --delimitador
+    #[arg(long, conflicts_with = "delimitador")]
+    detectar_delimitador: bool,
+
+    /// Aspas dos campos do --dados e do --teste que contêm o separador
+    #[arg(long, value_name = "CARACTERE", value_parser = ler_caractere, default_value = "\"")]
+    aspas: u8,
+
+    /// Os números do --dados e do --teste usam vírgula decimal e ponto de milhar (1.234,56), como nos
+    /// arquivos do Excel em português
+    #[arg(long)]
+    virgula_decimal: bool,
 
-// ==================== FUNÇÃO DE DISTÂNCIA ====================
-// fn define uma função "solta" (não associada a uma estrutura)
-// &Ponto indica uma referência a um Ponto (sem transferir propriedade)
-fn distancia_euclidiana(ponto1: &Ponto, ponto2: &Ponto) -> f64 {
-    ponto1.caracteristicas.iter()     // iter() cria um iterador sobre as características
-        .zip(ponto2.caracteristicas.iter()) // zip combina dois iteradores em pares
-        .map(|(a, b)| (a - b).powi(2))     // map transforma cada par em sua diferença ao quadrado
-        .sum::<f64>()                       // soma todos os valores (anotação de tipo explícita)
-        .sqrt()                             // calcula a raiz quadrada
+    /// O --dados e o --teste não têm cabeçalho: a primeira linha já é um ponto
+    #[arg(long)]
+    sem_cabecalho: bool,
+
+    /// Lê o --dados até o fim mesmo com linhas inválidas e mostra todos os problemas de
+    /// uma vez, em vez de parar no primeiro
+    #[arg(long)]
+    todos_os_erros: bool,
+
+    /// Número de vizinhos [padrão: raiz do número de pontos, arredondada para cima]
+    #[arg(long, value_parser = ler_k)]
+    k: Option<usize>,
+
+    /// Métrica de distância: euclidiana, euclidiana_parcial, manhattan, chebyshev,
+    /// minkowski:p, pearson, canberra, dtw ou dtw:janela
+    #[arg(long, default_value = "euclidiana")]
+    metrica: DistanciaMetrica,
+
+    /// Características do ponto a classificar, separadas por vírgula
+    #[arg(
+        long,
+        value_name = "X1,X2,...",
+        value_delimiter = ',',
+        allow_hyphen_values = true,
+        default_value = "4.5,8.0"
+    )]
+    ponto: Vec<f64>,
+
+    /// CSV sem rótulo com vários pontos a classificar (as mesmas colunas de características
+    /// do treinamento), lido com as mesmas opções de CSV do --dados; substitui o --ponto.
+    /// Com "-", o teste é lido da entrada padrão
+    #[arg(long, value_name = "CAMINHO", requires = "saida", conflicts_with = "ponto")]
+    teste: Option<String>,
+
+    /// CSV em que são gravadas as linhas do --teste com a coluna rotulo_previsto
+    #[arg(long, value_name = "CAMINHO", requires = "teste")]
+    saida: Option<String>,
+
+    /// Acrescenta à saída a coluna confianca (a fração dos k vizinhos que concordam)
+    #[arg(long, requires = "teste")]
+    confianca: bool,
+
+    /// Não limpa a tela antes de mostrar o resultado (ela já não é limpa quando a saída
+    /// vai para um pipe ou arquivo)
+    #[arg(long)]
+    sem_limpar: bool,
 }
 
-// ==================== ESTRUTURA AUXILIAR PARA VIZINHOS ====================
-#[derive(Debug)]
-struct Vizinho {
-    distancia: f64,
-    rotulo: String,
+// Cada subcomando vira uma variante; os campos são as opções dele, e o /// de cada
+// variante é a descrição que aparece no --help. Os nomes em inglês são apelidos
+#[derive(Subcommand, Debug)]
+enum Comando {
+    /// Cria um modelo a partir de um CSV e o salva em JSON, com o k escolhido
+    #[command(visible_alias = "train")]
+    Treinar {
+        /// CSV de treinamento, com cabeçalho e o rótulo na última coluna
+        #[arg(long, value_name = "CAMINHO")]
+        csv: String,
+
+        /// Número de vizinhos guardado no modelo [padrão: raiz do número de pontos]
+        #[arg(long, value_parser = ler_k, conflicts_with = "k_auto")]
+        k: Option<usize>,
+
+        /// Escolhe o k por validação cruzada em 5 folds (o menor k a até um erro padrão
+        /// da melhor acurácia), em vez da regra da raiz
+        #[arg(long)]
+        k_auto: bool,
+
+        /// Arquivo JSON em que o modelo é salvo
+        #[arg(long, visible_alias = "output", value_name = "CAMINHO")]
+        saida: String,
+    },
+
+    /// Classifica um ponto com um modelo salvo pelo subcomando treinar
+    #[command(visible_alias = "predict")]
+    Prever {
+        /// Modelo JSON salvo pelo treinar
+        #[arg(long, visible_alias = "model", value_name = "CAMINHO")]
+        modelo: String,
+
+        /// Características do ponto a classificar, separadas por vírgula
+        #[arg(
+            long,
+            visible_alias = "features",
+            value_name = "X1,X2,...",
+            value_delimiter = ',',
+            allow_hyphen_values = true,
+            required = true
+        )]
+        caracteristicas: Vec<f64>,
+    },
+
+    /// Mede a acurácia do KNN num CSV com validação cruzada (dados normalizados com min-max)
+    #[command(visible_alias = "evaluate")]
+    Avaliar {
+        /// CSV com cabeçalho e o rótulo na última coluna
+        #[arg(long, value_name = "CAMINHO")]
+        csv: String,
+
+        /// Número de vizinhos [padrão: raiz do número de pontos]
+        #[arg(long, value_parser = ler_k)]
+        k: Option<usize>,
+
+        /// Número de folds da validação cruzada (de 2 até o número de pontos)
+        #[arg(long, value_parser = ler_folds, default_value = "10")]
+        folds: usize,
+    },
+
+    /// Mostra um resumo de um CSV: pontos, classes e o intervalo, a média e o desvio
+    /// padrão de cada característica
+    #[command(visible_alias = "stats")]
+    Estatisticas {
+        /// CSV com cabeçalho e o rótulo na última coluna
+        #[arg(long, value_name = "CAMINHO")]
+        csv: String,
+    },
 }
 
-impl Vizinho {
-    fn novo(distancia: f64, rotulo: String) -> Self {
-        Self { distancia, rotulo }
+// k = 0 não tem nenhum vizinho para votar, então é recusado já na leitura
+fn ler_k(texto: &str) -> Result<usize, String> {
+    match texto.parse::<usize>() {
+        Ok(0) => Err("k deve ser maior que zero".to_string()),
+        Ok(k) => Ok(k),
+        Err(_) => Err(format!("'{}' não é um número inteiro positivo", texto)),
     }
 }
 
-// ==================== IMPLEMENTAÇÃO DE ORDENAÇÃO ====================
-// Em Rust, para usar uma estrutura em uma coleção ordenada (como BinaryHeap),
-// precisamos implementar traits (interfaces) de comparação
-
-// Ord é usado para definir uma ordenação total (todos elementos são comparáveis)
-impl Ord for Vizinho {
-    fn cmp(&self, outro: &Self) -> Ordering {
-        // partial_cmp para f64 retorna Option<Ordering>, unwrap converte para Ordering
-        // Invertemos a ordem para ter um heap de mínimo (menor distância = maior prioridade)
-        outro.distancia.partial_cmp(&self.distancia).unwrap()
+// Com um fold só não sobra nada para treinar; o limite superior (o número de pontos) só é
+// conhecido depois de ler o CSV
+fn ler_folds(texto: &str) -> Result<usize, String> {
+    match texto.parse::<usize>() {
+        Ok(folds) if folds >= 2 => Ok(folds),
+        Ok(_) => Err("são precisos ao menos 2 folds".to_string()),
+        Err(_) => Err(format!("'{}' não é um número inteiro positivo", texto)),
     }
 }
 
-// PartialOrd é necessário para tipos que podem ser parcialmente ordenados
-impl PartialOrd for Vizinho {
-    fn partial_cmp(&self, outro: &Self) -> Option<Ordering> {
-        Some(self.cmp(outro))
+// O leitor de CSV trabalha com bytes, então o separador e as aspas precisam ser um único
+// caractere ASCII; "tab" (ou "\t") é aceito porque uma tabulação é difícil de digitar
+fn ler_caractere(texto: &str) -> Result<u8, String> {
+    match texto {
+        "tab" | "\\t" => Ok(b'\t'),
+        _ if texto.len() == 1 && texto.is_ascii() => Ok(texto.as_bytes()[0]),
+        _ => Err(format!("'{}' não é um único caractere ASCII (use \"tab\" para tabulação)", texto)),
     }
 }
 
-// PartialEq define quando dois elementos são iguais
-impl PartialEq for Vizinho {
-    fn eq(&self, outro: &Self) -> bool {
-        self.distancia == outro.distancia
+// Aqui o ErroKnn vira Box<dyn Error>. Os erros que não dizem de onde vieram (conjunto
+// vazio, dimensão incompatível) ganham a origem dos dados na frente; os outros já citam
+// o arquivo e a linha
+fn com_origem(erro: ErroKnn, origem: &str) -> Box<dyn Error> {
+    match erro {
+        ErroKnn::ConjuntoVazio | ErroKnn::DimensaoIncompativel { .. } => format!("{}: {}", origem, erro).into(),
+        outro => outro.into(),
     }
 }
 
-// Eq é um trait marcador que indica que a igualdade é uma relação de equivalência
-impl Eq for Vizinho {}
-
-// ==================== ALGORITMO KNN ====================
-// &[Ponto] é uma fatia (slice) de Pontos - uma visão de um array
-// usize é o tipo usado para índices e tamanhos em Rust
-fn knn(treinamento: &[Ponto], ponto_teste: &Ponto, k: usize) -> String {
-    // BinaryHeap é uma fila de prioridade que mantém o menor elemento no topo
-    let mut heap = BinaryHeap::new();
+// Baixa o CSV de treinamento de uma URL (feature http)
+#[cfg(feature = "http")]
+fn carregar_de_url(url: &str, opcoes: &OpcoesCsv) -> Result<Vec<Ponto>, ErroKnn> {
+    let (_, pontos) = aprendizagem_knn::carregar_dados_de_url_com_opcoes(url, opcoes)?;
+    Ok(pontos)
+}
 
-    // Calcular distâncias e adicionar ao heap
-    for ponto_treinamento in treinamento {
-        let distancia = distancia_euclidiana(ponto_teste, ponto_treinamento);
-        heap.push(Vizinho::novo(distancia, ponto_treinamento.rotulo.clone()));
-    }
+// Sem a feature http, uma URL no --dados explica como compilar com o suporte
+#[cfg(not(feature = "http"))]
+fn carregar_de_url(url: &str, _opcoes: &OpcoesCsv) -> Result<Vec<Ponto>, ErroKnn> {
+    Err(ErroKnn::ParametroInvalido(format!("{}: para ler os dados de uma URL, compile com --features http", url)))
+}
 
-    // Coletar os k vizinhos mais próximos
-    let mut k_vizinhos_rotulos = Vec::new();
-    for _ in 0..k {
-        // if let é usado para desempacotar Option de forma segura
-        if let Some(vizinho) = heap.pop() {
-            k_vizinhos_rotulos.push(vizinho.rotulo);
+// ==================== FUNÇÃO PRINCIPAL ====================
+// main() é o ponto de entrada do programa. O trabalho fica em executar(); main só mostra
+// o erro, se houver, pela mensagem (Display), e não pelo {:?} que o Rust usaria ao
+// devolver o Result direto do main (que mostraria os campos do ErroKnn)
+fn main() -> ExitCode {
+    match executar() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(erro) => {
+            eprintln!("Erro: {}", erro);
+            ExitCode::FAILURE
         }
     }
-
-    // Contar frequência dos rótulos usando HashMap
-    let mut contador_rotulos = std::collections::HashMap::new();
-    for rotulo in k_vizinhos_rotulos {
-        // entry API fornece uma maneira elegante de inserir ou atualizar valores
-        *contador_rotulos.entry(rotulo).or_insert(0) += 1;
-    }
-
-    // Encontrar o rótulo mais frequente
-    contador_rotulos.into_iter()
-        .max_by_key(|&(_, count)| count) // Encontra entrada com maior contagem
-        .map(|(rotulo, _)| rotulo)       // Extrai apenas o rótulo
-        .unwrap()                        // Converte Option para valor (assume que existe)
 }
 
-// ==================== FUNÇÕES DE ENTRADA/SAÍDA ====================
-// Result é um tipo que representa sucesso (Ok) ou erro (Err)
-// Box<dyn Error> é um tipo que pode conter qualquer erro
-fn carregar_dados_do_csv(caminho_arquivo: &str) -> Result<Vec<Ponto>, Box<dyn Error>> {
-    let mut leitor = ReaderBuilder::new().from_path(caminho_arquivo)?; // ? propaga erros
-    let mut pontos = Vec::new();
+// -> Result<(), Box<dyn Error>> indica que a função pode retornar erro
+fn executar() -> Result<(), Box<dyn Error>> {
+    // Avisos da biblioteca (como categorias desconhecidas) aparecem no stderr;
+    // RUST_LOG=warn ou RUST_LOG=debug muda o nível
+    inicializar_logger("info");
 
-    // deserialize converte cada linha do CSV para uma tupla
-    for resultado in leitor.deserialize() {
-        let registro: (f64, f64, String) = resultado?;
-        pontos.push(Ponto::novo(vec![registro.0, registro.1], registro.2));
+    // Lê os argumentos; com --help, --version ou um argumento inválido o clap
+    // mostra a mensagem e encerra o programa aqui mesmo
+    let argumentos = Argumentos::parse();
+    if let Some(comando) = argumentos.comando {
+        return executar_comando(comando);
     }
 
-    Ok(pontos) // Retorna sucesso com os pontos
-}
+    // A entrada padrão só pode ser lida uma vez
+    let teste_da_entrada = argumentos.teste.as_deref() == Some("-");
+    if argumentos.stdin && teste_da_entrada {
+        Argumentos::command()
+            .error(ErrorKind::ArgumentConflict, "--stdin e --teste - não podem ler ambos da entrada padrão")
+            .exit();
+    }
 
-// Função para limpar o terminal de forma cross-platform
-fn limpar_terminal() {
-    // cfg! é uma macro que verifica o sistema operacional em tempo de compilação
-    if cfg!(target_os = "windows") {
-        Command::new("cmd").args(&["/C", "cls"]).status().unwrap();
+    // Carrega dados e trata possíveis erros com ?
+    let opcoes = OpcoesCsv {
+        tem_cabecalho: !argumentos.sem_cabecalho,
+        // None pede para o delimitador ser detectado pela primeira linha
+        delimitador: (!argumentos.detectar_delimitador).then_some(argumentos.delimitador),
+        aspas: argumentos.aspas,
+        virgula_decimal: Some(argumentos.virgula_decimal),
+        coletar_erros: argumentos.todos_os_erros,
+        ..OpcoesCsv::default()
+    };
+    // Nome da origem do treinamento, usado nas mensagens
+    let origem_dados = if argumentos.stdin { "<stdin>" } else { argumentos.dados.as_str() };
+    let carregados = if argumentos.stdin {
+        // Uma entrada vazia (Ctrl-D logo no começo) vira o erro de conjunto vazio
+        carregar_dados_de_leitor_com_opcoes(io::stdin().lock(), origem_dados, &opcoes).map(|(_, pontos)| pontos)
+    } else if argumentos.dados.starts_with("http://") || argumentos.dados.starts_with("https://") {
+        carregar_de_url(&argumentos.dados, &opcoes)
+    } else if argumentos.dados.ends_with(".json") {
+        carregar_dados_do_json(&argumentos.dados)
+    } else if argumentos.dados.ends_with(".libsvm") || argumentos.dados.ends_with(".svm") {
+        // A dimensão é o maior índice do arquivo; o --ponto precisa ter esse tamanho
+        carregar_dados_do_libsvm(&argumentos.dados, None)
     } else {
-        Command::new("clear").status().unwrap();
-    }
-}
+        carregar_dados_do_csv_com_opcoes(&argumentos.dados, &opcoes).map(|(_, pontos)| pontos)
+    };
+    let dados_treinamento = carregados.map_err(|erro| com_origem(erro, origem_dados))?;
 
-// Esta função calcula o valor de "k" para o algoritmo KNN
-// com base no tamanho do conjunto de dados de treinamento.
-// Ela usa a heurística de calcular a raiz quadrada do
-// total de dados e arredondar o resultado para cima.
-fn calcular_k(total_dados: usize) -> usize {
-    // Converte o tamanho do conjunto de dados (usize) em um número de ponto flutuante (f64)
-    // para poder calcular a raiz quadrada.
-    (total_dados as f64)
-        // Calcula a raiz quadrada do total de dados.
-        .sqrt()
-        // Arredonda o resultado para cima, para o próximo número inteiro.
-        .ceil()
-        // Converte o resultado (f64) de volta para usize, que é o tipo esperado pelo algoritmo KNN.
-        as usize
-}
+    // O ponto de teste precisa de uma característica para cada coluna do treinamento
+    let dimensao = dados_treinamento.first().map_or(0, |ponto| ponto.caracteristicas.len());
+    if argumentos.teste.is_none() && argumentos.ponto.len() != dimensao {
+        Argumentos::command()
+            .error(
+                ErrorKind::ValueValidation,
+                format!(
+                    "--ponto tem {} valor(es), mas os dados de treinamento em '{}' têm {} característica(s)",
+                    argumentos.ponto.len(),
+                    origem_dados,
+                    dimensao
+                ),
+            )
+            .exit();
+    }
 
-// ==================== FUNÇÃO PRINCIPAL ====================
-// main() é o ponto de entrada do programa
-// -> Result<(), Box<dyn Error>> indica que a função pode retornar erro
-fn main() -> Result<(), Box<dyn Error>> {
-    limpar_terminal();
+    // Só limpa o terminal depois das validações, para que as mensagens de erro fiquem
+    // visíveis; com a saída num pipe ou arquivo, limpar_terminal não escreve nada
+    if !argumentos.sem_limpar {
+        limpar_terminal();
+    }
 
-    // Carrega dados e trata possíveis erros com ?
-    let dados_treinamento = carregar_dados_do_csv("src/dados.csv")?;
+    // Mostra quantos exemplos de cada classe foram carregados
+    imprimir_distribuicao(&dados_treinamento);
+    println!();
 
     let total_dados = dados_treinamento.len();
 
-    let k = calcular_k(total_dados);
+    // Sem --k, usa a regra da raiz do número de pontos
+    let k = argumentos.k.unwrap_or_else(|| calcular_k(total_dados));
 
-    // Cria um ponto de teste com duas características
-    let ponto_teste = Ponto::novo(vec![4.5, 8.0], "Desconhecido".to_string());
+    // Normaliza o treinamento e aplica a MESMA transformação aos pontos de teste,
+    // para que as distâncias sejam calculadas na mesma escala
+    let normalizador = Preprocessamento::MinMax.ajustar(&dados_treinamento)?;
+    let treinamento_normalizado = normalizador.transformar(&dados_treinamento);
 
-    // Executa o algoritmo KNN
-    let rotulo = knn(&dados_treinamento, &ponto_teste, k);
+    // Modo em lote: classifica cada linha do CSV de teste e grava as previsões
+    if let (Some(teste), Some(saida)) = (&argumentos.teste, &argumentos.saida) {
+        let classificar = |ponto: &Ponto| {
+            knn_com_confianca(&treinamento_normalizado, &normalizador.transformar_ponto(ponto), k, &argumentos.metrica)
+        };
+        let origem_teste = if teste_da_entrada { "<stdin>" } else { teste.as_str() };
+        let classificados = if teste_da_entrada {
+            let entrada = io::stdin().lock();
+            let confianca = argumentos.confianca;
+            classificar_csv_de_leitor(entrada, origem_teste, saida, &opcoes, dimensao, classificar, confianca)
+        } else {
+            classificar_csv(teste, saida, &opcoes, dimensao, classificar, argumentos.confianca)
+        };
+        let total = classificados.map_err(|erro| com_origem(erro, origem_teste))?;
+        println!("{} pontos de {} classificados; previsões gravadas em {}", total, origem_teste, saida);
+        return Ok(());
+    }
+
+    // Cria o ponto de teste com as características de --ponto
+    let ponto_teste = Ponto::novo(argumentos.ponto, "Desconhecido".to_string());
+    let teste_normalizado = normalizador.transformar_ponto(&ponto_teste);
+
+    // Executa o algoritmo KNN com a métrica escolhida
+    let rotulo = knn_com_metrica(&treinamento_normalizado, &teste_normalizado, k, &argumentos.metrica)?;
 
     // Exibe resultado
     println!(
@@ -187,4 +359,110 @@ fn main() -> Result<(), Box<dyn Error>> {
     );
 
     Ok(()) // Retorna sucesso (unit type)
-}
\ No newline at end of file
+}
+// ==================== SUBCOMANDOS ====================
+// Cada subcomando chama as funções da biblioteca e só cuida da entrada e da saída. Como
+// no modo sem subcomando, as distâncias são calculadas com as características
+// normalizadas (min-max), e o normalizador é sempre ajustado nos pontos de treinamento
+
+fn executar_comando(comando: Comando) -> Result<(), Box<dyn Error>> {
+    match comando {
+        Comando::Treinar { csv, k, k_auto, saida } => treinar(&csv, k, k_auto, &saida),
+        Comando::Prever { modelo, caracteristicas } => prever(&modelo, caracteristicas),
+        Comando::Avaliar { csv, k, folds } => avaliar(&csv, k, folds),
+        Comando::Estatisticas { csv } => estatisticas(&csv),
+    }
+}
+
+fn treinar(csv: &str, k: Option<usize>, k_auto: bool, saida: &str) -> Result<(), Box<dyn Error>> {
+    let mut modelo = Modelo::do_csv(csv).map_err(|erro| com_origem(erro, csv))?;
+    if modelo.pontos.is_empty() {
+        return Err(com_origem(ErroKnn::ConjuntoVazio, csv));
+    }
+
+    let k = if k_auto {
+        let normalizados = Preprocessamento::MinMax.ajustar(&modelo.pontos)?.transformar(&modelo.pontos);
+        // Com menos de 5 pontos, cada fold tem um ponto só (leave-one-out)
+        let folds = normalizados.len().min(5);
+        let selecao = escolher_k(&normalizados, None, folds, obter_semente()).map_err(|erro| com_origem(erro, csv))?;
+        for ponto in &selecao.curva {
+            println!("k = {:>3}: acurácia {:.2}% ± {:.2}%", ponto.k, ponto.media * 100.0, ponto.erro_padrao * 100.0);
+        }
+        selecao.k_escolhido
+    } else {
+        k.unwrap_or_else(|| calcular_k(modelo.pontos.len()))
+    };
+
+    modelo.k = Some(k);
+    modelo.salvar(saida)?;
+    println!("Modelo com {} pontos e k = {} salvo em {}", modelo.pontos.len(), k, saida);
+    Ok(())
+}
+
+fn prever(caminho_modelo: &str, caracteristicas: Vec<f64>) -> Result<(), Box<dyn Error>> {
+    let modelo = Modelo::carregar(caminho_modelo)?;
+    if caracteristicas.len() != modelo.dimensao() {
+        Argumentos::command()
+            .error(
+                ErrorKind::ValueValidation,
+                format!(
+                    "--caracteristicas tem {} valor(es), mas o modelo em '{}' tem {} característica(s)",
+                    caracteristicas.len(),
+                    caminho_modelo,
+                    modelo.dimensao()
+                ),
+            )
+            .exit();
+    }
+
+    let k = modelo.k.unwrap_or_else(|| calcular_k(modelo.pontos.len()));
+    let normalizador =
+        Preprocessamento::MinMax.ajustar(&modelo.pontos).map_err(|erro| com_origem(erro, caminho_modelo))?;
+    let ponto = Ponto::novo(caracteristicas, "Desconhecido".to_string());
+    let rotulo = knn(&normalizador.transformar(&modelo.pontos), &normalizador.transformar_ponto(&ponto), k)
+        .map_err(|erro| com_origem(erro, caminho_modelo))?;
+    println!("Rótulo previsto para os dados de teste {:?} é {}", ponto.caracteristicas, rotulo);
+    Ok(())
+}
+
+fn avaliar(csv: &str, k: Option<usize>, folds: usize) -> Result<(), Box<dyn Error>> {
+    let dados = carregar_dados_do_csv(csv).map_err(|erro| com_origem(erro, csv))?;
+    let k = k.unwrap_or_else(|| calcular_k(dados.len()));
+    let normalizados = Preprocessamento::MinMax.ajustar(&dados)?.transformar(&dados);
+    // Mais folds que pontos é um ErroKnn::ParametroInvalido, mostrado como os outros erros
+    let relatorio = validacao_cruzada(&normalizados, k, folds, obter_semente()).map_err(|erro| com_origem(erro, csv))?;
+    println!("Validação cruzada de {} com {} folds e k = {}:", csv, folds, k);
+    for (i, fold) in relatorio.folds.iter().enumerate() {
+        println!("  fold {:>2}: {:.2}% ({} pontos)", i + 1, fold.acuracia * 100.0, fold.indices.len());
+    }
+    println!("Acurácia: {:.2}% ± {:.2}%", relatorio.media * 100.0, relatorio.desvio_padrao * 100.0);
+    Ok(())
+}
+
+fn estatisticas(csv: &str) -> Result<(), Box<dyn Error>> {
+    let modelo = Modelo::do_csv(csv).map_err(|erro| com_origem(erro, csv))?;
+    if modelo.pontos.is_empty() {
+        return Err(com_origem(ErroKnn::ConjuntoVazio, csv));
+    }
+
+    println!("{}: {} pontos, {} característica(s)", csv, modelo.pontos.len(), modelo.dimensao());
+    imprimir_distribuicao(&modelo.pontos);
+    println!();
+
+    let largura = modelo.colunas.iter().map(|coluna| coluna.chars().count()).max().unwrap_or(0).max(14);
+    println!(
+        "{:<largura$} {:>12} {:>12} {:>12} {:>13}",
+        "característica", "mínimo", "máximo", "média", "desvio padrão"
+    );
+    let desvios = modelo.estatisticas.desvio_padrao();
+    for (i, coluna) in modelo.colunas.iter().enumerate() {
+        let valores = modelo.pontos.iter().map(|ponto| ponto.caracteristicas[i]);
+        let minimo = valores.clone().fold(f64::INFINITY, f64::min);
+        let maximo = valores.fold(f64::NEG_INFINITY, f64::max);
+        println!(
+            "{:<largura$} {:>12.4} {:>12.4} {:>12.4} {:>13.4}",
+            coluna, minimo, maximo, modelo.estatisticas.media[i], desvios[i]
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,141 @@
+//! Matriz de distâncias calculada sob demanda e guardada para reaproveitamento.
+
+use std::collections::HashMap;
+
+use crate::distancia::distancia_euclidiana;
+use crate::ponto::Ponto;
+
+// ==================== MATRIZ DE DISTÂNCIAS PREGUIÇOSA ====================
+/// Distâncias euclidianas entre os pontos de um conjunto, calculadas só quando pedidas
+/// e memorizadas (memoização) para as próximas consultas.
+///
+/// Validação cruzada e leave-one-out pedem a mesma distância (i, j) muitas vezes; com a
+/// matriz cada par é calculado uma única vez. Como d(i, j) = d(j, i), o par é guardado
+/// uma vez só, com o menor índice primeiro.
+///
+/// # Exemplo
+///
+/// ```
+/// use aprendizagem_knn::{pontos, MatrizDistanciasLazy};
+///
+/// let mut matriz = MatrizDistanciasLazy::nova(pontos![[0.0, 0.0] => "A", [3.0, 4.0] => "B", [1.0, 0.0] => "A"]);
+/// assert_eq!(matriz.obter(1, 0), 5.0);
+/// assert_eq!(matriz.obter(0, 1), 5.0); // vem do cache
+/// assert_eq!(matriz.distancias_calculadas(), 1);
+///
+/// assert_eq!(matriz.buscar_k_vizinhos(0, 1), vec![(2, 1.0)]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct MatrizDistanciasLazy {
+    dados: Vec<Ponto>,
+    cache: HashMap<(usize, usize), f64>, // chave (menor índice, maior índice)
+}
+
+impl MatrizDistanciasLazy {
+    /// Cria a matriz sem nenhuma distância calculada.
+    ///
+    /// ```
+    /// use aprendizagem_knn::{pontos, MatrizDistanciasLazy};
+    ///
+    /// let matriz = MatrizDistanciasLazy::nova(pontos![[0.0] => "A", [1.0] => "B"]);
+    /// assert_eq!(matriz.dados().len(), 2);
+    /// assert_eq!(matriz.distancias_calculadas(), 0);
+    /// ```
+    pub fn nova(dados: Vec<Ponto>) -> Self {
+        Self { dados, cache: HashMap::new() }
+    }
+
+    /// Pontos sobre os quais as distâncias são calculadas.
+    ///
+    /// ```
+    /// use aprendizagem_knn::{pontos, MatrizDistanciasLazy};
+    ///
+    /// let matriz = MatrizDistanciasLazy::nova(pontos![[0.0] => "A"]);
+    /// assert_eq!(matriz.dados()[0].rotulo, "A");
+    /// ```
+    pub fn dados(&self) -> &[Ponto] {
+        &self.dados
+    }
+
+    /// Quantos pares distintos já estão no cache.
+    ///
+    /// ```
+    /// use aprendizagem_knn::{pontos, MatrizDistanciasLazy};
+    ///
+    /// let mut matriz = MatrizDistanciasLazy::nova(pontos![[0.0] => "A", [1.0] => "B"]);
+    /// matriz.obter(0, 0); // a distância de um ponto a ele mesmo não é guardada
+    /// assert_eq!(matriz.distancias_calculadas(), 0);
+    /// ```
+    pub fn distancias_calculadas(&self) -> usize {
+        self.cache.len()
+    }
+
+    /// Distância entre os pontos `i` e `j`, consultando o cache antes de calcular.
+    ///
+    /// Entra em pânico se algum índice estiver fora do conjunto.
+    ///
+    /// ```
+    /// use aprendizagem_knn::{pontos, MatrizDistanciasLazy};
+    ///
+    /// let mut matriz = MatrizDistanciasLazy::nova(pontos![[0.0] => "A", [2.0] => "B"]);
+    /// assert_eq!(matriz.obter(0, 1), 2.0);
+    /// assert_eq!(matriz.obter(1, 1), 0.0);
+    /// ```
+    pub fn obter(&mut self, i: usize, j: usize) -> f64 {
+        assert!(i < self.dados.len() && j < self.dados.len(), "índice fora do conjunto ({}, {})", i, j);
+        if i == j {
+            return 0.0;
+        }
+
+        let chave = (i.min(j), i.max(j));
+        let dados = &self.dados;
+        *self.cache
+            .entry(chave)
+            .or_insert_with(|| distancia_euclidiana(&dados[chave.0], &dados[chave.1]))
+    }
+
+    /// Calcula de uma vez todas as n(n-1)/2 distâncias que ainda não estão no cache.
+    ///
+    /// ```
+    /// use aprendizagem_knn::{pontos, MatrizDistanciasLazy};
+    ///
+    /// let mut matriz = MatrizDistanciasLazy::nova(pontos![[0.0] => "A", [1.0] => "A", [5.0] => "B"]);
+    /// matriz.preencher_completa();
+    /// assert_eq!(matriz.distancias_calculadas(), 3);
+    /// ```
+    pub fn preencher_completa(&mut self) {
+        let n = self.dados.len();
+        self.cache.reserve(n * n.saturating_sub(1) / 2);
+        for i in 0..n {
+            for j in (i + 1)..n {
+                self.obter(i, j);
+            }
+        }
+    }
+
+    /// Os `k` pontos mais próximos do ponto `indice_consulta` (sem contar ele mesmo),
+    /// como pares (índice, distância) do mais próximo para o mais distante.
+    ///
+    /// Em distâncias iguais vem primeiro o menor índice. Com `k` maior que o número de
+    /// outros pontos, todos são devolvidos.
+    ///
+    /// ```
+    /// use aprendizagem_knn::{pontos, MatrizDistanciasLazy};
+    ///
+    /// let mut matriz = MatrizDistanciasLazy::nova(pontos![[0.0] => "A", [3.0] => "B", [-1.0] => "A", [1.0] => "A"]);
+    /// assert_eq!(matriz.buscar_k_vizinhos(0, 2), vec![(2, 1.0), (3, 1.0)]);
+    /// assert_eq!(matriz.buscar_k_vizinhos(1, 10).len(), 3);
+    /// ```
+    pub fn buscar_k_vizinhos(&mut self, indice_consulta: usize, k: usize) -> Vec<(usize, f64)> {
+        let mut vizinhos: Vec<(usize, f64)> = (0..self.dados.len())
+            .filter(|&j| j != indice_consulta)
+            .map(|j| (j, self.obter(indice_consulta, j)))
+            .collect();
+
+        vizinhos.sort_by(|(indice_a, distancia_a), (indice_b, distancia_b)| {
+            distancia_a.total_cmp(distancia_b).then(indice_a.cmp(indice_b))
+        });
+        vizinhos.truncate(k);
+        vizinhos
+    }
+}
@@ -0,0 +1,792 @@
+//! Métricas de desempenho de classificadores calculadas a partir dos rótulos reais e previstos.
+
+use std::collections::{BTreeSet, HashMap};
+use std::error::Error;
+use std::io::Write;
+
+use csv::Writer;
+
+use crate::confusao::MatrizConfusao;
+use crate::distancia::DistanciaMetrica;
+use crate::erro::ErroKnn;
+use crate::knn::knn_com_confianca;
+use crate::ponto::Ponto;
+
+// ==================== ACURÁCIA BALANCEADA ====================
+/// Acurácia balanceada: a média, entre as classes reais, da fração de pontos de cada
+/// classe que foi acertada (o recall da classe):
+/// (1 / n_classes) * Σ TP_c / (TP_c + FN_c).
+///
+/// A acurácia comum premia quem só prevê a classe majoritária; aqui cada classe pesa o
+/// mesmo, não importa quantos pontos tenha. Um classificador que prevê sempre a mesma
+/// classe fica com 1 / n_classes, o nível do acaso. Só entram as classes que aparecem nos
+/// rótulos reais (uma classe só prevista não tem recall). Sem nenhum ponto, devolve 0.0.
+///
+/// É erro ([`ErroKnn::ParametroInvalido`]) se `reais` e `previstos` tiverem tamanhos diferentes.
+///
+/// ```
+/// use aprendizagem_knn::{acuracia_balanceada, ErroKnn, MatrizConfusao};
+///
+/// // 90% A e 10% B, e um classificador que sempre responde A
+/// let reais: Vec<String> = (0..100).map(|i| if i < 90 { "A" } else { "B" }.to_string()).collect();
+/// let previstos = vec!["A".to_string(); 100];
+///
+/// assert_eq!(MatrizConfusao::nova(&reais, &previstos).unwrap().acuracia(), 0.9);
+/// assert_eq!(acuracia_balanceada(&reais, &previstos), Ok(0.5));
+///
+/// assert!(matches!(acuracia_balanceada(&reais, &previstos[1..]), Err(ErroKnn::ParametroInvalido(_))));
+/// ```
+pub fn acuracia_balanceada(reais: &[String], previstos: &[String]) -> Result<f64, ErroKnn> {
+    Ok(MatrizConfusao::nova(reais, previstos)?.acuracia_balanceada())
+}
+
+impl MatrizConfusao {
+    /// Acurácia balanceada calculada da matriz; veja [`acuracia_balanceada`].
+    ///
+    /// ```
+    /// use aprendizagem_knn::MatrizConfusao;
+    ///
+    /// let reais: Vec<String> = ["A", "A", "B", "B"].iter().map(|r| r.to_string()).collect();
+    /// let previstos: Vec<String> = ["A", "B", "B", "B"].iter().map(|r| r.to_string()).collect();
+    /// // recall de A = 1/2, recall de B = 2/2
+    /// assert_eq!(MatrizConfusao::nova(&reais, &previstos).unwrap().acuracia_balanceada(), 0.75);
+    /// ```
+    pub fn acuracia_balanceada(&self) -> f64 {
+        let recalls: Vec<f64> = (0..self.rotulos.len())
+            .filter_map(|i| {
+                let total_real: usize = self.contagens[i].iter().sum();
+                (total_real > 0).then(|| self.contagens[i][i] as f64 / total_real as f64)
+            })
+            .collect();
+        if recalls.is_empty() {
+            return 0.0;
+        }
+        recalls.iter().sum::<f64>() / recalls.len() as f64
+    }
+}
+
+// ==================== PRECISÃO, RECALL E F1 ====================
+/// Precisão, recall e F1 de uma classe.
+///
+/// Quando uma divisão seria 0/0 o valor fica 0.0 e a flag correspondente é marcada, em vez
+/// de virar NaN: `precisao_indefinida` quando a classe nunca foi prevista e
+/// `recall_indefinido` quando ela nunca aparece nos rótulos reais.
+///
+/// # Exemplo
+///
+/// ```
+/// use aprendizagem_knn::relatorio_classificacao;
+///
+/// let reais = vec!["A".to_string(), "A".to_string()];
+/// let previstos = vec!["A".to_string(), "B".to_string()];
+/// let relatorio = relatorio_classificacao(&reais, &previstos).unwrap();
+/// let b = &relatorio.por_classe[1];
+/// assert_eq!((b.rotulo.as_str(), b.precisao, b.recall), ("B", 0.0, 0.0));
+/// assert!(b.recall_indefinido && !b.precisao_indefinida);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricasClasse {
+    /// A classe
+    pub rotulo: String,
+    /// Dos pontos previstos como esta classe, a fração que de fato era dela: TP / (TP + FP)
+    pub precisao: f64,
+    /// Dos pontos desta classe, a fração que foi prevista corretamente: TP / (TP + FN)
+    pub recall: f64,
+    /// Média harmônica de precisão e recall: 2PR / (P + R)
+    pub f1: f64,
+    /// Quantos pontos pertencem de fato à classe
+    pub suporte: usize,
+    /// A classe nunca foi prevista, então a precisão seria 0/0
+    pub precisao_indefinida: bool,
+    /// A classe não aparece nos rótulos reais, então o recall seria 0/0
+    pub recall_indefinido: bool,
+}
+
+/// Precisão, recall e F1 resumidos sobre todas as classes.
+///
+/// ```
+/// use aprendizagem_knn::relatorio_classificacao;
+///
+/// let rotulos = vec!["A".to_string(), "B".to_string()];
+/// let medias = relatorio_classificacao(&rotulos, &rotulos).unwrap().macro_media;
+/// assert_eq!((medias.precisao, medias.recall, medias.f1), (1.0, 1.0, 1.0));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MediasMetricas {
+    /// Precisão média
+    pub precisao: f64,
+    /// Recall médio
+    pub recall: f64,
+    /// F1 médio
+    pub f1: f64,
+}
+
+/// Métricas de cada classe e as suas médias.
+///
+/// - `macro_media`: média simples entre as classes (cada classe pesa o mesmo)
+/// - `micro_media`: soma os acertos e erros de todas as classes antes de dividir; com um
+///   rótulo por ponto, precisão, recall e F1 micro são todos iguais à acurácia
+/// - `media_ponderada`: média das classes pesada pelo suporte (número de pontos reais)
+///
+/// # Exemplo
+///
+/// ```
+/// use aprendizagem_knn::relatorio_classificacao;
+///
+/// let rotulos = |lista: &[&str]| lista.iter().map(|r| r.to_string()).collect::<Vec<_>>();
+/// let reais = rotulos(&["A", "A", "A", "B", "B", "C", "C", "C"]);
+/// let previstos = rotulos(&["A", "A", "B", "B", "C", "C", "C", "A"]);
+/// let relatorio = relatorio_classificacao(&reais, &previstos).unwrap();
+///
+/// // A: 2 acertos de 3 reais e de 3 previstos; B: 1 de 2 e de 2; C: 2 de 3 e de 3
+/// let b = &relatorio.por_classe[1];
+/// assert_eq!((b.precisao, b.recall, b.f1, b.suporte), (0.5, 0.5, 0.5, 2));
+///
+/// let perto = |a: f64, b: f64| (a - b).abs() < 1e-12;
+/// assert!(perto(relatorio.macro_media.f1, (2.0 / 3.0 + 0.5 + 2.0 / 3.0) / 3.0));
+/// assert!(perto(relatorio.micro_media.f1, 5.0 / 8.0));
+/// assert!(perto(relatorio.media_ponderada.f1, (3.0 * 2.0 / 3.0 + 2.0 * 0.5 + 3.0 * 2.0 / 3.0) / 8.0));
+/// assert_eq!(relatorio.acuracia, 5.0 / 8.0);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct RelatorioClassificacao {
+    /// Uma entrada por classe, em ordem alfabética
+    pub por_classe: Vec<MetricasClasse>,
+    /// Média simples entre as classes
+    pub macro_media: MediasMetricas,
+    /// Médias com as contagens somadas de todas as classes
+    pub micro_media: MediasMetricas,
+    /// Média pesada pelo suporte de cada classe
+    pub media_ponderada: MediasMetricas,
+    /// Fração de acertos
+    pub acuracia: f64,
+}
+
+// a / b, ou 0 quando b é zero
+fn dividir_ou_zero(a: f64, b: f64) -> f64 {
+    if b == 0.0 { 0.0 } else { a / b }
+}
+
+// F1 a partir de precisão e recall (0 quando os dois são 0)
+fn f1(precisao: f64, recall: f64) -> f64 {
+    dividir_ou_zero(2.0 * precisao * recall, precisao + recall)
+}
+
+/// Calcula precisão, recall e F1 por classe e as médias macro, micro e ponderada a partir
+/// dos rótulos reais e previstos. É erro se as listas tiverem tamanhos diferentes.
+///
+/// ```
+/// use aprendizagem_knn::relatorio_classificacao;
+///
+/// let reais = vec!["A".to_string()];
+/// assert!(relatorio_classificacao(&reais, &[]).is_err());
+/// ```
+pub fn relatorio_classificacao(reais: &[String], previstos: &[String]) -> Result<RelatorioClassificacao, ErroKnn> {
+    Ok(MatrizConfusao::nova(reais, previstos)?.relatorio_classificacao())
+}
+
+impl MatrizConfusao {
+    /// Precisão, recall e F1 calculados da matriz; veja [`relatorio_classificacao`].
+    ///
+    /// ```
+    /// use aprendizagem_knn::MatrizConfusao;
+    ///
+    /// let reais: Vec<String> = ["A", "B", "B"].iter().map(|r| r.to_string()).collect();
+    /// let previstos: Vec<String> = ["B", "B", "B"].iter().map(|r| r.to_string()).collect();
+    /// let relatorio = MatrizConfusao::nova(&reais, &previstos).unwrap().relatorio_classificacao();
+    /// assert!(relatorio.por_classe[0].precisao_indefinida); // A nunca foi prevista
+    /// ```
+    pub fn relatorio_classificacao(&self) -> RelatorioClassificacao {
+        let n_classes = self.rotulos.len();
+        let por_classe: Vec<MetricasClasse> = (0..n_classes)
+            .map(|i| {
+                let acertos = self.contagens[i][i] as f64;
+                let suporte: usize = self.contagens[i].iter().sum();
+                let total_previsto: usize = self.contagens.iter().map(|linha| linha[i]).sum();
+                let precisao = dividir_ou_zero(acertos, total_previsto as f64);
+                let recall = dividir_ou_zero(acertos, suporte as f64);
+                MetricasClasse {
+                    rotulo: self.rotulos[i].clone(),
+                    precisao,
+                    recall,
+                    f1: f1(precisao, recall),
+                    suporte,
+                    precisao_indefinida: total_previsto == 0,
+                    recall_indefinido: suporte == 0,
+                }
+            })
+            .collect();
+
+        let macro_media = MediasMetricas {
+            precisao: dividir_ou_zero(por_classe.iter().map(|c| c.precisao).sum(), n_classes as f64),
+            recall: dividir_ou_zero(por_classe.iter().map(|c| c.recall).sum(), n_classes as f64),
+            f1: dividir_ou_zero(por_classe.iter().map(|c| c.f1).sum(), n_classes as f64),
+        };
+
+        // Somando todas as classes, cada erro é um falso positivo de uma classe e um falso
+        // negativo de outra, então TP + FP = TP + FN = total: precisão = recall = acurácia
+        let acuracia = self.acuracia();
+        let micro_media = MediasMetricas { precisao: acuracia, recall: acuracia, f1: f1(acuracia, acuracia) };
+
+        let total = self.total() as f64;
+        let ponderar = |valor: fn(&MetricasClasse) -> f64| {
+            dividir_ou_zero(por_classe.iter().map(|c| valor(c) * c.suporte as f64).sum(), total)
+        };
+        let media_ponderada = MediasMetricas {
+            precisao: ponderar(|c| c.precisao),
+            recall: ponderar(|c| c.recall),
+            f1: ponderar(|c| c.f1),
+        };
+
+        RelatorioClassificacao { por_classe, macro_media, micro_media, media_ponderada, acuracia }
+    }
+}
+
+// ==================== MÉTRICAS COM PESOS POR PONTO ====================
+// Confere os pesos das métricas ponderadas: mesmo tamanho dos rótulos, nenhum negativo
+// ou NaN e ao menos um positivo. Devolve a soma dos pesos.
+fn validar_pesos(reais: &[String], previstos: &[String], pesos: &[f64]) -> Result<f64, ErroKnn> {
+    if reais.len() != previstos.len() || reais.len() != pesos.len() {
+        return Err(ErroKnn::ParametroInvalido(format!(
+            "há {} rótulos reais, {} previstos e {} pesos",
+            reais.len(),
+            previstos.len(),
+            pesos.len()
+        )));
+    }
+    if let Some(peso) = pesos.iter().find(|peso| peso.is_nan() || **peso < 0.0) {
+        return Err(ErroKnn::ParametroInvalido(format!("os pesos não podem ser negativos nem NaN (recebido {})", peso)));
+    }
+    let soma: f64 = pesos.iter().sum();
+    if soma == 0.0 {
+        return Err(ErroKnn::VetorVazio("os pesos estão vazios ou são todos zero".to_string()));
+    }
+    Ok(soma)
+}
+
+/// Acurácia em que cada ponto conta com o seu peso: Σ peso_i · (real_i == previsto_i) / Σ peso_i.
+///
+/// Serve quando os pontos de teste não têm a mesma importância (observações recentes
+/// valendo mais, por exemplo). Com todos os pesos iguais, é a acurácia comum.
+///
+/// É erro se as listas tiverem tamanhos diferentes, se algum peso for negativo ou NaN e
+/// ([`ErroKnn::VetorVazio`]) se os pesos estiverem vazios ou forem todos zero.
+///
+/// ```
+/// use aprendizagem_knn::{acuracia_ponderada, ErroKnn, MatrizConfusao};
+///
+/// let rotulos = |texto: &str| texto.chars().map(String::from).collect::<Vec<_>>();
+/// let (reais, previstos) = (rotulos("AABB"), rotulos("ABBB"));
+///
+/// // O erro está no ponto de peso 3: 3 de 6 fica errado
+/// assert_eq!(acuracia_ponderada(&reais, &previstos, &[1.0, 3.0, 1.0, 1.0]).unwrap(), 0.5);
+///
+/// // Pesos iguais: a acurácia comum
+/// let uniforme = acuracia_ponderada(&reais, &previstos, &[1.0; 4]).unwrap();
+/// assert_eq!(uniforme, MatrizConfusao::nova(&reais, &previstos).unwrap().acuracia());
+///
+/// assert!(matches!(acuracia_ponderada(&reais, &previstos, &[0.0; 4]), Err(ErroKnn::VetorVazio(_))));
+/// ```
+pub fn acuracia_ponderada(reais: &[String], previstos: &[String], pesos: &[f64]) -> Result<f64, ErroKnn> {
+    let soma = validar_pesos(reais, previstos, pesos)?;
+    let acertos: f64 = reais.iter()
+        .zip(previstos)
+        .zip(pesos)
+        .filter(|((real, previsto), _)| real == previsto)
+        .map(|(_, peso)| peso)
+        .sum();
+    Ok(acertos / soma)
+}
+
+/// F1 macro em que cada ponto conta com o seu peso: verdadeiros positivos, falsos
+/// positivos e falsos negativos de cada classe são somas de pesos em vez de contagens.
+///
+/// As classes e a média são as mesmas do F1 macro de [`relatorio_classificacao`] (todas
+/// as classes que aparecem nos rótulos reais ou previstos, cada uma com o mesmo peso na
+/// média), então com todos os pesos iguais o resultado é o mesmo. Os erros são os de
+/// [`acuracia_ponderada`].
+///
+/// ```
+/// use aprendizagem_knn::{f1_ponderado, relatorio_classificacao};
+///
+/// let rotulos = |texto: &str| texto.chars().map(String::from).collect::<Vec<_>>();
+/// let (reais, previstos) = (rotulos("AAABBC"), rotulos("AABBCC"));
+///
+/// let uniforme = f1_ponderado(&reais, &previstos, &[1.0; 6]).unwrap();
+/// let f1_macro = relatorio_classificacao(&reais, &previstos).unwrap().macro_media.f1;
+/// assert!((uniforme - f1_macro).abs() < 1e-12);
+///
+/// // Se só os pontos acertados importam, o F1 de cada classe prevista corretamente é 1
+/// let so_acertos = f1_ponderado(&reais, &previstos, &[1.0, 1.0, 0.0, 1.0, 0.0, 1.0]).unwrap();
+/// assert_eq!(so_acertos, 1.0);
+/// ```
+pub fn f1_ponderado(reais: &[String], previstos: &[String], pesos: &[f64]) -> Result<f64, ErroKnn> {
+    validar_pesos(reais, previstos, pesos)?;
+
+    let classes: BTreeSet<&String> = reais.iter().chain(previstos).collect();
+    let soma_f1: f64 = classes.iter()
+        .map(|&classe| {
+            let (mut verdadeiros, mut previstos_classe, mut reais_classe) = (0.0, 0.0, 0.0);
+            for ((real, previsto), peso) in reais.iter().zip(previstos).zip(pesos) {
+                if real == classe {
+                    reais_classe += peso;
+                }
+                if previsto == classe {
+                    previstos_classe += peso;
+                    if real == classe {
+                        verdadeiros += peso;
+                    }
+                }
+            }
+            f1(dividir_ou_zero(verdadeiros, previstos_classe), dividir_ou_zero(verdadeiros, reais_classe))
+        })
+        .sum();
+    Ok(soma_f1 / classes.len() as f64)
+}
+
+// ==================== KAPPA DE COHEN E CORRELAÇÃO DE MATTHEWS ====================
+impl MatrizConfusao {
+    // Totais usados pelo kappa e pelo MCC: acertos, total de pontos,
+    // e quantos pontos cada classe tem de verdade (linhas) e nas previsões (colunas)
+    fn totais_por_classe(&self) -> (f64, f64, Vec<f64>, Vec<f64>) {
+        let n_classes = self.rotulos.len();
+        let acertos: usize = (0..n_classes).map(|i| self.contagens[i][i]).sum();
+        let reais = self.contagens.iter().map(|linha| linha.iter().sum::<usize>() as f64).collect();
+        let previstos = (0..n_classes)
+            .map(|j| self.contagens.iter().map(|linha| linha[j]).sum::<usize>() as f64)
+            .collect();
+        (acertos as f64, self.total() as f64, reais, previstos)
+    }
+
+    /// Kappa de Cohen: quanto a acurácia supera a concordância esperada por acaso,
+    /// (p_o - p_e) / (1 - p_e), onde p_o é a acurácia e p_e a chance de real e previsto
+    /// coincidirem se fossem sorteados independentemente, cada um com as suas proporções.
+    ///
+    /// Vale 1 para acerto total, 0 para o nível do acaso e fica negativo abaixo dele.
+    /// Quando p_e = 1 (real e previsto com uma única e mesma classe) ou a matriz está
+    /// vazia, o kappa não é definido e a função devolve 0.0.
+    ///
+    /// ```
+    /// use aprendizagem_knn::MatrizConfusao;
+    ///
+    /// let rotulos = |lista: &[&str]| lista.iter().map(|r| r.to_string()).collect::<Vec<_>>();
+    ///
+    /// let binaria = MatrizConfusao { rotulos: rotulos(&["A", "B"]), contagens: vec![vec![5, 1], vec![2, 4]] };
+    /// assert!((binaria.kappa_cohen() - 0.5).abs() < 1e-12);
+    ///
+    /// let tres_classes = MatrizConfusao {
+    ///     rotulos: rotulos(&["A", "B", "C"]),
+    ///     contagens: vec![vec![3, 1, 0], vec![1, 2, 2], vec![0, 1, 4]],
+    /// };
+    /// assert!((tres_classes.kappa_cohen() - 6.0 / 13.0).abs() < 1e-12);
+    ///
+    /// // Uma classe só, real e prevista: nada a comparar com o acaso
+    /// let degenerada = MatrizConfusao::nova(&rotulos(&["A", "A"]), &rotulos(&["A", "A"])).unwrap();
+    /// assert_eq!(degenerada.kappa_cohen(), 0.0);
+    /// ```
+    pub fn kappa_cohen(&self) -> f64 {
+        let (acertos, total, reais, previstos) = self.totais_por_classe();
+        if total == 0.0 {
+            return 0.0;
+        }
+        let observada = acertos / total;
+        let esperada = reais.iter().zip(&previstos).map(|(r, p)| r * p).sum::<f64>() / (total * total);
+        dividir_ou_zero(observada - esperada, 1.0 - esperada)
+    }
+
+    /// Coeficiente de correlação de Matthews (MCC), na forma generalizada para várias
+    /// classes (Gorodkin, 2004):
+    ///
+    /// (c·s - Σ p_k·t_k) / √((s² - Σ p_k²)(s² - Σ t_k²))
+    ///
+    /// onde c são os acertos, s o total, t_k os pontos reais da classe k e p_k os
+    /// previstos como k. Com duas classes é o MCC binário de sempre,
+    /// (TP·TN - FP·FN) / √((TP+FP)(TP+FN)(TN+FP)(TN+FN)).
+    ///
+    /// Vai de -1 a 1 (0 é o nível do acaso) e usa as quatro células da matriz binária,
+    /// então não se deixa enganar por classes desbalanceadas. Se todas as previsões ou
+    /// todos os rótulos reais forem de uma classe só, o denominador é zero e a função
+    /// devolve 0.0.
+    ///
+    /// ```
+    /// use aprendizagem_knn::MatrizConfusao;
+    ///
+    /// let rotulos = |lista: &[&str]| lista.iter().map(|r| r.to_string()).collect::<Vec<_>>();
+    ///
+    /// // TP = 5, FN = 1, FP = 2, TN = 4: (20 - 2) / √(7·6·6·5)
+    /// let binaria = MatrizConfusao { rotulos: rotulos(&["A", "B"]), contagens: vec![vec![5, 1], vec![2, 4]] };
+    /// assert!((binaria.mcc() - 0.507092552837110).abs() < 1e-12);
+    ///
+    /// let tres_classes = MatrizConfusao {
+    ///     rotulos: rotulos(&["A", "B", "C"]),
+    ///     contagens: vec![vec![3, 1, 0], vec![1, 2, 2], vec![0, 1, 4]],
+    /// };
+    /// assert!((tres_classes.mcc() - 0.465130254709532).abs() < 1e-12);
+    ///
+    /// // Sempre prevê A: acurácia de 90%, mas MCC 0
+    /// let reais: Vec<String> = (0..10).map(|i| if i < 9 { "A" } else { "B" }.to_string()).collect();
+    /// let sempre_a = MatrizConfusao::nova(&reais, &vec!["A".to_string(); 10]).unwrap();
+    /// assert_eq!(sempre_a.mcc(), 0.0);
+    /// ```
+    pub fn mcc(&self) -> f64 {
+        let (acertos, total, reais, previstos) = self.totais_por_classe();
+        let produto: f64 = reais.iter().zip(&previstos).map(|(r, p)| r * p).sum();
+        let soma_quadrados = |totais: &[f64]| totais.iter().map(|x| x * x).sum::<f64>();
+        let denominador = ((total * total - soma_quadrados(&previstos)) * (total * total - soma_quadrados(&reais))).sqrt();
+        dividir_ou_zero(acertos * total - produto, denominador)
+    }
+}
+
+// ==================== LOG LOSS ====================
+/// Menor probabilidade considerada pelo [`log_loss`]; as probabilidades são cortadas em
+/// `[EPSILON_LOG_LOSS, 1 - EPSILON_LOG_LOSS]` para que ln(0) não vire infinito.
+///
+/// ```
+/// use std::collections::HashMap;
+/// use aprendizagem_knn::{log_loss, EPSILON_LOG_LOSS};
+///
+/// // A classe real recebeu probabilidade 0: o custo é -ln(ε), e não infinito
+/// let perda = log_loss(&["A".to_string()], &[HashMap::from([("B".to_string(), 1.0)])]).unwrap();
+/// assert_eq!(perda, -EPSILON_LOG_LOSS.ln());
+/// ```
+pub const EPSILON_LOG_LOSS: f64 = 1e-15;
+
+/// Log loss (entropia cruzada): a média de -ln(p), onde p é a probabilidade que o
+/// modelo deu à classe real de cada ponto.
+///
+/// Ao contrário da acurácia, ela olha para a confiança: acertar com 0.9 custa menos que
+/// acertar com 0.6, e errar com muita certeza custa caro. Por isso serve para comparar a
+/// calibração de formas de votação diferentes. 0 é o ideal; prever sempre 1/n para n
+/// classes dá ln(n).
+///
+/// `probabilidades[i]` mapeia cada classe à probabilidade prevista para o ponto `i`. Uma
+/// classe ausente do mapa conta como probabilidade 0, e toda probabilidade é cortada em
+/// `[ε, 1 - ε]` com ε = [`EPSILON_LOG_LOSS`], então uma classe real com probabilidade 0
+/// custa -ln(ε) ≈ 34.5 em vez de infinito.
+///
+/// É erro se as listas tiverem tamanhos diferentes ou estiverem vazias.
+///
+/// ```
+/// use std::collections::HashMap;
+/// use aprendizagem_knn::{log_loss, EPSILON_LOG_LOSS};
+///
+/// let reais = vec!["A".to_string()];
+/// let mapa = |pares: &[(&str, f64)]| pares.iter().map(|(c, p)| (c.to_string(), *p)).collect::<HashMap<_, _>>();
+///
+/// // Certeza total e acerto: custo (quase) zero
+/// let certo = log_loss(&reais, &[mapa(&[("A", 1.0), ("B", 0.0)])]).unwrap();
+/// assert!(certo < 1e-14);
+///
+/// // Certeza total e erro: a probabilidade 0 é cortada em ε
+/// let errado = log_loss(&reais, &[mapa(&[("A", 0.0), ("B", 1.0)])]).unwrap();
+/// assert!((errado + EPSILON_LOG_LOSS.ln()).abs() < 1e-9);
+///
+/// // A classe real nem aparece no mapa: mesmo custo que probabilidade 0
+/// assert_eq!(log_loss(&reais, &[mapa(&[("B", 1.0)])]).unwrap(), errado);
+///
+/// // Média entre os pontos: -(ln 0.5 + ln 0.25) / 2
+/// let reais = vec!["A".to_string(), "B".to_string()];
+/// let perda = log_loss(&reais, &[mapa(&[("A", 0.5), ("B", 0.5)]), mapa(&[("A", 0.75), ("B", 0.25)])]).unwrap();
+/// assert!((perda - -(0.5f64.ln() + 0.25f64.ln()) / 2.0).abs() < 1e-12);
+/// ```
+pub fn log_loss(reais: &[String], probabilidades: &[HashMap<String, f64>]) -> Result<f64, ErroKnn> {
+    if reais.len() != probabilidades.len() {
+        return Err(ErroKnn::ParametroInvalido(format!(
+            "há {} rótulos reais e {} mapas de probabilidades",
+            reais.len(),
+            probabilidades.len()
+        )));
+    }
+    if reais.is_empty() {
+        return Err(ErroKnn::ConjuntoVazio);
+    }
+
+    let soma: f64 = reais.iter()
+        .zip(probabilidades)
+        .map(|(real, mapa)| {
+            let probabilidade = mapa.get(real).copied().unwrap_or(0.0);
+            -probabilidade.clamp(EPSILON_LOG_LOSS, 1.0 - EPSILON_LOG_LOSS).ln()
+        })
+        .sum();
+    Ok(soma / reais.len() as f64)
+}
+
+// ==================== CURVA ROC E AUC ====================
+/// Um ponto da curva ROC: as taxas obtidas ao chamar de positivo todo ponto com
+/// pontuação maior ou igual a `limiar`.
+///
+/// ```
+/// use aprendizagem_knn::CurvaRoc;
+///
+/// let reais = vec!["P".to_string(), "N".to_string()];
+/// let curva = CurvaRoc::calcular(&reais, &[0.9, 0.2], "P").unwrap();
+/// // Com o limiar 0.9, só o positivo é chamado de positivo
+/// let ponto = curva.pontos.iter().find(|ponto| ponto.limiar == 0.9).unwrap();
+/// assert_eq!((ponto.fpr, ponto.tpr), (0.0, 1.0));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PontoRoc {
+    /// Limiar de decisão (o primeiro ponto usa infinito: ninguém é positivo)
+    pub limiar: f64,
+    /// Taxa de falsos positivos: FP / (FP + TN)
+    pub fpr: f64,
+    /// Taxa de verdadeiros positivos (recall): TP / (TP + FN)
+    pub tpr: f64,
+}
+
+/// Curva ROC de um problema binário e a área sob ela (AUC).
+///
+/// A AUC é a chance de um ponto positivo sorteado receber pontuação maior que um
+/// negativo sorteado: 1.0 separa as classes perfeitamente e 0.5 é o nível do acaso.
+///
+/// ```
+/// use aprendizagem_knn::CurvaRoc;
+///
+/// let reais: Vec<String> = ["P", "P", "N", "N"].iter().map(|rotulo| rotulo.to_string()).collect();
+/// let curva = CurvaRoc::calcular(&reais, &[0.8, 0.4, 0.6, 0.1], "P").unwrap();
+/// // Um dos quatro pares (positivo, negativo) está na ordem errada: 0.4 < 0.6
+/// assert_eq!(curva.auc, 0.75);
+/// let primeiro = curva.pontos.first().unwrap();
+/// let ultimo = curva.pontos.last().unwrap();
+/// assert_eq!((primeiro.fpr, primeiro.tpr, ultimo.fpr, ultimo.tpr), (0.0, 0.0, 1.0, 1.0));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct CurvaRoc {
+    /// Pontos da curva, de (0, 0) até (1, 1), com o limiar decrescendo
+    pub pontos: Vec<PontoRoc>,
+    /// Área sob a curva, pela regra dos trapézios
+    pub auc: f64,
+}
+
+impl CurvaRoc {
+    /// Calcula a curva ROC a partir dos rótulos reais e da pontuação de cada ponto para a
+    /// `classe_positiva` (por exemplo, a fração dos vizinhos que votou nela).
+    ///
+    /// Pontos com a mesma pontuação são tratados juntos: eles viram positivos no mesmo
+    /// limiar, o que gera um segmento diagonal na curva (e meio crédito na AUC) em vez de
+    /// uma escada que dependeria da ordem dos pontos.
+    ///
+    /// É erro se as listas tiverem tamanhos diferentes, se houver mais de duas classes
+    /// nos rótulos reais, se faltarem pontos positivos ou negativos, ou se alguma
+    /// pontuação for NaN.
+    ///
+    /// ```
+    /// use aprendizagem_knn::CurvaRoc;
+    ///
+    /// let rotulos = |lista: &[&str]| lista.iter().map(|r| r.to_string()).collect::<Vec<_>>();
+    /// let reais = rotulos(&["P", "P", "N", "N"]);
+    ///
+    /// // Classificador perfeito: todo positivo tem pontuação maior que todo negativo
+    /// let curva = CurvaRoc::calcular(&reais, &[0.9, 0.8, 0.3, 0.1], "P").unwrap();
+    /// assert_eq!(curva.auc, 1.0);
+    ///
+    /// // Sem informação: as pontuações se alternam entre as classes
+    /// let reais_alternados = rotulos(&["P", "N", "N", "P", "P", "N", "N", "P"]);
+    /// let pontuacoes = [0.8, 0.8, 0.6, 0.6, 0.4, 0.4, 0.2, 0.2];
+    /// assert_eq!(CurvaRoc::calcular(&reais_alternados, &pontuacoes, "P").unwrap().auc, 0.5);
+    ///
+    /// // Todas as pontuações empatadas: um único segmento de (0, 0) a (1, 1)
+    /// let curva = CurvaRoc::calcular(&reais, &[0.5; 4], "P").unwrap();
+    /// assert_eq!(curva.pontos.len(), 2);
+    /// assert_eq!(curva.auc, 0.5);
+    ///
+    /// // Empate entre um positivo e um negativo no meio da ordem vale meio par
+    /// let curva = CurvaRoc::calcular(&reais, &[0.9, 0.5, 0.5, 0.1], "P").unwrap();
+    /// assert_eq!(curva.auc, 0.875);
+    ///
+    /// assert!(CurvaRoc::calcular(&rotulos(&["P", "N", "X"]), &[0.1, 0.2, 0.3], "P").is_err());
+    /// ```
+    pub fn calcular(reais: &[String], pontuacoes: &[f64], classe_positiva: &str) -> Result<Self, ErroKnn> {
+        if reais.len() != pontuacoes.len() {
+            return Err(ErroKnn::ParametroInvalido(format!(
+                "há {} rótulos reais e {} pontuações",
+                reais.len(),
+                pontuacoes.len()
+            )));
+        }
+        let classes: BTreeSet<&String> = reais.iter().collect();
+        if classes.len() > 2 {
+            return Err(ErroKnn::ParametroInvalido(format!(
+                "a curva ROC é para problemas binários, mas há {} classes: {:?}",
+                classes.len(),
+                classes
+            )));
+        }
+        if pontuacoes.iter().any(|pontuacao| pontuacao.is_nan()) {
+            return Err(ErroKnn::ParametroInvalido("há pontuações NaN".to_string()));
+        }
+        let total_positivos = reais.iter().filter(|rotulo| *rotulo == classe_positiva).count();
+        let total_negativos = reais.len() - total_positivos;
+        if total_positivos == 0 || total_negativos == 0 {
+            return Err(ErroKnn::ParametroInvalido(format!(
+                "a curva ROC precisa de pontos positivos ({}) e negativos ({})",
+                total_positivos, total_negativos
+            )));
+        }
+
+        // Da maior pontuação para a menor: baixar o limiar vai transformando pontos em positivos
+        let mut ordem: Vec<usize> = (0..reais.len()).collect();
+        ordem.sort_by(|&a, &b| pontuacoes[b].total_cmp(&pontuacoes[a]));
+
+        let mut pontos = vec![PontoRoc { limiar: f64::INFINITY, fpr: 0.0, tpr: 0.0 }];
+        let (mut verdadeiros, mut falsos) = (0, 0);
+        for (posicao, &indice) in ordem.iter().enumerate() {
+            if reais[indice] == classe_positiva {
+                verdadeiros += 1;
+            } else {
+                falsos += 1;
+            }
+            // Só fecha o ponto da curva quando acaba o grupo de pontuações empatadas
+            let fim_do_grupo = ordem.get(posicao + 1).is_none_or(|&proximo| pontuacoes[proximo] != pontuacoes[indice]);
+            if fim_do_grupo {
+                pontos.push(PontoRoc {
+                    limiar: pontuacoes[indice],
+                    fpr: falsos as f64 / total_negativos as f64,
+                    tpr: verdadeiros as f64 / total_positivos as f64,
+                });
+            }
+        }
+
+        let coordenadas: Vec<(f64, f64)> = pontos.iter().map(|ponto| (ponto.fpr, ponto.tpr)).collect();
+        Ok(Self { auc: area_trapezios(&coordenadas), pontos })
+    }
+
+    /// Grava os pontos da curva em CSV (colunas `limiar,fpr,tpr`), para gerar o gráfico.
+    ///
+    /// ```
+    /// use aprendizagem_knn::CurvaRoc;
+    ///
+    /// let reais: Vec<String> = ["P", "N"].iter().map(|r| r.to_string()).collect();
+    /// let mut saida = Vec::new();
+    /// CurvaRoc::calcular(&reais, &[0.7, 0.2], "P").unwrap().escrever_csv(&mut saida).unwrap();
+    /// assert_eq!(String::from_utf8(saida).unwrap(), "limiar,fpr,tpr\ninf,0,0\n0.7,0,1\n0.2,1,1\n");
+    /// ```
+    pub fn escrever_csv<W: Write>(&self, destino: W) -> Result<(), Box<dyn Error>> {
+        let mut escritor = Writer::from_writer(destino);
+        escritor.write_record(["limiar", "fpr", "tpr"])?;
+        for ponto in &self.pontos {
+            escritor.write_record([ponto.limiar.to_string(), ponto.fpr.to_string(), ponto.tpr.to_string()])?;
+        }
+        escritor.flush()?;
+        Ok(())
+    }
+
+    /// Salva os pontos da curva em um arquivo CSV (veja [`CurvaRoc::escrever_csv`]).
+    ///
+    /// ```
+    /// use aprendizagem_knn::CurvaRoc;
+    ///
+    /// let caminho = std::env::temp_dir().join("aprendizagem_knn_doc_roc.csv");
+    /// let reais: Vec<String> = ["P", "N"].iter().map(|r| r.to_string()).collect();
+    /// let curva = CurvaRoc::calcular(&reais, &[0.7, 0.2], "P").unwrap();
+    /// curva.salvar_csv(caminho.to_str().unwrap()).unwrap();
+    /// assert_eq!(std::fs::read_to_string(&caminho).unwrap().lines().count(), 4);
+    /// ```
+    pub fn salvar_csv(&self, caminho_arquivo: &str) -> Result<(), Box<dyn Error>> {
+        self.escrever_csv(std::fs::File::create(caminho_arquivo)?)
+    }
+}
+
+/// Curva ROC de um KNN binário: os pares `(FPR, TPR)` obtidos variando o limiar de
+/// confiança de 0.0 a 1.0, em ordem crescente de FPR.
+///
+/// A pontuação de cada ponto de teste é a fração dos seus `k` vizinhos (distância
+/// euclidiana) que é da `classe_positiva`, tirada de [`knn_com_confianca`]: a própria
+/// confiança quando o rótulo previsto é o positivo e o complemento quando não é (com duas
+/// classes, os vizinhos que não votaram no vencedor votaram na outra). Um ponto é chamado
+/// de positivo quando a pontuação é maior ou igual ao limiar; cada limiar em que a
+/// classificação muda é um ponto da curva (veja [`CurvaRoc::calcular`]).
+///
+/// É [`ErroKnn::ParametroInvalido`] se `pontos_teste` e `rotulos_reais` tiverem tamanhos
+/// diferentes, ou se os rótulos reais tiverem mais de duas classes ou não tiverem
+/// positivos e negativos; os outros erros são os de [`knn_com_confianca`].
+///
+/// ```
+/// use aprendizagem_knn::{auc_roc, curva_roc, pontos};
+///
+/// let treinamento = pontos![[0.0] => "N", [1.0] => "N", [2.0] => "N", [8.0] => "P", [9.0] => "P", [10.0] => "P"];
+/// let teste = pontos![[0.5] => "", [1.5] => "", [8.5] => "", [9.5] => ""];
+/// let reais: Vec<String> = ["N", "N", "P", "P"].iter().map(|r| r.to_string()).collect();
+///
+/// let curva = curva_roc(&treinamento, &teste, &reais, 3, "P").unwrap();
+/// assert_eq!(curva.first(), Some(&(0.0, 0.0)));
+/// assert_eq!(curva.last(), Some(&(1.0, 1.0)));
+/// assert!(curva.windows(2).all(|par| par[0].0 <= par[1].0));
+/// // As classes estão bem separadas: classificador perfeito
+/// assert_eq!(auc_roc(&curva), 1.0);
+///
+/// // Sem treinamento não há vizinhos para dar a pontuação
+/// assert!(curva_roc(&[], &teste, &reais, 3, "P").is_err());
+/// ```
+///
+/// Com rótulos sorteados ao acaso, as características não dizem nada sobre a classe e a
+/// AUC fica perto de 0.5:
+///
+/// ```
+/// use aprendizagem_knn::{auc_roc, curva_roc, Ponto, Rng};
+///
+/// let mut rng = Rng::novo(3);
+/// let mut sortear = |quantidade: usize| -> Vec<Ponto> {
+///     (0..quantidade)
+///         .map(|_| {
+///             let caracteristicas = vec![rng.proximo_f64(), rng.proximo_f64()];
+///             Ponto::novo(caracteristicas, if rng.proximo_f64() < 0.5 { "P" } else { "N" }.to_string())
+///         })
+///         .collect()
+/// };
+/// let treinamento = sortear(500);
+/// let teste = sortear(500);
+/// let reais: Vec<String> = teste.iter().map(|ponto| ponto.rotulo.clone()).collect();
+///
+/// let auc = auc_roc(&curva_roc(&treinamento, &teste, &reais, 15, "P").unwrap());
+/// assert!((auc - 0.5).abs() < 0.06, "{}", auc);
+/// ```
+pub fn curva_roc(
+    treinamento: &[Ponto],
+    pontos_teste: &[Ponto],
+    rotulos_reais: &[String],
+    k: usize,
+    classe_positiva: &str,
+) -> Result<Vec<(f64, f64)>, ErroKnn> {
+    if pontos_teste.len() != rotulos_reais.len() {
+        return Err(ErroKnn::ParametroInvalido(format!(
+            "cada ponto de teste precisa do seu rótulo real: há {} pontos e {} rótulos",
+            pontos_teste.len(),
+            rotulos_reais.len()
+        )));
+    }
+    let pontuacoes = pontos_teste.iter()
+        .map(|ponto| {
+            let (rotulo, confianca) = knn_com_confianca(treinamento, ponto, k, &DistanciaMetrica::Euclidiana)?;
+            Ok(if rotulo == classe_positiva { confianca } else { 1.0 - confianca })
+        })
+        .collect::<Result<Vec<f64>, ErroKnn>>()?;
+    let curva = CurvaRoc::calcular(rotulos_reais, &pontuacoes, classe_positiva)?;
+    Ok(curva.pontos.iter().map(|ponto| (ponto.fpr, ponto.tpr)).collect())
+}
+
+/// Área sob a curva ROC (a AUC), pela regra dos trapézios, com os pontos `(FPR, TPR)`
+/// de [`curva_roc`].
+///
+/// Os pontos são ordenados por FPR antes do cálculo (no empate, pelo TPR). 1.0 é um
+/// classificador perfeito e 0.5 o nível do acaso; uma curva com menos de dois pontos
+/// tem área 0.
+///
+/// ```
+/// use aprendizagem_knn::auc_roc;
+///
+/// assert_eq!(auc_roc(&[(0.0, 0.0), (0.0, 1.0), (1.0, 1.0)]), 1.0);
+/// assert_eq!(auc_roc(&[(0.0, 0.0), (1.0, 1.0)]), 0.5);
+/// // A ordem dos pontos não importa
+/// assert_eq!(auc_roc(&[(1.0, 1.0), (0.5, 0.75), (0.0, 0.0)]), 0.625);
+/// ```
+pub fn auc_roc(curva: &[(f64, f64)]) -> f64 {
+    let mut pontos = curva.to_vec();
+    pontos.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1)));
+    area_trapezios(&pontos)
+}
+
+// Área sob uma poligonal (x, y) pela regra dos trapézios, com os pontos já ordenados por x
+pub(crate) fn area_trapezios(pontos: &[(f64, f64)]) -> f64 {
+    pontos.windows(2).map(|par| (par[1].0 - par[0].0) * (par[0].1 + par[1].1) / 2.0).sum()
+}
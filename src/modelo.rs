@@ -0,0 +1,269 @@
+//! Modelo treinado que pode ser salvo em disco e atualizado com novos dados.
+
+use serde::{Deserialize, Serialize};
+
+use crate::codificacao::CodificadorOneHot;
+use crate::dados::{codificar_registros, converter_caracteristicas, ler_csv_com_cabecalho, ler_registros_csv};
+use crate::erro::ErroKnn;
+use crate::estatisticas::EstatisticasIncrementais;
+use crate::ponto::Ponto;
+
+// ==================== MODELO PERSISTENTE ====================
+/// Modelo treinado que pode ser salvo em disco (JSON) e reaberto depois.
+///
+/// Como o KNN é "preguiçoso", o modelo é basicamente o próprio conjunto de treinamento,
+/// acompanhado das colunas, da tabela de rótulos e das estatísticas de normalização.
+///
+/// # Exemplo
+///
+/// ```
+/// use aprendizagem_knn::{knn, ponto, Modelo};
+///
+/// let modelo = Modelo::do_csv("src/dados.csv").unwrap();
+/// assert_eq!(modelo.colunas, vec!["feature1", "feature2"]);
+/// assert_eq!(modelo.coluna_rotulo, "label");
+///
+/// let rotulo = knn(&modelo.pontos, &ponto!([1.0, 2.0], "?"), 3).unwrap();
+/// assert!(modelo.rotulos.contains(&rotulo));
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Modelo {
+    /// Nomes das colunas de características, na ordem do CSV
+    pub colunas: Vec<String>,
+    /// Nome da coluna do rótulo
+    pub coluna_rotulo: String,
+    /// Rótulos conhecidos, na ordem em que apareceram
+    pub rotulos: Vec<String>,
+    /// Conjunto de treinamento
+    pub pontos: Vec<Ponto>,
+    /// Média e variância de cada característica, atualizadas a cada ponto incluído
+    pub estatisticas: EstatisticasIncrementais,
+    /// Presente quando o CSV tinha colunas categóricas
+    // #[serde(default)] permite abrir modelos salvos antes deste campo existir
+    #[serde(default)]
+    pub codificador: Option<CodificadorOneHot>,
+    /// Número de vizinhos escolhido no treinamento (`None`: quem usa o modelo decide, por
+    /// exemplo com [`calcular_k`](crate::calcular_k))
+    #[serde(default)]
+    pub k: Option<usize>,
+}
+
+impl Modelo {
+    /// Cria um modelo vazio a partir do cabeçalho (características seguidas do rótulo).
+    ///
+    /// ```
+    /// use aprendizagem_knn::{ponto, Modelo};
+    ///
+    /// let cabecalho = vec!["x".to_string(), "y".to_string(), "classe".to_string()];
+    /// let mut modelo = Modelo::novo(cabecalho, None);
+    /// modelo.adicionar(ponto!([1.0, 2.0], "A")).unwrap();
+    /// assert_eq!(modelo.coluna_rotulo, "classe");
+    /// assert_eq!(modelo.dimensao(), 2);
+    /// ```
+    pub fn novo(mut cabecalho: Vec<String>, codificador: Option<CodificadorOneHot>) -> Self {
+        let coluna_rotulo = cabecalho.pop().unwrap_or_default();
+        let dimensao = codificador.as_ref().map_or(cabecalho.len(), |codificador| codificador.dimensao());
+        Self {
+            colunas: cabecalho,
+            coluna_rotulo,
+            rotulos: Vec::new(),
+            pontos: Vec::new(),
+            estatisticas: EstatisticasIncrementais::nova(dimensao),
+            codificador,
+            k: None,
+        }
+    }
+
+    /// Cria o modelo a partir de um CSV com cabeçalho.
+    ///
+    /// ```
+    /// use aprendizagem_knn::Modelo;
+    ///
+    /// let modelo = Modelo::do_csv("src/dados.csv").unwrap();
+    /// assert_eq!(modelo.pontos.len(), 215);
+    /// ```
+    pub fn do_csv(caminho_arquivo: &str) -> Result<Self, ErroKnn> {
+        let (cabecalho, pontos) = ler_csv_com_cabecalho(caminho_arquivo, false)?;
+        let mut modelo = Self::novo(cabecalho, None);
+        for ponto in pontos {
+            modelo.adicionar(ponto)?;
+        }
+        Ok(modelo)
+    }
+
+    /// Cria o modelo a partir de um CSV com colunas categóricas, guardando o
+    /// codificador one-hot junto para que ele seja salvo e reutilizado.
+    ///
+    /// ```
+    /// use aprendizagem_knn::Modelo;
+    ///
+    /// let caminho = std::env::temp_dir().join("aprendizagem_knn_doc_modelo_categorico.csv");
+    /// std::fs::write(&caminho, "peso,cor,classe\n1.0,red,A\n2.0,blue,B\n").unwrap();
+    ///
+    /// let modelo = Modelo::do_csv_categorico(caminho.to_str().unwrap(), None).unwrap();
+    /// assert!(modelo.codificador.is_some());
+    /// assert_eq!(modelo.dimensao(), 3);
+    /// ```
+    pub fn do_csv_categorico(
+        caminho_arquivo: &str,
+        colunas_categoricas: Option<&[usize]>,
+    ) -> Result<Self, ErroKnn> {
+        let registros = ler_registros_csv(caminho_arquivo)?;
+        let (pontos, codificador) = codificar_registros(&registros, caminho_arquivo, colunas_categoricas)?;
+        let mut modelo = Self::novo(registros.cabecalho, Some(codificador));
+        for ponto in pontos {
+            modelo.adicionar(ponto)?;
+        }
+        Ok(modelo)
+    }
+
+    /// Número de características de cada ponto (depois da codificação one-hot, se houver).
+    ///
+    /// ```
+    /// use aprendizagem_knn::Modelo;
+    ///
+    /// assert_eq!(Modelo::do_csv("src/dados.csv").unwrap().dimensao(), 2);
+    /// ```
+    pub fn dimensao(&self) -> usize {
+        self.estatisticas.media.len()
+    }
+
+    /// Inclui um ponto, atualizando rótulos e estatísticas.
+    ///
+    /// Um ponto com número de características diferente de [`Modelo::dimensao`] é
+    /// [`ErroKnn::DimensaoIncompativel`], e nesse caso o modelo não é alterado.
+    ///
+    /// ```
+    /// use aprendizagem_knn::{ponto, ErroKnn, Modelo};
+    ///
+    /// let mut modelo = Modelo::novo(vec!["x".to_string(), "classe".to_string()], None);
+    /// modelo.adicionar(ponto!([1.0], "A")).unwrap();
+    /// modelo.adicionar(ponto!([3.0], "A")).unwrap();
+    /// assert_eq!(modelo.rotulos, vec!["A"]);
+    /// assert_eq!(modelo.estatisticas.media, vec![2.0]);
+    ///
+    /// let erro = modelo.adicionar(ponto!([1.0, 2.0], "B")).unwrap_err();
+    /// assert_eq!(erro, ErroKnn::DimensaoIncompativel { esperado: 1, obtido: 2 });
+    /// assert_eq!(modelo.pontos.len(), 2);
+    /// ```
+    pub fn adicionar(&mut self, ponto: Ponto) -> Result<(), ErroKnn> {
+        let (esperado, obtido) = (self.dimensao(), ponto.caracteristicas.len());
+        if obtido != esperado {
+            return Err(ErroKnn::DimensaoIncompativel { esperado, obtido });
+        }
+        if !self.rotulos.contains(&ponto.rotulo) {
+            self.rotulos.push(ponto.rotulo.clone());
+        }
+        self.estatisticas.atualizar(&ponto.caracteristicas);
+        self.pontos.push(ponto);
+        Ok(())
+    }
+
+    /// Acrescenta as linhas de um novo CSV ao modelo, sem reler os dados originais.
+    ///
+    /// O cabeçalho precisa ter exatamente as mesmas colunas do modelo; qualquer
+    /// coluna desconhecida ou linha com dimensão diferente gera um
+    /// [`ErroKnn::LinhaInvalida`] citando o arquivo e a linha (a 1 para o cabeçalho), e
+    /// nesse caso o modelo não é alterado. Retorna quantos pontos foram adicionados.
+    ///
+    /// ```
+    /// use aprendizagem_knn::{ErroKnn, Modelo};
+    ///
+    /// let caminho = std::env::temp_dir().join("aprendizagem_knn_doc_anexar.csv");
+    /// std::fs::write(&caminho, "feature1,feature2,label\n1.0,1.0,Classe E\n").unwrap();
+    ///
+    /// let mut modelo = Modelo::do_csv("src/dados.csv").unwrap();
+    /// assert_eq!(modelo.anexar_csv(caminho.to_str().unwrap()).unwrap(), 1);
+    /// assert_eq!(modelo.pontos.len(), 216);
+    ///
+    /// std::fs::write(&caminho, "feature1,altura,label\n1.0,1.0,Classe E\n").unwrap();
+    /// let erro = modelo.anexar_csv(caminho.to_str().unwrap()).unwrap_err();
+    /// assert!(matches!(erro, ErroKnn::LinhaInvalida { linha: 1, .. }), "{:?}", erro);
+    /// assert_eq!(modelo.pontos.len(), 216);
+    /// ```
+    pub fn anexar_csv(&mut self, caminho_arquivo: &str) -> Result<usize, ErroKnn> {
+        let registros = ler_registros_csv(caminho_arquivo)?;
+        let cabecalho = &registros.cabecalho;
+
+        for coluna in cabecalho {
+            if !self.colunas.contains(coluna) && *coluna != self.coluna_rotulo {
+                return Err(ErroKnn::LinhaInvalida {
+                    origem: caminho_arquivo.to_string(),
+                    linha: 1,
+                    motivo: format!(
+                        "coluna desconhecida '{}' (o modelo usa {:?} e rótulo '{}')",
+                        coluna, self.colunas, self.coluna_rotulo
+                    ),
+                });
+            }
+        }
+
+        let (colunas, rotulo) = cabecalho.split_at(cabecalho.len() - 1);
+        if colunas != self.colunas.as_slice() || rotulo[0] != self.coluna_rotulo {
+            return Err(ErroKnn::LinhaInvalida {
+                origem: caminho_arquivo.to_string(),
+                linha: 1,
+                motivo: format!(
+                    "o cabeçalho {:?} não corresponde às colunas do modelo {:?} seguidas de '{}'",
+                    cabecalho, self.colunas, self.coluna_rotulo
+                ),
+            });
+        }
+
+        // Converte todas as linhas antes de alterar o modelo
+        let mut pontos = Vec::with_capacity(registros.linhas.len());
+        for (linha, campos) in &registros.linhas {
+            let (caracteristicas, rotulo) = campos.split_at(colunas.len());
+            let caracteristicas = match &self.codificador {
+                Some(codificador) => codificador.codificar(caracteristicas).map_err(|motivo| {
+                    ErroKnn::LinhaInvalida { origem: caminho_arquivo.to_string(), linha: *linha, motivo }
+                })?,
+                None => converter_caracteristicas(caracteristicas, cabecalho, caminho_arquivo, *linha, false)?,
+            };
+            pontos.push(Ponto::novo(caracteristicas, rotulo[0].clone()));
+        }
+
+        let quantidade = pontos.len();
+        for ponto in pontos {
+            self.adicionar(ponto)?;
+        }
+        Ok(quantidade)
+    }
+
+    /// Salva o modelo em um arquivo JSON.
+    ///
+    /// ```
+    /// use aprendizagem_knn::Modelo;
+    ///
+    /// let caminho = std::env::temp_dir().join("aprendizagem_knn_doc_salvar.json");
+    /// let modelo = Modelo::do_csv("src/dados.csv").unwrap();
+    /// modelo.salvar(caminho.to_str().unwrap()).unwrap();
+    /// assert!(caminho.exists());
+    /// ```
+    pub fn salvar(&self, caminho_arquivo: &str) -> Result<(), ErroKnn> {
+        let arquivo = std::fs::File::create(caminho_arquivo).map_err(|erro| ErroKnn::de_io(caminho_arquivo, &erro))?;
+        serde_json::to_writer_pretty(std::io::BufWriter::new(arquivo), self)
+            .map_err(|erro| ErroKnn::Arquivo(format!("{}: {}", caminho_arquivo, erro)))
+    }
+
+    /// Abre um modelo salvo com [`Modelo::salvar`].
+    ///
+    /// ```
+    /// use aprendizagem_knn::Modelo;
+    ///
+    /// let caminho = std::env::temp_dir().join("aprendizagem_knn_doc_carregar.json");
+    /// let mut original = Modelo::do_csv("src/dados.csv").unwrap();
+    /// original.k = Some(5);
+    /// original.salvar(caminho.to_str().unwrap()).unwrap();
+    ///
+    /// let reaberto = Modelo::carregar(caminho.to_str().unwrap()).unwrap();
+    /// assert_eq!(reaberto.pontos.len(), original.pontos.len());
+    /// assert_eq!(reaberto.rotulos, original.rotulos);
+    /// assert_eq!(reaberto.k, Some(5));
+    /// ```
+    pub fn carregar(caminho_arquivo: &str) -> Result<Self, ErroKnn> {
+        let arquivo = std::fs::File::open(caminho_arquivo).map_err(|erro| ErroKnn::de_io(caminho_arquivo, &erro))?;
+        serde_json::from_reader(std::io::BufReader::new(arquivo))
+            .map_err(|erro| ErroKnn::Arquivo(format!("{}: modelo inválido: {}", caminho_arquivo, erro)))
+    }
+}
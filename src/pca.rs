@@ -0,0 +1,258 @@
+//! Análise de Componentes Principais (PCA) para redução de dimensionalidade.
+
+use serde::{Deserialize, Serialize};
+
+use crate::aleatorio::Rng;
+use crate::distancia::distancia_euclidiana;
+use crate::erro::ErroKnn;
+use crate::ponto::Ponto;
+use crate::preprocessamento::Transformador;
+
+// ==================== PCA ====================
+// O nome segue a sigla consagrada, por isso liberamos o aviso do clippy sobre siglas
+/// Análise de Componentes Principais (PCA): projeta os pontos nas direções de maior
+/// variância dos dados, reduzindo o número de características.
+///
+/// Os autovetores da matriz de covariância são encontrados pelo método da potência,
+/// que é simples e suficiente quando há poucas características.
+///
+/// # Exemplo
+///
+/// ```
+/// use aprendizagem_knn::{ponto, pontos, Transformador, PCA};
+///
+/// // pontos sobre a reta y = x: um único componente explica toda a variância
+/// let dados = pontos![[1.0, 1.0] => "A", [2.0, 2.0] => "A", [3.0, 3.0] => "B"];
+/// let pca = PCA::ajustar(&dados, 1).unwrap();
+/// assert!((pca.razao_variancia_explicada()[0] - 1.0).abs() < 1e-9);
+/// assert_eq!(pca.transformar_ponto(&ponto!([2.0, 2.0], "?")).caracteristicas.len(), 1);
+/// ```
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PCA {
+    /// Cada componente é um autovetor unitário
+    pub componentes: Vec<Vec<f64>>,
+    /// Média usada para centralizar os dados
+    pub media: Vec<f64>,
+    /// Quantidade de componentes mantidos
+    pub n_componentes: usize,
+    /// Variância dos dados ao longo de cada componente
+    pub autovalores: Vec<f64>,
+    /// Soma das variâncias de todas as características
+    pub variancia_total: f64,
+}
+
+impl PCA {
+    /// Encontra os `n_componentes` componentes principais do conjunto de treinamento.
+    ///
+    /// É erro se o conjunto estiver vazio ou se `n_componentes` não estiver entre 1
+    /// e o número de características.
+    ///
+    /// ```
+    /// use aprendizagem_knn::{pontos, ErroKnn, PCA};
+    ///
+    /// let dados = pontos![[1.0, 2.0] => "A", [3.0, 5.0] => "B"];
+    /// assert_eq!(PCA::ajustar(&dados, 2).unwrap().componentes.len(), 2);
+    /// assert!(matches!(PCA::ajustar(&dados, 3), Err(ErroKnn::ParametroInvalido(_))));
+    /// assert_eq!(PCA::ajustar(&[], 1).unwrap_err(), ErroKnn::ConjuntoVazio);
+    /// ```
+    pub fn ajustar(dados: &[Ponto], n_componentes: usize) -> Result<PCA, ErroKnn> {
+        let primeiro = dados.first().ok_or(ErroKnn::ConjuntoVazio)?;
+        let dimensao = primeiro.caracteristicas.len();
+        if n_componentes == 0 || n_componentes > dimensao {
+            return Err(ErroKnn::ParametroInvalido(format!(
+                "n_componentes deve estar entre 1 e {} (recebido {})",
+                dimensao, n_componentes
+            )));
+        }
+
+        // Média de cada característica
+        let n = dados.len() as f64;
+        let mut media = vec![0.0; dimensao];
+        for ponto in dados {
+            for (m, valor) in media.iter_mut().zip(&ponto.caracteristicas) {
+                *m += valor / n;
+            }
+        }
+
+        // Matriz de covariância (amostral) dos dados centralizados
+        let divisor = if dados.len() > 1 { n - 1.0 } else { 1.0 };
+        let mut covariancia = vec![vec![0.0; dimensao]; dimensao];
+        for ponto in dados {
+            let centrado: Vec<f64> = ponto.caracteristicas.iter().zip(&media).map(|(x, m)| x - m).collect();
+            for i in 0..dimensao {
+                for j in 0..dimensao {
+                    covariancia[i][j] += centrado[i] * centrado[j] / divisor;
+                }
+            }
+        }
+
+        // O traço da matriz de covariância é a variância total dos dados
+        let variancia_total = (0..dimensao).map(|i| covariancia[i][i]).sum();
+
+        let mut componentes: Vec<Vec<f64>> = Vec::with_capacity(n_componentes);
+        let mut autovalores = Vec::with_capacity(n_componentes);
+        // Semente fixa: o vetor inicial é "aleatório", mas o resultado é sempre o mesmo
+        let mut rng = Rng::novo(42);
+        for _ in 0..n_componentes {
+            let inicial: Vec<f64> = (0..dimensao).map(|_| rng.proximo_f64() - 0.5).collect();
+            let (autovetor, autovalor) = iteracao_da_potencia(&covariancia, inicial, &componentes);
+
+            // Deflação: remove da matriz a direção já encontrada, para que a
+            // próxima iteração encontre o próximo maior autovalor
+            for i in 0..dimensao {
+                for j in 0..dimensao {
+                    covariancia[i][j] -= autovalor * autovetor[i] * autovetor[j];
+                }
+            }
+            componentes.push(autovetor);
+            autovalores.push(autovalor);
+        }
+
+        Ok(PCA { componentes, media, n_componentes, autovalores, variancia_total })
+    }
+
+    /// Fração da variância total explicada por cada componente, em ordem decrescente.
+    ///
+    /// Para escolher quantas dimensões manter, ajuste com n_componentes igual ao número
+    /// de características e veja onde a soma acumulada fica boa o suficiente.
+    ///
+    /// ```
+    /// use aprendizagem_knn::{pontos, PCA};
+    ///
+    /// let dados = pontos![[0.0, 0.0] => "A", [4.0, 1.0] => "B", [8.0, 0.0] => "C"];
+    /// let razoes = PCA::ajustar(&dados, 2).unwrap().razao_variancia_explicada();
+    /// assert!(razoes[0] >= razoes[1]);
+    /// assert!((razoes.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+    /// ```
+    pub fn razao_variancia_explicada(&self) -> Vec<f64> {
+        if self.variancia_total == 0.0 {
+            return vec![0.0; self.autovalores.len()];
+        }
+        self.autovalores.iter().map(|autovalor| autovalor / self.variancia_total).collect()
+    }
+
+    /// Volta do espaço reduzido para o espaço original: media + Σ coordenada_i * componente_i.
+    ///
+    /// A informação descartada junto com os componentes não usados não é recuperada.
+    ///
+    /// ```
+    /// use aprendizagem_knn::{ponto, pontos, Transformador, PCA};
+    ///
+    /// let dados = pontos![[1.0, 1.0] => "A", [3.0, 3.0] => "B"];
+    /// let pca = PCA::ajustar(&dados, 1).unwrap();
+    /// let original = ponto!([2.5, 2.5], "?");
+    /// let reconstruido = pca.reconstruir_ponto(&pca.transformar_ponto(&original));
+    /// assert!((reconstruido.caracteristicas[0] - 2.5).abs() < 1e-9);
+    /// ```
+    pub fn reconstruir_ponto(&self, ponto: &Ponto) -> Ponto {
+        let mut caracteristicas = self.media.clone();
+        for (coordenada, componente) in ponto.caracteristicas.iter().zip(&self.componentes) {
+            for (valor, c) in caracteristicas.iter_mut().zip(componente) {
+                *valor += coordenada * c;
+            }
+        }
+        Ponto::novo(caracteristicas, ponto.rotulo.clone())
+    }
+
+    /// Erro quadrático médio entre os pontos e suas reconstruções depois da projeção.
+    ///
+    /// ```
+    /// use aprendizagem_knn::{pontos, PCA};
+    ///
+    /// let dados = pontos![[1.0, 1.0] => "A", [2.0, 2.0] => "A", [3.0, 3.0] => "B"];
+    /// assert!(PCA::ajustar(&dados, 1).unwrap().erro_reconstrucao(&dados) < 1e-12);
+    /// ```
+    pub fn erro_reconstrucao(&self, dados: &[Ponto]) -> f64 {
+        if dados.is_empty() {
+            return 0.0;
+        }
+        let soma: f64 = dados.iter()
+            .map(|ponto| {
+                let reconstruido = self.reconstruir_ponto(&self.transformar_ponto(ponto));
+                distancia_euclidiana(ponto, &reconstruido).powi(2)
+            })
+            .sum();
+        soma / dados.len() as f64
+    }
+}
+
+impl Transformador for PCA {
+    // Centraliza o ponto e calcula sua projeção em cada componente
+    fn transformar_ponto(&self, ponto: &Ponto) -> Ponto {
+        let centrado: Vec<f64> = ponto.caracteristicas.iter().zip(&self.media).map(|(x, m)| x - m).collect();
+        let projecao = self.componentes.iter()
+            .map(|componente| produto_escalar(componente, &centrado))
+            .collect();
+        Ponto::novo(projecao, ponto.rotulo.clone())
+    }
+}
+
+fn produto_escalar(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+// Remove de `vetor` as componentes nas direções já encontradas (Gram-Schmidt)
+// e normaliza o resultado. Devolve false se sobrar um vetor praticamente nulo.
+fn ortonormalizar(vetor: &mut [f64], anteriores: &[Vec<f64>]) -> bool {
+    for anterior in anteriores {
+        let projecao = produto_escalar(vetor, anterior);
+        for (v, a) in vetor.iter_mut().zip(anterior) {
+            *v -= projecao * a;
+        }
+    }
+    let norma = produto_escalar(vetor, vetor).sqrt();
+    if norma < 1e-12 {
+        return false;
+    }
+    for v in vetor.iter_mut() {
+        *v /= norma;
+    }
+    true
+}
+
+// Método da potência: multiplicar repetidamente um vetor pela matriz faz ele convergir
+// para o autovetor do maior autovalor. Manter o vetor ortogonal às componentes anteriores
+// evita que erros de arredondamento o puxem de volta para direções já usadas.
+// Devolve (autovetor unitário, autovalor).
+fn iteracao_da_potencia(matriz: &[Vec<f64>], inicial: Vec<f64>, anteriores: &[Vec<f64>]) -> (Vec<f64>, f64) {
+    let dimensao = matriz.len();
+    let mut vetor = inicial;
+    if !ortonormalizar(&mut vetor, anteriores) {
+        // Vetor inicial degenerado: usa o primeiro eixo que sobreviver à ortogonalização
+        for eixo in 0..dimensao {
+            vetor = vec![0.0; dimensao];
+            vetor[eixo] = 1.0;
+            if ortonormalizar(&mut vetor, anteriores) {
+                break;
+            }
+        }
+    }
+
+    for _ in 0..1000 {
+        let mut proximo: Vec<f64> = matriz.iter().map(|linha| produto_escalar(linha, &vetor)).collect();
+        // Se a matriz "zerou" o vetor, o autovalor restante é 0 e qualquer direção
+        // ortogonal às anteriores serve
+        if !ortonormalizar(&mut proximo, anteriores) {
+            break;
+        }
+        let diferenca: f64 = proximo.iter().zip(&vetor).map(|(a, b)| (a - b).abs()).sum();
+        vetor = proximo;
+        if diferenca < 1e-12 {
+            break;
+        }
+    }
+
+    // Convenção de sinal: a maior coordenada em módulo fica positiva,
+    // para que o mesmo conjunto de dados gere sempre os mesmos componentes
+    let maior = vetor.iter().cloned().fold(0.0, |acc: f64, v| if v.abs() > acc.abs() { v } else { acc });
+    if maior < 0.0 {
+        for v in vetor.iter_mut() {
+            *v = -*v;
+        }
+    }
+
+    let imagem: Vec<f64> = matriz.iter().map(|linha| produto_escalar(linha, &vetor)).collect();
+    let autovalor = produto_escalar(&vetor, &imagem).max(0.0);
+    (vetor, autovalor)
+}
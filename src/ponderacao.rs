@@ -0,0 +1,175 @@
+//! Pesos das características aprendidos a partir dos dados (informação mútua com o rótulo).
+
+use std::collections::HashMap;
+
+use crate::distancia::DistanciaMetrica;
+use crate::erro::ErroKnn;
+use crate::knn::knn_com_metrica;
+use crate::ponto::Ponto;
+
+// ==================== INFORMAÇÃO MÚTUA ====================
+/// Informação mútua (em bits) entre uma característica, discretizada em `n_bins` faixas
+/// de mesma largura, e o rótulo: Σ p(x,y) * log2(p(x,y) / (p(x) * p(y))).
+///
+/// Vale 0 quando a característica não diz nada sobre a classe e no máximo a entropia
+/// do rótulo quando ela a determina por completo. Ao contrário da variância, ela mede a
+/// relação com o rótulo, então serve para escolher as características que mais ajudam
+/// o KNN a separar as classes.
+///
+/// Valores ausentes (NaN) são ignorados; uma característica constante fica toda na
+/// mesma faixa e, portanto, tem informação mútua 0 (assim como um conjunto vazio ou
+/// `n_bins` 0). Entra em pânico se `indice_feature` não for uma característica dos pontos.
+///
+/// ```
+/// use aprendizagem_knn::{informacao_mutua, pontos};
+///
+/// // A primeira característica determina a classe (1 bit, já que as classes são meio a meio);
+/// // a segunda não diz nada sobre ela
+/// let dados = pontos![
+///     [0.0, 0.0] => "A", [0.1, 1.0] => "A",
+///     [1.0, 0.0] => "B", [0.9, 1.0] => "B",
+/// ];
+/// assert!((informacao_mutua(&dados, 0, 2) - 1.0).abs() < 1e-12);
+/// assert_eq!(informacao_mutua(&dados, 1, 2), 0.0);
+/// ```
+pub fn informacao_mutua(dados: &[Ponto], indice_feature: usize, n_bins: usize) -> f64 {
+    let presentes: Vec<(f64, &str)> = dados.iter()
+        .map(|ponto| (ponto.caracteristicas[indice_feature], ponto.rotulo.as_str()))
+        .filter(|(valor, _)| !valor.is_nan())
+        .collect();
+    if presentes.is_empty() || n_bins == 0 {
+        return 0.0;
+    }
+
+    let minimo = presentes.iter().map(|(valor, _)| *valor).fold(f64::INFINITY, f64::min);
+    let maximo = presentes.iter().map(|(valor, _)| *valor).fold(f64::NEG_INFINITY, f64::max);
+    let largura = (maximo - minimo) / n_bins as f64;
+    let faixa = |valor: f64| {
+        if largura == 0.0 || !largura.is_finite() {
+            0
+        } else {
+            // O máximo cairia na faixa n_bins; min o coloca na última faixa válida
+            (((valor - minimo) / largura) as usize).min(n_bins - 1)
+        }
+    };
+
+    // Contagens conjuntas (faixa, rótulo) e marginais
+    let mut conjuntas: HashMap<(usize, &str), usize> = HashMap::new();
+    let mut por_faixa: HashMap<usize, usize> = HashMap::new();
+    let mut por_rotulo: HashMap<&str, usize> = HashMap::new();
+    for &(valor, rotulo) in &presentes {
+        let x = faixa(valor);
+        *conjuntas.entry((x, rotulo)).or_insert(0) += 1;
+        *por_faixa.entry(x).or_insert(0) += 1;
+        *por_rotulo.entry(rotulo).or_insert(0) += 1;
+    }
+
+    // Só entram células com contagem positiva, então nenhum log recebe zero.
+    // Escrevendo p(x,y) / (p(x) p(y)) como n * n_xy / (n_x * n_y) evitamos divisões pequenas.
+    let n = presentes.len() as f64;
+    let soma: f64 = conjuntas.iter()
+        .map(|(&(x, rotulo), &n_xy)| {
+            let n_xy = n_xy as f64;
+            let n_x = por_faixa[&x] as f64;
+            let n_y = por_rotulo[rotulo] as f64;
+            (n_xy / n) * (n * n_xy / (n_x * n_y)).log2()
+        })
+        .sum();
+
+    // Arredondamentos podem deixar um valor como -1e-17 quando a informação é nula
+    soma.max(0.0)
+}
+
+/// Ordena as características da maior para a menor [`informacao_mutua`] com o rótulo.
+///
+/// Devolve pares `(índice da característica, informação mútua)`; em caso de empate, o
+/// menor índice vem primeiro. Um conjunto vazio devolve uma lista vazia.
+///
+/// ```
+/// use aprendizagem_knn::{pontos, rankear_features_por_mi};
+///
+/// // A segunda característica separa as classes; a primeira e a terceira, não
+/// let dados = pontos![
+///     [5.0, 0.0, 1.0] => "A", [5.0, 0.1, 0.0] => "A",
+///     [5.0, 1.0, 1.0] => "B", [5.0, 0.9, 0.0] => "B",
+/// ];
+/// let ranking = rankear_features_por_mi(&dados, 2);
+/// let ordem: Vec<usize> = ranking.iter().map(|&(indice, _)| indice).collect();
+/// assert_eq!(ordem, vec![1, 0, 2]);
+/// ```
+pub fn rankear_features_por_mi(dados: &[Ponto], n_bins: usize) -> Vec<(usize, f64)> {
+    let n_caracteristicas = dados.first().map_or(0, |ponto| ponto.caracteristicas.len());
+    let mut ranking: Vec<(usize, f64)> = (0..n_caracteristicas)
+        .map(|indice| (indice, informacao_mutua(dados, indice, n_bins)))
+        .collect();
+    ranking.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+    ranking
+}
+
+/// Aprende um peso por característica: a informação mútua (em bits) entre a característica,
+/// discretizada em `n_bins` faixas de mesma largura, e o rótulo.
+///
+/// Características que ajudam a separar as classes recebem pesos maiores; as que não dizem
+/// nada sobre a classe (ruído, constantes) ficam com peso 0 e são ignoradas pela
+/// [`distancia_euclidiana_ponderada`](crate::distancia_euclidiana_ponderada).
+/// Se nenhuma característica tiver informação, todos os pesos viram 1.0 (distância euclidiana
+/// comum), já que pesos todos zero deixariam todos os pontos empatados.
+///
+/// Com poucos pontos por faixa a estimativa tende a exagerar a informação, então prefira
+/// poucas faixas (algo como √n) em conjuntos pequenos. Os pesos não corrigem diferenças
+/// de escala: normalize os dados antes se as características tiverem unidades diferentes.
+///
+/// ```
+/// use aprendizagem_knn::{ajustar_pesos_informacao_mutua, pontos};
+///
+/// // a primeira característica separa as classes; a segunda é constante
+/// let dados = pontos![
+///     [0.0, 5.0] => "A", [0.2, 5.0] => "A",
+///     [1.0, 5.0] => "B", [0.9, 5.0] => "B",
+/// ];
+/// let pesos = ajustar_pesos_informacao_mutua(&dados, 2).unwrap();
+/// assert_eq!(pesos, vec![1.0, 0.0]);
+/// ```
+pub fn ajustar_pesos_informacao_mutua(treinamento: &[Ponto], n_bins: usize) -> Result<Vec<f64>, ErroKnn> {
+    let primeiro = treinamento.first().ok_or(ErroKnn::ConjuntoVazio)?;
+    if n_bins == 0 {
+        return Err(ErroKnn::ParametroInvalido("n_bins deve ser maior que zero".to_string()));
+    }
+
+    let pesos: Vec<f64> = (0..primeiro.caracteristicas.len())
+        .map(|indice| informacao_mutua(treinamento, indice, n_bins))
+        .collect();
+
+    if pesos.iter().all(|&peso| peso == 0.0) {
+        return Ok(vec![1.0; pesos.len()]);
+    }
+    Ok(pesos)
+}
+
+/// Classifica os pontos de teste com a distância euclidiana ponderada, usando pesos
+/// aprendidos por [`ajustar_pesos_informacao_mutua`] no treinamento.
+///
+/// ```
+/// use aprendizagem_knn::{knn_ponderado_por_informacao_mutua, pontos};
+///
+/// // a classe depende só da primeira característica; a segunda é ruído com escala maior
+/// let treinamento = pontos![
+///     [0.0, 9.0] => "A", [0.1, 0.0] => "A", [0.2, 5.0] => "A",
+///     [1.0, 1.0] => "B", [0.9, 8.0] => "B", [0.8, 4.0] => "B",
+/// ];
+/// let testes = pontos![[0.05, 8.5] => "?", [0.95, 0.5] => "?"];
+/// let previstos = knn_ponderado_por_informacao_mutua(&treinamento, &testes, 1, 2).unwrap();
+/// assert_eq!(previstos, vec!["A", "B"]);
+/// ```
+pub fn knn_ponderado_por_informacao_mutua(
+    treinamento: &[Ponto],
+    pontos_teste: &[Ponto],
+    k: usize,
+    n_bins: usize,
+) -> Result<Vec<String>, ErroKnn> {
+    let pesos = ajustar_pesos_informacao_mutua(treinamento, n_bins)?;
+    let metrica = DistanciaMetrica::euclidiana_ponderada(pesos)?;
+    pontos_teste.iter()
+        .map(|ponto| knn_com_metrica(treinamento, ponto, k, &metrica))
+        .collect()
+}
@@ -0,0 +1,106 @@
+//! Estrutura básica do KNN: um ponto com suas características e seu rótulo.
+
+use std::fmt; // Módulo para formatar tipos como texto (trait Display)
+
+use serde::{Deserialize, Serialize}; // Biblioteca para converter (deserializar/serializar) dados de forma automática
+
+// ==================== ESTRUTURA DE DADOS PRINCIPAIS ====================
+// #[derive] são atributos em Rust que adicionam funcionalidades às estruturas
+// Debug: permite imprimir a estrutura para debug
+// Clone: permite criar cópias da estrutura
+// PartialEq: permite comparar dois pontos com == (mesmas características e mesmo rótulo)
+// Deserialize: permite converter dados externos (como CSV) para esta estrutura
+// Serialize: permite o caminho inverso (usado para salvar o modelo em JSON)
+// Arbitrary (só com a feature fuzz): cria pontos quaisquer a partir de bytes aleatórios
+// pub torna a estrutura (e cada campo marcado) visível para quem usa a biblioteca
+/// Um exemplo do conjunto de dados: as características numéricas e a classe a que
+/// pertence, com o rótulo de qualquer tipo `R`.
+///
+/// Quase sempre o rótulo é texto, e para isso existe o apelido [`Ponto`]
+/// (`PontoGenerico<String>`), usado no resto da biblioteca. Rótulos de outro tipo (números
+/// como 0, 1 e 2, ou um `enum` próprio) evitam criar e clonar uma `String` para cada um;
+/// o [`knn`](fn@crate::knn) aceita qualquer rótulo `Clone + Eq + Hash + Ord`.
+///
+/// # Exemplo
+///
+/// ```
+/// use aprendizagem_knn::PontoGenerico;
+///
+/// let ponto = PontoGenerico::novo(vec![1.0, 2.0], 3u32);
+/// assert_eq!(ponto.caracteristicas, vec![1.0, 2.0]);
+/// assert_eq!(ponto.rotulo, 3);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub struct PontoGenerico<R> {
+    /// Valores das características (coordenadas) do ponto
+    pub caracteristicas: Vec<f64>, // Vec<f64> é um vetor dinâmico de números decimais
+    /// Classe do ponto
+    pub rotulo: R,                 // R é o tipo do rótulo, escolhido por quem cria o ponto
+}
+
+// type cria um apelido: Ponto e PontoGenerico<String> são o mesmo tipo, então o código
+// que usa Ponto continua igual
+/// Um ponto com o rótulo em texto, o tipo usado em toda a biblioteca (leitura de CSV,
+/// validação, métricas...).
+///
+/// ```
+/// use aprendizagem_knn::Ponto;
+///
+/// let ponto = Ponto::novo(vec![1.0, 2.0], "Classe A".to_string());
+/// assert_eq!(ponto.caracteristicas, vec![1.0, 2.0]);
+/// assert_eq!(ponto.rotulo, "Classe A");
+/// ```
+pub type Ponto = PontoGenerico<String>;
+
+// impl em Rust define a implementação de métodos para uma estrutura
+// Similar a métodos de classe em outras linguagens; impl<R> vale para qualquer tipo de rótulo
+impl<R> PontoGenerico<R> {
+    // fn define uma função em Rust
+    // -> indica o tipo de retorno da função
+    // Self refere-se ao tipo atual (PontoGenerico<R>)
+    /// Cria um ponto a partir das características e do rótulo.
+    ///
+    /// ```
+    /// use aprendizagem_knn::Ponto;
+    ///
+    /// let ponto = Ponto::novo(vec![4.5, 8.0], "Desconhecido".to_string());
+    /// assert_eq!(ponto.caracteristicas.len(), 2);
+    /// ```
+    pub fn novo(caracteristicas: Vec<f64>, rotulo: R) -> Self {
+        Self { caracteristicas, rotulo } // Sintaxe curta quando o nome do campo e da variável são iguais
+    }
+
+    /// Troca o rótulo pelo resultado de `map_rotulo`, mantendo as características.
+    ///
+    /// ```
+    /// use aprendizagem_knn::ponto;
+    ///
+    /// let ponto = ponto!([1.0], "2").mapear_rotulo(|rotulo| rotulo.parse::<u32>().unwrap());
+    /// assert_eq!(ponto.rotulo, 2);
+    /// ```
+    pub fn mapear_rotulo<S>(self, map_rotulo: impl FnOnce(R) -> S) -> PontoGenerico<S> {
+        PontoGenerico { caracteristicas: self.caracteristicas, rotulo: map_rotulo(self.rotulo) }
+    }
+}
+
+// Display define como o ponto aparece com {} (println!, format!, to_string):
+// as características com 2 casas decimais e o rótulo entre aspas, depois de uma seta
+/// ```
+/// use aprendizagem_knn::ponto;
+///
+/// let ponto = ponto!([1.2, 3.4, 5.6], "ClassA");
+/// assert_eq!(format!("{}", ponto), r#"Ponto([1.20, 3.40, 5.60] -> "ClassA")"#);
+/// ```
+impl<R: fmt::Debug> fmt::Display for PontoGenerico<R> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Ponto([")?;
+        for (indice, valor) in self.caracteristicas.iter().enumerate() {
+            if indice > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{:.2}", valor)?;
+        }
+        write!(f, "] -> {:?})", self.rotulo)
+    }
+}
@@ -0,0 +1,919 @@
+//! Transformações aplicadas às características antes do cálculo das distâncias.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::distancia::DistanciaMetrica;
+use crate::erro::ErroKnn;
+use crate::estatisticas::{percentil, EstatisticasIncrementais};
+use crate::knn::{knn_batch, knn_com_metrica};
+use crate::pca::PCA;
+use crate::ponto::Ponto;
+
+// ==================== PRÉ-PROCESSAMENTO ====================
+// trait define uma interface comum: todo pré-processamento sabe transformar um ponto.
+/// Interface comum dos pré-processamentos.
+///
+/// Os parâmetros (mínimos, médias...) são aprendidos só no treinamento e depois
+/// reaproveitados, sem reajuste, em cada ponto de teste.
+///
+/// # Exemplo
+///
+/// ```
+/// use aprendizagem_knn::{ponto, pontos, NormalizadorMinMax, Transformador};
+///
+/// let treinamento = pontos![[0.0, 100.0] => "A", [10.0, 300.0] => "B"];
+/// let normalizador = NormalizadorMinMax::ajustar(&treinamento);
+/// let teste = normalizador.transformar_ponto(&ponto!([5.0, 200.0], "?"));
+/// assert_eq!(teste.caracteristicas, vec![0.5, 0.5]);
+/// assert_eq!(normalizador.transformar(&treinamento).len(), 2);
+/// ```
+pub trait Transformador {
+    /// Aplica a transformação a um único ponto (o rótulo é preservado)
+    fn transformar_ponto(&self, ponto: &Ponto) -> Ponto;
+
+    /// Aplica a transformação a todos os pontos; a implementação padrão
+    /// transforma ponto a ponto
+    fn transformar(&self, dados: &[Ponto]) -> Vec<Ponto> {
+        dados.iter().map(|ponto| self.transformar_ponto(ponto)).collect()
+    }
+}
+
+/// Transformação identidade, usada quando nenhum pré-processamento é escolhido.
+///
+/// ```
+/// use aprendizagem_knn::{ponto, SemTransformacao, Transformador};
+///
+/// let ponto = ponto!([1.0, 2.0], "A");
+/// assert_eq!(SemTransformacao.transformar_ponto(&ponto).caracteristicas, vec![1.0, 2.0]);
+/// ```
+pub struct SemTransformacao;
+
+impl Transformador for SemTransformacao {
+    fn transformar_ponto(&self, ponto: &Ponto) -> Ponto {
+        ponto.clone()
+    }
+}
+
+/// Normalização min-max: leva cada característica para o intervalo [0, 1].
+///
+/// Sem isso, uma característica com valores na casa dos milhares domina a distância
+/// e as outras praticamente não influenciam o resultado.
+/// Os mínimos e máximos são aprendidos no treinamento e reaproveitados nos pontos de teste.
+///
+/// ```
+/// use aprendizagem_knn::{ponto, pontos, NormalizadorMinMax, Transformador};
+///
+/// let treinamento = pontos![[1.0, 7.0] => "A", [3.0, 7.0] => "B"];
+/// let normalizador = NormalizadorMinMax::ajustar(&treinamento);
+/// // a segunda característica é constante e vira 0
+/// assert_eq!(normalizador.transformar_ponto(&ponto!([2.0, 7.0], "?")).caracteristicas, vec![0.5, 0.0]);
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NormalizadorMinMax {
+    /// Menor valor de cada característica no treinamento
+    pub minimos: Vec<f64>,
+    /// Maior valor de cada característica no treinamento
+    pub maximos: Vec<f64>,
+}
+
+impl NormalizadorMinMax {
+    /// Calcula o mínimo e o máximo de cada característica no conjunto de treinamento.
+    ///
+    /// ```
+    /// use aprendizagem_knn::{pontos, NormalizadorMinMax};
+    ///
+    /// let normalizador = NormalizadorMinMax::ajustar(&pontos![[1.0] => "A", [5.0] => "B"]);
+    /// assert_eq!((normalizador.minimos[0], normalizador.maximos[0]), (1.0, 5.0));
+    /// ```
+    pub fn ajustar(dados: &[Ponto]) -> Self {
+        let dimensao = dados.first().map_or(0, |ponto| ponto.caracteristicas.len());
+        let mut minimos = vec![f64::INFINITY; dimensao];
+        let mut maximos = vec![f64::NEG_INFINITY; dimensao];
+
+        for ponto in dados {
+            for (i, &valor) in ponto.caracteristicas.iter().enumerate() {
+                minimos[i] = minimos[i].min(valor);
+                maximos[i] = maximos[i].max(valor);
+            }
+        }
+
+        Self { minimos, maximos }
+    }
+
+    /// Desfaz a normalização, voltando à escala original
+    /// (características constantes voltam para o seu único valor).
+    ///
+    /// ```
+    /// use aprendizagem_knn::{ponto, pontos, NormalizadorMinMax};
+    ///
+    /// let normalizador = NormalizadorMinMax::ajustar(&pontos![[1.0] => "A", [5.0] => "B"]);
+    /// assert_eq!(normalizador.inverter_ponto(&ponto!([0.5], "?")).caracteristicas, vec![3.0]);
+    /// ```
+    pub fn inverter_ponto(&self, ponto: &Ponto) -> Ponto {
+        let caracteristicas = ponto.caracteristicas.iter()
+            .zip(self.minimos.iter().zip(&self.maximos))
+            .map(|(&valor, (&minimo, &maximo))| minimo + valor * (maximo - minimo))
+            .collect();
+        Ponto::novo(caracteristicas, ponto.rotulo.clone())
+    }
+}
+
+impl Transformador for NormalizadorMinMax {
+    // Aplica (x - min) / (max - min) em cada característica.
+    // Características constantes (max == min) viram 0 em vez de NaN
+    fn transformar_ponto(&self, ponto: &Ponto) -> Ponto {
+        let caracteristicas = ponto.caracteristicas.iter()
+            .zip(self.minimos.iter().zip(&self.maximos))
+            .map(|(&valor, (&minimo, &maximo))| {
+                let amplitude = maximo - minimo;
+                if amplitude == 0.0 { 0.0 } else { (valor - minimo) / amplitude }
+            })
+            .collect();
+        Ponto::novo(caracteristicas, ponto.rotulo.clone())
+    }
+}
+
+/// Padronização (z-score): subtrai a média e divide pelo desvio padrão de cada característica,
+/// deixando o treinamento com média 0 e desvio padrão 1.
+///
+/// Guarda médias e desvios para poder ser salvo junto com o modelo.
+///
+/// ```
+/// use aprendizagem_knn::{ponto, pontos, Padronizador, Transformador};
+///
+/// let padronizador = Padronizador::ajustar(&pontos![[2.0] => "A", [6.0] => "B"]);
+/// assert_eq!(padronizador.transformar_ponto(&ponto!([6.0], "?")).caracteristicas, vec![1.0]);
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Padronizador {
+    /// Média de cada característica no treinamento
+    pub medias: Vec<f64>,
+    /// Desvio padrão (populacional) de cada característica no treinamento
+    pub desvios_padrao: Vec<f64>,
+}
+
+impl Padronizador {
+    /// Calcula média e desvio padrão (populacional) de cada característica.
+    ///
+    /// ```
+    /// use aprendizagem_knn::{pontos, Padronizador};
+    ///
+    /// let padronizador = Padronizador::ajustar(&pontos![[2.0] => "A", [6.0] => "B"]);
+    /// assert_eq!((padronizador.medias[0], padronizador.desvios_padrao[0]), (4.0, 2.0));
+    /// ```
+    pub fn ajustar(dados: &[Ponto]) -> Self {
+        let dimensao = dados.first().map_or(0, |ponto| ponto.caracteristicas.len());
+        let mut estatisticas = EstatisticasIncrementais::nova(dimensao);
+        for ponto in dados {
+            estatisticas.atualizar(&ponto.caracteristicas);
+        }
+
+        Self {
+            desvios_padrao: estatisticas.desvio_padrao(),
+            medias: estatisticas.media,
+        }
+    }
+
+    /// Volta para a escala original: x = z * desvio + media.
+    ///
+    /// ```
+    /// use aprendizagem_knn::{ponto, pontos, Padronizador};
+    ///
+    /// let padronizador = Padronizador::ajustar(&pontos![[2.0] => "A", [6.0] => "B"]);
+    /// assert_eq!(padronizador.inverter_ponto(&ponto!([-1.0], "?")).caracteristicas, vec![2.0]);
+    /// ```
+    pub fn inverter_ponto(&self, ponto: &Ponto) -> Ponto {
+        let caracteristicas = ponto.caracteristicas.iter()
+            .zip(self.medias.iter().zip(&self.desvios_padrao))
+            .map(|(&valor, (&media, &desvio))| media + valor * desvio)
+            .collect();
+        Ponto::novo(caracteristicas, ponto.rotulo.clone())
+    }
+}
+
+impl Transformador for Padronizador {
+    // Aplica (x - media) / desvio. Com desvio zero (característica constante)
+    // a característica fica em 0 em vez de virar NaN ou infinito
+    fn transformar_ponto(&self, ponto: &Ponto) -> Ponto {
+        let caracteristicas = ponto.caracteristicas.iter()
+            .zip(self.medias.iter().zip(&self.desvios_padrao))
+            .map(|(&valor, (&media, &desvio))| {
+                if desvio == 0.0 { 0.0 } else { (valor - media) / desvio }
+            })
+            .collect();
+        Ponto::novo(caracteristicas, ponto.rotulo.clone())
+    }
+}
+
+/// Escalonamento robusto: centraliza pela mediana e divide pelo intervalo interquartil
+/// (IQR = Q3 - Q1).
+///
+/// Mediana e quartis quase não mudam com alguns valores extremos,
+/// então um único outlier não distorce a escala do restante dos dados.
+///
+/// ```
+/// use aprendizagem_knn::{ponto, pontos, EscalonadorRobusto, Transformador};
+///
+/// let treinamento = pontos![[1.0] => "A", [2.0] => "A", [3.0] => "B", [1000.0] => "B"];
+/// let escalonador = EscalonadorRobusto::ajustar(&treinamento);
+/// assert_eq!(escalonador.medianas, vec![2.5]);
+/// assert_eq!(escalonador.transformar_ponto(&ponto!([2.5], "?")).caracteristicas, vec![0.0]);
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EscalonadorRobusto {
+    /// Mediana de cada característica no treinamento
+    pub medianas: Vec<f64>,
+    /// Q3 - Q1 de cada característica no treinamento
+    pub intervalos_interquartis: Vec<f64>,
+}
+
+impl EscalonadorRobusto {
+    /// Calcula a mediana e o intervalo interquartil de cada característica.
+    ///
+    /// ```
+    /// use aprendizagem_knn::{pontos, EscalonadorRobusto};
+    ///
+    /// let escalonador = EscalonadorRobusto::ajustar(&pontos![[0.0] => "A", [4.0] => "B"]);
+    /// assert_eq!(escalonador.intervalos_interquartis, vec![2.0]);
+    /// ```
+    pub fn ajustar(dados: &[Ponto]) -> Self {
+        let dimensao = dados.first().map_or(0, |ponto| ponto.caracteristicas.len());
+        let mut medianas = Vec::with_capacity(dimensao);
+        let mut intervalos_interquartis = Vec::with_capacity(dimensao);
+
+        for i in 0..dimensao {
+            let mut valores: Vec<f64> = dados.iter().map(|ponto| ponto.caracteristicas[i]).collect();
+            valores.sort_by(|a, b| a.total_cmp(b));
+            medianas.push(percentil(&valores, 0.5));
+            intervalos_interquartis.push(percentil(&valores, 0.75) - percentil(&valores, 0.25));
+        }
+
+        Self { medianas, intervalos_interquartis }
+    }
+}
+
+impl Transformador for EscalonadorRobusto {
+    // Aplica (x - mediana) / IQR. Assim como no z-score, IQR zero deixa a característica em 0
+    fn transformar_ponto(&self, ponto: &Ponto) -> Ponto {
+        let caracteristicas = ponto.caracteristicas.iter()
+            .zip(self.medianas.iter().zip(&self.intervalos_interquartis))
+            .map(|(&valor, (&mediana, &iqr))| {
+                if iqr == 0.0 { 0.0 } else { (valor - mediana) / iqr }
+            })
+            .collect();
+        Ponto::novo(caracteristicas, ponto.rotulo.clone())
+    }
+}
+
+/// Valor usado para preencher os ausentes de cada característica.
+///
+/// ```
+/// use aprendizagem_knn::{pontos, EstrategiaImputacao, Imputador};
+///
+/// let dados = pontos![[1.0] => "A", [2.0] => "A", [9.0] => "B"];
+/// assert_eq!(Imputador::ajustar(&dados, EstrategiaImputacao::Media).valores, vec![4.0]);
+/// assert_eq!(Imputador::ajustar(&dados, EstrategiaImputacao::Mediana).valores, vec![2.0]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum EstrategiaImputacao {
+    /// Média dos valores presentes
+    Media,
+    /// Mediana dos valores presentes
+    Mediana,
+}
+
+/// Imputação: troca cada valor ausente (NaN) pela média ou mediana da característica,
+/// calculada apenas com os valores presentes no treinamento.
+///
+/// Uma característica sem nenhum valor presente é preenchida com 0.
+///
+/// ```
+/// use aprendizagem_knn::{ponto, pontos, EstrategiaImputacao, Imputador, Transformador};
+///
+/// let treinamento = pontos![[1.0, f64::NAN] => "A", [3.0, 5.0] => "B"];
+/// let imputador = Imputador::ajustar(&treinamento, EstrategiaImputacao::Media);
+/// let preenchido = imputador.transformar_ponto(&ponto!([f64::NAN, 1.0], "?"));
+/// assert_eq!(preenchido.caracteristicas, vec![2.0, 1.0]);
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Imputador {
+    /// Valor de preenchimento de cada característica
+    pub valores: Vec<f64>,
+}
+
+impl Imputador {
+    /// Calcula o valor de preenchimento de cada característica com a estratégia escolhida.
+    ///
+    /// ```
+    /// use aprendizagem_knn::{pontos, EstrategiaImputacao, Imputador};
+    ///
+    /// let imputador = Imputador::ajustar(&pontos![[f64::NAN] => "A"], EstrategiaImputacao::Media);
+    /// assert_eq!(imputador.valores, vec![0.0]);
+    /// ```
+    pub fn ajustar(dados: &[Ponto], estrategia: EstrategiaImputacao) -> Self {
+        let dimensao = dados.first().map_or(0, |ponto| ponto.caracteristicas.len());
+        let valores = (0..dimensao)
+            .map(|i| {
+                let mut presentes: Vec<f64> = dados.iter()
+                    .map(|ponto| ponto.caracteristicas[i])
+                    .filter(|valor| !valor.is_nan())
+                    .collect();
+                if presentes.is_empty() {
+                    return 0.0;
+                }
+                match estrategia {
+                    EstrategiaImputacao::Media => presentes.iter().sum::<f64>() / presentes.len() as f64,
+                    EstrategiaImputacao::Mediana => {
+                        presentes.sort_by(|a, b| a.total_cmp(b));
+                        percentil(&presentes, 0.5)
+                    }
+                }
+            })
+            .collect();
+
+        Self { valores }
+    }
+}
+
+impl Transformador for Imputador {
+    fn transformar_ponto(&self, ponto: &Ponto) -> Ponto {
+        let caracteristicas = ponto.caracteristicas.iter()
+            .zip(&self.valores)
+            .map(|(&valor, &substituto)| if valor.is_nan() { substituto } else { valor })
+            .collect();
+        Ponto::novo(caracteristicas, ponto.rotulo.clone())
+    }
+}
+
+/// Característica descartada pela seleção por variância.
+///
+/// ```
+/// use aprendizagem_knn::{pontos, SeletorVariancia};
+///
+/// let dados = pontos![[1.0, 5.0] => "A", [3.0, 5.0] => "B"];
+/// let seletor = SeletorVariancia::ajustar(&dados, 0.1, None).unwrap();
+/// assert_eq!(seletor.descartadas[0].indice, 1);
+/// assert_eq!(seletor.descartadas[0].variancia, 0.0);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ColunaDescartada {
+    /// Posição da característica no ponto original
+    pub indice: usize,
+    /// Nome da coluna, presente quando o CSV tinha cabeçalho
+    pub nome: Option<String>,
+    /// Variância da característica no treinamento
+    pub variancia: f64,
+}
+
+/// Seleção de características por variância: descarta as colunas cuja variância no
+/// treinamento é menor que o limiar.
+///
+/// Colunas constantes ou quase constantes só adicionam ruído e custo.
+/// Os índices mantidos são guardados para recortar os pontos de teste igual.
+///
+/// ```
+/// use aprendizagem_knn::{ponto, pontos, SeletorVariancia, Transformador};
+///
+/// let nomes = vec!["x".to_string(), "fixa".to_string()];
+/// let dados = pontos![[1.0, 5.0] => "A", [3.0, 5.0] => "B"];
+/// let seletor = SeletorVariancia::ajustar(&dados, 0.1, Some(&nomes)).unwrap();
+/// assert_eq!(seletor.indices_mantidos, vec![0]);
+/// assert_eq!(seletor.descartadas[0].nome.as_deref(), Some("fixa"));
+/// assert_eq!(seletor.transformar_ponto(&ponto!([2.0, 5.0], "?")).caracteristicas, vec![2.0]);
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeletorVariancia {
+    /// Índices das características mantidas, em ordem
+    pub indices_mantidos: Vec<usize>,
+    /// Características removidas, com o motivo (variância)
+    pub descartadas: Vec<ColunaDescartada>,
+}
+
+impl SeletorVariancia {
+    /// Escolhe as características com variância maior ou igual ao limiar.
+    ///
+    /// `nomes` são os nomes das colunas (do cabeçalho), usados só no relatório.
+    /// É erro se o limiar descartar todas as características.
+    ///
+    /// ```
+    /// use aprendizagem_knn::{pontos, SeletorVariancia};
+    ///
+    /// let dados = pontos![[1.0] => "A", [1.0] => "B"];
+    /// assert!(SeletorVariancia::ajustar(&dados, 0.5, None).is_err());
+    /// ```
+    pub fn ajustar(dados: &[Ponto], limiar: f64, nomes: Option<&[String]>) -> Result<Self, ErroKnn> {
+        let primeiro = dados.first().ok_or(ErroKnn::ConjuntoVazio)?;
+        let mut estatisticas = EstatisticasIncrementais::nova(primeiro.caracteristicas.len());
+        for ponto in dados {
+            estatisticas.atualizar(&ponto.caracteristicas);
+        }
+
+        let mut indices_mantidos = Vec::new();
+        let mut descartadas = Vec::new();
+        for (indice, variancia) in estatisticas.variancia().into_iter().enumerate() {
+            if variancia >= limiar {
+                indices_mantidos.push(indice);
+            } else {
+                let nome = nomes.and_then(|nomes| nomes.get(indice)).cloned();
+                descartadas.push(ColunaDescartada { indice, nome, variancia });
+            }
+        }
+
+        if indices_mantidos.is_empty() {
+            return Err(ErroKnn::ParametroInvalido(format!(
+                "o limiar de variância {} descartaria todas as {} características",
+                limiar,
+                descartadas.len()
+            )));
+        }
+
+        Ok(Self { indices_mantidos, descartadas })
+    }
+}
+
+impl Transformador for SeletorVariancia {
+    fn transformar_ponto(&self, ponto: &Ponto) -> Ponto {
+        let caracteristicas = self.indices_mantidos.iter().map(|&i| ponto.caracteristicas[i]).collect();
+        Ponto::novo(caracteristicas, ponto.rotulo.clone())
+    }
+}
+
+// Variância (populacional) de cada característica, na ordem das colunas
+fn variancias_por_caracteristica(dados: &[Ponto]) -> Vec<f64> {
+    let Some(primeiro) = dados.first() else {
+        return Vec::new();
+    };
+    let mut estatisticas = EstatisticasIncrementais::nova(primeiro.caracteristicas.len());
+    for ponto in dados {
+        estatisticas.atualizar(&ponto.caracteristicas);
+    }
+    estatisticas.variancia()
+}
+
+// Mantém só as colunas de `indices` em cada ponto
+fn recortar_caracteristicas(dados: &[Ponto], indices: &[usize]) -> Vec<Ponto> {
+    dados
+        .iter()
+        .map(|ponto| Ponto::novo(indices.iter().map(|&i| ponto.caracteristicas[i]).collect(), ponto.rotulo.clone()))
+        .collect()
+}
+
+/// Ordena as características da maior para a menor variância.
+///
+/// Devolve pares `(índice da característica, variância)`; em caso de empate, o menor
+/// índice vem primeiro. Um conjunto vazio devolve uma lista vazia.
+///
+/// ```
+/// use aprendizagem_knn::{pontos, rankear_features_por_variancia};
+///
+/// let dados = pontos![[1.0, 0.0, 5.0] => "A", [3.0, 10.0, 5.0] => "B"];
+/// assert_eq!(rankear_features_por_variancia(&dados), vec![(1, 25.0), (0, 1.0), (2, 0.0)]);
+/// ```
+pub fn rankear_features_por_variancia(dados: &[Ponto]) -> Vec<(usize, f64)> {
+    let mut ranking: Vec<(usize, f64)> = variancias_por_caracteristica(dados).into_iter().enumerate().collect();
+    ranking.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+    ranking
+}
+
+/// Mantém só as `n` características de maior variância.
+///
+/// Devolve os pontos recortados e os índices das características mantidas, na ordem
+/// original das colunas (use os índices para recortar os pontos de teste igual).
+/// É erro se `n` for 0 ou maior que o número de características.
+///
+/// ```
+/// use aprendizagem_knn::{pontos, selecionar_top_n_features, ErroKnn};
+///
+/// let dados = pontos![[1.0, 0.0, 5.0] => "A", [3.0, 10.0, 5.0] => "B"];
+/// let (selecionados, indices) = selecionar_top_n_features(&dados, 2).unwrap();
+/// assert_eq!(indices, vec![0, 1]);
+/// assert_eq!(selecionados[1].caracteristicas, vec![3.0, 10.0]);
+///
+/// assert_eq!(selecionar_top_n_features(&dados, 4).unwrap_err(), ErroKnn::KInvalido { k: 4, maximo: 3 });
+/// ```
+pub fn selecionar_top_n_features(dados: &[Ponto], n: usize) -> Result<(Vec<Ponto>, Vec<usize>), ErroKnn> {
+    let ranking = rankear_features_por_variancia(dados);
+    if dados.is_empty() {
+        return Err(ErroKnn::ConjuntoVazio);
+    }
+    if n == 0 || n > ranking.len() {
+        return Err(ErroKnn::KInvalido { k: n, maximo: ranking.len() });
+    }
+
+    let mut indices: Vec<usize> = ranking[..n].iter().map(|&(indice, _)| indice).collect();
+    indices.sort_unstable();
+    Ok((recortar_caracteristicas(dados, &indices), indices))
+}
+
+/// Mantém o menor número de características (das de maior variância para as de menor)
+/// cuja soma das variâncias alcança a `proporcao` da variância total.
+///
+/// `proporcao` deve estar em `[0.0, 1.0]`. Sempre fica ao menos uma característica, mesmo
+/// com `proporcao` 0 ou quando todas as colunas são constantes. Os índices voltam na
+/// ordem original das colunas, como em [`selecionar_top_n_features`].
+///
+/// ```
+/// use aprendizagem_knn::{pontos, selecionar_variancia_acumulada};
+///
+/// // Variâncias 25, 1 e 0: a primeira sozinha já tem 25/26 ≈ 96% do total
+/// let dados = pontos![[1.0, 0.0, 5.0] => "A", [3.0, 10.0, 5.0] => "B"];
+/// assert_eq!(selecionar_variancia_acumulada(&dados, 0.9).unwrap().1, vec![1]);
+/// assert_eq!(selecionar_variancia_acumulada(&dados, 0.99).unwrap().1, vec![0, 1]);
+/// assert!(selecionar_variancia_acumulada(&dados, 1.5).is_err());
+/// ```
+pub fn selecionar_variancia_acumulada(dados: &[Ponto], proporcao: f64) -> Result<(Vec<Ponto>, Vec<usize>), ErroKnn> {
+    if !(0.0..=1.0).contains(&proporcao) {
+        return Err(ErroKnn::ParametroInvalido(format!("a proporção deve estar entre 0 e 1, recebido {}", proporcao)));
+    }
+    if dados.is_empty() {
+        return Err(ErroKnn::ConjuntoVazio);
+    }
+    let ranking = rankear_features_por_variancia(dados);
+    if ranking.is_empty() {
+        return Err(ErroKnn::ParametroInvalido("os pontos não têm características".to_string()));
+    }
+
+    let total: f64 = ranking.iter().map(|&(_, variancia)| variancia).sum();
+    let alvo = proporcao * total;
+    let mut acumulada = 0.0;
+    let mut indices = Vec::new();
+    for &(indice, variancia) in &ranking {
+        indices.push(indice);
+        acumulada += variancia;
+        // Com proporção 1, erros de arredondamento na soma poderiam deixar `acumulada`
+        // um pouco abaixo do total; o laço termina de qualquer jeito na última coluna
+        if acumulada >= alvo {
+            break;
+        }
+    }
+
+    indices.sort_unstable();
+    Ok((recortar_caracteristicas(dados, &indices), indices))
+}
+
+/// Sequência de transformações aplicadas uma após a outra, na ordem em que foram adicionadas.
+///
+/// ```
+/// use aprendizagem_knn::{ponto, pontos, CadeiaTransformadores, Preprocessamento, Transformador};
+///
+/// let treinamento = pontos![[0.0, 7.0] => "A", [10.0, 7.0] => "B"];
+/// let cadeia = CadeiaTransformadores::ajustar(&treinamento, Some(0.1), None, Preprocessamento::MinMax).unwrap();
+/// assert_eq!(cadeia.etapas.len(), 2);
+/// assert_eq!(cadeia.transformar_ponto(&ponto!([5.0, 7.0], "?")).caracteristicas, vec![0.5]);
+/// ```
+pub struct CadeiaTransformadores {
+    /// Transformações, na ordem em que são aplicadas
+    pub etapas: Vec<Box<dyn Transformador>>,
+}
+
+impl CadeiaTransformadores {
+    /// Monta a cadeia padrão: primeiro a seleção por variância (se houver limiar),
+    /// depois o pré-processamento.
+    ///
+    /// A ordem importa: depois de um z-score todas as variâncias valem 1, então a seleção
+    /// precisa olhar a escala original; e o escalonador é ajustado só nas colunas mantidas.
+    ///
+    /// ```
+    /// use aprendizagem_knn::{pontos, CadeiaTransformadores, Preprocessamento};
+    ///
+    /// let treinamento = pontos![[0.0] => "A", [10.0] => "B"];
+    /// let cadeia = CadeiaTransformadores::ajustar(&treinamento, None, None, Preprocessamento::ZScore).unwrap();
+    /// assert_eq!(cadeia.etapas.len(), 1);
+    /// ```
+    pub fn ajustar(
+        treinamento: &[Ponto],
+        limiar_variancia: Option<f64>,
+        nomes: Option<&[String]>,
+        preprocessamento: Preprocessamento,
+    ) -> Result<Self, ErroKnn> {
+        let mut etapas: Vec<Box<dyn Transformador>> = Vec::new();
+        let mut dados = treinamento.to_vec();
+
+        if let Some(limiar) = limiar_variancia {
+            let seletor = SeletorVariancia::ajustar(&dados, limiar, nomes)?;
+            dados = seletor.transformar(&dados);
+            etapas.push(Box::new(seletor));
+        }
+        etapas.push(preprocessamento.ajustar(&dados)?);
+
+        Ok(Self { etapas })
+    }
+}
+
+impl Transformador for CadeiaTransformadores {
+    fn transformar_ponto(&self, ponto: &Ponto) -> Ponto {
+        self.etapas.iter().fold(ponto.clone(), |atual, etapa| etapa.transformar_ponto(&atual))
+    }
+}
+
+/// Escolha do pré-processamento aplicado antes do cálculo das distâncias.
+///
+/// ```
+/// use aprendizagem_knn::{ponto, pontos, Preprocessamento};
+///
+/// let treinamento = pontos![[0.0, 0.0] => "A", [2.0, 200.0] => "B"];
+/// let transformador = Preprocessamento::MinMax.ajustar(&treinamento).unwrap();
+/// assert_eq!(transformador.transformar_ponto(&ponto!([1.0, 100.0], "?")).caracteristicas, vec![0.5, 0.5]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Preprocessamento {
+    /// Usa as características como estão
+    Nenhum,
+    /// Ver [`NormalizadorMinMax`]
+    MinMax,
+    /// Ver [`Padronizador`]
+    ZScore,
+    /// Ver [`EscalonadorRobusto`]
+    Robusto,
+    /// Projeta nos n componentes principais; ver [`PCA`]
+    Pca(usize),
+}
+
+impl Preprocessamento {
+    // Box<dyn Transformador> permite devolver tipos diferentes pela mesma interface
+    /// Aprende os parâmetros no treinamento e devolve o transformador pronto para uso.
+    ///
+    /// ```
+    /// use aprendizagem_knn::{pontos, Preprocessamento};
+    ///
+    /// let treinamento = pontos![[0.0, 1.0] => "A", [2.0, 3.0] => "B"];
+    /// assert!(Preprocessamento::Pca(1).ajustar(&treinamento).is_ok());
+    /// assert!(Preprocessamento::Pca(5).ajustar(&treinamento).is_err());
+    /// ```
+    pub fn ajustar(&self, treinamento: &[Ponto]) -> Result<Box<dyn Transformador>, ErroKnn> {
+        Ok(match self {
+            Preprocessamento::Nenhum => Box::new(SemTransformacao),
+            Preprocessamento::MinMax => Box::new(NormalizadorMinMax::ajustar(treinamento)),
+            Preprocessamento::ZScore => Box::new(Padronizador::ajustar(treinamento)),
+            Preprocessamento::Robusto => Box::new(EscalonadorRobusto::ajustar(treinamento)),
+            Preprocessamento::Pca(n_componentes) => Box::new(PCA::ajustar(treinamento, *n_componentes)?),
+        })
+    }
+}
+
+/// Classifica vários pontos aplicando o pré-processamento escolhido.
+///
+/// O transformador é ajustado UMA vez no treinamento e reutilizado em todos os pontos de teste.
+///
+/// ```
+/// use aprendizagem_knn::{knn_preprocessado, pontos, Preprocessamento};
+///
+/// let treinamento = pontos![[0.0, 0.0] => "A", [1.0, 1000.0] => "B"];
+/// let testes = pontos![[0.9, 200.0] => "?"];
+/// let previstos = knn_preprocessado(&treinamento, &testes, 1, Preprocessamento::MinMax).unwrap();
+/// assert_eq!(previstos, vec!["B"]);
+/// ```
+pub fn knn_preprocessado(
+    treinamento: &[Ponto],
+    pontos_teste: &[Ponto],
+    k: usize,
+    preprocessamento: Preprocessamento,
+) -> Result<Vec<String>, ErroKnn> {
+    let transformador = preprocessamento.ajustar(treinamento)?;
+    let treinamento_transformado = transformador.transformar(treinamento);
+    let teste_transformado = transformador.transformar(pontos_teste);
+    knn_batch(&treinamento_transformado, &teste_transformado, k)
+}
+
+/// Como tratar valores ausentes na hora de classificar.
+///
+/// ```
+/// use aprendizagem_knn::{EstrategiaImputacao, PoliticaAusentes};
+///
+/// let politica = PoliticaAusentes::Imputar(EstrategiaImputacao::Mediana);
+/// assert_ne!(politica, PoliticaAusentes::DistanciaParcial);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PoliticaAusentes {
+    /// Preenche os ausentes antes do KNN
+    Imputar(EstrategiaImputacao),
+    /// Calcula a distância só nas dimensões presentes
+    DistanciaParcial,
+}
+
+/// Classifica pontos que podem ter valores ausentes (NaN) segundo a política escolhida.
+///
+/// Na imputação, os valores de preenchimento vêm só do treinamento. Os erros são os de
+/// [`knn`](crate::knn()).
+///
+/// ```
+/// use aprendizagem_knn::{knn_com_ausentes, pontos, PoliticaAusentes};
+///
+/// let treinamento = pontos![[0.0, 0.0] => "A", [10.0, 10.0] => "B"];
+/// let testes = pontos![[9.0, f64::NAN] => "?"];
+/// assert_eq!(knn_com_ausentes(&treinamento, &testes, 1, PoliticaAusentes::DistanciaParcial).unwrap(), vec!["B"]);
+/// ```
+pub fn knn_com_ausentes(
+    treinamento: &[Ponto],
+    pontos_teste: &[Ponto],
+    k: usize,
+    politica: PoliticaAusentes,
+) -> Result<Vec<String>, ErroKnn> {
+    match politica {
+        PoliticaAusentes::Imputar(estrategia) => {
+            let imputador = Imputador::ajustar(treinamento, estrategia);
+            knn_batch(&imputador.transformar(treinamento), &imputador.transformar(pontos_teste), k)
+        }
+        PoliticaAusentes::DistanciaParcial => pontos_teste.iter()
+            .map(|ponto| knn_com_metrica(treinamento, ponto, k, &DistanciaMetrica::EuclidianaParcial))
+            .collect(),
+    }
+}
+
+// ==================== RECOMENDAÇÃO DE NORMALIZAÇÃO ====================
+/// Tipo de normalização sugerido por [`recomendar_normalizacao`] para uma característica.
+///
+/// Pode ser convertido no [`Preprocessamento`] correspondente:
+///
+/// ```
+/// use aprendizagem_knn::{MetodoNormalizacao, Preprocessamento};
+///
+/// assert_eq!(Preprocessamento::from(MetodoNormalizacao::ZScore), Preprocessamento::ZScore);
+/// assert_eq!(MetodoNormalizacao::MinMax.to_string(), "Min-Max");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MetodoNormalizacao {
+    /// Ver [`NormalizadorMinMax`]
+    MinMax,
+    /// Ver [`Padronizador`]
+    ZScore,
+    /// A característica é constante: não há escala para ajustar
+    NenhumNecessario,
+}
+
+impl fmt::Display for MetodoNormalizacao {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            MetodoNormalizacao::MinMax => "Min-Max",
+            MetodoNormalizacao::ZScore => "Z-score",
+            MetodoNormalizacao::NenhumNecessario => "nenhuma",
+        })
+    }
+}
+
+impl From<MetodoNormalizacao> for Preprocessamento {
+    fn from(metodo: MetodoNormalizacao) -> Self {
+        match metodo {
+            MetodoNormalizacao::MinMax => Preprocessamento::MinMax,
+            MetodoNormalizacao::ZScore => Preprocessamento::ZScore,
+            MetodoNormalizacao::NenhumNecessario => Preprocessamento::Nenhum,
+        }
+    }
+}
+
+/// Resumo da forma de uma característica e a normalização recomendada para ela.
+///
+/// É devolvido por [`recomendar_normalizacao`], um por característica.
+///
+/// ```
+/// use aprendizagem_knn::{pontos, recomendar_normalizacao, MetodoNormalizacao};
+///
+/// let dados = pontos![[1.0, 5.0] => "A", [2.0, 5.0] => "A", [3.0, 5.0] => "B"];
+/// let recomendacoes = recomendar_normalizacao(&dados);
+/// assert_eq!((recomendacoes[0].indice_feature, recomendacoes[0].amplitude), (0, 2.0));
+/// assert_eq!(recomendacoes[0].media, 2.0);
+/// // A segunda característica é constante
+/// assert_eq!(recomendacoes[1].metodo_recomendado, MetodoNormalizacao::NenhumNecessario);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecomendacaoNormalizacao {
+    /// Posição da característica no vetor de características
+    pub indice_feature: usize,
+    /// Maior valor menos o menor
+    pub amplitude: f64,
+    /// Média dos valores
+    pub media: f64,
+    /// Assimetria (coeficiente de Fisher): 0 numa distribuição simétrica, positiva quando a
+    /// cauda da direita é mais longa e negativa quando é a da esquerda
+    pub assimetria: f64,
+    /// Normalização sugerida
+    pub metodo_recomendado: MetodoNormalizacao,
+}
+
+// Acima destes limites a característica deixa de parecer uma normal bem comportada
+const RAZAO_AMPLITUDE_MAXIMA: f64 = 100.0;
+const ASSIMETRIA_MAXIMA: f64 = 2.0;
+
+/// Analisa cada característica (amplitude, média e assimetria) e recomenda como
+/// normalizá-la antes do KNN.
+///
+/// A regra é simples:
+/// - característica constante (amplitude 0): [`MetodoNormalizacao::NenhumNecessario`];
+/// - amplitude mais de 100 vezes maior que a mediana das amplitudes de todas as
+///   características: [`MetodoNormalizacao::MinMax`], que põe tudo em [0, 1];
+/// - assimetria com módulo acima de 2 (distribuição longe de uma normal): também
+///   [`MetodoNormalizacao::MinMax`], já que média e desvio padrão descrevem mal esses dados;
+/// - nos demais casos: [`MetodoNormalizacao::ZScore`].
+///
+/// Valores ausentes (NaN) são ignorados. Um conjunto vazio devolve uma lista vazia.
+/// Para ver o resultado como tabela, use [`imprimir_recomendacoes_normalizacao`].
+///
+/// ```
+/// use aprendizagem_knn::{recomendar_normalizacao, MetodoNormalizacao, Ponto};
+///
+/// // Coluna 0: de 0 a 9; coluna 1: quase sempre 1, com um valor enorme; coluna 2: constante
+/// let dados: Vec<Ponto> = (0..10)
+///     .map(|i| Ponto::novo(vec![i as f64, if i == 9 { 50.0 } else { 1.0 }, 3.0], "A".to_string()))
+///     .collect();
+/// let recomendacoes = recomendar_normalizacao(&dados);
+///
+/// assert_eq!(recomendacoes[0].amplitude, 9.0);
+/// assert_eq!(recomendacoes[0].media, 4.5);
+/// assert_eq!(recomendacoes[0].assimetria, 0.0);
+/// assert_eq!(recomendacoes[0].metodo_recomendado, MetodoNormalizacao::ZScore);
+/// assert!(recomendacoes[1].assimetria > 2.0);
+/// assert_eq!(recomendacoes[1].metodo_recomendado, MetodoNormalizacao::MinMax);
+/// assert_eq!(recomendacoes[2].metodo_recomendado, MetodoNormalizacao::NenhumNecessario);
+/// ```
+pub fn recomendar_normalizacao(dados: &[Ponto]) -> Vec<RecomendacaoNormalizacao> {
+    let dimensao = dados.first().map_or(0, |ponto| ponto.caracteristicas.len());
+    let mut resumos: Vec<(f64, f64, f64)> = (0..dimensao)
+        .map(|indice| {
+            let valores: Vec<f64> = dados.iter()
+                .map(|ponto| ponto.caracteristicas[indice])
+                .filter(|valor| !valor.is_nan())
+                .collect();
+            resumir_caracteristica(&valores)
+        })
+        .collect();
+
+    let mut amplitudes: Vec<f64> = resumos.iter().map(|&(amplitude, _, _)| amplitude).collect();
+    amplitudes.sort_by(f64::total_cmp);
+    let amplitude_mediana = if amplitudes.is_empty() { 0.0 } else { percentil(&amplitudes, 0.5) };
+
+    resumos.drain(..)
+        .enumerate()
+        .map(|(indice_feature, (amplitude, media, assimetria))| {
+            let metodo_recomendado = if amplitude == 0.0 {
+                MetodoNormalizacao::NenhumNecessario
+            } else if amplitude > RAZAO_AMPLITUDE_MAXIMA * amplitude_mediana || assimetria.abs() > ASSIMETRIA_MAXIMA {
+                MetodoNormalizacao::MinMax
+            } else {
+                MetodoNormalizacao::ZScore
+            };
+            RecomendacaoNormalizacao { indice_feature, amplitude, media, assimetria, metodo_recomendado }
+        })
+        .collect()
+}
+
+// (amplitude, média, assimetria) dos valores; tudo 0 se não houver valores
+fn resumir_caracteristica(valores: &[f64]) -> (f64, f64, f64) {
+    if valores.is_empty() {
+        return (0.0, 0.0, 0.0);
+    }
+    let n = valores.len() as f64;
+    let minimo = valores.iter().copied().fold(f64::INFINITY, f64::min);
+    let maximo = valores.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let media = valores.iter().sum::<f64>() / n;
+    // Momentos centrais de ordem 2 e 3; a assimetria é m3 / m2^(3/2)
+    let m2 = valores.iter().map(|valor| (valor - media).powi(2)).sum::<f64>() / n;
+    let m3 = valores.iter().map(|valor| (valor - media).powi(3)).sum::<f64>() / n;
+    let assimetria = if m2 > 0.0 { m3 / m2.powf(1.5) } else { 0.0 };
+    (maximo - minimo, media, assimetria)
+}
+
+/// Monta a tabela das recomendações, uma característica por linha, com os nomes das
+/// colunas na primeira.
+///
+/// ```
+/// use aprendizagem_knn::{pontos, recomendar_normalizacao, tabela_recomendacoes_normalizacao};
+///
+/// let dados = pontos![[1.0, 5.0] => "A", [3.0, 5.0] => "B"];
+/// let tabela = tabela_recomendacoes_normalizacao(&recomendar_normalizacao(&dados));
+/// let linhas: Vec<&str> = tabela.lines().collect();
+/// assert_eq!(linhas[0], "característica   amplitude       média  assimetria  recomendação");
+/// assert_eq!(linhas[1], "             0      2.0000      2.0000      0.0000  Z-score");
+/// assert_eq!(linhas[2], "             1      0.0000      5.0000      0.0000  nenhuma");
+/// ```
+pub fn tabela_recomendacoes_normalizacao(recomendacoes: &[RecomendacaoNormalizacao]) -> String {
+    let mut tabela = format!(
+        "{:>14}  {:>10}  {:>10}  {:>10}  {}",
+        "característica", "amplitude", "média", "assimetria", "recomendação"
+    );
+    for recomendacao in recomendacoes {
+        tabela.push_str(&format!(
+            "\n{:>14}  {:>10.4}  {:>10.4}  {:>10.4}  {}",
+            recomendacao.indice_feature,
+            recomendacao.amplitude,
+            recomendacao.media,
+            recomendacao.assimetria,
+            recomendacao.metodo_recomendado
+        ));
+    }
+    tabela
+}
+
+/// Mostra no terminal a tabela de [`tabela_recomendacoes_normalizacao`].
+///
+/// ```
+/// use aprendizagem_knn::{imprimir_recomendacoes_normalizacao, pontos, recomendar_normalizacao};
+///
+/// // característica   amplitude       média  assimetria  recomendação
+/// //              0      2.0000      2.0000      0.0000  Z-score
+/// imprimir_recomendacoes_normalizacao(&recomendar_normalizacao(&pontos![[1.0] => "A", [3.0] => "B"]));
+/// ```
+pub fn imprimir_recomendacoes_normalizacao(recomendacoes: &[RecomendacaoNormalizacao]) {
+    println!("{}", tabela_recomendacoes_normalizacao(recomendacoes));
+}
@@ -0,0 +1,139 @@
+//! Regiões de decisão do KNN em conjuntos com duas características, para visualização.
+
+use std::fs::File;
+
+use csv::Writer;
+
+use crate::erro::ErroKnn;
+use crate::knn::knn;
+use crate::ponto::Ponto;
+
+// ==================== REGIÕES DE DECISÃO ====================
+// Com duas características, cada ponto do plano recebe a classe que o KNN daria a ele.
+// Classificar o centro de cada célula de uma grade mostra essas regiões e as fronteiras
+// entre elas: é ali que a escolha do k (e os pontos ruidosos) fica visível. A grade pode
+// ser desenhada como um mapa de calor (matplotlib, ggplot2, uma planilha...).
+
+/// Classifica com o [`knn`](fn@crate::knn) o centro de cada célula de uma grade de
+/// `resolucao × resolucao` células sobre o retângulo `[x_min, x_max] × [y_min, y_max]`.
+///
+/// Devolve uma linha da grade por faixa de `y`, de `y_min` para `y_max`, e em cada linha
+/// uma coluna por faixa de `x`, de `x_min` para `x_max`: `grade[i][j]` é a classe prevista
+/// para o ponto `(x_min + (j + 0.5) * largura, y_min + (i + 0.5) * altura)`, com
+/// `largura = (x_max - x_min) / resolucao` e `altura = (y_max - y_min) / resolucao`.
+///
+/// Todos os pontos de treinamento precisam ter exatamente duas características; se algum
+/// não tiver, o erro é [`ErroKnn::DimensaoIncompativel`]. Uma `resolucao` zero ou um
+/// intervalo vazio ou não finito (`x_min >= x_max`, por exemplo) é
+/// [`ErroKnn::ParametroInvalido`], e os erros do [`knn`](fn@crate::knn) (treinamento
+/// vazio, `k = 0`) são repassados.
+///
+/// ```
+/// use aprendizagem_knn::{exportar_regioes_decisao, pontos, ErroKnn};
+///
+/// // A classe muda na metade do eixo x
+/// let treinamento = pontos![[0.0, 0.0] => "A", [0.0, 4.0] => "A", [4.0, 0.0] => "B", [4.0, 4.0] => "B"];
+/// let grade = exportar_regioes_decisao(&treinamento, 1, 0.0, 4.0, 0.0, 4.0, 4).unwrap();
+/// assert_eq!(grade.len(), 4);
+/// for linha in &grade {
+///     assert_eq!(linha, &vec!["A", "A", "B", "B"]);
+/// }
+///
+/// let tres = pontos![[0.0, 0.0, 1.0] => "A"];
+/// let erro = exportar_regioes_decisao(&tres, 1, 0.0, 1.0, 0.0, 1.0, 2).unwrap_err();
+/// assert_eq!(erro, ErroKnn::DimensaoIncompativel { esperado: 2, obtido: 3 });
+/// ```
+pub fn exportar_regioes_decisao(
+    treinamento: &[Ponto],
+    k: usize,
+    x_min: f64,
+    x_max: f64,
+    y_min: f64,
+    y_max: f64,
+    resolucao: usize,
+) -> Result<Vec<Vec<String>>, ErroKnn> {
+    if let Some(ponto) = treinamento.iter().find(|ponto| ponto.caracteristicas.len() != 2) {
+        return Err(ErroKnn::DimensaoIncompativel { esperado: 2, obtido: ponto.caracteristicas.len() });
+    }
+    if resolucao == 0 {
+        return Err(ErroKnn::ParametroInvalido("a resolução da grade deve ser maior que zero".to_string()));
+    }
+    validar_intervalo("x", x_min, x_max)?;
+    validar_intervalo("y", y_min, y_max)?;
+
+    (0..resolucao)
+        .map(|i| {
+            let y = centro_da_celula(y_min, y_max, resolucao, i);
+            (0..resolucao)
+                .map(|j| {
+                    let x = centro_da_celula(x_min, x_max, resolucao, j);
+                    knn(treinamento, &Ponto::novo(vec![x, y], String::new()), k)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Grava em `caminho` uma grade de [`exportar_regioes_decisao`] como CSV, com as colunas
+/// `x,y,rotulo`: uma linha por célula, com as coordenadas do centro dela e a classe prevista.
+///
+/// As linhas seguem a ordem da grade (cada faixa de `y`, de baixo para cima, e nela cada
+/// `x`, da esquerda para a direita). O retângulo precisa ser o mesmo usado para calcular a
+/// grade; um intervalo vazio ou não finito é [`ErroKnn::ParametroInvalido`], e uma falha
+/// ao criar ou gravar o arquivo é [`ErroKnn::Arquivo`].
+///
+/// ```
+/// use aprendizagem_knn::{exportar_regioes_csv, exportar_regioes_decisao, pontos};
+///
+/// let treinamento = pontos![[0.0, 0.0] => "A", [4.0, 0.0] => "B"];
+/// let grade = exportar_regioes_decisao(&treinamento, 1, 0.0, 4.0, 0.0, 2.0, 2).unwrap();
+///
+/// let caminho = std::env::temp_dir().join("aprendizagem_knn_doc_regioes.csv");
+/// let caminho = caminho.to_str().unwrap();
+/// exportar_regioes_csv(&grade, 0.0, 4.0, 0.0, 2.0, caminho).unwrap();
+/// assert_eq!(
+///     std::fs::read_to_string(caminho).unwrap(),
+///     "x,y,rotulo\n1,0.5,A\n3,0.5,B\n1,1.5,A\n3,1.5,B\n"
+/// );
+/// ```
+pub fn exportar_regioes_csv(
+    grade: &[Vec<String>],
+    x_min: f64,
+    x_max: f64,
+    y_min: f64,
+    y_max: f64,
+    caminho: &str,
+) -> Result<(), ErroKnn> {
+    validar_intervalo("x", x_min, x_max)?;
+    validar_intervalo("y", y_min, y_max)?;
+
+    let arquivo = File::create(caminho).map_err(|erro| ErroKnn::de_io(caminho, &erro))?;
+    let erro_csv = |erro: csv::Error| ErroKnn::Arquivo(format!("{}: {}", caminho, erro));
+    let mut escritor = Writer::from_writer(arquivo);
+    escritor.write_record(["x", "y", "rotulo"]).map_err(erro_csv)?;
+    for (i, linha) in grade.iter().enumerate() {
+        let y = centro_da_celula(y_min, y_max, grade.len(), i);
+        for (j, rotulo) in linha.iter().enumerate() {
+            let x = centro_da_celula(x_min, x_max, linha.len(), j);
+            escritor.write_record([x.to_string(), y.to_string(), rotulo.clone()]).map_err(erro_csv)?;
+        }
+    }
+    escritor.flush().map_err(|erro| ErroKnn::de_io(caminho, &erro))
+}
+
+// O intervalo de cada eixo precisa ter largura positiva e finita
+fn validar_intervalo(eixo: &str, minimo: f64, maximo: f64) -> Result<(), ErroKnn> {
+    if minimo.is_finite() && maximo.is_finite() && minimo < maximo {
+        Ok(())
+    } else {
+        Err(ErroKnn::ParametroInvalido(format!(
+            "o intervalo de {} deve ter mínimo menor que o máximo, ambos finitos (recebido {} a {})",
+            eixo, minimo, maximo
+        )))
+    }
+}
+
+// Centro da célula `i` quando [minimo, maximo] é dividido em `n` partes iguais
+fn centro_da_celula(minimo: f64, maximo: f64, n: usize, i: usize) -> f64 {
+    minimo + (i as f64 + 0.5) * (maximo - minimo) / n as f64
+}
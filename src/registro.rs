@@ -0,0 +1,100 @@
+//! Registro (log) das previsões em um arquivo JSONL, para auditoria e depuração.
+
+use std::fs::{self, File};
+use std::io::Write;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde_json::{json, Value};
+
+use crate::distancia::DistanciaMetrica;
+use crate::erro::ErroKnn;
+use crate::knn::{k_vizinhos_mais_proximos, votar_por_maioria};
+use crate::ponto::Ponto;
+
+// ==================== LOG DE PREVISÕES ====================
+// JSONL (JSON Lines): um objeto JSON completo por linha. Cada previsão vira uma linha
+// nova no fim do arquivo, então o log pode crescer para sempre sem ser reescrito e
+// uma linha corrompida não estraga as demais.
+
+/// Classifica `ponto_teste` como o [`knn`](fn@crate::knn) e acrescenta uma linha JSON ao
+/// `arquivo_log` descrevendo a previsão.
+///
+/// A linha tem o instante (`timestamp`, segundos desde 1970 no padrão Unix), as
+/// `caracteristicas` do ponto, o `rotulo_previsto`, a `confianca` (fração dos vizinhos que
+/// votaram no rótulo previsto) e os `vizinhos`, do mais próximo ao mais distante, cada um
+/// com `indice` no treinamento, `rotulo` e `distancia`.
+///
+/// Abra o arquivo em modo de acréscimo (`OpenOptions::new().create(true).append(true)`),
+/// para que as linhas antigas sejam preservadas. Uma falha ao escrever o log não impede a
+/// previsão: ela é avisada na saída de erro e o rótulo é devolvido normalmente.
+///
+/// ```
+/// use std::fs::OpenOptions;
+/// use aprendizagem_knn::{carregar_log, knn_com_log, ponto, pontos};
+///
+/// let caminho = std::env::temp_dir().join("aprendizagem_knn_doc_log.jsonl");
+/// let _ = std::fs::remove_file(&caminho);
+/// let mut arquivo = OpenOptions::new().create(true).append(true).open(&caminho).unwrap();
+///
+/// let treinamento = pontos![[0.0] => "A", [1.0] => "A", [9.0] => "B"];
+/// assert_eq!(knn_com_log(&treinamento, &ponto!([0.4], "?"), 3, &mut arquivo), "A");
+/// assert_eq!(knn_com_log(&treinamento, &ponto!([8.0], "?"), 1, &mut arquivo), "B");
+///
+/// let registros = carregar_log(caminho.to_str().unwrap()).unwrap();
+/// assert_eq!(registros.len(), 2);
+/// assert_eq!(registros[0]["rotulo_previsto"], "A");
+/// assert_eq!(registros[0]["confianca"], 2.0 / 3.0);
+/// assert_eq!(registros[0]["vizinhos"][0]["indice"], 0);
+/// ```
+pub fn knn_com_log(treinamento: &[Ponto], ponto_teste: &Ponto, k: usize, arquivo_log: &mut File) -> String {
+    let vizinhos = k_vizinhos_mais_proximos(treinamento, ponto_teste, k, &DistanciaMetrica::Euclidiana);
+    let rotulo_previsto = votar_por_maioria(vizinhos.iter().map(|vizinho| vizinho.rotulo.clone()));
+    let votos = vizinhos.iter().filter(|vizinho| vizinho.rotulo == rotulo_previsto).count();
+
+    // Um relógio antes de 1970 é um erro de configuração; nesse caso o instante fica 0
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |duracao| duracao.as_secs());
+    let registro = json!({
+        "timestamp": timestamp,
+        "caracteristicas": ponto_teste.caracteristicas,
+        "rotulo_previsto": rotulo_previsto,
+        "confianca": votos as f64 / vizinhos.len() as f64,
+        "vizinhos": vizinhos.iter()
+            .map(|vizinho| json!({
+                "indice": vizinho.indice,
+                "rotulo": vizinho.rotulo,
+                "distancia": vizinho.distancia,
+            }))
+            .collect::<Vec<Value>>(),
+    });
+
+    // A linha inteira é escrita de uma vez, para não se misturar com a de outro processo
+    if let Err(erro) = arquivo_log.write_all(format!("{}\n", registro).as_bytes()) {
+        log::warn!("não foi possível registrar a previsão no log: {}", erro);
+    }
+    rotulo_previsto
+}
+
+/// Lê de volta um log gravado por [`knn_com_log`]: um valor JSON por linha não vazia.
+///
+/// Devolve [`ErroKnn::Arquivo`] se o arquivo não puder ser lido ou se alguma linha não for
+/// JSON válido (a mensagem cita o número da linha).
+///
+/// ```
+/// use aprendizagem_knn::{carregar_log, ErroKnn};
+///
+/// let caminho = std::env::temp_dir().join("aprendizagem_knn_doc_log_invalido.jsonl");
+/// std::fs::write(&caminho, "{\"rotulo_previsto\": \"A\"}\nisto não é json\n").unwrap();
+/// assert!(matches!(carregar_log(caminho.to_str().unwrap()), Err(ErroKnn::Arquivo(_))));
+/// ```
+pub fn carregar_log(caminho: &str) -> Result<Vec<Value>, ErroKnn> {
+    let conteudo = fs::read_to_string(caminho).map_err(|erro| ErroKnn::de_io(caminho, &erro))?;
+
+    conteudo.lines()
+        .enumerate()
+        .filter(|(_, linha)| !linha.trim().is_empty())
+        .map(|(numero, linha)| {
+            serde_json::from_str(linha)
+                .map_err(|erro| ErroKnn::Arquivo(format!("{}: linha {}: {}", caminho, numero + 1, erro)))
+        })
+        .collect()
+}
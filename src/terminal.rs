@@ -0,0 +1,86 @@
+//! Limpeza da tela do terminal, usada pela linha de comando (feature `cli`).
+//!
+//! A tela é limpa com sequências de escape ANSI escritas na própria saída, sem executar
+//! `clear` ou `cls` (que podem não existir, como num contêiner mínimo). Quando a saída não é
+//! um terminal (um pipe ou um arquivo), nada é escrito: os códigos de escape só sujariam o
+//! texto gravado.
+
+use std::io::{self, IsTerminal, Write};
+
+// ==================== LIMPEZA DA TELA ====================
+// \x1b[2J apaga a tela inteira e \x1b[H leva o cursor para o canto superior esquerdo;
+// são entendidos por praticamente todos os terminais (no Windows 10 em diante, depois de
+// ligar o modo de terminal virtual do console)
+const SEQUENCIA_LIMPAR: &[u8] = b"\x1b[2J\x1b[H";
+
+/// Limpa a tela se a saída padrão for um terminal; se não for, não faz nada.
+///
+/// Devolve `true` se a tela foi limpa. Nunca entra em pânico: um erro ao escrever (como
+/// um terminal que fechou) só faz a função devolver `false`.
+///
+/// ```
+/// use aprendizagem_knn::limpar_terminal;
+///
+/// // Nos testes a saída é capturada, então não é um terminal e nada é escrito
+/// assert!(!limpar_terminal());
+/// ```
+pub fn limpar_terminal() -> bool {
+    let mut saida = io::stdout().lock();
+    let terminal = saida.is_terminal() && habilitar_sequencias_ansi();
+    limpar_se_terminal(&mut saida, terminal).unwrap_or(false)
+}
+
+/// Escreve em `saida` a sequência ANSI que limpa a tela, mas só se `terminal` for `true`.
+///
+/// É o passo de [`limpar_terminal`] que não depende de um terminal de verdade, o que
+/// permite conferir o que é escrito em cada caso. Devolve se a sequência foi escrita, ou o
+/// erro de escrita.
+///
+/// ```
+/// use aprendizagem_knn::limpar_se_terminal;
+///
+/// let mut capturada = Vec::new();
+/// assert!(!limpar_se_terminal(&mut capturada, false).unwrap());
+/// assert!(capturada.is_empty());
+///
+/// let mut tela = Vec::new();
+/// assert!(limpar_se_terminal(&mut tela, true).unwrap());
+/// assert_eq!(tela, b"\x1b[2J\x1b[H");
+/// ```
+pub fn limpar_se_terminal<W: Write>(saida: &mut W, terminal: bool) -> io::Result<bool> {
+    if !terminal {
+        return Ok(false);
+    }
+    saida.write_all(SEQUENCIA_LIMPAR)?;
+    saida.flush()?;
+    Ok(true)
+}
+
+// O console do Windows só interpreta as sequências ANSI com o modo de terminal virtual
+// ligado. Se não der para ligá-lo (um console antigo, ou a saída não é um console), a
+// tela não é limpa, em vez de aparecerem os códigos na tela
+#[cfg(windows)]
+fn habilitar_sequencias_ansi() -> bool {
+    use windows_sys::Win32::System::Console::{
+        GetConsoleMode, GetStdHandle, SetConsoleMode, CONSOLE_MODE, ENABLE_VIRTUAL_TERMINAL_PROCESSING,
+        STD_OUTPUT_HANDLE,
+    };
+
+    // SAFETY: as funções só leem e alteram o modo do console da saída padrão, e `modo` é
+    // uma variável local válida durante a chamada
+    unsafe {
+        let console = GetStdHandle(STD_OUTPUT_HANDLE);
+        let mut modo: CONSOLE_MODE = 0;
+        if GetConsoleMode(console, &mut modo) == 0 {
+            return false;
+        }
+        modo & ENABLE_VIRTUAL_TERMINAL_PROCESSING != 0
+            || SetConsoleMode(console, modo | ENABLE_VIRTUAL_TERMINAL_PROCESSING) != 0
+    }
+}
+
+// Nos outros sistemas os terminais já entendem as sequências
+#[cfg(not(windows))]
+fn habilitar_sequencias_ansi() -> bool {
+    true
+}
@@ -0,0 +1,1328 @@
+//! Divisão dos dados para avaliar o modelo em pontos que ele não viu no treinamento.
+
+use std::error::Error;
+use std::fmt;
+use std::fs::OpenOptions;
+use std::io::Write;
+
+use csv::Writer;
+
+use crate::aleatorio::Rng;
+use crate::analise::contagem_ordenada;
+use crate::confusao::MatrizConfusao;
+use crate::distancia::{distancia_euclidiana, DistanciaMetrica};
+use crate::erro::ErroKnn;
+use crate::estatisticas::EstatisticasIncrementais;
+use crate::knn::{erro_sem_vizinhos, k_vizinhos_mais_proximos, knn_sobre_com_votacao, votar_por_maioria, Votacao};
+use crate::metricas::RelatorioClassificacao;
+use crate::ponto::Ponto;
+
+// ==================== DIVISÃO TREINO/TESTE ====================
+/// Separa os dados em treinamento e teste, sorteando uma fração `fracao_teste` dos pontos
+/// para o teste.
+///
+/// Com `estratificado`, o sorteio é feito dentro de cada classe, para que treino e teste
+/// mantenham as proporções das classes do conjunto completo (tanto quanto contagens inteiras
+/// permitem: cada classe manda `fracao_teste * n_classe` pontos, arredondado, para o teste).
+/// Cada lado fica com ao menos um ponto de cada classe que tenha 2 ou mais pontos; uma
+/// classe com um único ponto fica inteira no treinamento, já que o KNN só pode prever
+/// classes que viu.
+///
+/// Sem estratificação, `fracao_teste * n` pontos (arredondado, e no mínimo 1 de cada lado
+/// quando há ao menos 2 pontos) são sorteados do conjunto inteiro.
+///
+/// Os dois conjuntos mantêm a ordem original dos pontos e a mesma `semente` gera sempre a
+/// mesma divisão. É erro se `fracao_teste` não estiver em (0, 1) ou se `dados` for vazio.
+///
+/// ```
+/// use aprendizagem_knn::{contar_rotulos, dividir_treino_teste, Ponto};
+///
+/// // 80 pontos de A e 20 de B
+/// let dados: Vec<Ponto> = (0..100)
+///     .map(|i| Ponto::novo(vec![i as f64], if i < 80 { "A" } else { "B" }.to_string()))
+///     .collect();
+///
+/// let (treino, teste) = dividir_treino_teste(dados.clone(), 0.25, 7, true).unwrap();
+/// assert_eq!((treino.len(), teste.len()), (75, 25));
+/// // a proporção 80/20 se mantém no teste
+/// assert_eq!((contar_rotulos(&teste)["A"], contar_rotulos(&teste)["B"]), (20, 5));
+///
+/// // treino e teste são disjuntos e juntos formam os dados
+/// let mut valores: Vec<f64> = treino.iter().chain(&teste).map(|p| p.caracteristicas[0]).collect();
+/// valores.sort_by(f64::total_cmp);
+/// assert_eq!(valores, (0..100).map(|i| i as f64).collect::<Vec<_>>());
+///
+/// // a mesma semente repete a divisão
+/// let (_, de_novo) = dividir_treino_teste(dados, 0.25, 7, true).unwrap();
+/// assert!(teste.iter().zip(&de_novo).all(|(a, b)| a.caracteristicas == b.caracteristicas));
+/// ```
+pub fn dividir_treino_teste(
+    dados: Vec<Ponto>,
+    fracao_teste: f64,
+    semente: u64,
+    estratificado: bool,
+) -> Result<(Vec<Ponto>, Vec<Ponto>), ErroKnn> {
+    if !(fracao_teste > 0.0 && fracao_teste < 1.0) {
+        return Err(ErroKnn::ParametroInvalido(format!(
+            "fracao_teste deve estar em (0.0, 1.0) (recebido {})",
+            fracao_teste
+        )));
+    }
+    if dados.is_empty() {
+        return Err(ErroKnn::ConjuntoVazio);
+    }
+
+    let vai_para_teste = sortear_teste(&dados, fracao_teste, semente, estratificado);
+
+    // into_iter move os pontos para os dois lados, sem copiar
+    let mut treino = Vec::with_capacity(dados.len());
+    let mut teste = Vec::new();
+    for (ponto, para_teste) in dados.into_iter().zip(vai_para_teste) {
+        if para_teste {
+            teste.push(ponto);
+        } else {
+            treino.push(ponto);
+        }
+    }
+    Ok((treino, teste))
+}
+
+// Marca quais pontos vão para o teste (veja dividir_treino_teste); a fração já foi validada
+fn sortear_teste(dados: &[Ponto], fracao_teste: f64, semente: u64, estratificado: bool) -> Vec<bool> {
+    let mut rng = Rng::novo(semente);
+    let mut vai_para_teste = vec![false; dados.len()];
+
+    if estratificado {
+        // As classes são percorridas em ordem alfabética para que a semente dê sempre o mesmo resultado
+        for (rotulo, quantidade) in contagem_ordenada(dados) {
+            let indices_classe: Vec<usize> = dados.iter()
+                .enumerate()
+                .filter(|(_, ponto)| ponto.rotulo == rotulo)
+                .map(|(indice, _)| indice)
+                .collect();
+            for posicao in rng.amostrar_indices(quantidade, tamanho_teste(quantidade, fracao_teste)) {
+                vai_para_teste[indices_classe[posicao]] = true;
+            }
+        }
+    } else {
+        for indice in rng.amostrar_indices(dados.len(), tamanho_teste(dados.len(), fracao_teste)) {
+            vai_para_teste[indice] = true;
+        }
+    }
+    vai_para_teste
+}
+
+// Quantos de `n` pontos vão para o teste: a fração arredondada, deixando ao menos um
+// ponto de cada lado. Com um único ponto não há como dividir, e ele fica no treinamento.
+fn tamanho_teste(n: usize, fracao_teste: f64) -> usize {
+    if n < 2 {
+        return 0;
+    }
+    ((fracao_teste * n as f64).round() as usize).clamp(1, n - 1)
+}
+
+// ==================== VALIDAÇÃO CRUZADA ====================
+/// Resultado de uma validação cruzada: a acurácia de cada fold e o seu resumo.
+///
+/// # Exemplo
+///
+/// ```
+/// use aprendizagem_knn::ResultadoCV;
+///
+/// let resultado = ResultadoCV::das_acuracias(vec![0.5, 1.0]);
+/// assert_eq!(resultado.media, 0.75);
+/// assert_eq!(resultado.desvio_padrao, 0.25);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResultadoCV {
+    /// Média das acurácias de todos os folds
+    pub media: f64,
+    /// Desvio padrão populacional das acurácias (o mesmo de `numpy.std`)
+    pub desvio_padrao: f64,
+    /// Acurácia de cada fold, na ordem em que foram avaliados
+    pub acuracias_por_fold: Vec<f64>,
+}
+
+impl ResultadoCV {
+    /// Resume as acurácias dos folds em média e desvio padrão.
+    ///
+    /// ```
+    /// use aprendizagem_knn::ResultadoCV;
+    ///
+    /// let resultado = ResultadoCV::das_acuracias(vec![0.8, 0.8, 0.8]);
+    /// assert_eq!(resultado.desvio_padrao, 0.0);
+    /// ```
+    pub fn das_acuracias(acuracias_por_fold: Vec<f64>) -> Self {
+        let mut estatisticas = EstatisticasIncrementais::nova(1);
+        for &acuracia in &acuracias_por_fold {
+            estatisticas.atualizar(&[acuracia]);
+        }
+        Self {
+            media: estatisticas.media[0],
+            desvio_padrao: estatisticas.desvio_padrao()[0],
+            acuracias_por_fold,
+        }
+    }
+}
+
+// Display resume a validação em uma linha: média ± desvio padrão e o número de folds
+/// ```
+/// use aprendizagem_knn::ResultadoCV;
+///
+/// let resultado = ResultadoCV::das_acuracias(vec![0.5, 1.0]);
+/// assert_eq!(resultado.to_string(), "acurácia 0.750 ± 0.250 (2 folds)");
+/// ```
+impl fmt::Display for ResultadoCV {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "acurácia {:.3} ± {:.3} ({} folds)",
+            self.media,
+            self.desvio_padrao,
+            self.acuracias_por_fold.len()
+        )
+    }
+}
+
+// Embaralha os índices 0..n e os reparte em k_folds folds consecutivos. Como no KFold do
+// scikit-learn, os n % k_folds primeiros folds recebem um ponto a mais que os demais.
+pub(crate) fn dividir_em_folds(n: usize, k_folds: usize, semente: u64) -> Vec<Vec<usize>> {
+    let embaralhados = Rng::novo(semente).amostrar_indices(n, n);
+    let mut folds = Vec::with_capacity(k_folds);
+    let mut inicio = 0;
+    for fold in 0..k_folds {
+        let tamanho = n / k_folds + usize::from(fold < n % k_folds);
+        folds.push(embaralhados[inicio..inicio + tamanho].to_vec());
+        inicio += tamanho;
+    }
+    folds
+}
+
+// Sorteia a ordem dos pontos dentro de cada classe e os distribui entre os folds em rodízio
+// (round-robin), continuando a contagem de uma classe para a outra. Assim cada fold recebe
+// quase a mesma quantidade de cada classe, e os tamanhos dos folds diferem em no máximo 1.
+fn dividir_em_folds_estratificados(dados: &[Ponto], k_folds: usize, semente: u64) -> Vec<Vec<usize>> {
+    let mut rng = Rng::novo(semente);
+    let mut folds = vec![Vec::new(); k_folds];
+    let mut proximo_fold = 0;
+
+    // As classes são percorridas em ordem alfabética para que a semente dê sempre o mesmo resultado
+    for (rotulo, quantidade) in contagem_ordenada(dados) {
+        let indices_classe: Vec<usize> = dados.iter()
+            .enumerate()
+            .filter(|(_, ponto)| ponto.rotulo == rotulo)
+            .map(|(indice, _)| indice)
+            .collect();
+        for posicao in rng.amostrar_indices(quantidade, quantidade) {
+            folds[proximo_fold].push(indices_classe[posicao]);
+            proximo_fold = (proximo_fold + 1) % k_folds;
+        }
+    }
+    folds
+}
+
+// Parâmetros que toda validação cruzada precisa respeitar, com os mesmos erros da
+// validação estratificada (veja validar_parametros_estratificados)
+pub(crate) fn validar_parametros_cv(n: usize, k_folds: usize, k_vizinhos: usize) -> Result<(), ErroKnn> {
+    if n == 0 {
+        return Err(ErroKnn::ConjuntoVazio);
+    }
+    if k_folds < 2 {
+        return Err(ErroKnn::ParametroInvalido(format!("k_folds deve ser ao menos 2, recebido {}", k_folds)));
+    }
+    if k_folds > n {
+        return Err(ErroKnn::ParametroInvalido(format!(
+            "k_folds ({}) não pode ser maior que o número de pontos ({})",
+            k_folds, n
+        )));
+    }
+    if k_vizinhos == 0 {
+        return Err(ErroKnn::ParametroInvalido("k_vizinhos deve ser maior que zero".to_string()));
+    }
+    Ok(())
+}
+
+/// Um fold de uma validação cruzada: quais pontos ficaram de fora do treinamento e o que
+/// o KNN previu para cada um deles.
+///
+/// ```
+/// use aprendizagem_knn::{pontos, validacao_cruzada};
+///
+/// let dados = pontos![[0.0] => "A", [1.0] => "A", [9.0] => "B", [10.0] => "B"];
+/// let fold = &validacao_cruzada(&dados, 1, 2, 42).unwrap().folds[0];
+/// assert_eq!(fold.indices.len(), 2);
+/// assert_eq!(fold.reais.len(), fold.previstos.len());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct FoldCV {
+    /// Posições em `dados` dos pontos avaliados neste fold
+    pub indices: Vec<usize>,
+    /// Rótulo verdadeiro de cada ponto avaliado
+    pub reais: Vec<String>,
+    /// Rótulo previsto para cada ponto avaliado
+    pub previstos: Vec<String>,
+    /// Fração de acertos dentro do fold
+    pub acuracia: f64,
+}
+
+// Treina com todos os pontos fora do fold e classifica os pontos dele
+// (KNN comum: distância euclidiana e votação por maioria)
+fn avaliar_fold(dados: &[Ponto], fold: &[usize], k_vizinhos: usize) -> FoldCV {
+    avaliar_fold_com(dados, fold, k_vizinhos, &DistanciaMetrica::Euclidiana, Votacao::Maioria)
+}
+
+// Mesmo que avaliar_fold, com a métrica e a votação escolhidas
+pub(crate) fn avaliar_fold_com(
+    dados: &[Ponto],
+    fold: &[usize],
+    k_vizinhos: usize,
+    metrica: &DistanciaMetrica,
+    votacao: Votacao,
+) -> FoldCV {
+    let mut no_fold = vec![false; dados.len()];
+    for &indice in fold {
+        no_fold[indice] = true;
+    }
+    // Referências aos pontos de treinamento, sem copiar o conjunto
+    let treinamento: Vec<&Ponto> = dados.iter()
+        .zip(&no_fold)
+        .filter(|(_, &esta_no_fold)| !esta_no_fold)
+        .map(|(ponto, _)| ponto)
+        .collect();
+
+    let reais: Vec<String> = fold.iter().map(|&indice| dados[indice].rotulo.clone()).collect();
+    let previstos: Vec<String> = fold.iter()
+        .map(|&indice| knn_sobre_com_votacao(treinamento.iter().copied(), &dados[indice], k_vizinhos, metrica, votacao))
+        .collect();
+    let acertos = reais.iter().zip(&previstos).filter(|(real, previsto)| real == previsto).count();
+
+    FoldCV {
+        indices: fold.to_vec(),
+        reais,
+        previstos,
+        acuracia: acertos as f64 / fold.len() as f64,
+    }
+}
+
+/// Validação cruzada k-fold: embaralha os dados (com a `semente`), os divide em `k_folds`
+/// partes e, para cada parte, classifica os seus pontos com o KNN treinado nas demais.
+///
+/// Devolve a acurácia de cada fold. Dados vazios são [`ErroKnn::ConjuntoVazio`], e
+/// `k_folds` menor que 2 ou maior que o número de pontos, ou `k_vizinhos` igual a zero, é
+/// [`ErroKnn::ParametroInvalido`].
+///
+/// ```
+/// use aprendizagem_knn::{pontos, validacao_cruzada_k_fold, ErroKnn};
+///
+/// let dados = pontos![
+///     [0.0] => "A", [0.5] => "A", [1.0] => "A",
+///     [9.0] => "B", [9.5] => "B", [10.0] => "B",
+/// ];
+/// let acuracias = validacao_cruzada_k_fold(&dados, 3, 1, 42).unwrap();
+/// assert_eq!(acuracias, vec![1.0, 1.0, 1.0]);
+///
+/// assert!(matches!(validacao_cruzada_k_fold(&dados, 7, 1, 42), Err(ErroKnn::ParametroInvalido(_))));
+/// assert!(matches!(validacao_cruzada_k_fold(&dados, 3, 0, 42), Err(ErroKnn::ParametroInvalido(_))));
+/// assert_eq!(validacao_cruzada_k_fold(&[], 3, 1, 42), Err(ErroKnn::ConjuntoVazio));
+/// ```
+pub fn validacao_cruzada_k_fold(
+    dados: &[Ponto],
+    k_folds: usize,
+    k_vizinhos: usize,
+    semente: u64,
+) -> Result<Vec<f64>, ErroKnn> {
+    validar_parametros_cv(dados.len(), k_folds, k_vizinhos)?;
+
+    Ok(dividir_em_folds(dados.len(), k_folds, semente)
+        .iter()
+        .map(|fold| avaliar_fold(dados, fold, k_vizinhos).acuracia)
+        .collect())
+}
+
+/// Validação cruzada k-fold repetida `n_repeticoes` vezes, cada uma com um embaralhamento
+/// diferente (semente `semente_base + repeticao`), como o `RepeatedKFold` do scikit-learn.
+///
+/// Uma única validação cruzada depende muito de como os pontos caíram nos folds; repetir
+/// com outros sorteios e juntar todas as `k_folds * n_repeticoes` acurácias dá uma
+/// estimativa mais estável. `n_repeticoes` igual a zero é [`ErroKnn::ParametroInvalido`];
+/// os outros erros são os de [`validacao_cruzada_k_fold`].
+///
+/// ```
+/// use aprendizagem_knn::{carregar_dados_do_csv, validacao_cruzada_repetida};
+///
+/// let dados = carregar_dados_do_csv("src/dados.csv").unwrap();
+/// let resultado = validacao_cruzada_repetida(&dados, 5, 3, 15, 42).unwrap();
+/// assert_eq!(resultado.acuracias_por_fold.len(), 15);
+/// assert!((0.0..=1.0).contains(&resultado.media));
+/// println!("acurácia: {:.3} ± {:.3}", resultado.media, resultado.desvio_padrao);
+///
+/// assert!(validacao_cruzada_repetida(&dados, 5, 0, 15, 42).is_err());
+/// assert!(validacao_cruzada_repetida(&dados, 1, 3, 15, 42).is_err());
+/// ```
+pub fn validacao_cruzada_repetida(
+    dados: &[Ponto],
+    k_folds: usize,
+    n_repeticoes: usize,
+    k_vizinhos: usize,
+    semente_base: u64,
+) -> Result<ResultadoCV, ErroKnn> {
+    if n_repeticoes == 0 {
+        return Err(ErroKnn::ParametroInvalido("n_repeticoes deve ser maior que zero".to_string()));
+    }
+
+    let mut acuracias = Vec::with_capacity(k_folds * n_repeticoes);
+    for repeticao in 0..n_repeticoes as u64 {
+        acuracias.extend(validacao_cruzada_k_fold(dados, k_folds, k_vizinhos, semente_base.wrapping_add(repeticao))?);
+    }
+    Ok(ResultadoCV::das_acuracias(acuracias))
+}
+
+/// Relatório completo de uma validação cruzada: cada fold com as suas previsões, além da
+/// média e do desvio padrão das acurácias.
+///
+/// As previsões permitem calcular outras métricas (matriz de confusão, F1...) a partir da
+/// mesma execução, sem rodar o KNN de novo.
+///
+/// # Exemplo
+///
+/// ```
+/// use aprendizagem_knn::{pontos, validacao_cruzada};
+///
+/// let dados = pontos![[0.0] => "A", [1.0] => "A", [2.0] => "A", [9.0] => "B", [10.0] => "B"];
+/// let relatorio = validacao_cruzada(&dados, 1, 5, 3).unwrap();
+/// let (reais, previstos) = relatorio.reais_e_previstos();
+/// assert_eq!(reais.len(), 5);
+/// assert_eq!(reais, previstos);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct RelatorioCV {
+    /// Os folds, na ordem em que foram avaliados
+    pub folds: Vec<FoldCV>,
+    /// Média das acurácias dos folds
+    pub media: f64,
+    /// Desvio padrão populacional das acurácias dos folds
+    pub desvio_padrao: f64,
+}
+
+impl RelatorioCV {
+    /// Acurácia de cada fold.
+    ///
+    /// ```
+    /// use aprendizagem_knn::{pontos, validacao_cruzada};
+    ///
+    /// let dados = pontos![[0.0] => "A", [1.0] => "A", [9.0] => "B", [10.0] => "B"];
+    /// assert_eq!(validacao_cruzada(&dados, 1, 2, 0).unwrap().acuracias_por_fold(), vec![1.0, 1.0]);
+    /// ```
+    pub fn acuracias_por_fold(&self) -> Vec<f64> {
+        self.folds.iter().map(|fold| fold.acuracia).collect()
+    }
+
+    /// Rótulos reais e previstos de todos os folds juntos, na ordem dos folds.
+    ///
+    /// ```
+    /// use aprendizagem_knn::{pontos, validacao_cruzada};
+    ///
+    /// let dados = pontos![[0.0] => "A", [1.0] => "A", [9.0] => "B", [10.0] => "B"];
+    /// let (reais, previstos) = validacao_cruzada(&dados, 1, 4, 0).unwrap().reais_e_previstos();
+    /// assert_eq!((reais.len(), previstos.len()), (4, 4));
+    /// ```
+    pub fn reais_e_previstos(&self) -> (Vec<String>, Vec<String>) {
+        let reais = self.folds.iter().flat_map(|fold| fold.reais.iter().cloned()).collect();
+        let previstos = self.folds.iter().flat_map(|fold| fold.previstos.iter().cloned()).collect();
+        (reais, previstos)
+    }
+
+    /// Matriz de confusão de todas as previsões da validação cruzada.
+    ///
+    /// ```
+    /// use aprendizagem_knn::{pontos, validacao_cruzada};
+    ///
+    /// let dados = pontos![[0.0] => "A", [1.0] => "A", [9.0] => "B", [10.0] => "B"];
+    /// let matriz = validacao_cruzada(&dados, 1, 4, 0).unwrap().matriz_confusao();
+    /// assert_eq!(matriz.contagens, vec![vec![2, 0], vec![0, 2]]);
+    /// ```
+    pub fn matriz_confusao(&self) -> MatrizConfusao {
+        let (reais, previstos) = self.reais_e_previstos();
+        MatrizConfusao::nova(&reais, &previstos).expect("cada fold tem um rótulo previsto por rótulo real")
+    }
+
+    /// Precisão, recall e F1 (por classe e médias) de todas as previsões da validação cruzada.
+    ///
+    /// ```
+    /// use aprendizagem_knn::{pontos, validacao_cruzada};
+    ///
+    /// let dados = pontos![[0.0] => "A", [1.0] => "A", [9.0] => "B", [10.0] => "B"];
+    /// let relatorio = validacao_cruzada(&dados, 1, 4, 0).unwrap().relatorio_classificacao();
+    /// assert_eq!(relatorio.macro_media.f1, 1.0);
+    /// ```
+    pub fn relatorio_classificacao(&self) -> RelatorioClassificacao {
+        self.matriz_confusao().relatorio_classificacao()
+    }
+}
+
+/// Validação cruzada com `n_folds` folds e relatório completo: embaralha os dados (com a
+/// `semente`), os divide em folds que não se sobrepõem e cobrem todos os pontos (os tamanhos
+/// diferem em no máximo 1), e classifica cada fold com o [`knn`](fn@crate::knn) treinado
+/// nos demais.
+///
+/// Os erros são os de [`validacao_cruzada_k_fold`]: dados vazios são
+/// [`ErroKnn::ConjuntoVazio`], e `n_folds` menor que 2 ou maior que o número de pontos, ou
+/// `k_vizinhos` igual a zero, é [`ErroKnn::ParametroInvalido`]. Para manter as proporções
+/// das classes em cada fold, use [`validacao_cruzada_com_estratificacao`].
+///
+/// ```
+/// use aprendizagem_knn::{pontos, validacao_cruzada, ErroKnn};
+///
+/// let dados = pontos![
+///     [0.0, 0.0] => "A", [0.0, 1.0] => "A", [1.0, 0.0] => "A", [1.0, 1.0] => "A",
+///     [9.0, 9.0] => "B", [9.0, 8.0] => "B", [8.0, 9.0] => "B",
+/// ];
+/// let relatorio = validacao_cruzada(&dados, 1, 3, 42).unwrap();
+/// assert_eq!(relatorio.media, 1.0);
+///
+/// // 7 pontos em 3 folds: tamanhos 3, 2 e 2, e a mesma semente repete a divisão
+/// let indices: Vec<Vec<usize>> = relatorio.folds.iter().map(|fold| fold.indices.clone()).collect();
+/// assert_eq!(indices, vec![vec![4, 5, 2], vec![1, 6], vec![3, 0]]);
+///
+/// assert!(matches!(validacao_cruzada(&dados, 1, 8, 42), Err(ErroKnn::ParametroInvalido(_))));
+/// assert!(matches!(validacao_cruzada(&dados, 1, 1, 42), Err(ErroKnn::ParametroInvalido(_))));
+/// assert!(matches!(validacao_cruzada(&dados, 0, 3, 42), Err(ErroKnn::ParametroInvalido(_))));
+/// assert_eq!(validacao_cruzada(&[], 1, 3, 42), Err(ErroKnn::ConjuntoVazio));
+/// ```
+pub fn validacao_cruzada(
+    dados: &[Ponto],
+    k_vizinhos: usize,
+    n_folds: usize,
+    semente: u64,
+) -> Result<RelatorioCV, ErroKnn> {
+    validacao_cruzada_com_estratificacao(dados, k_vizinhos, n_folds, semente, false)
+}
+
+/// Mesmo que [`validacao_cruzada`], mas com a opção de folds estratificados.
+///
+/// Com `estratificado`, os pontos de cada classe são sorteados e distribuídos entre os folds
+/// em rodízio, para que todos os folds tenham quase a mesma proporção de classes do conjunto
+/// completo. É o que se quer com classes desbalanceadas: sem isso, uma classe rara pode
+/// ficar de fora de alguns folds. Uma classe com menos pontos que folds aparece só em
+/// parte dos folds. Os erros são os de [`validacao_cruzada`].
+///
+/// ```
+/// use aprendizagem_knn::{contar_rotulos, validacao_cruzada_com_estratificacao, Ponto};
+///
+/// // 16 pontos de A e 4 de B
+/// let dados: Vec<Ponto> = (0..20)
+///     .map(|i| Ponto::novo(vec![i as f64], if i < 16 { "A" } else { "B" }.to_string()))
+///     .collect();
+///
+/// let relatorio = validacao_cruzada_com_estratificacao(&dados, 1, 4, 7, true).unwrap();
+/// for fold in &relatorio.folds {
+///     let rotulos: Vec<Ponto> = fold.indices.iter().map(|&i| dados[i].clone()).collect();
+///     let contagem = contar_rotulos(&rotulos);
+///     assert_eq!((contagem["A"], contagem["B"]), (4, 1));
+/// }
+/// ```
+pub fn validacao_cruzada_com_estratificacao(
+    dados: &[Ponto],
+    k_vizinhos: usize,
+    n_folds: usize,
+    semente: u64,
+    estratificado: bool,
+) -> Result<RelatorioCV, ErroKnn> {
+    validar_parametros_cv(dados.len(), n_folds, k_vizinhos)?;
+
+    let indices_folds = if estratificado {
+        dividir_em_folds_estratificados(dados, n_folds, semente)
+    } else {
+        dividir_em_folds(dados.len(), n_folds, semente)
+    };
+    let folds: Vec<FoldCV> = indices_folds.iter().map(|fold| avaliar_fold(dados, fold, k_vizinhos)).collect();
+
+    let resumo = ResultadoCV::das_acuracias(folds.iter().map(|fold| fold.acuracia).collect());
+    Ok(RelatorioCV { folds, media: resumo.media, desvio_padrao: resumo.desvio_padrao })
+}
+
+/// Validação cruzada estratificada: cada fold recebe quase a mesma proporção de cada
+/// classe que o conjunto completo.
+///
+/// Os pontos de cada classe são sorteados (com a `semente`) e distribuídos entre os
+/// `k_folds` folds em rodízio, e cada fold é avaliado com o KNN treinado nos demais, como
+/// em [`validacao_cruzada_k_fold`]. Na k-fold comum, uma classe rara pode cair inteira em
+/// um só fold, que então é avaliado com um treinamento que nunca viu essa classe.
+///
+/// Os folds são os mesmos de [`validacao_cruzada_com_estratificacao`] com `estratificado`
+/// e a mesma semente, que mostra quais pontos caíram em cada fold.
+///
+/// É erro se alguma classe tiver menos pontos que `k_folds` (ela não estaria em todos os
+/// folds), se `k_folds` for menor que 2, se `k_vizinhos` for zero ou se os dados forem vazios.
+///
+/// ```
+/// use aprendizagem_knn::{contar_rotulos, validacao_cruzada_com_estratificacao, validacao_cruzada_estratificada, Ponto};
+///
+/// // 30 pontos de A, 15 de B e 5 de C
+/// let dados: Vec<Ponto> = (0..50)
+///     .map(|i| {
+///         let rotulo = if i < 30 { "A" } else if i < 45 { "B" } else { "C" };
+///         Ponto::novo(vec![i as f64], rotulo.to_string())
+///     })
+///     .collect();
+///
+/// let resultado = validacao_cruzada_estratificada(&dados, 5, 3, 42).unwrap();
+/// assert_eq!(resultado.acuracias_por_fold.len(), 5);
+///
+/// // em cada fold, a fração de cada classe fica a menos de 10% da fração no conjunto todo
+/// let relatorio = validacao_cruzada_com_estratificacao(&dados, 3, 5, 42, true).unwrap();
+/// assert_eq!(relatorio.acuracias_por_fold(), resultado.acuracias_por_fold);
+/// for fold in &relatorio.folds {
+///     let pontos_fold: Vec<Ponto> = fold.indices.iter().map(|&i| dados[i].clone()).collect();
+///     for (rotulo, total) in contar_rotulos(&dados) {
+///         let fracao_fold = contar_rotulos(&pontos_fold)[&rotulo] as f64 / pontos_fold.len() as f64;
+///         assert!((fracao_fold - total as f64 / dados.len() as f64).abs() < 0.1);
+///     }
+/// }
+///
+/// // C tem 5 pontos: não dá para ter C em cada um de 6 folds
+/// assert!(validacao_cruzada_estratificada(&dados, 6, 3, 42).is_err());
+/// ```
+pub fn validacao_cruzada_estratificada(
+    dados: &[Ponto],
+    k_folds: usize,
+    k_vizinhos: usize,
+    semente: u64,
+) -> Result<ResultadoCV, ErroKnn> {
+    validar_parametros_estratificados(dados, k_folds, k_vizinhos)?;
+
+    let acuracias = dividir_em_folds_estratificados(dados, k_folds, semente)
+        .iter()
+        .map(|fold| avaliar_fold(dados, fold, k_vizinhos).acuracia)
+        .collect();
+    Ok(ResultadoCV::das_acuracias(acuracias))
+}
+
+// Os erros da validação cruzada estratificada (veja validacao_cruzada_estratificada)
+fn validar_parametros_estratificados(dados: &[Ponto], k_folds: usize, k_vizinhos: usize) -> Result<(), ErroKnn> {
+    if dados.is_empty() {
+        return Err(ErroKnn::ConjuntoVazio);
+    }
+    if k_folds < 2 {
+        return Err(ErroKnn::ParametroInvalido(format!("k_folds deve ser ao menos 2, recebido {}", k_folds)));
+    }
+    if k_vizinhos == 0 {
+        return Err(ErroKnn::ParametroInvalido("k_vizinhos deve ser maior que zero".to_string()));
+    }
+    if let Some((rotulo, quantidade)) = contagem_ordenada(dados).into_iter().find(|(_, quantidade)| *quantidade < k_folds) {
+        return Err(ErroKnn::ParametroInvalido(format!(
+            "a classe '{}' tem {} pontos, menos que os {} folds",
+            rotulo, quantidade, k_folds
+        )));
+    }
+    Ok(())
+}
+
+/// Resumo de uma métrica medida várias vezes: média, desvio padrão (populacional),
+/// mínimo e máximo.
+///
+/// ```
+/// use aprendizagem_knn::ResumoMetrica;
+///
+/// let resumo = ResumoMetrica::dos_valores(&[0.5, 1.0, 0.75]);
+/// assert_eq!((resumo.media, resumo.minimo, resumo.maximo), (0.75, 0.5, 1.0));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResumoMetrica {
+    /// Média dos valores
+    pub media: f64,
+    /// Desvio padrão populacional dos valores
+    pub desvio_padrao: f64,
+    /// Menor valor
+    pub minimo: f64,
+    /// Maior valor
+    pub maximo: f64,
+}
+
+impl ResumoMetrica {
+    /// Resume uma lista de valores (uma lista vazia dá média e desvio 0, mínimo +∞ e máximo -∞).
+    ///
+    /// ```
+    /// use aprendizagem_knn::ResumoMetrica;
+    ///
+    /// assert_eq!(ResumoMetrica::dos_valores(&[0.5, 1.0]).desvio_padrao, 0.25);
+    /// ```
+    pub fn dos_valores(valores: &[f64]) -> Self {
+        let mut estatisticas = EstatisticasIncrementais::nova(1);
+        for &valor in valores {
+            estatisticas.atualizar(&[valor]);
+        }
+        Self {
+            media: estatisticas.media[0],
+            desvio_padrao: estatisticas.desvio_padrao()[0],
+            minimo: valores.iter().copied().fold(f64::INFINITY, f64::min),
+            maximo: valores.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        }
+    }
+}
+
+/// Desempenho de um fold em uma repetição da validação cruzada repetida.
+///
+/// ```
+/// use aprendizagem_knn::{pontos, validacao_cruzada_estratificada_repetida};
+///
+/// let dados = pontos![[0.0] => "A", [1.0] => "A", [9.0] => "B", [10.0] => "B"];
+/// let relatorio = validacao_cruzada_estratificada_repetida(&dados, 2, 3, 1, 0).unwrap();
+/// let ultima = relatorio.avaliacoes.last().unwrap();
+/// assert_eq!((ultima.repeticao, ultima.fold, ultima.n_treino, ultima.n_teste), (2, 1, 2, 2));
+/// assert_eq!((ultima.acuracia, ultima.f1_macro), (1.0, 1.0));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct AvaliacaoFold {
+    /// Número da repetição (a partir de 0)
+    pub repeticao: usize,
+    /// Número do fold dentro da repetição (a partir de 0)
+    pub fold: usize,
+    /// Pontos usados no treinamento
+    pub n_treino: usize,
+    /// Pontos avaliados
+    pub n_teste: usize,
+    /// Fração de acertos no fold
+    pub acuracia: f64,
+    /// F1 com média macro entre as classes do fold
+    pub f1_macro: f64,
+}
+
+/// Resultado de uma validação cruzada estratificada repetida: todos os folds de todas as
+/// repetições e o resumo da acurácia e do F1 macro entre eles.
+///
+/// É devolvido por [`validacao_cruzada_estratificada_repetida`].
+///
+/// ```
+/// use aprendizagem_knn::{pontos, validacao_cruzada_estratificada_repetida};
+///
+/// let dados = pontos![[0.0] => "A", [1.0] => "A", [9.0] => "B", [10.0] => "B"];
+/// let relatorio = validacao_cruzada_estratificada_repetida(&dados, 2, 3, 1, 0).unwrap();
+/// // 2 folds em cada uma das 3 repetições
+/// assert_eq!(relatorio.avaliacoes.len(), 6);
+/// assert_eq!(relatorio.acuracia.media, 1.0);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct RelatorioCVRepetida {
+    /// Um item por fold por repetição, ordenado por repetição e depois por fold
+    pub avaliacoes: Vec<AvaliacaoFold>,
+    /// Resumo da acurácia de todos os folds
+    pub acuracia: ResumoMetrica,
+    /// Resumo do F1 macro de todos os folds
+    pub f1_macro: ResumoMetrica,
+}
+
+impl RelatorioCVRepetida {
+    /// Grava uma linha por fold por repetição em CSV, com as colunas
+    /// `repeticao,fold,n_treino,n_teste,acuracia,f1_macro`.
+    ///
+    /// ```
+    /// use aprendizagem_knn::{pontos, validacao_cruzada_estratificada_repetida};
+    ///
+    /// let dados = pontos![[0.0] => "A", [1.0] => "A", [9.0] => "B", [10.0] => "B"];
+    /// let relatorio = validacao_cruzada_estratificada_repetida(&dados, 2, 3, 1, 0).unwrap();
+    /// let mut saida = Vec::new();
+    /// relatorio.escrever_csv(&mut saida).unwrap();
+    /// let texto = String::from_utf8(saida).unwrap();
+    /// assert_eq!(texto.lines().next(), Some("repeticao,fold,n_treino,n_teste,acuracia,f1_macro"));
+    /// assert_eq!(texto.lines().count(), 1 + 3 * 2);
+    /// ```
+    pub fn escrever_csv<W: Write>(&self, destino: W) -> Result<(), Box<dyn Error>> {
+        let mut escritor = Writer::from_writer(destino);
+        escritor.write_record(["repeticao", "fold", "n_treino", "n_teste", "acuracia", "f1_macro"])?;
+        for avaliacao in &self.avaliacoes {
+            escritor.write_record([
+                avaliacao.repeticao.to_string(),
+                avaliacao.fold.to_string(),
+                avaliacao.n_treino.to_string(),
+                avaliacao.n_teste.to_string(),
+                avaliacao.acuracia.to_string(),
+                avaliacao.f1_macro.to_string(),
+            ])?;
+        }
+        escritor.flush()?;
+        Ok(())
+    }
+
+    /// Salva a tabela de folds em um arquivo CSV (veja [`RelatorioCVRepetida::escrever_csv`]).
+    ///
+    /// ```
+    /// use aprendizagem_knn::{pontos, validacao_cruzada_estratificada_repetida};
+    ///
+    /// let caminho = std::env::temp_dir().join("aprendizagem_knn_doc_cv_repetida.csv");
+    /// let dados = pontos![[0.0] => "A", [1.0] => "A", [9.0] => "B", [10.0] => "B"];
+    /// let relatorio = validacao_cruzada_estratificada_repetida(&dados, 2, 2, 1, 0).unwrap();
+    /// relatorio.salvar_csv(caminho.to_str().unwrap()).unwrap();
+    /// assert_eq!(std::fs::read_to_string(&caminho).unwrap().lines().count(), 5);
+    /// ```
+    pub fn salvar_csv(&self, caminho_arquivo: &str) -> Result<(), Box<dyn Error>> {
+        self.escrever_csv(std::fs::File::create(caminho_arquivo)?)
+    }
+}
+
+/// Validação cruzada estratificada repetida `n_repeticoes` vezes (como o
+/// `RepeatedStratifiedKFold` do scikit-learn), com a acurácia e o F1 macro de cada fold.
+///
+/// Em conjuntos pequenos o resultado de uma única validação cruzada depende muito de
+/// como os pontos caíram nos folds; repetir com embaralhamentos diferentes e olhar média,
+/// desvio, mínimo e máximo mostra o quanto ele varia. A repetição `r` divide os folds com
+/// a semente `semente + r`, então cada repetição é independente das outras e a mesma
+/// `semente` repete tudo.
+///
+/// Os erros são os mesmos de [`validacao_cruzada_estratificada`], mais `n_repeticoes` 0.
+///
+/// ```
+/// use aprendizagem_knn::{validacao_cruzada_estratificada_repetida, Ponto};
+///
+/// // A em [0, 20) e B em [12, 32): as classes se sobrepõem
+/// let dados: Vec<Ponto> = (0..40)
+///     .map(|i| {
+///         let (deslocamento, rotulo) = if i < 20 { (0.0, "A") } else { (12.0, "B") };
+///         Ponto::novo(vec![(i % 20) as f64 + deslocamento], rotulo.to_string())
+///     })
+///     .collect();
+///
+/// // 5 repetições de 10 folds: 50 avaliações
+/// let relatorio = validacao_cruzada_estratificada_repetida(&dados, 10, 5, 3, 42).unwrap();
+/// assert_eq!(relatorio.avaliacoes.len(), 50);
+/// assert!(relatorio.avaliacoes.iter().all(|avaliacao| avaliacao.n_teste == 4 && avaliacao.n_treino == 36));
+/// let acuracia = relatorio.acuracia;
+/// assert!(acuracia.minimo <= acuracia.media && acuracia.media <= acuracia.maximo);
+/// assert!(acuracia.minimo < acuracia.maximo);
+///
+/// // A mesma semente repete todas as divisões
+/// assert_eq!(validacao_cruzada_estratificada_repetida(&dados, 10, 5, 3, 42).unwrap(), relatorio);
+/// ```
+pub fn validacao_cruzada_estratificada_repetida(
+    dados: &[Ponto],
+    k_folds: usize,
+    n_repeticoes: usize,
+    k_vizinhos: usize,
+    semente: u64,
+) -> Result<RelatorioCVRepetida, ErroKnn> {
+    validar_parametros_estratificados(dados, k_folds, k_vizinhos)?;
+    if n_repeticoes == 0 {
+        return Err(ErroKnn::ParametroInvalido("n_repeticoes deve ser maior que zero".to_string()));
+    }
+
+    let mut avaliacoes = Vec::with_capacity(n_repeticoes * k_folds);
+    for repeticao in 0..n_repeticoes {
+        let folds = dividir_em_folds_estratificados(dados, k_folds, semente.wrapping_add(repeticao as u64));
+        for (indice_fold, fold) in folds.iter().enumerate() {
+            let resultado = avaliar_fold(dados, fold, k_vizinhos);
+            let f1_macro = MatrizConfusao::nova(&resultado.reais, &resultado.previstos)?
+                .relatorio_classificacao()
+                .macro_media
+                .f1;
+            avaliacoes.push(AvaliacaoFold {
+                repeticao,
+                fold: indice_fold,
+                n_treino: dados.len() - fold.len(),
+                n_teste: fold.len(),
+                acuracia: resultado.acuracia,
+                f1_macro,
+            });
+        }
+    }
+
+    let acuracias: Vec<f64> = avaliacoes.iter().map(|avaliacao| avaliacao.acuracia).collect();
+    let f1s: Vec<f64> = avaliacoes.iter().map(|avaliacao| avaliacao.f1_macro).collect();
+    Ok(RelatorioCVRepetida {
+        acuracia: ResumoMetrica::dos_valores(&acuracias),
+        f1_macro: ResumoMetrica::dos_valores(&f1s),
+        avaliacoes,
+    })
+}
+
+// ==================== RESULTADOS DOS FOLDS EM CSV ====================
+/// Validação cruzada k-fold (como [`validacao_cruzada_k_fold`]) que também grava o
+/// resultado de cada fold em um CSV, para guardar e comparar execuções.
+///
+/// O arquivo tem o cabeçalho `fold_index,n_treino,n_teste,acuracia`, uma linha por fold
+/// (numerados a partir de 0) e, por fim, uma linha `media` com a acurácia no formato
+/// `média ± desvio padrão`. `modo_escrita` escolhe o que fazer com um arquivo existente:
+///
+/// - `"novo"`: apaga o conteúdo anterior
+/// - `"adicionar"`: escreve no final, para acumular várias execuções (por exemplo, com
+///   valores de k diferentes) no mesmo arquivo; o cabeçalho só é escrito se o arquivo
+///   estiver vazio ou não existir
+///
+/// É erro se os dados estiverem vazios, se `k_folds` for menor que 2 ou maior que o
+/// número de pontos, se `k_vizinhos` for zero, se o modo for outro ou se o arquivo não
+/// puder ser escrito.
+///
+/// ```
+/// use aprendizagem_knn::{pontos, validacao_cruzada_k_fold_csv};
+///
+/// let caminho = std::env::temp_dir().join("aprendizagem_knn_doc_folds.csv");
+/// let caminho = caminho.to_str().unwrap();
+/// let dados = pontos![
+///     [0.0] => "A", [0.5] => "A", [1.0] => "A",
+///     [9.0] => "B", [9.5] => "B", [10.0] => "B",
+/// ];
+///
+/// let resultado = validacao_cruzada_k_fold_csv(&dados, 3, 1, 42, caminho, "novo").unwrap();
+/// assert_eq!(resultado.media, 1.0);
+/// assert_eq!(
+///     std::fs::read_to_string(caminho).unwrap(),
+///     "fold_index,n_treino,n_teste,acuracia\n0,4,2,1\n1,4,2,1\n2,4,2,1\nmedia,,,1 ± 0\n"
+/// );
+///
+/// // Uma segunda execução no mesmo arquivo, sem repetir o cabeçalho
+/// validacao_cruzada_k_fold_csv(&dados, 2, 1, 42, caminho, "adicionar").unwrap();
+/// let conteudo = std::fs::read_to_string(caminho).unwrap();
+/// assert_eq!(conteudo.lines().count(), 8);
+/// assert_eq!(conteudo.matches("fold_index").count(), 1);
+///
+/// assert!(validacao_cruzada_k_fold_csv(&dados, 3, 1, 42, caminho, "sobrescrever").is_err());
+/// ```
+pub fn validacao_cruzada_k_fold_csv(
+    dados: &[Ponto],
+    k_folds: usize,
+    k_vizinhos: usize,
+    semente: u64,
+    caminho_saida: &str,
+    modo_escrita: &str,
+) -> Result<ResultadoCV, ErroKnn> {
+    if dados.is_empty() {
+        return Err(ErroKnn::ConjuntoVazio);
+    }
+    if k_folds < 2 || k_folds > dados.len() {
+        return Err(ErroKnn::ParametroInvalido(format!(
+            "k_folds deve estar entre 2 e {} (o número de pontos), recebido {}",
+            dados.len(),
+            k_folds
+        )));
+    }
+    if k_vizinhos == 0 {
+        return Err(ErroKnn::ParametroInvalido("k_vizinhos deve ser maior que zero".to_string()));
+    }
+
+    let folds = dividir_em_folds(dados.len(), k_folds, semente);
+    let acuracias: Vec<f64> = folds.iter().map(|fold| avaliar_fold(dados, fold, k_vizinhos).acuracia).collect();
+    let resultado = ResultadoCV::das_acuracias(acuracias);
+
+    let linhas = folds.iter().zip(&resultado.acuracias_por_fold).enumerate().map(|(indice, (fold, acuracia))| {
+        [indice.to_string(), (dados.len() - fold.len()).to_string(), fold.len().to_string(), acuracia.to_string()]
+    });
+    let media = format!("{} ± {}", resultado.media, resultado.desvio_padrao);
+    let resumo = ["media".to_string(), String::new(), String::new(), media];
+    let cabecalho = ["fold_index", "n_treino", "n_teste", "acuracia"];
+    escrever_linhas_csv(caminho_saida, modo_escrita, &cabecalho, linhas.chain([resumo]))
+        .map_err(|erro| ErroKnn::Arquivo(format!("{}: {}", caminho_saida, erro)))?;
+
+    Ok(resultado)
+}
+
+// Grava as linhas em um CSV no modo "novo" (apaga o arquivo) ou "adicionar" (escreve no
+// final). O cabeçalho só entra se o arquivo estiver vazio depois de aberto.
+fn escrever_linhas_csv<L, I>(
+    caminho: &str,
+    modo_escrita: &str,
+    cabecalho: &[&str],
+    linhas: I,
+) -> Result<(), Box<dyn Error>>
+where
+    L: AsRef<[String]>,
+    I: IntoIterator<Item = L>,
+{
+    let mut opcoes = OpenOptions::new();
+    match modo_escrita {
+        "novo" => opcoes.write(true).create(true).truncate(true),
+        "adicionar" => opcoes.append(true).create(true),
+        outro => return Err(format!("modo de escrita '{}' inválido (use \"novo\" ou \"adicionar\")", outro).into()),
+    };
+    let arquivo = opcoes.open(caminho)?;
+    let vazio = arquivo.metadata()?.len() == 0;
+
+    let mut escritor = Writer::from_writer(arquivo);
+    if vazio {
+        escritor.write_record(cabecalho)?;
+    }
+    for linha in linhas {
+        escritor.write_record(linha.as_ref())?;
+    }
+    escritor.flush()?;
+    Ok(())
+}
+
+// ==================== LEAVE-ONE-OUT ====================
+// Acima deste número de pontos a matriz de distâncias (n(n-1)/2 valores f64, uns 100 MB
+// para 5.000 pontos) deixaria de caber confortavelmente na memória, e o loocv passa a
+// calcular as distâncias de cada ponto na hora, como o knn faz
+const LIMITE_PONTOS_MATRIZ_LOOCV: usize = 5_000;
+
+/// Validação leave-one-out (LOOCV): classifica cada ponto com o KNN treinado em todos os
+/// outros e devolve a fração de acertos.
+///
+/// É a avaliação natural para conjuntos pequenos, já que cada rodada treina com quase todos
+/// os dados e não há sorteio. Em vez de recalcular as distâncias n vezes, calcula a matriz
+/// de distâncias euclidianas uma vez (cada par uma única vez, aproveitando d(i, j) = d(j, i))
+/// e, para cada ponto, escolhe os vizinhos entre os outros. A votação e o desempate são os
+/// mesmos do [`knn`](fn@crate::knn), então o resultado é idêntico a rodar o knn n vezes.
+/// Com mais de 5.000 pontos a matriz não é montada e as distâncias são calculadas na hora
+/// (veja [`loocv_com_limite`]).
+///
+/// Com menos de 2 pontos não sobra treinamento para cada ponto, e o erro é
+/// [`ErroKnn::ConjuntoVazio`]; `k` igual a zero é [`ErroKnn::KInvalido`]. Distâncias NaN
+/// não contam como vizinhas, como no knn; um ponto cuja distância até todos os outros dá
+/// NaN é [`ErroKnn::VetorVazio`].
+///
+/// ```
+/// use aprendizagem_knn::{knn, loocv, pontos, ErroKnn};
+///
+/// let dados = pontos![
+///     [0.0, 0.0] => "A", [0.0, 1.0] => "A", [1.0, 0.0] => "A",
+///     [5.0, 5.0] => "B", [5.0, 6.0] => "B", [0.5, 0.5] => "B",
+/// ];
+/// // o último B está no meio dos A e é o único erro
+/// assert_eq!(loocv(&dados, 3), Ok(5.0 / 6.0));
+///
+/// // o mesmo que tirar cada ponto e rodar o knn nos demais
+/// let acertos = (0..dados.len())
+///     .filter(|&i| {
+///         let mut outros = dados.clone();
+///         let ponto = outros.remove(i);
+///         knn(&outros, &ponto, 3).unwrap() == ponto.rotulo
+///     })
+///     .count();
+/// assert_eq!(loocv(&dados, 3), Ok(acertos as f64 / dados.len() as f64));
+///
+/// assert_eq!(loocv(&dados[..1], 3), Err(ErroKnn::ConjuntoVazio));
+/// assert_eq!(loocv(&dados, 0), Err(ErroKnn::KInvalido { k: 0, maximo: 5 }));
+/// ```
+pub fn loocv(dados: &[Ponto], k: usize) -> Result<f64, ErroKnn> {
+    loocv_com_limite(dados, k, LIMITE_PONTOS_MATRIZ_LOOCV)
+}
+
+/// Como [`loocv`], escolhendo a partir de quantos pontos a matriz de distâncias deixa de
+/// ser montada: com até `limite_pontos_matriz` pontos ela é calculada uma vez; acima disso
+/// as distâncias de cada ponto são calculadas na hora. O resultado é o mesmo nos dois
+/// caminhos; só mudam o tempo e a memória. O [`loocv`] usa o limite de 5.000 pontos.
+///
+/// ```
+/// use aprendizagem_knn::{loocv, loocv_com_limite, pontos};
+///
+/// let dados = pontos![[0.0] => "A", [1.0] => "A", [1.5] => "B", [9.0] => "B", [10.0] => "B"];
+/// // 0: nunca monta a matriz
+/// assert_eq!(loocv_com_limite(&dados, 3, 0), loocv(&dados, 3));
+/// ```
+pub fn loocv_com_limite(dados: &[Ponto], k: usize, limite_pontos_matriz: usize) -> Result<f64, ErroKnn> {
+    if dados.len() < 2 {
+        return Err(ErroKnn::ConjuntoVazio);
+    }
+    // Cada ponto é classificado com os outros n - 1
+    if k == 0 {
+        return Err(ErroKnn::KInvalido { k, maximo: dados.len() - 1 });
+    }
+
+    let previstos = if dados.len() <= limite_pontos_matriz {
+        previsoes_loocv_com_matriz(dados, k)?
+    } else {
+        previsoes_loocv_sem_matriz(dados, k)?
+    };
+    let acertos = dados.iter().zip(&previstos).filter(|(ponto, previsto)| ponto.rotulo == **previsto).count();
+    Ok(acertos as f64 / dados.len() as f64)
+}
+
+// LOOCV com a matriz de distâncias calculada uma única vez
+fn previsoes_loocv_com_matriz(dados: &[Ponto], k: usize) -> Result<Vec<String>, ErroKnn> {
+    let n = dados.len();
+    // Matriz "condensada": só a parte acima da diagonal, linha por linha.
+    // O par (i, j), com i < j, fica na posição i*n - i*(i+1)/2 + (j - i - 1)
+    let posicao = |i: usize, j: usize| {
+        let (i, j) = (i.min(j), i.max(j));
+        i * n - i * (i + 1) / 2 + (j - i - 1)
+    };
+    let mut distancias = Vec::with_capacity(n * (n - 1) / 2);
+    for i in 0..n {
+        for j in (i + 1)..n {
+            distancias.push(distancia_euclidiana(&dados[i], &dados[j]));
+        }
+    }
+
+    (0..n)
+        .map(|i| {
+            // Distâncias NaN ficam de fora, como na busca do knn
+            let mut candidatos: Vec<(f64, usize)> = (0..n)
+                .filter(|&j| j != i)
+                .map(|j| (distancias[posicao(i, j)], j))
+                .filter(|(distancia, _)| !distancia.is_nan())
+                .collect();
+            if candidatos.is_empty() {
+                return Err(erro_sem_vizinhos(&dados[i].caracteristicas));
+            }
+            // Os k menores primeiro; no empate de distância, o menor índice, como no knn
+            let ordem = |a: &(f64, usize), b: &(f64, usize)| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1));
+            if k < candidatos.len() {
+                candidatos.select_nth_unstable_by(k - 1, ordem);
+                candidatos.truncate(k);
+            }
+            Ok(votar_por_maioria(candidatos.iter().map(|&(_, j)| dados[j].rotulo.clone())))
+        })
+        .collect()
+}
+
+// LOOCV para conjuntos grandes: as distâncias de cada ponto são calculadas na hora
+fn previsoes_loocv_sem_matriz(dados: &[Ponto], k: usize) -> Result<Vec<String>, ErroKnn> {
+    (0..dados.len())
+        .map(|i| {
+            let outros = dados.iter().enumerate().filter(|(j, _)| *j != i).map(|(_, ponto)| ponto);
+            let vizinhos = k_vizinhos_mais_proximos(outros, &dados[i], k, &DistanciaMetrica::Euclidiana);
+            if vizinhos.is_empty() {
+                return Err(erro_sem_vizinhos(&dados[i].caracteristicas));
+            }
+            Ok(votar_por_maioria(vizinhos.into_iter().map(|vizinho| vizinho.rotulo)))
+        })
+        .collect()
+}
+
+// ==================== CURVA DE APRENDIZADO ====================
+/// Um ponto da curva de aprendizado: o desempenho com uma fração dos dados no treinamento.
+///
+/// ```
+/// use aprendizagem_knn::{curva_aprendizado, Ponto};
+///
+/// let dados: Vec<Ponto> = (0..20)
+///     .map(|i| Ponto::novo(vec![i as f64], (if i < 10 { "A" } else { "B" }).to_string()))
+///     .collect();
+/// let curva = curva_aprendizado(&dados, &[0.5], 1, 3, 42).unwrap();
+/// let ponto = &curva.pontos[0];
+/// assert_eq!((ponto.fracao, ponto.tamanho_treino), (0.5, 10));
+/// assert_eq!(ponto.acuracias.len(), 3);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct PontoCurvaAprendizado {
+    /// Fração dos dados usada no treinamento
+    pub fracao: f64,
+    /// Quantos pontos ficaram no treinamento (a fração, arredondada dentro de cada classe)
+    pub tamanho_treino: usize,
+    /// Acurácia média no restante dos dados, entre as repetições
+    pub media: f64,
+    /// Desvio padrão (populacional) da acurácia entre as repetições
+    pub desvio_padrao: f64,
+    /// Acurácia de cada repetição
+    pub acuracias: Vec<f64>,
+}
+
+/// Curva de aprendizado: como a acurácia muda conforme o treinamento cresce.
+///
+/// Se a acurácia ainda sobe nas maiores frações, coletar mais dados rotulados deve
+/// ajudar; se ela já estabilizou, mais dados provavelmente não mudam muito.
+///
+/// É devolvida por [`curva_aprendizado`].
+///
+/// ```
+/// use aprendizagem_knn::{curva_aprendizado, Ponto};
+///
+/// let dados: Vec<Ponto> = (0..20)
+///     .map(|i| Ponto::novo(vec![i as f64], (if i < 10 { "A" } else { "B" }).to_string()))
+///     .collect();
+/// let curva = curva_aprendizado(&dados, &[0.2, 0.8], 1, 2, 42).unwrap();
+/// let fracoes: Vec<f64> = curva.pontos.iter().map(|ponto| ponto.fracao).collect();
+/// assert_eq!(fracoes, vec![0.2, 0.8]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct CurvaAprendizado {
+    /// Um ponto por fração, na ordem em que as frações foram pedidas
+    pub pontos: Vec<PontoCurvaAprendizado>,
+}
+
+impl CurvaAprendizado {
+    /// Grava a curva em CSV (colunas `fracao,tamanho_treino,media,desvio_padrao`), para
+    /// gerar o gráfico.
+    ///
+    /// ```
+    /// use aprendizagem_knn::{curva_aprendizado, pontos};
+    ///
+    /// let dados = pontos![[0.0] => "A", [1.0] => "A", [9.0] => "B", [10.0] => "B"];
+    /// let curva = curva_aprendizado(&dados, &[0.5], 1, 3, 0).unwrap();
+    /// let mut saida = Vec::new();
+    /// curva.escrever_csv(&mut saida).unwrap();
+    /// assert_eq!(String::from_utf8(saida).unwrap(), "fracao,tamanho_treino,media,desvio_padrao\n0.5,2,1,0\n");
+    /// ```
+    pub fn escrever_csv<W: Write>(&self, destino: W) -> Result<(), Box<dyn Error>> {
+        let mut escritor = Writer::from_writer(destino);
+        escritor.write_record(["fracao", "tamanho_treino", "media", "desvio_padrao"])?;
+        for ponto in &self.pontos {
+            escritor.write_record([
+                ponto.fracao.to_string(),
+                ponto.tamanho_treino.to_string(),
+                ponto.media.to_string(),
+                ponto.desvio_padrao.to_string(),
+            ])?;
+        }
+        escritor.flush()?;
+        Ok(())
+    }
+
+    /// Salva a curva em um arquivo CSV (veja [`CurvaAprendizado::escrever_csv`]).
+    ///
+    /// ```
+    /// use aprendizagem_knn::{curva_aprendizado, pontos};
+    ///
+    /// let caminho = std::env::temp_dir().join("aprendizagem_knn_doc_curva.csv");
+    /// let dados = pontos![[0.0] => "A", [1.0] => "A", [9.0] => "B", [10.0] => "B"];
+    /// let curva = curva_aprendizado(&dados, &[0.5, 0.75], 1, 2, 0).unwrap();
+    /// curva.salvar_csv(caminho.to_str().unwrap()).unwrap();
+    /// assert_eq!(std::fs::read_to_string(&caminho).unwrap().lines().count(), 3);
+    /// ```
+    pub fn salvar_csv(&self, caminho_arquivo: &str) -> Result<(), Box<dyn Error>> {
+        self.escrever_csv(std::fs::File::create(caminho_arquivo)?)
+    }
+}
+
+/// Calcula a curva de aprendizado do KNN: para cada fração em `fracoes`, sorteia
+/// `n_repeticoes` vezes essa fração dos dados para o treinamento (estratificado, como em
+/// [`dividir_treino_teste`]), classifica os pontos restantes e resume as acurácias.
+///
+/// A repetição `r` usa a semente `semente + r` para todas as frações, então a mesma
+/// semente repete a curva inteira. É erro se alguma fração estiver fora de (0.0, 1.0), se
+/// `k` ou `n_repeticoes` forem 0, se os dados estiverem vazios ou se nenhuma classe tiver
+/// pontos suficientes para sobrar algum para o teste.
+///
+/// ```
+/// use aprendizagem_knn::{curva_aprendizado, Ponto};
+///
+/// // Grade 10x10 dividida por uma reta: com poucos pontos a fronteira fica imprecisa
+/// let dados: Vec<Ponto> = (0..100)
+///     .map(|i| {
+///         let (x, y) = ((i % 10) as f64, (i / 10) as f64);
+///         Ponto::novo(vec![x, y], if x + 0.5 * y < 7.0 { "A" } else { "B" }.to_string())
+///     })
+///     .collect();
+///
+/// let curva = curva_aprendizado(&dados, &[0.1, 0.3, 0.7], 1, 10, 42).unwrap();
+/// let tamanhos: Vec<usize> = curva.pontos.iter().map(|ponto| ponto.tamanho_treino).collect();
+/// assert_eq!(tamanhos, vec![10, 30, 70]);
+/// // Mais dados de treinamento, fronteira mais precisa
+/// assert!(curva.pontos[0].media < curva.pontos[1].media && curva.pontos[1].media < curva.pontos[2].media);
+/// assert!(curva.pontos.iter().all(|ponto| ponto.media > 0.5 && ponto.acuracias.len() == 10));
+///
+/// // A mesma semente repete a curva
+/// assert_eq!(curva_aprendizado(&dados, &[0.1, 0.3, 0.7], 1, 10, 42).unwrap(), curva);
+///
+/// assert!(curva_aprendizado(&dados, &[1.0], 1, 10, 42).is_err());
+/// ```
+pub fn curva_aprendizado(
+    dados: &[Ponto],
+    fracoes: &[f64],
+    k: usize,
+    n_repeticoes: usize,
+    semente: u64,
+) -> Result<CurvaAprendizado, ErroKnn> {
+    if let Some(fracao) = fracoes.iter().find(|&&fracao| !(fracao > 0.0 && fracao < 1.0)) {
+        return Err(ErroKnn::ParametroInvalido(format!("as frações devem estar em (0.0, 1.0) (recebido {})", fracao)));
+    }
+    if dados.is_empty() {
+        return Err(ErroKnn::ConjuntoVazio);
+    }
+    if k == 0 {
+        return Err(ErroKnn::KInvalido { k, maximo: dados.len() });
+    }
+    if n_repeticoes == 0 {
+        return Err(ErroKnn::ParametroInvalido("n_repeticoes deve ser maior que zero".to_string()));
+    }
+
+    let mut pontos = Vec::with_capacity(fracoes.len());
+    for &fracao in fracoes {
+        let mut acuracias = Vec::with_capacity(n_repeticoes);
+        let mut tamanho_treino = 0;
+        for repeticao in 0..n_repeticoes {
+            let vai_para_teste = sortear_teste(dados, 1.0 - fracao, semente.wrapping_add(repeticao as u64), true);
+            let teste: Vec<usize> = (0..dados.len()).filter(|&indice| vai_para_teste[indice]).collect();
+            if teste.is_empty() {
+                return Err(ErroKnn::ParametroInvalido(
+                    "nenhuma classe tem pontos suficientes para separar treino e teste".to_string(),
+                ));
+            }
+            // O tamanho por classe não depende do sorteio, então é o mesmo em toda repetição
+            tamanho_treino = dados.len() - teste.len();
+            acuracias.push(avaliar_fold(dados, &teste, k).acuracia);
+        }
+
+        let resumo = ResultadoCV::das_acuracias(acuracias);
+        pontos.push(PontoCurvaAprendizado {
+            fracao,
+            tamanho_treino,
+            media: resumo.media,
+            desvio_padrao: resumo.desvio_padrao,
+            acuracias: resumo.acuracias_por_fold,
+        });
+    }
+
+    Ok(CurvaAprendizado { pontos })
+}
+
+/// Curva de aprendizado por número de pontos de treinamento, em vez de fração: para cada
+/// tamanho em `tamanhos_treino`, sorteia `k_folds` vezes (sem reposição) esse número de
+/// pontos para o treinamento, classifica todos os restantes com `k_vizinhos` vizinhos e
+/// devolve `(tamanho_treino, média das acurácias, desvio padrão)`.
+///
+/// O sorteio é simples (não estratificado), e a repetição `r` usa a semente `semente + r`
+/// para todos os tamanhos. Os itens vêm na ordem de `tamanhos_treino`. É erro se algum
+/// tamanho for 0 ou passar do número de pontos; como é preciso sobrar ao menos um ponto
+/// para o teste, usar todos os pontos também é erro. Também é erro se `k_vizinhos` ou
+/// `k_folds` forem 0.
+///
+/// ```
+/// use aprendizagem_knn::{curva_aprendizado_por_tamanho, Ponto};
+///
+/// // Grade 10x10 dividida por uma reta: com poucos pontos a fronteira fica imprecisa
+/// let dados: Vec<Ponto> = (0..100)
+///     .map(|i| {
+///         let (x, y) = ((i % 10) as f64, (i / 10) as f64);
+///         Ponto::novo(vec![x, y], if x + 0.5 * y < 7.0 { "A" } else { "B" }.to_string())
+///     })
+///     .collect();
+///
+/// let curva = curva_aprendizado_por_tamanho(&dados, &[5, 20, 80], 1, 10, 42).unwrap();
+/// assert_eq!(curva.iter().map(|&(tamanho, _, _)| tamanho).collect::<Vec<_>>(), vec![5, 20, 80]);
+/// // Mais pontos de treinamento, mais acertos
+/// assert!(curva[0].1 < curva[1].1 && curva[1].1 < curva[2].1);
+///
+/// assert!(curva_aprendizado_por_tamanho(&dados, &[101], 1, 10, 42).is_err());
+/// assert!(curva_aprendizado_por_tamanho(&dados, &[100], 1, 10, 42).is_err());
+/// ```
+pub fn curva_aprendizado_por_tamanho(
+    dados: &[Ponto],
+    tamanhos_treino: &[usize],
+    k_vizinhos: usize,
+    k_folds: usize,
+    semente: u64,
+) -> Result<Vec<(usize, f64, f64)>, ErroKnn> {
+    if let Some(&tamanho) = tamanhos_treino.iter().find(|&&tamanho| tamanho == 0 || tamanho >= dados.len()) {
+        return Err(ErroKnn::ParametroInvalido(format!(
+            "os tamanhos de treino devem estar entre 1 e {} (o número de pontos menos um, para sobrar um ponto \
+             de teste), recebido {}",
+            dados.len().saturating_sub(1),
+            tamanho
+        )));
+    }
+    if k_vizinhos == 0 {
+        return Err(ErroKnn::KInvalido { k: k_vizinhos, maximo: dados.len() });
+    }
+    if k_folds == 0 {
+        return Err(ErroKnn::ParametroInvalido("k_folds deve ser maior que zero".to_string()));
+    }
+
+    let curva = tamanhos_treino.iter()
+        .map(|&tamanho| {
+            let acuracias = (0..k_folds as u64)
+                .map(|repeticao| {
+                    // Os pontos que não foram sorteados para o treinamento formam o teste
+                    let mut no_treino = vec![false; dados.len()];
+                    for indice in Rng::novo(semente.wrapping_add(repeticao)).amostrar_indices(dados.len(), tamanho) {
+                        no_treino[indice] = true;
+                    }
+                    let teste: Vec<usize> = (0..dados.len()).filter(|&indice| !no_treino[indice]).collect();
+                    avaliar_fold(dados, &teste, k_vizinhos).acuracia
+                })
+                .collect();
+            let resumo = ResultadoCV::das_acuracias(acuracias);
+            (tamanho, resumo.media, resumo.desvio_padrao)
+        })
+        .collect();
+
+    Ok(curva)
+}
@@ -0,0 +1,79 @@
+//! Funções para usar o KNN no navegador via WebAssembly (feature `wasm`).
+//!
+//! Compile com `wasm-pack build --target web --features wasm --out-dir www/pkg` e abra
+//! a demonstração da pasta `www/`. Os pontos trafegam entre JavaScript e Rust como JSON.
+
+use wasm_bindgen::prelude::*;
+
+use crate::dados::{carregar_dados_de_texto_csv, ORIGEM_TEXTO};
+use crate::erro::ErroKnn;
+use crate::knn::knn;
+use crate::ponto::Ponto;
+
+// ==================== FUNÇÕES EXPORTADAS PARA JAVASCRIPT ====================
+// #[wasm_bindgen] gera a "cola" que converte os tipos entre JavaScript e Rust
+// (&str <-> string, &[f64] <-> Float64Array, usize <-> number).
+// Um Err vira uma exceção em JavaScript, com a mensagem do erro.
+
+/// Classifica um ponto com o KNN a partir de um treinamento em JSON
+/// (um array de objetos `{"caracteristicas": [...], "rotulo": "..."}`).
+///
+/// Devolve o rótulo previsto; JSON inválido, treinamento vazio, `k` zero ou número de
+/// características diferente do treinamento viram exceção no JavaScript.
+#[wasm_bindgen]
+pub fn knn_wasm(
+    treinamento_json: &str,
+    caracteristicas_teste: &[f64],
+    rotulo_teste: &str,
+    k: usize,
+) -> Result<String, JsError> {
+    classificar_json(treinamento_json, caracteristicas_teste, rotulo_teste, k)
+        .map_err(|erro| JsError::new(&erro.to_string()))
+}
+
+/// Lê o conteúdo de um CSV (o texto, não um caminho) e devolve os pontos como um
+/// array JSON, pronto para ser passado a [`knn_wasm`].
+///
+/// O formato do CSV é o de [`carregar_dados_de_texto_csv`],
+/// mas sem valores ausentes, que não têm representação em JSON.
+#[wasm_bindgen]
+pub fn carregar_csv_wasm(conteudo_csv: &str) -> Result<String, JsError> {
+    converter_csv_para_json(conteudo_csv).map_err(|erro| JsError::new(&erro.to_string()))
+}
+
+// As funções abaixo fazem o trabalho de verdade com o ErroKnn, para que a lógica
+// possa ser testada fora do navegador (JsError só existe dentro do wasm)
+
+fn classificar_json(
+    treinamento_json: &str,
+    caracteristicas_teste: &[f64],
+    rotulo_teste: &str,
+    k: usize,
+) -> Result<String, ErroKnn> {
+    let treinamento: Vec<Ponto> = serde_json::from_str(treinamento_json)
+        .map_err(|erro| ErroKnn::ParametroInvalido(format!("o treinamento não é um JSON de pontos válido: {}", erro)))?;
+    let primeiro = treinamento.first().ok_or(ErroKnn::ConjuntoVazio)?;
+    if caracteristicas_teste.len() != primeiro.caracteristicas.len() {
+        return Err(ErroKnn::DimensaoIncompativel {
+            esperado: primeiro.caracteristicas.len(),
+            obtido: caracteristicas_teste.len(),
+        });
+    }
+
+    let ponto_teste = Ponto::novo(caracteristicas_teste.to_vec(), rotulo_teste.to_string());
+    // k = 0 é recusado pelo próprio knn
+    knn(&treinamento, &ponto_teste, k)
+}
+
+fn converter_csv_para_json(conteudo_csv: &str) -> Result<String, ErroKnn> {
+    let pontos = carregar_dados_de_texto_csv(conteudo_csv)?;
+    // JSON não tem NaN (serde_json o escreveria como null), então ausentes são recusados aqui
+    if let Some(posicao) = pontos.iter().position(|ponto| ponto.caracteristicas.iter().any(|valor| valor.is_nan())) {
+        return Err(ErroKnn::Arquivo(format!(
+            "{}: o ponto {} tem valores ausentes, que não são aceitos aqui",
+            ORIGEM_TEXTO,
+            posicao + 1
+        )));
+    }
+    serde_json::to_string(&pontos).map_err(|erro| ErroKnn::Arquivo(format!("{}: {}", ORIGEM_TEXTO, erro)))
+}
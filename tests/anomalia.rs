@@ -0,0 +1,32 @@
+// Testes de integração da detecção de anomalias: a distância de Canberra enxerga desvios
+// em características de escala pequena que a euclidiana deixa passar
+
+use aprendizagem_knn::{comparar_scores_anomalia, ponto, Ponto};
+
+// Pontos normais com uma característica na casa dos milhares e outra na dos centésimos
+fn normais() -> Vec<Ponto> {
+    (0..10).map(|i| ponto!([1000.0 + i as f64, 0.010 + 0.0002 * i as f64], "")).collect()
+}
+
+#[test]
+fn desvio_numa_caracteristica_pequena_so_aparece_no_canberra() {
+    let normais = normais();
+    let tipico = comparar_scores_anomalia(&normais, &ponto!([1004.5, 0.011], ""), 3);
+    // Só a segunda característica muda, e fica dez vezes maior
+    let desviado = comparar_scores_anomalia(&normais, &ponto!([1004.5, 0.11], ""), 3);
+
+    // Na euclidiana o desvio de 0.1 some perto das diferenças de 1 na primeira característica
+    assert!(desviado.0 < tipico.0 + 0.2, "euclidiana: {} e {}", tipico.0, desviado.0);
+    // Na de Canberra a pontuação passa de 20 vezes a de um ponto típico
+    assert!(desviado.1 > 20.0 * tipico.1, "canberra: {} e {}", tipico.1, desviado.1);
+}
+
+#[test]
+fn desvio_numa_caracteristica_grande_pesa_mais_na_euclidiana() {
+    let normais = normais();
+    // As duas anomalias invertem de posição conforme a métrica
+    let grande = comparar_scores_anomalia(&normais, &ponto!([1050.0, 0.011], ""), 3);
+    let pequena = comparar_scores_anomalia(&normais, &ponto!([1004.5, 0.11], ""), 3);
+    assert!(grande.0 > pequena.0, "euclidiana: {:?} {:?}", grande, pequena);
+    assert!(grande.1 < pequena.1, "canberra: {:?} {:?}", grande, pequena);
+}
@@ -0,0 +1,116 @@
+// Testes de integração da leitura de arquivos ARFF (o formato do Weka), a partir do arquivo
+// de exemplo em tests/fixtures e de cabeçalhos mal formados
+
+use aprendizagem_knn::{carregar_dados_de_texto_arff, carregar_dados_do_arff, knn, ponto, ErroKnn};
+
+const FLORES: &str = "tests/fixtures/flores.arff";
+
+fn erro_do_texto(texto: &str) -> String {
+    match carregar_dados_de_texto_arff(texto, None) {
+        Err(ErroKnn::Arquivo(mensagem)) => mensagem,
+        outro => panic!("esperava ErroKnn::Arquivo, veio {:?}", outro),
+    }
+}
+
+#[test]
+fn carrega_caracteristicas_numericas_e_classe_nominal() {
+    let dados = carregar_dados_do_arff(FLORES, None).unwrap();
+    assert_eq!(dados.len(), 6);
+
+    let rotulos: Vec<&str> = dados.iter().map(|ponto| ponto.rotulo.as_str()).collect();
+    assert_eq!(
+        rotulos,
+        vec!["Iris-setosa", "Iris-setosa", "Iris-versicolor", "Iris-versicolor", "Iris virginica", "Iris virginica"]
+    );
+    assert_eq!(dados[0].caracteristicas, vec![5.1, 3.5, 1.4, 0.0]);
+    assert_eq!(dados[5].caracteristicas, vec![5.8, 2.7, 5.1, 2.0]);
+}
+
+#[test]
+fn interrogacao_vira_nan() {
+    let dados = carregar_dados_do_arff(FLORES, None).unwrap();
+    let com_ausente = &dados[3].caracteristicas;
+    assert_eq!(com_ausente[0], 6.4);
+    assert!(com_ausente[1].is_nan());
+    assert_eq!(com_ausente[2..], [4.5, 2.0]);
+    assert_eq!(dados.iter().flat_map(|ponto| &ponto.caracteristicas).filter(|v| v.is_nan()).count(), 1);
+}
+
+#[test]
+fn knn_classifica_com_os_dados_do_arff() {
+    let dados: Vec<_> = carregar_dados_do_arff(FLORES, None)
+        .unwrap()
+        .into_iter()
+        .filter(|ponto| ponto.caracteristicas.iter().all(|v| !v.is_nan()))
+        .collect();
+    assert_eq!(knn(&dados, &ponto!([5.0, 3.4, 1.5, 0.0], "?"), 1).unwrap(), "Iris-setosa");
+    assert_eq!(knn(&dados, &ponto!([6.1, 3.0, 5.6, 2.5], "?"), 1).unwrap(), "Iris virginica");
+}
+
+#[test]
+fn cabecalho_mal_formado() {
+    // Tipo desconhecido
+    let erro = erro_do_texto("@relation r\n@attribute x numerico\n@attribute c {A}\n@data\n1, A\n");
+    assert!(erro.contains("linha 2") && erro.contains("tipo desconhecido"), "{}", erro);
+
+    // Lista nominal sem fechar
+    let erro = erro_do_texto("@attribute x numeric\n@attribute c {A, B\n@data\n1, A\n");
+    assert!(erro.contains("linha 2") && erro.contains("'}'"), "{}", erro);
+
+    // Atributo sem tipo
+    let erro = erro_do_texto("@attribute x\n@attribute c {A}\n@data\n");
+    assert!(erro.contains("linha 1") && erro.contains("não tem tipo"), "{}", erro);
+
+    // Atributo repetido
+    let erro = erro_do_texto("@attribute x real\n@attribute x real\n@attribute c {A}\n@data\n");
+    assert!(erro.contains("linha 2") && erro.contains("já foi declarado na linha 1"), "{}", erro);
+
+    // Sem @data
+    let erro = erro_do_texto("@relation r\n@attribute x real\n@attribute c {A}\n");
+    assert!(erro.contains("@data"), "{}", erro);
+
+    // Declaração desconhecida
+    let erro = erro_do_texto("@relation r\n@atribute x real\n@data\n");
+    assert!(erro.contains("linha 2") && erro.contains("@atribute"), "{}", erro);
+
+    // Só a classe, sem características
+    let erro = erro_do_texto("@attribute c {A}\n@data\nA\n");
+    assert!(erro.contains("dois atributos"), "{}", erro);
+}
+
+#[test]
+fn atributos_nao_numericos_sao_recusados() {
+    let erro = erro_do_texto("@attribute nome string\n@attribute x real\n@attribute c {A}\n@data\n'a', 1, A\n");
+    assert!(erro.contains("linha 1") && erro.contains("'nome' é string"), "{}", erro);
+}
+
+#[test]
+fn erros_nos_dados_citam_a_linha() {
+    let cabecalho = "@attribute x real\n@attribute c {A, B}\n@data\n";
+
+    let erro = erro_do_texto(&format!("{}1, A\n1, 2, A\n", cabecalho));
+    assert!(erro.contains("linha 5") && erro.contains("3 valores, mas há 2 atributos"), "{}", erro);
+
+    let erro = erro_do_texto(&format!("{}abc, A\n", cabecalho));
+    assert!(erro.contains("linha 4") && erro.contains("'x'") && erro.contains("'abc'"), "{}", erro);
+
+    let erro = erro_do_texto(&format!("{}1, ?\n", cabecalho));
+    assert!(erro.contains("linha 4") && erro.contains("ausente"), "{}", erro);
+
+    let erro = erro_do_texto(&format!("{}1, 'A\n", cabecalho));
+    assert!(erro.contains("linha 4") && erro.contains("aspas"), "{}", erro);
+
+    let erro = erro_do_texto(&format!("{}{{0 1, 1 A}}\n", cabecalho));
+    assert!(erro.contains("esparso"), "{}", erro);
+}
+
+#[test]
+fn classe_escolhida_pelo_nome() {
+    let texto = "@attribute c {A, B}\n@attribute x real\n@data\nB, 2\n";
+    let dados = carregar_dados_de_texto_arff(texto, Some("c")).unwrap();
+    assert_eq!(dados[0].rotulo, "B");
+    assert_eq!(dados[0].caracteristicas, vec![2.0]);
+
+    let erro = carregar_dados_de_texto_arff(texto, Some("especie")).unwrap_err().to_string();
+    assert!(erro.contains("'especie'"), "{}", erro);
+}
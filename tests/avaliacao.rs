@@ -0,0 +1,55 @@
+// Testes de integração do bootstrap da acurácia: num conjunto em que a acurácia verdadeira
+// é conhecida de antemão, o intervalo de confiança precisa contê-la
+
+use aprendizagem_knn::{bootstrap_acuracia, Ponto};
+
+// Duas classes bem separadas (A perto de 0, B perto de 10): o knn com k = 1 sempre devolve
+// a classe da região, então só erra os pontos de teste rotulados de propósito com a outra
+fn treinamento() -> Vec<Ponto> {
+    (0..10)
+        .flat_map(|i| {
+            let deslocamento = i as f64 * 0.1;
+            [
+                Ponto::novo(vec![deslocamento, deslocamento], "A".to_string()),
+                Ponto::novo(vec![10.0 + deslocamento, 10.0 + deslocamento], "B".to_string()),
+            ]
+        })
+        .collect()
+}
+
+// n_pontos de teste, dos quais os n_errados primeiros levam o rótulo da outra classe:
+// a acurácia verdadeira é exatamente 1 - n_errados / n_pontos
+fn teste_com_erros(n_pontos: usize, n_errados: usize) -> Vec<Ponto> {
+    (0..n_pontos)
+        .map(|i| {
+            let (centro, certo, errado) = if i % 2 == 0 { (0.5, "A", "B") } else { (10.5, "B", "A") };
+            let rotulo = if i < n_errados { errado } else { certo };
+            Ponto::novo(vec![centro, centro], rotulo.to_string())
+        })
+        .collect()
+}
+
+#[test]
+fn intervalo_do_bootstrap_contem_a_acuracia_verdadeira() {
+    let treinamento = treinamento();
+    for (n_pontos, n_errados) in [(100, 20), (60, 15), (50, 5)] {
+        let teste = teste_com_erros(n_pontos, n_errados);
+        let verdadeira = 1.0 - n_errados as f64 / n_pontos as f64;
+
+        for semente in [1, 7, 42] {
+            let (media, inferior, superior) = bootstrap_acuracia(&treinamento, &teste, 1, 500, semente).unwrap();
+            assert!(
+                inferior <= verdadeira && verdadeira <= superior,
+                "{verdadeira} fora de [{inferior}, {superior}] (n = {n_pontos}, semente = {semente})"
+            );
+            // A média das réplicas fica perto da acurácia observada
+            assert!((media - verdadeira).abs() < 0.05, "média {media}, verdadeira {verdadeira}");
+        }
+    }
+}
+
+#[test]
+fn sem_erros_o_intervalo_se_reduz_a_um_ponto() {
+    let (media, inferior, superior) = bootstrap_acuracia(&treinamento(), &teste_com_erros(40, 0), 1, 200, 3).unwrap();
+    assert_eq!((media, inferior, superior), (1.0, 1.0, 1.0));
+}
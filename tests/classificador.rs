@@ -0,0 +1,119 @@
+// Testes de integração do ClassificadorKnn: os padrões do construtor, as configurações
+// inválidas e a classificação de ponta a ponta, comparada com as funções livres
+
+use aprendizagem_knn::{
+    calcular_k, knn, knn_com_votacao, knn_preprocessado, ponto, pontos, ClassificadorKnn, DistanciaMetrica, ErroKnn,
+    Ponto, Preprocessamento, Votacao,
+};
+
+// Duas classes separadas na primeira característica; a segunda tem escala muito maior
+// e só atrapalha sem normalização
+fn dados() -> Vec<Ponto> {
+    pontos![
+        [0.0, 900.0] => "A", [1.0, 100.0] => "A", [2.0, 500.0] => "A", [1.5, 300.0] => "A",
+        [8.0, 200.0] => "B", [9.0, 800.0] => "B", [10.0, 400.0] => "B", [8.5, 600.0] => "B",
+    ]
+}
+
+#[test]
+fn construtor_sem_parametros_usa_os_padroes_do_knn() {
+    let mut classificador = ClassificadorKnn::builder().construir().unwrap();
+    assert_eq!(classificador.k(), None);
+    assert_eq!(classificador.metrica(), &DistanciaMetrica::Euclidiana);
+    assert_eq!(classificador.votacao(), Votacao::Maioria);
+    assert_eq!(classificador.normalizacao(), Preprocessamento::Nenhum);
+
+    // Com os padrões, o classificador é o knn com o k de calcular_k
+    let dados = dados();
+    classificador.treinar(dados.clone()).unwrap();
+    for teste in pontos![[1.0, 850.0] => "?", [9.0, 150.0] => "?", [5.0, 500.0] => "?"] {
+        assert_eq!(classificador.classificar(&teste), knn(&dados, &teste, calcular_k(dados.len())));
+    }
+}
+
+#[test]
+fn configuracoes_invalidas_sao_recusadas_no_construir() {
+    let parametro_invalido = |resultado: Result<ClassificadorKnn, ErroKnn>| {
+        matches!(resultado.unwrap_err(), ErroKnn::ParametroInvalido(_))
+    };
+    assert!(parametro_invalido(ClassificadorKnn::builder().k(0).construir()));
+    assert!(parametro_invalido(ClassificadorKnn::builder().metrica(DistanciaMetrica::Minkowski(-1.0)).construir()));
+    let pesos_negativos = DistanciaMetrica::EuclidianaPonderada(vec![1.0, -2.0]);
+    assert!(parametro_invalido(ClassificadorKnn::builder().metrica(pesos_negativos).construir()));
+    assert!(parametro_invalido(ClassificadorKnn::builder().normalizacao(Preprocessamento::Pca(0)).construir()));
+    let ponderada_com_pca = ClassificadorKnn::builder()
+        .metrica(DistanciaMetrica::EuclidianaPonderada(vec![1.0, 1.0]))
+        .normalizacao(Preprocessamento::Pca(1));
+    assert!(parametro_invalido(ponderada_com_pca.construir()));
+
+    // Cada parâmetro sozinho é válido
+    assert!(ClassificadorKnn::builder().normalizacao(Preprocessamento::Pca(1)).construir().is_ok());
+    let ponderada = DistanciaMetrica::EuclidianaPonderada(vec![1.0, 1.0]);
+    assert!(ClassificadorKnn::builder().metrica(ponderada).normalizacao(Preprocessamento::ZScore).construir().is_ok());
+}
+
+#[test]
+fn classificar_antes_de_treinar_e_nao_treinado() {
+    let mut classificador = ClassificadorKnn::builder().k(3).construir().unwrap();
+    let teste = ponto!([1.0, 1.0], "?");
+    assert_eq!(classificador.classificar(&teste), Err(ErroKnn::NaoTreinado));
+    assert_eq!(classificador.classificar_lote(std::slice::from_ref(&teste)), Err(ErroKnn::NaoTreinado));
+
+    // Um treinamento que falha não deixa o classificador treinado
+    assert_eq!(classificador.treinar(Vec::new()), Err(ErroKnn::ConjuntoVazio));
+    assert_eq!(classificador.classificar(&teste), Err(ErroKnn::NaoTreinado));
+}
+
+#[test]
+fn erros_de_dimensao_no_treinamento_e_na_classificacao() {
+    let ponderada = DistanciaMetrica::EuclidianaPonderada(vec![1.0, 1.0, 1.0]);
+    let mut classificador = ClassificadorKnn::builder().metrica(ponderada).construir().unwrap();
+    // Três pesos para duas características
+    let erro = classificador.treinar(dados()).unwrap_err();
+    assert_eq!(erro, ErroKnn::DimensaoIncompativel { esperado: 2, obtido: 3 });
+
+    let mut classificador = ClassificadorKnn::builder().normalizacao(Preprocessamento::Pca(3)).construir().unwrap();
+    assert!(classificador.treinar(dados()).is_err());
+
+    let mut classificador = ClassificadorKnn::builder().construir().unwrap();
+    classificador.treinar(dados()).unwrap();
+    let testes = pontos![[1.0, 1.0] => "?", [1.0] => "?"];
+    let erro = classificador.classificar_lote(&testes).unwrap_err();
+    assert_eq!(erro, ErroKnn::DimensaoIncompativel { esperado: 2, obtido: 1 });
+}
+
+#[test]
+fn classifica_de_ponta_a_ponta_como_as_funcoes_livres() {
+    let dados = dados();
+    let testes = pontos![[1.0, 850.0] => "A", [9.0, 150.0] => "B", [2.5, 700.0] => "A", [7.5, 50.0] => "B"];
+
+    let mut classificador = ClassificadorKnn::builder()
+        .k(3)
+        .metrica(DistanciaMetrica::Manhattan)
+        .votacao(Votacao::InversoDaDistancia)
+        .normalizacao(Preprocessamento::ZScore)
+        .construir()
+        .unwrap();
+    classificador.treinar(dados.clone()).unwrap();
+    let previstos = classificador.classificar_lote(&testes).unwrap();
+    let reais: Vec<String> = testes.iter().map(|ponto| ponto.rotulo.clone()).collect();
+    assert_eq!(previstos, reais);
+
+    // Sem normalização, a segunda característica decide e os testes erram
+    let manhattan = DistanciaMetrica::Manhattan;
+    let sem_normalizar: Vec<String> = testes
+        .iter()
+        .map(|teste| knn_com_votacao(&dados, teste, 3, &manhattan, Votacao::InversoDaDistancia).unwrap())
+        .collect();
+    assert_ne!(sem_normalizar, reais);
+
+    // Com a votação por maioria e a euclidiana, é o knn_preprocessado
+    let min_max = Preprocessamento::MinMax;
+    let mut classificador = ClassificadorKnn::builder().k(3).normalizacao(min_max).construir().unwrap();
+    classificador.treinar(dados.clone()).unwrap();
+    assert_eq!(classificador.classificar_lote(&testes), knn_preprocessado(&dados, &testes, 3, min_max));
+
+    // Treinar de novo substitui o treinamento
+    classificador.treinar(pontos![[0.0, 0.0] => "C", [1.0, 1.0] => "C"]).unwrap();
+    assert_eq!(classificador.classificar(&testes[0]), Ok("C".to_string()));
+}
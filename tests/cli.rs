@@ -0,0 +1,387 @@
+// Testes de integração: executam o binário aprendizagemKNN com argumentos de verdade
+// e conferem a saída e o código de retorno (o binário só existe com a feature cli)
+#![cfg(feature = "cli")]
+
+use assert_cmd::Command;
+
+// O binário do pacote, já compilado pelo cargo test
+fn programa() -> Command {
+    Command::cargo_bin("aprendizagemKNN").expect("o binário aprendizagemKNN deve existir")
+}
+
+#[test]
+fn ajuda_lista_as_opcoes() {
+    let saida = programa().arg("--help").assert().success().get_output().stdout.clone();
+    let texto = String::from_utf8(saida).unwrap();
+    for opcao in ["--dados", "--k", "--metrica", "--ponto"] {
+        assert!(texto.contains(opcao), "a ajuda não menciona {}:\n{}", opcao, texto);
+    }
+}
+
+#[test]
+fn classifica_com_os_argumentos_informados() {
+    let saida = programa()
+        .args(["--dados", "src/dados.csv", "--k", "3", "--metrica", "manhattan", "--ponto", "4.5,8.0"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let texto = String::from_utf8(saida).unwrap();
+    assert!(texto.contains("Rótulo previsto para os dados de teste [4.5, 8.0] é Classe"), "{}", texto);
+}
+
+#[test]
+fn ponto_com_dimensao_errada_e_recusado() {
+    let saida = programa().args(["--ponto", "1.0,2.0,3.0"]).assert().failure().get_output().stderr.clone();
+    let texto = String::from_utf8(saida).unwrap();
+    assert!(texto.contains("--ponto tem 3 valor(es)"), "{}", texto);
+    assert!(texto.contains("têm 2 característica(s)"), "{}", texto);
+}
+
+#[test]
+fn metrica_desconhecida_e_recusada() {
+    let saida = programa().args(["--metrica", "hamming"]).assert().failure().get_output().stderr.clone();
+    assert!(String::from_utf8(saida).unwrap().contains("métrica desconhecida 'hamming'"));
+}
+
+// Arquivo temporário com um nome próprio de cada teste, para que testes em paralelo não se atrapalhem
+fn arquivo_temporario(nome: &str) -> String {
+    std::env::temp_dir().join(format!("aprendizagem_knn_cli_{}", nome)).to_str().unwrap().to_string()
+}
+
+#[test]
+fn classifica_um_csv_de_teste_e_grava_as_previsoes() {
+    let teste = arquivo_temporario("teste.csv");
+    let saida = arquivo_temporario("previsoes.csv");
+    std::fs::write(&teste, "feature1,feature2\n4.5,8.0\n1,2\n9.25,9\n").unwrap();
+
+    programa()
+        .args(["--k", "3", "--teste", &teste, "--saida", &saida, "--confianca"])
+        .assert()
+        .success();
+
+    let conteudo = std::fs::read_to_string(&saida).unwrap();
+    let linhas: Vec<&str> = conteudo.lines().collect();
+    assert_eq!(linhas[0], "feature1,feature2,rotulo_previsto,confianca");
+    assert_eq!(linhas.len(), 4);
+    // As características saem como estavam no teste, na mesma ordem
+    for (linha, inicio) in linhas[1..].iter().zip(["4.5,8.0,Classe ", "1,2,Classe ", "9.25,9,Classe "]) {
+        assert!(linha.starts_with(inicio), "{}", linha);
+        let confianca: f64 = linha.rsplit(',').next().unwrap().parse().unwrap();
+        assert!(confianca > 0.0 && confianca <= 1.0);
+    }
+}
+
+#[test]
+fn linha_invalida_no_teste_cita_o_numero_da_linha() {
+    let teste = arquivo_temporario("teste_invalido.csv");
+    let saida = arquivo_temporario("previsoes_invalidas.csv");
+    std::fs::write(&teste, "feature1,feature2\n4.5,8.0\n1,abc\n").unwrap();
+
+    let erro = programa().args(["--teste", &teste, "--saida", &saida]).assert().failure().get_output().stderr.clone();
+    assert!(String::from_utf8(erro).unwrap().contains("linha 3"));
+}
+
+#[test]
+fn le_dados_com_outro_delimitador() {
+    let casos: [&[&str]; 3] = [
+        &["--dados", "tests/fixtures/delimitador_ponto_e_virgula.csv", "--delimitador", ";"],
+        &["--dados", "tests/fixtures/delimitador_tab.tsv", "--delimitador", "tab"],
+        &["--dados", "tests/fixtures/delimitador_tab.tsv", "--detectar-delimitador", "--aspas", "\""],
+    ];
+    for argumentos in casos {
+        let saida = programa().args(argumentos).args(["--k", "1", "--ponto", "5.0,3.4"]).assert().success();
+        let texto = String::from_utf8(saida.get_output().stdout.clone()).unwrap();
+        assert!(texto.contains("é setosa"), "{}", texto);
+    }
+
+    // Sem cabeçalho, a primeira linha ("comprimento", ...) é lida como um ponto e não é numérica
+    let erro = programa()
+        .args(["--dados", "tests/fixtures/delimitador_tab.tsv", "--delimitador", "tab", "--sem-cabecalho"])
+        .assert()
+        .failure()
+        .get_output()
+        .stderr
+        .clone();
+    assert!(String::from_utf8(erro).unwrap().contains("linha 1"));
+}
+
+#[test]
+fn le_dados_com_virgula_decimal() {
+    let saida = programa()
+        .args(["--dados", "tests/fixtures/virgula_decimal.csv", "--delimitador", ";", "--virgula-decimal"])
+        .args(["--k", "1", "--ponto", "5.0,3.4"])
+        .assert()
+        .success();
+    let texto = String::from_utf8(saida.get_output().stdout.clone()).unwrap();
+    assert!(texto.contains("é setosa"), "{}", texto);
+}
+
+#[test]
+fn teste_e_lido_com_as_mesmas_opcoes_de_csv_do_treinamento() {
+    // Ponto e vírgula e vírgula decimal, como no treinamento; a saída usa o mesmo separador
+    let teste = arquivo_temporario("teste_virgula_decimal.csv");
+    let saida = arquivo_temporario("previsoes_virgula_decimal.csv");
+    std::fs::write(&teste, "comprimento;largura\n5,0;3,4\n1.234,5;-0,2\n").unwrap();
+    programa()
+        .args(["--dados", "tests/fixtures/virgula_decimal.csv", "--delimitador", ";", "--virgula-decimal"])
+        .args(["--k", "1", "--teste", &teste, "--saida", &saida, "--confianca"])
+        .assert()
+        .success();
+    assert_eq!(
+        std::fs::read_to_string(&saida).unwrap(),
+        "comprimento;largura;rotulo_previsto;confianca\n5,0;3,4;setosa;1\n1.234,5;-0,2;\"grande; \"\"rara\"\"\";1\n"
+    );
+
+    // Delimitador detectado em cada arquivo: o treinamento é separado por tabulações e o
+    // teste, por '|' (com o separador dentro de um nome de coluna entre aspas)
+    let teste = arquivo_temporario("teste_barra.csv");
+    let saida = arquivo_temporario("previsoes_barra.csv");
+    std::fs::write(&teste, "comprimento|\"largura | cm\"\n5.0|3.4\n7.1|3.2\n").unwrap();
+    programa()
+        .args(["--dados", "tests/fixtures/delimitador_tab.tsv", "--detectar-delimitador"])
+        .args(["--k", "1", "--teste", &teste, "--saida", &saida])
+        .assert()
+        .success();
+    assert_eq!(
+        std::fs::read_to_string(&saida).unwrap(),
+        concat!(
+            "comprimento|\"largura | cm\"|rotulo_previsto\n",
+            "5.0|3.4|setosa\n",
+            "7.1|3.2|\"versicolor; \"\"comum\"\", de jardim\"\n",
+        )
+    );
+}
+
+#[test]
+fn le_dados_em_json() {
+    let dados = arquivo_temporario("dados.json");
+    std::fs::write(
+        &dados,
+        r#"[{"caracteristicas": [0, 0], "rotulo": "perto"}, {"caracteristicas": [10, 10], "rotulo": "longe"}]"#,
+    ).unwrap();
+    let saida = programa().args(["--dados", &dados, "--k", "1", "--ponto", "1,2"]).assert().success();
+    let texto = String::from_utf8(saida.get_output().stdout.clone()).unwrap();
+    assert!(texto.contains("é perto"), "{}", texto);
+}
+
+#[test]
+fn le_dados_em_libsvm_pela_extensao() {
+    let saida = programa()
+        .args(["--dados", "tests/fixtures/pequeno.libsvm", "--k", "3", "--ponto", "0.1,0,5,5"])
+        .assert()
+        .success();
+    let texto = String::from_utf8(saida.get_output().stdout.clone()).unwrap();
+    assert!(texto.contains("é -1"), "{}", texto);
+
+    // .svm também é LIBSVM; um índice 0 é erro, com a linha
+    let dados = arquivo_temporario("dados.svm");
+    std::fs::write(&dados, "A 1:0.5
+B 0:1.0
+").unwrap();
+    let saida = programa().args(["--dados", &dados, "--ponto", "1"]).assert().failure();
+    let erro = String::from_utf8(saida.get_output().stderr.clone()).unwrap();
+    assert!(erro.contains("linha 2") && erro.contains("começam em 1"), "{}", erro);
+}
+
+#[test]
+fn le_o_treinamento_da_entrada_padrao() {
+    let saida = programa()
+        .args(["--stdin", "--k", "1", "--ponto", "1,2"])
+        .write_stdin("x,y,classe\n0,0,perto\n10,10,longe\n")
+        .assert()
+        .success();
+    let texto = String::from_utf8(saida.get_output().stdout.clone()).unwrap();
+    assert!(texto.contains("é perto"), "{}", texto);
+
+    // As opções de CSV também valem para a entrada padrão
+    let saida = programa()
+        .args(["--stdin", "--detectar-delimitador", "--virgula-decimal", "--k", "1", "--ponto", "9,9"])
+        .write_stdin("x;y;classe\n0,5;0;perto\n10,5;10;longe\n")
+        .assert()
+        .success();
+    let texto = String::from_utf8(saida.get_output().stdout.clone()).unwrap();
+    assert!(texto.contains("é longe"), "{}", texto);
+}
+
+#[test]
+fn le_o_teste_da_entrada_padrao() {
+    let saida = arquivo_temporario("previsoes_stdin.csv");
+    let resultado = programa()
+        .args(["--k", "3", "--teste", "-", "--saida", &saida])
+        .write_stdin("feature1,feature2\n4.5,8.0\n1,2\n")
+        .assert()
+        .success();
+    let texto = String::from_utf8(resultado.get_output().stdout.clone()).unwrap();
+    assert!(texto.contains("2 pontos de <stdin> classificados"), "{}", texto);
+    let conteudo = std::fs::read_to_string(&saida).unwrap();
+    assert!(conteudo.starts_with("feature1,feature2,rotulo_previsto\n4.5,8.0,Classe "), "{}", conteudo);
+    assert_eq!(conteudo.lines().count(), 3);
+}
+
+#[test]
+fn entrada_padrao_vazia_e_um_erro_claro() {
+    for entrada in ["", "x,y,classe\n"] {
+        let erro = programa().arg("--stdin").write_stdin(entrada).assert().failure().get_output().stderr.clone();
+        let texto = String::from_utf8(erro).unwrap();
+        assert!(texto.contains("vazio") && !texto.contains("panicked"), "{}", texto);
+    }
+
+    let saida = arquivo_temporario("previsoes_stdin_vazio.csv");
+    let erro = programa()
+        .args(["--teste", "-", "--saida", &saida])
+        .write_stdin("")
+        .assert()
+        .failure()
+        .get_output()
+        .stderr
+        .clone();
+    assert!(String::from_utf8(erro).unwrap().contains("vazio"));
+}
+
+#[test]
+fn stdin_nao_pode_ser_usado_duas_vezes() {
+    let saida = arquivo_temporario("previsoes_stdin_duplo.csv");
+    let erro = programa()
+        .args(["--stdin", "--teste", "-", "--saida", &saida])
+        .write_stdin("x,classe\n1,A\n")
+        .assert()
+        .failure()
+        .get_output()
+        .stderr
+        .clone();
+    assert!(String::from_utf8(erro).unwrap().contains("--stdin e --teste -"));
+}
+
+#[test]
+fn erros_mostram_a_mensagem_e_nao_a_variante() {
+    let erro = programa()
+        .args(["--dados", "tests/fixtures/nao_existe.csv"])
+        .assert()
+        .failure()
+        .get_output()
+        .stderr
+        .clone();
+    let texto = String::from_utf8(erro).unwrap();
+    assert!(texto.contains("tests/fixtures/nao_existe.csv: arquivo não encontrado"), "{}", texto);
+    assert!(!texto.contains("ArquivoNaoEncontrado"), "{}", texto);
+
+    let saida = arquivo_temporario("previsoes_dimensao.csv");
+    let erro = programa()
+        .args(["--teste", "tests/fixtures/teste_3_colunas.csv", "--saida", &saida])
+        .assert()
+        .failure()
+        .get_output()
+        .stderr
+        .clone();
+    let texto = String::from_utf8(erro).unwrap();
+    assert!(texto.contains("tests/fixtures/teste_3_colunas.csv: dimensão incompatível"), "{}", texto);
+}
+
+#[test]
+fn treina_salva_o_modelo_e_preve_com_ele() {
+    let modelo = arquivo_temporario("modelo.json");
+    let saida = programa()
+        .args(["train", "--csv", "src/dados.csv", "--k", "3", "--output", &modelo])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    assert!(String::from_utf8(saida).unwrap().contains("k = 3 salvo em"));
+
+    // O modelo dá a mesma previsão que o modo sem subcomando com o mesmo k
+    let direto = programa().args(["--k", "3", "--ponto", "4.5,8.0"]).assert().success().get_output().stdout.clone();
+    let previsto = direto.split(|&byte| byte == b'\n').find(|linha| linha.starts_with(b"R")).unwrap().to_vec();
+    for nomes in [["predict", "--model", "--features"], ["prever", "--modelo", "--caracteristicas"]] {
+        let saida = programa()
+            .args([nomes[0], nomes[1], &modelo, nomes[2], "4.5,8.0"])
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone();
+        assert_eq!(String::from_utf8(saida).unwrap().trim(), String::from_utf8(previsto.clone()).unwrap());
+    }
+
+    let erro = programa()
+        .args(["predict", "--model", &modelo, "--features", "1.0,2.0,3.0"])
+        .assert()
+        .code(2)
+        .get_output()
+        .stderr
+        .clone();
+    assert!(String::from_utf8(erro).unwrap().contains("tem 2 característica(s)"));
+}
+
+#[test]
+fn treina_com_k_automatico() {
+    let modelo = arquivo_temporario("modelo_k_auto.json");
+    programa().args(["train", "--csv", "src/dados.csv", "--k-auto", "--output", &modelo]).assert().success();
+    let salvo: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&modelo).unwrap()).unwrap();
+    assert!(salvo["k"].as_u64().is_some_and(|k| k >= 1), "{}", salvo["k"]);
+}
+
+#[test]
+fn avalia_com_validacao_cruzada() {
+    let saida = programa()
+        .args(["evaluate", "--csv", "src/dados.csv", "--k", "5", "--folds", "4"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let texto = String::from_utf8(saida).unwrap();
+    assert_eq!(texto.matches("  fold ").count(), 4, "{}", texto);
+    assert!(texto.contains("Acurácia: "), "{}", texto);
+
+    // Menos de 2 folds e um k que não é número são erros de argumento
+    programa().args(["evaluate", "--csv", "src/dados.csv", "--folds", "1"]).assert().code(2);
+    programa().args(["evaluate", "--csv", "src/dados.csv", "--k", "cinco"]).assert().code(2);
+
+    // Mais folds que pontos só se sabe depois de ler o CSV: é o erro da validação cruzada
+    let erro = programa()
+        .args(["evaluate", "--csv", "src/dados.csv", "--folds", "1000"])
+        .assert()
+        .code(1)
+        .get_output()
+        .stderr
+        .clone();
+    let texto = String::from_utf8(erro).unwrap();
+    assert!(texto.contains("k_folds (1000) não pode ser maior que o número de pontos (215)"), "{}", texto);
+}
+
+#[test]
+fn estatisticas_resumem_o_csv() {
+    let saida = programa().args(["stats", "--csv", "src/dados.csv"]).assert().success().get_output().stdout.clone();
+    let texto = String::from_utf8(saida).unwrap();
+    assert!(texto.contains("215 pontos, 2 característica(s)"), "{}", texto);
+    assert!(texto.contains("Classe A: 51"), "{}", texto);
+    assert!(texto.lines().any(|linha| linha.starts_with("feature1") && linha.contains("2.0000")), "{}", texto);
+}
+
+#[test]
+fn subcomando_com_arquivo_inexistente_falha_com_codigo_1() {
+    let erro = programa()
+        .args(["stats", "--csv", "tests/fixtures/nao_existe.csv"])
+        .assert()
+        .code(1)
+        .get_output()
+        .stderr
+        .clone();
+    assert!(String::from_utf8(erro).unwrap().contains("nao_existe.csv: arquivo não encontrado"));
+}
+
+#[test]
+fn saida_capturada_nao_recebe_codigos_de_limpar_a_tela() {
+    // Aqui o stdout é um pipe, não um terminal: nada de sequências ANSI nem de `clear`,
+    // e a saída começa direto pela distribuição das classes
+    for argumentos in [&["--k", "3"][..], &["--k", "3", "--sem-limpar"]] {
+        let saida = programa().args(argumentos).env_remove("TERM").assert().success().get_output().clone();
+        let texto = String::from_utf8(saida.stdout).unwrap();
+        assert!(!texto.contains('\u{1b}'), "{:?}", texto);
+        assert!(texto.starts_with("Distribuição das classes"), "{:?}", texto);
+        assert!(saida.stderr.is_empty(), "{}", String::from_utf8_lossy(&saida.stderr));
+    }
+}
@@ -0,0 +1,112 @@
+// Testes de integração da leitura de CSV comprimido com gzip, a partir de um arquivo
+// de exemplo em tests/fixtures (o mesmo CSV de 4 pontos, comprimido) e de arquivos
+// comprimidos na hora, numa pasta temporária
+
+use aprendizagem_knn::{
+    carregar_dados_do_csv, carregar_dados_do_csv_com_opcoes, carregar_dados_do_csv_gz, carregar_dados_mmap,
+    classificar_csv, knn, ponto, ErroKnn, OpcoesCsv, Ponto,
+};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+
+const ARQUIVO_GZ: &str = "tests/fixtures/pequeno.csv.gz";
+
+#[test]
+fn carrega_csv_gz_com_cabecalho() {
+    let dados = carregar_dados_do_csv_gz(ARQUIVO_GZ, true).unwrap();
+    assert_eq!(dados.len(), 4);
+    assert_eq!(dados[2].caracteristicas, vec![9.0, 9.5]);
+    assert_eq!(dados[2].rotulo, "B");
+    assert_eq!(knn(&dados, &ponto!([0.2, 0.1], "?"), 1).unwrap(), "A");
+}
+
+#[test]
+fn carregar_dados_do_csv_detecta_gz_pela_extensao() {
+    let dados = carregar_dados_do_csv(ARQUIVO_GZ).unwrap();
+    let caracteristicas: Vec<Vec<f64>> = dados.iter().map(|ponto| ponto.caracteristicas.clone()).collect();
+    let rotulos: Vec<&str> = dados.iter().map(|ponto| ponto.rotulo.as_str()).collect();
+    assert_eq!(caracteristicas, vec![vec![0.0, 0.0], vec![0.5, 0.2], vec![9.0, 9.5], vec![10.0, 9.0]]);
+    assert_eq!(rotulos, vec!["A", "A", "B", "B"]);
+}
+
+#[test]
+fn sem_cabecalho_a_primeira_linha_e_um_ponto() {
+    // O cabeçalho "x,y,classe" vira um ponto, e "x" não é um número
+    let erro = carregar_dados_do_csv_gz(ARQUIVO_GZ, false).unwrap_err();
+    assert!(erro.to_string().contains("linha 1"), "{}", erro);
+}
+
+#[test]
+fn arquivo_inexistente_e_arquivo_nao_encontrado() {
+    let caminho = "tests/fixtures/nao_existe.csv.gz";
+    let erro = carregar_dados_do_csv_gz(caminho, true).unwrap_err();
+    assert_eq!(erro, ErroKnn::ArquivoNaoEncontrado(caminho.to_string()));
+}
+
+// Grava `conteudo` comprimido com gzip num arquivo temporário e devolve o caminho
+fn gravar_gz(nome: &str, conteudo: &[u8]) -> String {
+    use std::io::Write;
+
+    let caminho = std::env::temp_dir().join(format!("aprendizagem_knn_gz_{}", nome));
+    let mut compressor = GzEncoder::new(std::fs::File::create(&caminho).unwrap(), Compression::default());
+    compressor.write_all(conteudo).unwrap();
+    compressor.finish().unwrap();
+    caminho.to_str().unwrap().to_string()
+}
+
+#[test]
+fn csv_gz_carrega_igual_ao_descomprimido() {
+    let conteudo = std::fs::read("src/dados.csv").unwrap();
+    let comprimido = gravar_gz("dados.csv.gz", &conteudo);
+    assert_eq!(carregar_dados_do_csv(&comprimido).unwrap(), carregar_dados_do_csv("src/dados.csv").unwrap());
+
+    let opcoes = OpcoesCsv::default();
+    assert_eq!(
+        carregar_dados_do_csv_com_opcoes(&comprimido, &opcoes).unwrap(),
+        carregar_dados_do_csv_com_opcoes("src/dados.csv", &opcoes).unwrap()
+    );
+}
+
+#[test]
+fn gzip_e_detectado_pela_assinatura_sem_a_extensao() {
+    let conteudo = std::fs::read("src/dados.csv").unwrap();
+    let comprimido = gravar_gz("dados_sem_extensao.csv", &conteudo);
+    assert_eq!(carregar_dados_do_csv(&comprimido).unwrap(), carregar_dados_do_csv("src/dados.csv").unwrap());
+
+    // O mapeamento na memória não descomprime, então recusa o arquivo
+    let erro = carregar_dados_mmap(&comprimido, true).unwrap_err();
+    assert!(erro.to_string().contains("gzip"), "{}", erro);
+}
+
+#[test]
+fn csv_de_teste_gz_e_classificado() {
+    let teste = gravar_gz("teste.csv.gz", b"x,y\n0.1,0.1\n9.5,9.5\n");
+    let saida = std::env::temp_dir().join("aprendizagem_knn_gz_previsoes.csv");
+    let saida = saida.to_str().unwrap();
+    let treinamento = carregar_dados_do_csv(ARQUIVO_GZ).unwrap();
+    let classificar = |ponto: &Ponto| Ok((knn(&treinamento, ponto, 1)?, 1.0));
+
+    assert_eq!(classificar_csv(&teste, saida, &OpcoesCsv::default(), 2, classificar, false).unwrap(), 2);
+    assert_eq!(std::fs::read_to_string(saida).unwrap(), "x,y,rotulo_previsto\n0.1,0.1,A\n9.5,9.5,B\n");
+}
+
+#[test]
+fn gzip_corrompido_e_erro_de_arquivo_com_o_caminho() {
+    let conteudo = std::fs::read("src/dados.csv").unwrap();
+    let comprimido = gravar_gz("truncado.csv.gz", &conteudo);
+    let mut bytes = std::fs::read(&comprimido).unwrap();
+    // Corta o arquivo no meio do fluxo comprimido
+    bytes.truncate(bytes.len() / 2);
+    std::fs::write(&comprimido, &bytes).unwrap();
+
+    let erro = carregar_dados_do_csv(&comprimido).unwrap_err();
+    assert!(matches!(erro, ErroKnn::Arquivo(_)), "não é erro de arquivo: {:?}", erro);
+    assert!(erro.to_string().contains(&comprimido), "{}", erro);
+
+    // Um arquivo .gz que nem é gzip também
+    let falso = std::env::temp_dir().join("aprendizagem_knn_gz_falso.csv.gz");
+    std::fs::write(&falso, "x,classe\n1,A\n").unwrap();
+    let erro = carregar_dados_do_csv(falso.to_str().unwrap()).unwrap_err();
+    assert!(matches!(erro, ErroKnn::Arquivo(_)), "não é erro de arquivo: {:?}", erro);
+    assert!(erro.to_string().contains("aprendizagem_knn_gz_falso.csv.gz"), "{}", erro);
+}
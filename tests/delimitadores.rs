@@ -0,0 +1,76 @@
+// Testes de integração da leitura de CSV com outros separadores: o mesmo conjunto de dados
+// gravado com vírgula, ponto e vírgula e tabulação (em tests/fixtures) precisa dar os
+// mesmos pontos, inclusive nos campos entre aspas que contêm os separadores e aspas dobradas
+
+use aprendizagem_knn::{carregar_dados_do_csv, carregar_dados_do_csv_com_opcoes, pontos, ErroKnn, OpcoesCsv, Ponto};
+
+const VIRGULA: &str = "tests/fixtures/delimitador_virgula.csv";
+const PONTO_E_VIRGULA: &str = "tests/fixtures/delimitador_ponto_e_virgula.csv";
+const TAB: &str = "tests/fixtures/delimitador_tab.tsv";
+
+fn esperado() -> Vec<Ponto> {
+    pontos![
+        [5.1, 3.5] => "setosa",
+        [4.9, 3.0] => "setosa",
+        [7.0, 3.2] => "versicolor; \"comum\", de jardim",
+        [6.3, 3.3] => "virginica\tazul",
+        [-1.5, 0.0] => "outra",
+    ]
+}
+
+fn carregar(caminho: &str, delimitador: Option<u8>) -> (Vec<String>, Vec<Ponto>) {
+    let opcoes = OpcoesCsv { delimitador, ..OpcoesCsv::default() };
+    carregar_dados_do_csv_com_opcoes(caminho, &opcoes).unwrap()
+}
+
+#[test]
+fn os_tres_separadores_dao_os_mesmos_pontos() {
+    let (colunas_virgula, virgula) = carregar(VIRGULA, Some(b','));
+    let (colunas_ponto_e_virgula, ponto_e_virgula) = carregar(PONTO_E_VIRGULA, Some(b';'));
+    let (colunas_tab, tab) = carregar(TAB, Some(b'\t'));
+
+    assert_eq!(virgula, esperado());
+    assert_eq!(ponto_e_virgula, virgula);
+    assert_eq!(tab, virgula);
+    assert_eq!(colunas_virgula, vec!["comprimento", "largura, cm"]);
+    assert_eq!(colunas_ponto_e_virgula, colunas_virgula);
+    assert_eq!(colunas_tab, colunas_virgula);
+}
+
+#[test]
+fn delimitador_detectado_pela_primeira_linha() {
+    for caminho in [VIRGULA, PONTO_E_VIRGULA, TAB] {
+        assert_eq!(carregar(caminho, None).1, esperado(), "{}", caminho);
+    }
+}
+
+#[test]
+fn o_padrao_continua_sendo_a_virgula() {
+    assert_eq!(carregar_dados_do_csv(VIRGULA).unwrap(), esperado());
+    assert_eq!(carregar_dados_do_csv_com_opcoes(VIRGULA, &OpcoesCsv::default()).unwrap().1, esperado());
+}
+
+#[test]
+fn delimitador_errado_e_erro_com_a_linha() {
+    // Lido com vírgula, o cabeçalho do arquivo com ponto e vírgula tem duas colunas
+    // ("comprimento;largura" e "cm;especie"), mas a linha 2 só tem uma
+    let opcoes = OpcoesCsv { delimitador: Some(b','), ..OpcoesCsv::default() };
+    let erro = carregar_dados_do_csv_com_opcoes(PONTO_E_VIRGULA, &opcoes).unwrap_err();
+    assert_eq!(
+        erro,
+        ErroKnn::LinhaInvalida {
+            origem: PONTO_E_VIRGULA.to_string(),
+            linha: 2,
+            motivo: "tem 1 colunas, mas o cabeçalho tem 2: [\"5.1;3.5;setosa\"]".to_string(),
+        }
+    );
+}
+
+#[test]
+fn aspas_configuraveis() {
+    let caminho = std::env::temp_dir().join("aprendizagem_knn_teste_aspas_simples.csv");
+    std::fs::write(&caminho, "x|classe\n1|'a|b'\n2|'diz ''oi'''\n").unwrap();
+    let opcoes = OpcoesCsv { delimitador: Some(b'|'), aspas: b'\'', ..OpcoesCsv::default() };
+    let (_, dados) = carregar_dados_do_csv_com_opcoes(caminho.to_str().unwrap(), &opcoes).unwrap();
+    assert_eq!(dados, pontos![[1.0] => "a|b", [2.0] => "diz 'oi'"]);
+}
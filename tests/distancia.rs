@@ -0,0 +1,134 @@
+// Testes de integração da distância de Minkowski: com p = 1 ela é a de Manhattan, com p = 2
+// a euclidiana, e conforme p cresce ela se aproxima da de Chebyshev, em pares de vetores
+// escolhidos à mão. Também os casos de referência das distâncias de correlação de Pearson
+// e de Canberra
+
+use aprendizagem_knn::{
+    distancia_canberra, distancia_chebyshev, distancia_correlacao_pearson, distancia_euclidiana, distancia_manhattan,
+    distancia_minkowski, ponto, Ponto,
+};
+
+// Pares com dimensões, sinais e escalas diferentes, um par de pontos iguais e um em que
+// duas características empatam na maior diferença
+fn pares() -> Vec<(Ponto, Ponto)> {
+    vec![
+        (ponto!([0.0, 0.0], "A"), ponto!([3.0, 4.0], "B")),
+        (ponto!([1.5], "A"), ponto!([-2.5], "B")),
+        (ponto!([1.0, -2.0, 3.0], "A"), ponto!([-1.0, 2.0, 0.5], "B")),
+        (ponto!([0.1, 0.2, 0.3, 0.4, 0.5], "A"), ponto!([0.5, 0.4, 0.3, 0.2, 0.1], "B")),
+        (ponto!([10.0, -7.0, 2.0, 0.0], "A"), ponto!([1.0, -1.0, 2.0, 9.0], "B")),
+        (ponto!([2.0, 2.0, 2.0], "A"), ponto!([2.0, 2.0, 2.0], "B")),
+    ]
+}
+
+// Igualdade com uma folga relativa para os arredondamentos de powf
+fn quase_igual(a: f64, b: f64) -> bool {
+    (a - b).abs() <= 1e-12 * a.abs().max(b.abs()).max(1.0)
+}
+
+#[test]
+fn p_igual_a_1_e_a_distancia_de_manhattan() {
+    for (a, b) in pares() {
+        let minkowski = distancia_minkowski(&a, &b, 1.0).unwrap();
+        let manhattan = distancia_manhattan(&a, &b);
+        assert!(quase_igual(minkowski, manhattan), "{:?}: {} != {}", a.caracteristicas, minkowski, manhattan);
+    }
+}
+
+#[test]
+fn p_igual_a_2_e_a_distancia_euclidiana() {
+    for (a, b) in pares() {
+        let minkowski = distancia_minkowski(&a, &b, 2.0).unwrap();
+        let euclidiana = distancia_euclidiana(&a, &b);
+        assert!(quase_igual(minkowski, euclidiana), "{:?}: {} != {}", a.caracteristicas, minkowski, euclidiana);
+    }
+}
+
+#[test]
+fn p_crescente_se_aproxima_da_distancia_de_chebyshev() {
+    for (a, b) in pares() {
+        let chebyshev = distancia_chebyshev(&a, &b);
+        let dimensao = a.caracteristicas.len() as f64;
+
+        // Para qualquer p: chebyshev <= minkowski <= dimensao^(1/p) * chebyshev, e a
+        // distância não aumenta quando p aumenta
+        let mut anterior = f64::INFINITY;
+        for p in [1.0, 2.0, 4.0, 8.0, 16.0, 32.0, 64.0, 128.0] {
+            let minkowski = distancia_minkowski(&a, &b, p).unwrap();
+            let limite = dimensao.powf(1.0 / p) * chebyshev;
+            assert!(chebyshev <= minkowski * (1.0 + 1e-12), "p = {}: {} < {}", p, minkowski, chebyshev);
+            assert!(minkowski <= limite * (1.0 + 1e-12), "p = {}: {} > {}", p, minkowski, limite);
+            assert!(minkowski <= anterior * (1.0 + 1e-12), "p = {}: {} > {}", p, minkowski, anterior);
+            anterior = minkowski;
+        }
+
+        // Com p = 128 já está a menos de 1% da Chebyshev, e com p infinito é exatamente ela
+        let grande = distancia_minkowski(&a, &b, 128.0).unwrap();
+        assert!(grande - chebyshev <= 0.01 * chebyshev, "{:?}: {} e {}", a.caracteristicas, grande, chebyshev);
+        assert_eq!(distancia_minkowski(&a, &b, f64::INFINITY).unwrap(), chebyshev);
+    }
+}
+
+#[test]
+fn correlacao_negativa_perfeita_da_distancia_2() {
+    // b = c - m·a com m > 0: r = -1, qualquer que seja a escala ou o deslocamento
+    let casos = [
+        (ponto!([1.0, 2.0, 3.0], "A"), ponto!([-2.0, -4.0, -6.0], "B")),
+        (ponto!([0.5, 1.0, 2.0, 4.0], "A"), ponto!([3.5, 2.0, -1.0, -7.0], "B")),
+        (ponto!([-10.0, 0.0, 10.0], "A"), ponto!([100.1, 100.0, 99.9], "B")),
+    ];
+    for (a, b) in casos {
+        let distancia = distancia_correlacao_pearson(&a, &b);
+        assert!(quase_igual(distancia, 2.0), "{:?}: {}", b.caracteristicas, distancia);
+    }
+}
+
+#[test]
+fn vetores_sem_correlacao_dao_distancia_1() {
+    // Covariância zero: r = 0
+    let casos = [
+        (ponto!([1.0, 0.0, -1.0, 0.0], "A"), ponto!([0.0, 1.0, 0.0, -1.0], "B")),
+        (ponto!([1.0, 2.0, 3.0, 4.0], "A"), ponto!([1.0, -1.0, -1.0, 1.0], "B")),
+        (ponto!([-3.0, -1.0, 1.0, 3.0], "A"), ponto!([5.0, 2.0, 2.0, 5.0], "B")),
+    ];
+    for (a, b) in casos {
+        let distancia = distancia_correlacao_pearson(&a, &b);
+        assert!(quase_igual(distancia, 1.0), "{:?}: {}", b.caracteristicas, distancia);
+    }
+}
+
+#[test]
+fn variancia_zero_da_distancia_1() {
+    // A correlação não está definida; a distância é a "neutra", com um ou os dois constantes
+    let constante = ponto!([2.0, 2.0, 2.0], "A");
+    assert_eq!(distancia_correlacao_pearson(&constante, &ponto!([1.0, 2.0, 3.0], "B")), 1.0);
+    assert_eq!(distancia_correlacao_pearson(&ponto!([1.0, 2.0, 3.0], "B"), &constante), 1.0);
+    assert_eq!(distancia_correlacao_pearson(&constante, &ponto!([-5.0, -5.0, -5.0], "B")), 1.0);
+    assert_eq!(distancia_correlacao_pearson(&constante, &constante), 1.0);
+}
+
+#[test]
+fn canberra_termo_com_um_zero_vale_1() {
+    // |a - 0| / (|a| + 0) = 1 para qualquer a diferente de zero, com qualquer sinal
+    for valor in [1.0, -1.0, 0.001, -250.0, 1e9] {
+        assert_eq!(distancia_canberra(&ponto!([valor], "A"), &ponto!([0.0], "B")), 1.0, "{valor}");
+        assert_eq!(distancia_canberra(&ponto!([0.0], "A"), &ponto!([valor], "B")), 1.0, "{valor}");
+    }
+    // Um termo com um zero (1), um com os dois zeros (0) e um comum (|2 - 6| / 8 = 0.5)
+    let a = ponto!([3.0, 0.0, 2.0], "A");
+    let b = ponto!([0.0, 0.0, 6.0], "B");
+    assert_eq!(distancia_canberra(&a, &b), 1.5);
+}
+
+#[test]
+fn canberra_e_simetrica() {
+    // Os pares de sempre, mais alguns com zeros de um lado só e sinais trocados
+    let mut casos = pares();
+    casos.push((ponto!([0.0, 5.0, -3.0], "A"), ponto!([4.0, 0.0, 3.0], "B")));
+    casos.push((ponto!([1e-6, -1e6], "A"), ponto!([-1e-6, 1e6], "B")));
+    for (a, b) in casos {
+        let ida = distancia_canberra(&a, &b);
+        let volta = distancia_canberra(&b, &a);
+        assert_eq!(ida, volta, "{:?} e {:?}", a.caracteristicas, b.caracteristicas);
+    }
+}
@@ -0,0 +1,179 @@
+// Testes de integração dos erros: cada entrada com problema precisa devolver a variante
+// certa do ErroKnn, para que quem usa a biblioteca possa tratar cada caso com match
+
+use aprendizagem_knn::{
+    carregar_dados_do_arff, carregar_dados_do_csv, carregar_dados_do_csv_com_ausentes, carregar_dados_do_csv_com_opcoes,
+    carregar_dados_do_csv_com_rotulo, carregar_dados_do_csv_gz, carregar_dados_do_csv_mapeando_rotulos,
+    carregar_dados_do_json, carregar_dados_mmap, carregar_pontos_sem_rotulo, classificar_csv, ErroKnn, Modelo,
+    OpcoesCsv, Ponto,
+};
+
+const VALOR_INVALIDO: &str = "tests/fixtures/valor_invalido.csv";
+const COLUNA_FALTANDO: &str = "tests/fixtures/coluna_faltando.csv";
+const SO_CABECALHO: &str = "tests/fixtures/so_cabecalho.csv";
+const SO_CABECALHO_GZ: &str = "tests/fixtures/so_cabecalho.csv.gz";
+const TESTE_3_COLUNAS: &str = "tests/fixtures/teste_3_colunas.csv";
+// Uma linha de cada problema: NaN, inf, coluna faltando, rótulo vazio e texto (linhas 3 a 7)
+const VARIOS_PROBLEMAS: &str = "tests/fixtures/varios_problemas.csv";
+
+#[test]
+fn arquivo_inexistente_e_arquivo_nao_encontrado() {
+    let caminho = "tests/fixtures/nao_existe.csv";
+    let esperado = ErroKnn::ArquivoNaoEncontrado(caminho.to_string());
+    assert_eq!(carregar_dados_do_csv(caminho), Err(esperado.clone()));
+    assert_eq!(carregar_dados_mmap(caminho, true), Err(esperado.clone()));
+    assert_eq!(carregar_pontos_sem_rotulo(caminho), Err(esperado.clone()));
+    assert_eq!(carregar_dados_do_json(caminho), Err(esperado.clone()));
+    assert_eq!(carregar_dados_do_arff(caminho, None), Err(esperado.clone()));
+    assert_eq!(Modelo::do_csv(caminho).unwrap_err(), esperado);
+
+    // A mensagem diz o caminho e o que conferir
+    let mensagem = esperado.to_string();
+    assert!(mensagem.starts_with(caminho) && mensagem.contains("não encontrado"), "{}", mensagem);
+}
+
+#[test]
+fn valor_nao_numerico_e_linha_invalida() {
+    let esperado = ErroKnn::LinhaInvalida {
+        origem: VALOR_INVALIDO.to_string(),
+        linha: 3,
+        motivo: "coluna 2 ('y'): valor numérico inválido 'abc'".to_string(),
+    };
+    assert_eq!(carregar_dados_do_csv(VALOR_INVALIDO), Err(esperado.clone()));
+    assert_eq!(carregar_dados_mmap(VALOR_INVALIDO, true), Err(esperado));
+    assert!(matches!(Modelo::do_csv(VALOR_INVALIDO), Err(ErroKnn::LinhaInvalida { linha: 3, .. })));
+}
+
+#[test]
+fn coluna_faltando_e_linha_invalida() {
+    let erro = carregar_dados_do_csv(COLUNA_FALTANDO).unwrap_err();
+    assert_eq!(
+        erro,
+        ErroKnn::LinhaInvalida {
+            origem: COLUNA_FALTANDO.to_string(),
+            linha: 3,
+            motivo: "tem 2 colunas, mas o cabeçalho tem 3: [\"3.0\", \"B\"]".to_string(),
+        }
+    );
+    assert_eq!(
+        erro.to_string(),
+        format!("{}: linha 3: tem 2 colunas, mas o cabeçalho tem 3: [\"3.0\", \"B\"]", COLUNA_FALTANDO)
+    );
+}
+
+#[test]
+fn arquivo_so_com_cabecalho_e_conjunto_vazio() {
+    assert_eq!(carregar_dados_do_csv(SO_CABECALHO), Err(ErroKnn::ConjuntoVazio));
+    // Os outros carregadores de treinamento seguem o carregar_dados_do_csv
+    assert_eq!(carregar_dados_mmap(SO_CABECALHO, true), Err(ErroKnn::ConjuntoVazio));
+    assert_eq!(carregar_dados_do_csv_gz(SO_CABECALHO_GZ, true), Err(ErroKnn::ConjuntoVazio));
+    assert_eq!(carregar_dados_do_csv_gz(SO_CABECALHO, true), Err(ErroKnn::ConjuntoVazio));
+    assert_eq!(carregar_dados_do_csv_com_rotulo(SO_CABECALHO, 0), Err(ErroKnn::ConjuntoVazio));
+    let numero = |rotulo: &str| rotulo.parse::<u32>().map_err(|erro| erro.to_string());
+    assert_eq!(carregar_dados_do_csv_mapeando_rotulos(SO_CABECALHO, numero), Err(ErroKnn::ConjuntoVazio));
+
+    // Um arquivo sem nem o cabeçalho também
+    let vazio = std::env::temp_dir().join("aprendizagem_knn_erros_vazio.csv");
+    std::fs::write(&vazio, "").unwrap();
+    let vazio = vazio.to_str().unwrap();
+    assert_eq!(carregar_dados_do_csv(vazio), Err(ErroKnn::ConjuntoVazio));
+    assert_eq!(carregar_dados_mmap(vazio, true), Err(ErroKnn::ConjuntoVazio));
+    assert_eq!(carregar_dados_do_csv_gz(vazio, false), Err(ErroKnn::ConjuntoVazio));
+    assert_eq!(carregar_dados_do_csv_com_rotulo(vazio, 0), Err(ErroKnn::ConjuntoVazio));
+}
+
+#[test]
+fn teste_com_outra_dimensao_e_dimensao_incompativel() {
+    let saida = std::env::temp_dir().join("aprendizagem_knn_erros_previsoes.csv");
+    let classificar = |_: &Ponto| Ok(("A".to_string(), 1.0));
+    let saida = saida.to_str().unwrap();
+    let erro = classificar_csv(TESTE_3_COLUNAS, saida, &OpcoesCsv::default(), 2, classificar, false).unwrap_err();
+    assert_eq!(erro, ErroKnn::DimensaoIncompativel { esperado: 2, obtido: 3 });
+}
+
+#[test]
+fn coluna_do_rotulo_fora_do_cabecalho_e_parametro_invalido() {
+    let erro = carregar_dados_do_csv_com_rotulo(VALOR_INVALIDO, 5).unwrap_err();
+    assert!(matches!(&erro, ErroKnn::ParametroInvalido(motivo) if motivo.contains(VALOR_INVALIDO)), "{:?}", erro);
+}
+
+#[test]
+fn valores_nao_finitos_sao_linha_invalida() {
+    let caminho = std::env::temp_dir().join("aprendizagem_knn_erros_nao_finitos.csv");
+    let caminho = caminho.to_str().unwrap();
+    for valor in ["NaN", "inf", "-infinity"] {
+        std::fs::write(caminho, format!("x,y,classe\n1,2,A\n3,{},B\n", valor)).unwrap();
+        let esperado = ErroKnn::LinhaInvalida {
+            origem: caminho.to_string(),
+            linha: 3,
+            motivo: format!("coluna 2 ('y'): valor não finito '{}' (NaN e infinito não são aceitos)", valor),
+        };
+        assert_eq!(carregar_dados_do_csv(caminho), Err(esperado.clone()));
+        assert_eq!(carregar_dados_mmap(caminho, true), Err(esperado));
+    }
+}
+
+#[test]
+fn nan_com_ausentes_segue_a_politica_de_ausentes() {
+    let caminho = std::env::temp_dir().join("aprendizagem_knn_erros_nan_ausente.csv");
+    let caminho = caminho.to_str().unwrap();
+    std::fs::write(caminho, "x,y,classe\n1,2,A\n3,NaN,B\n").unwrap();
+    let dados = carregar_dados_do_csv_com_ausentes(caminho).unwrap();
+    assert!(dados[1].caracteristicas[1].is_nan());
+
+    // Infinito não é um valor ausente: continua sendo erro
+    std::fs::write(caminho, "x,y,classe\n1,2,A\n3,inf,B\n").unwrap();
+    assert!(matches!(carregar_dados_do_csv_com_ausentes(caminho), Err(ErroKnn::LinhaInvalida { linha: 3, .. })));
+}
+
+#[test]
+fn rotulo_vazio_e_linha_invalida() {
+    let caminho = std::env::temp_dir().join("aprendizagem_knn_erros_rotulo_vazio.csv");
+    let caminho = caminho.to_str().unwrap();
+    std::fs::write(caminho, "x,y,classe\n1,2,A\n3,4, \n").unwrap();
+    let esperado = ErroKnn::LinhaInvalida {
+        origem: caminho.to_string(),
+        linha: 3,
+        motivo: "coluna 3 ('classe'): o rótulo está vazio".to_string(),
+    };
+    assert_eq!(carregar_dados_do_csv(caminho), Err(esperado.clone()));
+    assert_eq!(carregar_dados_mmap(caminho, true), Err(esperado.clone()));
+    let com_opcoes = carregar_dados_do_csv_com_opcoes(caminho, &OpcoesCsv::default());
+    assert_eq!(com_opcoes.map(|(_, pontos)| pontos), Err(esperado));
+}
+
+#[test]
+fn todos_os_erros_sao_coletados_na_ordem_do_arquivo() {
+    let opcoes = OpcoesCsv { coletar_erros: true, ..OpcoesCsv::default() };
+    let Err(ErroKnn::VariosErros(erros)) = carregar_dados_do_csv_com_opcoes(VARIOS_PROBLEMAS, &opcoes) else {
+        panic!("esperava VariosErros");
+    };
+    let linhas: Vec<usize> = erros
+        .iter()
+        .map(|erro| match erro {
+            ErroKnn::LinhaInvalida { linha, .. } => *linha,
+            outro => panic!("esperava LinhaInvalida, veio {:?}", outro),
+        })
+        .collect();
+    assert_eq!(linhas, vec![3, 4, 5, 6, 7]);
+
+    // A mensagem conta os problemas e mostra um por linha, com o texto lido
+    let mensagem = ErroKnn::VariosErros(erros).to_string();
+    assert!(mensagem.starts_with("5 problema(s) nos dados:\n  - "), "{}", mensagem);
+    for trecho in ["'NaN'", "'inf'", "[\"7.0\", \"8.0\"]", "rótulo está vazio", "'abc'"] {
+        assert!(mensagem.contains(trecho), "{} em {}", trecho, mensagem);
+    }
+
+    // Sem coletar, a leitura para no primeiro problema encontrado
+    let erro = carregar_dados_do_csv_com_opcoes(VARIOS_PROBLEMAS, &OpcoesCsv::default()).unwrap_err();
+    assert!(matches!(erro, ErroKnn::LinhaInvalida { .. }), "{:?}", erro);
+}
+
+#[test]
+fn coletar_erros_sem_problemas_devolve_os_pontos() {
+    let opcoes = OpcoesCsv { coletar_erros: true, ..OpcoesCsv::default() };
+    let (_, dados) = carregar_dados_do_csv_com_opcoes("src/dados.csv", &opcoes).unwrap();
+    assert_eq!(dados, carregar_dados_do_csv("src/dados.csv").unwrap());
+    // Só com o cabeçalho continua sendo um conjunto vazio
+    assert_eq!(carregar_dados_do_csv_com_opcoes(SO_CABECALHO, &opcoes), Err(ErroKnn::ConjuntoVazio));
+}
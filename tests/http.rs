@@ -0,0 +1,108 @@
+// Testes de integração do download de dados por HTTP, com um servidor local (tiny_http)
+// servindo o src/dados.csv, sem depender da internet (rodam com --features http)
+#![cfg(feature = "http")]
+
+use std::io::Write;
+
+use aprendizagem_knn::{
+    carregar_dados_de_url, carregar_dados_de_url_com_opcoes, carregar_dados_do_csv, ErroKnn, OpcoesCsv,
+};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use tiny_http::{Header, Response, Server};
+
+const ARQUIVO: &str = "src/dados.csv";
+
+// Sobe um servidor numa porta livre, numa thread que atende enquanto o teste roda, e
+// devolve o endereço base ("http://127.0.0.1:porta")
+fn servidor() -> String {
+    let servidor = Server::http("127.0.0.1:0").unwrap();
+    let base = format!("http://{}", servidor.server_addr().to_ip().unwrap());
+    let csv = std::fs::read(ARQUIVO).unwrap();
+    let mut compressor = GzEncoder::new(Vec::new(), Compression::default());
+    compressor.write_all(&csv).unwrap();
+    let comprimido = compressor.finish().unwrap();
+
+    std::thread::spawn(move || {
+        for pedido in servidor.incoming_requests() {
+            let resposta = match pedido.url() {
+                "/dados.csv" => Response::from_data(csv.clone()),
+                // Comprimido pela extensão (com uma query string) e só pela assinatura
+                "/dados.csv.gz?token=abc" | "/exportacao" => Response::from_data(comprimido.clone()),
+                "/codificado.csv" => Response::from_data(comprimido.clone())
+                    .with_header(Header::from_bytes("Content-Encoding", "gzip").unwrap()),
+                "/truncado.csv.gz" => Response::from_data(comprimido[..comprimido.len() / 2].to_vec()),
+                "/vazio.csv" => Response::from_data(Vec::new()),
+                "/invalido.csv" => Response::from_data(b"x,classe\n1,A\nabc,B\n".to_vec()),
+                _ => Response::from_data(b"nao encontrado".to_vec()).with_status_code(404),
+            };
+            let _ = pedido.respond(resposta);
+        }
+    });
+    base
+}
+
+#[test]
+fn baixa_o_mesmo_que_o_arquivo_local() {
+    let base = servidor();
+    let esperado = carregar_dados_do_csv(ARQUIVO).unwrap();
+    assert_eq!(carregar_dados_de_url(&format!("{}/dados.csv", base)).unwrap(), esperado);
+}
+
+#[test]
+fn conteudo_gzip_e_descomprimido() {
+    let base = servidor();
+    let esperado = carregar_dados_do_csv(ARQUIVO).unwrap();
+    for caminho in ["/dados.csv.gz?token=abc", "/exportacao", "/codificado.csv"] {
+        let url = format!("{}{}", base, caminho);
+        assert_eq!(carregar_dados_de_url(&url).unwrap(), esperado, "{}", url);
+    }
+
+    let url = format!("{}/truncado.csv.gz", base);
+    let erro = carregar_dados_de_url(&url).unwrap_err().to_string();
+    assert!(erro.contains(&url) && erro.contains("gzip"), "{}", erro);
+}
+
+#[test]
+fn status_http_de_erro_cita_a_url_e_o_codigo() {
+    let url = format!("{}/nao_existe.csv", servidor());
+    let erro = carregar_dados_de_url(&url).unwrap_err();
+    assert!(matches!(erro, ErroKnn::Arquivo(_)));
+    let texto = erro.to_string();
+    assert!(texto.contains(&url) && texto.contains("404"), "{}", texto);
+}
+
+#[test]
+fn falha_de_conexao_cita_a_url() {
+    // Uma porta que estava livre e foi fechada: ninguém escuta nela
+    let porta = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    let url = format!("http://127.0.0.1:{}/dados.csv", porta);
+    let erro = carregar_dados_de_url(&url).unwrap_err();
+    assert!(matches!(erro, ErroKnn::Arquivo(_)));
+    assert!(erro.to_string().contains(&url), "{}", erro);
+}
+
+#[test]
+fn erros_do_csv_sao_os_de_um_arquivo() {
+    let base = servidor();
+    let url = format!("{}/invalido.csv", base);
+    let erro = carregar_dados_de_url_com_opcoes(&url, &OpcoesCsv::default()).unwrap_err();
+    assert!(matches!(&erro, ErroKnn::LinhaInvalida { origem, linha: 3, .. } if *origem == url), "{:?}", erro);
+    assert!(erro.to_string().starts_with(&format!("{}: linha 3", url)), "{}", erro);
+
+    let url = format!("{}/vazio.csv", base);
+    assert_eq!(carregar_dados_de_url(&url), Err(ErroKnn::ConjuntoVazio));
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn linha_de_comando_aceita_uma_url_no_dados() {
+    let url = format!("{}/dados.csv", servidor());
+    let saida = assert_cmd::Command::cargo_bin("aprendizagemKNN")
+        .unwrap()
+        .args(["--dados", &url, "--k", "3", "--ponto", "4.5,8.0"])
+        .assert()
+        .success();
+    let texto = String::from_utf8(saida.get_output().stdout.clone()).unwrap();
+    assert!(texto.contains("Rótulo previsto para os dados de teste [4.5, 8.0] é Classe"), "{}", texto);
+}
@@ -0,0 +1,75 @@
+// Testes de integração do JSON: carregar um conjunto de dados em JSON, classificar,
+// gravar as previsões nos dois formatos e lê-las de volta sem perder nada
+
+use aprendizagem_knn::{
+    carregar_dados_do_csv, carregar_dados_do_json, carregar_previsoes_json, knn_batch, prever_para_json,
+    salvar_previsoes_json, FormatoJson, Ponto,
+};
+
+fn arquivo_temporario(nome: &str) -> String {
+    std::env::temp_dir().join(format!("aprendizagem_knn_teste_json_{}", nome)).to_str().unwrap().to_string()
+}
+
+// O conjunto de exemplo do projeto, gravado em JSON com o Serialize do Ponto
+fn dados_em_json() -> (Vec<Ponto>, String) {
+    let dados = carregar_dados_do_csv("src/dados.csv").unwrap();
+    let caminho = arquivo_temporario("dados.json");
+    std::fs::write(&caminho, serde_json::to_string_pretty(&dados).unwrap()).unwrap();
+    (dados, caminho)
+}
+
+#[test]
+fn json_e_csv_dao_os_mesmos_pontos() {
+    let (dados, caminho) = dados_em_json();
+    assert_eq!(carregar_dados_do_json(&caminho).unwrap(), dados);
+}
+
+#[test]
+fn previsoes_vao_e_voltam_nos_dois_formatos() {
+    let (_, caminho) = dados_em_json();
+    let dados = carregar_dados_do_json(&caminho).unwrap();
+    let (treinamento, teste) = dados.split_at(180);
+
+    for incluir_vizinhos in [false, true] {
+        let previsoes = prever_para_json(treinamento, teste, 5, incluir_vizinhos).unwrap();
+        let rotulos: Vec<String> = previsoes.iter().map(|previsao| previsao.rotulo_previsto.clone()).collect();
+        assert_eq!(rotulos, knn_batch(treinamento, teste, 5).unwrap());
+
+        for (formato, nome) in [(FormatoJson::Linhas, "previsoes.jsonl"), (FormatoJson::Array, "previsoes.json")] {
+            let saida = arquivo_temporario(nome);
+            salvar_previsoes_json(&previsoes, &saida, formato).unwrap();
+            assert_eq!(carregar_previsoes_json(&saida).unwrap(), previsoes, "{:?}", formato);
+        }
+    }
+}
+
+#[test]
+fn vizinhos_gravados_batem_com_o_treinamento() {
+    let (dados, _) = dados_em_json();
+    let (treinamento, teste) = dados.split_at(200);
+    let saida = arquivo_temporario("vizinhos.jsonl");
+    let previsoes = prever_para_json(treinamento, teste, 3, true).unwrap();
+    salvar_previsoes_json(&previsoes, &saida, FormatoJson::Linhas).unwrap();
+
+    for previsao in carregar_previsoes_json(&saida).unwrap() {
+        let vizinhos = previsao.vizinhos.unwrap();
+        assert_eq!(vizinhos.len(), 3);
+        for vizinho in vizinhos {
+            assert_eq!(vizinho.rotulo, treinamento[vizinho.indice].rotulo);
+        }
+    }
+}
+
+#[test]
+fn erro_de_esquema_cita_a_posicao_no_array() {
+    let caminho = arquivo_temporario("invalido.json");
+    std::fs::write(
+        &caminho,
+        r#"[{"caracteristicas": [1.0], "rotulo": "A"}, {"caracteristicas": ["x"], "rotulo": "B"}]"#,
+    ).unwrap();
+    let erro = carregar_dados_do_json(&caminho).unwrap_err().to_string();
+    assert!(erro.contains("elemento 1"), "{}", erro);
+
+    std::fs::write(&caminho, r#"{"caracteristicas": [1.0], "rotulo": "A"}"#).unwrap();
+    assert!(carregar_dados_do_json(&caminho).unwrap_err().to_string().contains("array"));
+}
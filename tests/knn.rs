@@ -0,0 +1,256 @@
+// Testes de integração do knn: o caminho feliz, cada entrada inválida (que é um ErroKnn
+// em vez de um pânico, inclusive uma métrica com parâmetros inválidos, também na árvore k-d,
+// no ensemble, no JSON de previsões e no centroide), distâncias NaN ou
+// infinitas vindas de uma métrica personalizada e o desempate entre vizinhos à mesma
+// distância, e o k escolhido pela densidade local
+
+use aprendizagem_knn::{
+    calcular_k, calcular_k_local, distancia_euclidiana, knn, knn_batch, knn_com_confianca, knn_com_metrica,
+    knn_com_votacao, knn_subamostrado, ponto, pontos, prever_para_json, ClassificadorCentroide, DistanciaMetrica,
+    EnsembleKnn, ErroKnn, KdArvore, KnnClassificador, Ponto, Rng, Votacao,
+};
+
+fn treinamento() -> Vec<Ponto> {
+    pontos![[0.0, 0.0] => "A", [0.5, 1.0] => "A", [1.0, 0.0] => "A", [9.0, 9.0] => "B", [8.0, 9.5] => "B"]
+}
+
+#[test]
+fn classifica_pela_maioria_dos_vizinhos() {
+    let dados = treinamento();
+    assert_eq!(knn(&dados, &ponto!([0.4, 0.3], "?"), 3), Ok("A".to_string()));
+    assert_eq!(knn(&dados, &ponto!([8.5, 9.0], "?"), 1), Ok("B".to_string()));
+    let manhattan = knn_com_metrica(&dados, &ponto!([8.5, 9.0], "?"), 2, &DistanciaMetrica::Manhattan);
+    assert_eq!(manhattan, Ok("B".to_string()));
+}
+
+#[test]
+fn treinamento_vazio_e_conjunto_vazio() {
+    let teste = ponto!([0.4, 0.3], "?");
+    assert_eq!(knn(&[], &teste, 3), Err(ErroKnn::ConjuntoVazio));
+    let votacao = knn_com_votacao(&[], &teste, 3, &DistanciaMetrica::Euclidiana, Votacao::InversoDaDistancia);
+    assert_eq!(votacao, Err(ErroKnn::ConjuntoVazio));
+    assert_eq!(knn_batch(&[], std::slice::from_ref(&teste), 3), Err(ErroKnn::ConjuntoVazio));
+
+    // Os outros classificadores devolvem o mesmo erro
+    assert_eq!(KdArvore::construir(&[]).classificar(&teste, 3), Err(ErroKnn::ConjuntoVazio));
+    assert_eq!(EnsembleKnn { k_valores: vec![1, 3] }.prever(&[], &teste), Err(ErroKnn::ConjuntoVazio));
+    assert_eq!(prever_para_json(&[], std::slice::from_ref(&teste), 3, true), Err(ErroKnn::ConjuntoVazio));
+    assert_eq!(ClassificadorCentroide::treinar(&[]).prever(&teste), Err(ErroKnn::ConjuntoVazio));
+}
+
+#[test]
+fn k_zero_e_k_invalido() {
+    let dados = treinamento();
+    let esperado = Err(ErroKnn::KInvalido { k: 0, maximo: dados.len() });
+    assert_eq!(knn(&dados, &ponto!([0.4, 0.3], "?"), 0), esperado);
+    assert_eq!(knn_com_metrica(&dados, &ponto!([0.4, 0.3], "?"), 0, &DistanciaMetrica::Chebyshev), esperado);
+    assert!(KnnClassificador::prever_iter(&dados, treinamento(), 0).is_err());
+
+    let teste = ponto!([0.4, 0.3], "?");
+    assert_eq!(KdArvore::construir(&dados).classificar(&teste, 0), esperado);
+    assert_eq!(EnsembleKnn { k_valores: vec![3, 0] }.prever(&dados, &teste), esperado);
+    let json = prever_para_json(&dados, std::slice::from_ref(&teste), 0, false);
+    assert_eq!(json, Err(ErroKnn::KInvalido { k: 0, maximo: dados.len() }));
+}
+
+#[test]
+fn metrica_invalida_e_parametro_invalido() {
+    let dados = treinamento();
+    let teste = ponto!([0.4, 0.3], "?");
+    let parametro_invalido =
+        |resultado: Result<String, ErroKnn>| matches!(resultado, Err(ErroKnn::ParametroInvalido(_)));
+
+    for metrica in [
+        DistanciaMetrica::Minkowski(-1.0),
+        DistanciaMetrica::Minkowski(f64::NAN),
+        DistanciaMetrica::EuclidianaPonderada(vec![1.0, -2.0]),
+        DistanciaMetrica::EuclidianaPonderada(vec![f64::INFINITY, 1.0]),
+    ] {
+        assert!(parametro_invalido(knn_com_metrica(&dados, &teste, 1, &metrica)), "{:?}", metrica);
+        let votacao = knn_com_votacao(&dados, &teste, 1, &metrica, Votacao::InversoDaDistancia);
+        assert!(parametro_invalido(votacao), "{:?}", metrica);
+        let confianca = knn_com_confianca(&dados, &teste, 1, &metrica);
+        assert!(matches!(confianca, Err(ErroKnn::ParametroInvalido(_))), "{:?}", metrica);
+    }
+}
+
+#[test]
+fn confianca_subamostra_e_iterador_devolvem_erros_sem_panico() {
+    let dados = treinamento();
+    let teste = ponto!([0.4, 0.3], "?");
+    let euclidiana = DistanciaMetrica::Euclidiana;
+    assert_eq!(knn_com_confianca(&[], &teste, 3, &euclidiana), Err(ErroKnn::ConjuntoVazio));
+    assert_eq!(knn_com_confianca(&dados, &teste, 0, &euclidiana), Err(ErroKnn::KInvalido { k: 0, maximo: 5 }));
+    assert_eq!(knn_com_confianca(&dados, &teste, 3, &euclidiana), Ok(("A".to_string(), 1.0)));
+
+    for fracao in [0.0, -0.5, 1.5, f64::NAN] {
+        let erro = knn_subamostrado(&dados, &teste, 3, fracao, 7);
+        assert!(matches!(erro, Err(ErroKnn::ParametroInvalido(_))), "fracao {}", fracao);
+    }
+
+    // Um ponto com NaN não tem vizinhos: o iterador devolve o erro e segue para o próximo
+    let testes = pontos![[f64::NAN, 0.0] => "?", [8.5, 9.0] => "?"];
+    let previsoes: Vec<Result<String, ErroKnn>> =
+        KnnClassificador::prever_iter(&dados, testes, 1).unwrap().map(|(_, rotulo)| rotulo).collect();
+    assert!(matches!(previsoes[0], Err(ErroKnn::VetorVazio(_))), "{:?}", previsoes);
+    assert_eq!(previsoes[1], Ok("B".to_string()));
+}
+
+#[test]
+fn k_maior_que_o_treinamento_usa_todos_os_pontos() {
+    let dados = treinamento();
+    // Com todos os 5 pontos votando, A vence por 3 a 2 mesmo perto dos B
+    assert_eq!(knn(&dados, &ponto!([9.0, 9.0], "?"), 100), Ok("A".to_string()));
+    assert_eq!(knn(&dados, &ponto!([9.0, 9.0], "?"), 100), knn(&dados, &ponto!([9.0, 9.0], "?"), dados.len()));
+}
+
+// Euclidiana, mas NaN até os pontos rotulados "nan" e infinita até os rotulados "longe"
+fn distancia_com_problemas(a: &Ponto, b: &Ponto) -> f64 {
+    match b.rotulo.as_str() {
+        "nan" => f64::NAN,
+        "longe" => f64::INFINITY,
+        _ => distancia_euclidiana(a, b),
+    }
+}
+
+#[test]
+fn distancia_nan_ignora_o_ponto_sem_panico() {
+    let metrica = DistanciaMetrica::Personalizada(distancia_com_problemas);
+    // O ponto "nan" está em cima do teste, mas a distância até ele não vale
+    let dados = pontos![[0.0] => "nan", [1.0] => "A", [5.0] => "B"];
+    let teste = ponto!([0.0], "?");
+    assert_eq!(knn_com_metrica(&dados, &teste, 1, &metrica), Ok("A".to_string()));
+    // Com k maior que os pontos válidos, votam só os válidos (A e B: empate, vence A)
+    assert_eq!(knn_com_metrica(&dados, &teste, 3, &metrica), Ok("A".to_string()));
+
+    // Um ponto de teste com NaN dá NaN até todos: não há vizinhos
+    let erro = knn(&dados, &ponto!([f64::NAN], "?"), 1).unwrap_err();
+    assert!(matches!(erro, ErroKnn::VetorVazio(_)), "{:?}", erro);
+}
+
+#[test]
+fn distancia_infinita_vota_como_a_mais_distante() {
+    let metrica = DistanciaMetrica::Personalizada(distancia_com_problemas);
+    let dados = pontos![[0.0] => "longe", [0.0] => "longe", [3.0] => "A"];
+    let teste = ponto!([0.0], "?");
+    // O vizinho mais próximo é o único a uma distância finita
+    assert_eq!(knn_com_metrica(&dados, &teste, 1, &metrica), Ok("A".to_string()));
+    // Com todos votando, a maioria vence mesmo infinitamente longe
+    assert_eq!(knn_com_metrica(&dados, &teste, 3, &metrica), Ok("longe".to_string()));
+    // No inverso da distância, peso 1/∞ = 0: só o A conta
+    let inverso = knn_com_votacao(&dados, &teste, 3, &metrica, Votacao::InversoDaDistancia);
+    assert_eq!(inverso, Ok("A".to_string()));
+}
+
+// 24 pontos exatamente à distância 2 da origem (coordenadas inteiras, sem arredondamento):
+// os 16 cantos (±1, ±1, ±1, ±1) e os 8 (±2, 0, 0, 0) e permutações. Os 5 primeiros votam
+// A A A B B e todos os outros são B, então os 5 vizinhos escolhidos mudam a previsão.
+// Depois deles vêm 10 pontos mais distantes
+fn empatados_e_distantes() -> (Vec<Ponto>, usize) {
+    let mut dados = Vec::new();
+    for canto in 0..16 {
+        let coordenadas = (0..4).map(|eixo| if canto >> eixo & 1 == 1 { -1.0 } else { 1.0 }).collect();
+        dados.push(Ponto::novo(coordenadas, if canto < 3 { "A" } else { "B" }.to_string()));
+    }
+    for eixo in 0..8 {
+        let mut coordenadas = vec![0.0; 4];
+        coordenadas[eixo / 2] = if eixo % 2 == 0 { 2.0 } else { -2.0 };
+        dados.push(Ponto::novo(coordenadas, "B".to_string()));
+    }
+    let empatados = dados.len();
+    for i in 0..10 {
+        dados.push(Ponto::novo(vec![5.0 + i as f64, 0.0, 0.0, 0.0], if i % 2 == 0 { "A" } else { "C" }.to_string()));
+    }
+    (dados, empatados)
+}
+
+// Índices (no treinamento) dos k vizinhos do knn, da árvore k-d e do JSON de previsões
+fn indices_dos_vizinhos(dados: &[Ponto], teste: &Ponto, k: usize) -> Vec<usize> {
+    let previsao = prever_para_json(dados, std::slice::from_ref(teste), k, true).unwrap().remove(0);
+    let indices: Vec<usize> = previsao.vizinhos.unwrap().iter().map(|vizinho| vizinho.indice).collect();
+    let arvore: Vec<usize> =
+        KdArvore::construir(dados).buscar_k_vizinhos(teste, k).iter().map(|vizinho| vizinho.indice).collect();
+    assert_eq!(arvore, indices);
+    indices
+}
+
+#[test]
+fn empates_escolhem_os_que_vem_antes_em_qualquer_ordem() {
+    let (base, empatados) = empatados_e_distantes();
+    let teste = ponto!([0.0, 0.0, 0.0, 0.0], "?");
+
+    for semente in 0..20 {
+        // Embaralha tudo e depois devolve os empatados às suas posições na ordem original:
+        // os distantes se misturam a eles, mas os empatados continuam na mesma ordem relativa
+        let mut ordem = Rng::novo(semente).amostrar_indices(base.len(), base.len());
+        let mut proximo_empatado = 0..empatados;
+        for id in ordem.iter_mut().filter(|id| **id < empatados) {
+            *id = proximo_empatado.next().unwrap();
+        }
+        let dados: Vec<Ponto> = ordem.iter().map(|&id| base[id].clone()).collect();
+
+        let escolhidos: Vec<usize> = indices_dos_vizinhos(&dados, &teste, 5).iter().map(|&i| ordem[i]).collect();
+        assert_eq!(escolhidos, vec![0, 1, 2, 3, 4], "semente {}", semente);
+        assert_eq!(knn(&dados, &teste, 5), Ok("A".to_string()), "semente {}", semente);
+        assert_eq!(knn_batch(&dados, &[teste.clone(), teste.clone()], 5), Ok(vec!["A".to_string(); 2]));
+    }
+}
+
+#[test]
+fn empates_desempatam_pela_posicao_no_treinamento() {
+    let (base, empatados) = empatados_e_distantes();
+    let teste = ponto!([0.0, 0.0, 0.0, 0.0], "?");
+
+    for semente in 0..20 {
+        let ordem = Rng::novo(semente).amostrar_indices(base.len(), base.len());
+        let dados: Vec<Ponto> = ordem.iter().map(|&id| base[id].clone()).collect();
+
+        // Os esperados são as 5 primeiras posições com um ponto empatado, em ordem crescente
+        let esperados: Vec<usize> = (0..dados.len()).filter(|&i| ordem[i] < empatados).take(5).collect();
+        assert_eq!(indices_dos_vizinhos(&dados, &teste, 5), esperados, "semente {}", semente);
+        // A mesma entrada dá sempre a mesma previsão, a dos pontos escolhidos
+        let so_escolhidos: Vec<Ponto> = esperados.iter().map(|&i| dados[i].clone()).collect();
+        let previsao = knn(&dados, &teste, 5);
+        assert_eq!(previsao, knn(&so_escolhidos, &teste, 5), "semente {}", semente);
+        assert_eq!(previsao, knn(&dados, &teste, 5));
+    }
+}
+
+// Uma região densa de 16 A (numa grade de passo 0.5) com um B ruidoso bem no meio dela,
+// e longe dali um B isolado
+fn denso_e_esparso() -> Vec<Ponto> {
+    let mut dados: Vec<Ponto> =
+        (0..16).map(|i| Ponto::novo(vec![(i % 4) as f64 * 0.5, (i / 4) as f64 * 0.5], "A".to_string())).collect();
+    dados.push(ponto!([0.5, 0.6], "B"));
+    dados.push(ponto!([20.0, 20.0], "B"));
+    dados
+}
+
+#[test]
+fn k_local_muda_a_previsao_entre_regiao_densa_e_esparsa() {
+    let dados = denso_e_esparso();
+    let denso = ponto!([0.5, 0.58], "?");
+    let esparso = ponto!([20.5, 20.5], "?");
+
+    // 17 pontos no raio do ponto denso (k = 5) e só 1 no do esparso (k = 1)
+    let k_denso = calcular_k_local(&dados, &denso, 2.0);
+    let k_esparso = calcular_k_local(&dados, &esparso, 2.0);
+    assert_eq!((k_denso, k_esparso), (5, 1));
+
+    // Na região densa, o k grande vota A apesar do B ruidoso, que sozinho daria B
+    assert_eq!(knn(&dados, &denso, 1), Ok("B".to_string()));
+    assert_eq!(knn(&dados, &denso, k_denso), Ok("A".to_string()));
+    // Na região esparsa, o k pequeno segue o B isolado, que o k global (5) deixaria perder para os A
+    assert_eq!(calcular_k(dados.len()), 5);
+    assert_eq!(knn(&dados, &esparso, calcular_k(dados.len())), Ok("A".to_string()));
+    assert_eq!(knn(&dados, &esparso, k_esparso), Ok("B".to_string()));
+}
+
+#[test]
+fn k_local_sem_pontos_no_raio_usa_o_k_global() {
+    let dados = denso_e_esparso();
+    let longe = ponto!([100.0, 100.0], "?");
+    assert_eq!(calcular_k_local(&dados, &longe, 2.0), calcular_k(dados.len()));
+    assert_eq!(calcular_k_local(&dados, &ponto!([0.5, 0.5], "?"), -1.0), calcular_k(dados.len()));
+    assert_eq!(calcular_k_local(&dados, &ponto!([0.5, 0.5], "?"), f64::NAN), calcular_k(dados.len()));
+}
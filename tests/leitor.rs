@@ -0,0 +1,45 @@
+// Testes de integração da leitura de CSV a partir de um leitor qualquer (io::Read),
+// usando um Cursor com os dados em memória no lugar da entrada padrão
+
+use std::io::Cursor;
+
+use aprendizagem_knn::{
+    carregar_dados_de_leitor, carregar_dados_de_leitor_com_opcoes, carregar_dados_do_csv, ErroKnn, OpcoesCsv,
+};
+
+#[test]
+fn leitor_carrega_o_mesmo_que_o_arquivo() {
+    let conteudo = std::fs::read("src/dados.csv").unwrap();
+    let do_leitor = carregar_dados_de_leitor(Cursor::new(conteudo), "<memória>").unwrap();
+    assert_eq!(do_leitor, carregar_dados_do_csv("src/dados.csv").unwrap());
+}
+
+#[test]
+fn entrada_sem_linhas_de_dados_e_conjunto_vazio() {
+    for entrada in ["", "x,y,classe\n", "x,y,classe"] {
+        let erro = carregar_dados_de_leitor(Cursor::new(entrada), "<stdin>").unwrap_err();
+        assert_eq!(erro, ErroKnn::ConjuntoVazio, "entrada {:?}", entrada);
+    }
+
+    // Sem cabeçalho, uma entrada vazia também não tem nenhum ponto
+    let opcoes = OpcoesCsv { tem_cabecalho: false, ..OpcoesCsv::default() };
+    let erro = carregar_dados_de_leitor_com_opcoes(Cursor::new(""), "<stdin>", &opcoes).unwrap_err();
+    assert_eq!(erro, ErroKnn::ConjuntoVazio);
+}
+
+#[test]
+fn erros_citam_a_origem_e_a_linha() {
+    let erro = carregar_dados_de_leitor(Cursor::new("x,classe\n1,A\n2\n"), "<stdin>").unwrap_err();
+    let texto = erro.to_string();
+    assert!(texto.starts_with("<stdin>: linha 3"), "{}", texto);
+}
+
+#[test]
+fn delimitador_detectado_sem_perder_a_primeira_linha() {
+    let opcoes = OpcoesCsv { delimitador: None, tem_cabecalho: false, ..OpcoesCsv::default() };
+    let entrada = Cursor::new("1\t2\tA\n3\t4\tB\n");
+    let (_, dados) = carregar_dados_de_leitor_com_opcoes(entrada, "<stdin>", &opcoes).unwrap();
+    assert_eq!(dados.len(), 2);
+    assert_eq!(dados[0].caracteristicas, vec![1.0, 2.0]);
+    assert_eq!(dados[0].rotulo, "A");
+}
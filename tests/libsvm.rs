@@ -0,0 +1,46 @@
+// Testes de integração da leitura do formato LIBSVM, a partir do arquivo de exemplo em
+// tests/fixtures (com comentários, uma linha em branco e índices fora de ordem)
+
+use aprendizagem_knn::{carregar_dados_do_libsvm, knn, ponto, pontos, ErroKnn};
+
+const ARQUIVO: &str = "tests/fixtures/pequeno.libsvm";
+
+#[test]
+fn carrega_pontos_densos_com_zeros_nos_ausentes() {
+    let dados = carregar_dados_do_libsvm(ARQUIVO, None).unwrap();
+    assert_eq!(
+        dados,
+        pontos![
+            [1.0, 0.9, 0.0, 0.0] => "+1",
+            [0.8, 1.1, 0.0, 0.0] => "+1",
+            [1.2, 1.0, 0.0, 0.1] => "+1",
+            [0.0, 0.0, 5.0, 5.2] => "-1",
+            [0.0, 0.0, 5.1, 4.9] => "-1",
+            [0.2, 0.0, 4.8, 5.0] => "-1",
+        ]
+    );
+}
+
+#[test]
+fn dimensao_informada_completa_com_zeros() {
+    let dados = carregar_dados_do_libsvm(ARQUIVO, Some(6)).unwrap();
+    assert!(dados.iter().all(|ponto| ponto.caracteristicas.len() == 6));
+    assert_eq!(dados[3].caracteristicas, vec![0.0, 0.0, 5.0, 5.2, 0.0, 0.0]);
+
+    // Menor que o maior índice do arquivo: erro na primeira linha com o índice 4
+    let erro = carregar_dados_do_libsvm(ARQUIVO, Some(3)).unwrap_err().to_string();
+    assert!(erro.contains("linha 6"), "{}", erro);
+}
+
+#[test]
+fn knn_classifica_pontos_do_arquivo() {
+    let dados = carregar_dados_do_libsvm(ARQUIVO, None).unwrap();
+    assert_eq!(knn(&dados, &ponto!([1.0, 1.0, 0.0, 0.0], "?"), 3).unwrap(), "+1");
+    assert_eq!(knn(&dados, &ponto!([0.0, 0.5, 4.0, 6.0], "?"), 3).unwrap(), "-1");
+}
+
+#[test]
+fn arquivo_inexistente_e_arquivo_nao_encontrado() {
+    let erro = carregar_dados_do_libsvm("tests/fixtures/nao_existe.libsvm", None).unwrap_err();
+    assert_eq!(erro, ErroKnn::ArquivoNaoEncontrado("tests/fixtures/nao_existe.libsvm".to_string()));
+}
@@ -0,0 +1,68 @@
+// Testes de integração do Modelo: anexar um CSV a um modelo já treinado precisa dar o mesmo
+// resultado que treinar de uma vez com os dois arquivos juntos, e um ponto com a dimensão
+// errada é recusado sem alterar o modelo
+
+use aprendizagem_knn::{knn, ponto, ErroKnn, Modelo};
+
+const PARTE_A: &str = "tests/fixtures/modelo_parte_a.csv";
+const PARTE_B: &str = "tests/fixtures/modelo_parte_b.csv";
+
+// Grava A seguido das linhas de B (sem o segundo cabeçalho) num arquivo temporário
+fn juntar_a_e_b() -> String {
+    let a = std::fs::read_to_string(PARTE_A).unwrap();
+    let b = std::fs::read_to_string(PARTE_B).unwrap();
+    let linhas_b: String = b.lines().skip(1).map(|linha| format!("{linha}\n")).collect();
+
+    let caminho = std::env::temp_dir().join("aprendizagem_knn_teste_modelo_a_e_b.csv");
+    std::fs::write(&caminho, a + &linhas_b).unwrap();
+    caminho.to_str().unwrap().to_string()
+}
+
+#[test]
+fn anexar_csv_equivale_a_treinar_com_os_dois_arquivos() {
+    let mut anexado = Modelo::do_csv(PARTE_A).unwrap();
+    assert_eq!(anexado.anexar_csv(PARTE_B).unwrap(), 4);
+    let junto = Modelo::do_csv(&juntar_a_e_b()).unwrap();
+
+    assert_eq!(anexado.dimensao(), junto.dimensao());
+    assert_eq!(anexado.colunas, junto.colunas);
+    assert_eq!(anexado.rotulos, junto.rotulos);
+    assert_eq!(anexado.pontos, junto.pontos);
+    // Os pontos entram na mesma ordem, então as contas incrementais são as mesmas
+    assert_eq!(anexado.estatisticas.n, junto.estatisticas.n);
+    assert_eq!(anexado.estatisticas.media, junto.estatisticas.media);
+    assert_eq!(anexado.estatisticas.variancia(), junto.estatisticas.variancia());
+
+    let consultas = [
+        ponto!([1.52, 54.0], "?"),
+        ponto!([1.71, 69.0], "?"),
+        ponto!([1.88, 92.0], "?"),
+        ponto!([1.65, 64.0], "?"),
+    ];
+    for consulta in &consultas {
+        for k in [1, 3] {
+            assert_eq!(
+                knn(&anexado.pontos, consulta, k).unwrap(),
+                knn(&junto.pontos, consulta, k).unwrap(),
+                "{:?} com k = {k}",
+                consulta.caracteristicas
+            );
+        }
+    }
+}
+
+#[test]
+fn adicionar_ponto_com_outra_dimensao_e_dimensao_incompativel() {
+    let mut modelo = Modelo::do_csv(PARTE_A).unwrap();
+    let media = modelo.estatisticas.media.clone();
+
+    let erro = modelo.adicionar(ponto!([1.7], "medio")).unwrap_err();
+    assert_eq!(erro, ErroKnn::DimensaoIncompativel { esperado: 2, obtido: 1 });
+    let erro = modelo.adicionar(ponto!([1.7, 70.0, 3.0], "medio")).unwrap_err();
+    assert_eq!(erro, ErroKnn::DimensaoIncompativel { esperado: 2, obtido: 3 });
+
+    // Nada mudou: nem os pontos, nem os rótulos, nem as estatísticas
+    assert_eq!(modelo.pontos.len(), 5);
+    assert!(!modelo.rotulos.contains(&"medio".to_string()));
+    assert_eq!(modelo.estatisticas.media, media);
+}
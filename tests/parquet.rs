@@ -0,0 +1,169 @@
+// Testes de integração da leitura e gravação de Parquet: cada teste grava um arquivo pequeno na pasta
+// temporária (com o ArrowWriter ou com exportar_pontos_parquet) e o lê de volta. Só rodam
+// com `cargo test --features parquet`
+#![cfg(feature = "parquet")]
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use aprendizagem_knn::{carregar_dados_do_parquet, exportar_pontos_parquet, knn, ponto, pontos, ErroKnn, Ponto};
+use arrow_array::{ArrayRef, BooleanArray, Float32Array, Float64Array, Int64Array, RecordBatch, StringArray};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+
+// Grava as colunas em um arquivo Parquet com o nome dado e devolve o caminho
+fn gravar(nome: &str, colunas: Vec<(&str, ArrayRef)>) -> PathBuf {
+    let lote = RecordBatch::try_from_iter(colunas).unwrap();
+    let caminho = std::env::temp_dir().join(format!("aprendizagem_knn_teste_{}.parquet", nome));
+    let propriedades = WriterProperties::builder().set_compression(Compression::SNAPPY).build();
+    let arquivo = std::fs::File::create(&caminho).unwrap();
+    let mut escritor = ArrowWriter::try_new(arquivo, lote.schema(), Some(propriedades)).unwrap();
+    escritor.write(&lote).unwrap();
+    escritor.close().unwrap();
+    caminho
+}
+
+fn carregar(caminho: &Path, coluna_rotulo: &str) -> Result<Vec<Ponto>, ErroKnn> {
+    carregar_dados_do_parquet(caminho.to_str().unwrap(), coluna_rotulo)
+}
+
+#[test]
+fn ida_e_volta_com_tipos_numericos_diferentes() {
+    let caminho = gravar(
+        "ida_e_volta",
+        vec![
+            ("comprimento", Arc::new(Float64Array::from(vec![5.1, 7.0, 6.3])) as ArrayRef),
+            ("largura", Arc::new(Float32Array::from(vec![3.5, 3.25, 3.0])) as ArrayRef),
+            ("especie", Arc::new(StringArray::from(vec!["setosa", "versicolor", "virginica"])) as ArrayRef),
+            ("petalas", Arc::new(Int64Array::from(vec![1, 4, 6])) as ArrayRef),
+        ],
+    );
+    let dados = carregar(&caminho, "especie").unwrap();
+    assert_eq!(
+        dados,
+        pontos![
+            [5.1, 3.5, 1.0] => "setosa",
+            [7.0, 3.25, 4.0] => "versicolor",
+            [6.3, 3.0, 6.0] => "virginica",
+        ]
+    );
+    assert_eq!(knn(&dados, &ponto!([6.9, 3.2, 4.2], "?"), 1).unwrap(), "versicolor");
+}
+
+#[test]
+fn arquivo_com_varios_lotes() {
+    // Mais linhas que o tamanho de lote padrão do leitor (1024)
+    let n = 2500;
+    let valores: Vec<f64> = (0..n).map(f64::from).collect();
+    let classes: Vec<String> = (0..n).map(|i| format!("c{}", i % 3)).collect();
+    let caminho = gravar(
+        "varios_lotes",
+        vec![
+            ("x", Arc::new(Float64Array::from(valores)) as ArrayRef),
+            ("classe", Arc::new(StringArray::from(classes)) as ArrayRef),
+        ],
+    );
+    let dados = carregar(&caminho, "classe").unwrap();
+    assert_eq!(dados.len(), n as usize);
+    assert_eq!(dados[2499].caracteristicas, vec![2499.0]);
+    assert_eq!(dados[2499].rotulo, "c0");
+}
+
+#[test]
+fn caracteristica_nula_vira_nan_e_rotulo_nulo_e_erro() {
+    let caminho = gravar(
+        "nulos",
+        vec![
+            ("x", Arc::new(Float64Array::from(vec![Some(1.0), None])) as ArrayRef),
+            ("classe", Arc::new(StringArray::from(vec![Some("A"), Some("B")])) as ArrayRef),
+        ],
+    );
+    let dados = carregar(&caminho, "classe").unwrap();
+    assert!(dados[1].caracteristicas[0].is_nan());
+
+    let caminho = gravar(
+        "rotulo_nulo",
+        vec![
+            ("x", Arc::new(Float64Array::from(vec![1.0, 2.0])) as ArrayRef),
+            ("classe", Arc::new(StringArray::from(vec![Some("A"), None])) as ArrayRef),
+        ],
+    );
+    let erro = carregar(&caminho, "classe").unwrap_err().to_string();
+    assert!(erro.contains("linha 2") && erro.contains("nulo"), "{}", erro);
+}
+
+#[test]
+fn tipos_errados_sao_erros_descritivos() {
+    let caminho = gravar(
+        "tipos_errados",
+        vec![
+            ("x", Arc::new(Float64Array::from(vec![1.0])) as ArrayRef),
+            ("classe", Arc::new(Int64Array::from(vec![1])) as ArrayRef),
+            ("ativo", Arc::new(BooleanArray::from(vec![true])) as ArrayRef),
+            ("nome", Arc::new(StringArray::from(vec!["a"])) as ArrayRef),
+        ],
+    );
+
+    // Rótulo numérico
+    let erro = carregar(&caminho, "classe").unwrap_err().to_string();
+    assert!(erro.contains("'classe'") && erro.contains("Int64") && erro.contains("texto"), "{}", erro);
+
+    // Característica que não é numérica
+    let erro = carregar(&caminho, "nome").unwrap_err().to_string();
+    assert!(erro.contains("'ativo'") && erro.contains("Boolean") && erro.contains("numéricas"), "{}", erro);
+
+    // Coluna de rótulo que não existe
+    let erro = carregar(&caminho, "especie").unwrap_err().to_string();
+    assert!(erro.contains("'especie'"), "{}", erro);
+}
+
+#[test]
+fn arquivo_que_nao_e_parquet() {
+    let caminho = std::env::temp_dir().join("aprendizagem_knn_teste_nao_e_parquet.parquet");
+    std::fs::write(&caminho, "x,classe\n1,A\n").unwrap();
+    let erro = carregar(&caminho, "classe").unwrap_err();
+    assert!(matches!(&erro, ErroKnn::Arquivo(mensagem) if mensagem.contains("Parquet")), "{}", erro);
+}
+
+#[test]
+fn exportar_e_carregar_de_volta_da_os_mesmos_pontos() {
+    // Mais pontos que um lote da gravação, com um NaN e rótulos com acentos
+    let dados: Vec<Ponto> = (0..10_000)
+        .map(|i| {
+            let x = if i == 7 { f64::NAN } else { f64::from(i) * 0.5 };
+            Ponto::novo(vec![x, -f64::from(i)], if i % 2 == 0 { "ímpar?" } else { "não" }.to_string())
+        })
+        .collect();
+    let nomes = vec!["x".to_string(), "y".to_string()];
+    let caminho = std::env::temp_dir().join("aprendizagem_knn_teste_exportado.parquet");
+    exportar_pontos_parquet(&dados, &nomes, caminho.to_str().unwrap()).unwrap();
+
+    let lidos = carregar(&caminho, "rotulo").unwrap();
+    assert_eq!(lidos.len(), dados.len());
+    assert!(lidos[7].caracteristicas[0].is_nan());
+    assert_eq!(lidos[..7], dados[..7]);
+    assert_eq!(lidos[8..], dados[8..]);
+}
+
+#[test]
+fn exportar_confere_os_nomes_e_a_dimensao() {
+    let caminho = std::env::temp_dir().join("aprendizagem_knn_teste_exportar_erros.parquet");
+    let caminho = caminho.to_str().unwrap();
+    let dados = pontos![[1.0, 2.0] => "A", [3.0] => "B"];
+    let nomes = vec!["x".to_string(), "y".to_string()];
+
+    let erro = exportar_pontos_parquet(&dados, &nomes, caminho).unwrap_err();
+    assert_eq!(erro, ErroKnn::DimensaoIncompativel { esperado: 2, obtido: 1 });
+
+    let dados = pontos![[1.0, 2.0] => "A"];
+    for nomes in [vec![], vec!["x".to_string(), "x".to_string()], vec!["x".to_string(), "rotulo".to_string()]] {
+        let erro = exportar_pontos_parquet(&dados, &nomes, caminho).unwrap_err();
+        assert!(matches!(erro, ErroKnn::ParametroInvalido(_)), "{:?}: {:?}", nomes, erro);
+    }
+
+    // Uma pasta que não existe
+    let caminho = "/nao/existe/dados.parquet";
+    let erro = exportar_pontos_parquet(&dados, &["x".to_string(), "y".to_string()], caminho);
+    assert_eq!(erro, Err(ErroKnn::ArquivoNaoEncontrado(caminho.to_string())));
+}
@@ -0,0 +1,109 @@
+// Testes de integração dos pré-processamentos: a normalização min-max num conjunto com
+// escalas muito diferentes (e a sua inversa), e o escalonador robusto contra o z-score
+// quando um outlier extremo entra no treinamento
+
+use aprendizagem_knn::{
+    knn, ponto, pontos, EscalonadorRobusto, NormalizadorMinMax, Padronizador, Ponto, Rng, Transformador,
+};
+
+// A maior mudança, em qualquer característica de qualquer ponto, entre duas versões
+// transformadas dos mesmos pontos
+fn maior_diferenca(antes: &[Ponto], depois: &[Ponto]) -> f64 {
+    antes
+        .iter()
+        .zip(depois)
+        .flat_map(|(a, b)| a.caracteristicas.iter().zip(&b.caracteristicas).map(|(x, y)| (x - y).abs()))
+        .fold(0.0, f64::max)
+}
+
+// A primeira característica (entre 0 e 1) separa as classes; a segunda vai até 10000 e não
+// tem nada a ver com a classe
+fn escalas_desbalanceadas() -> Vec<Ponto> {
+    pontos![
+        [0.05, 9000.0] => "A", [0.10, 1000.0] => "A", [0.20, 5000.0] => "A", [0.15, 7000.0] => "A",
+        [0.85, 2000.0] => "B", [0.90, 8000.0] => "B", [0.95, 4000.0] => "B", [0.80, 6000.0] => "B",
+    ]
+}
+
+#[test]
+fn normalizar_muda_a_classificacao_quando_as_escalas_sao_diferentes() {
+    let treinamento = escalas_desbalanceadas();
+    let normalizador = NormalizadorMinMax::ajustar(&treinamento);
+    let normalizado = normalizador.transformar(&treinamento);
+
+    // Cada teste está na região da sua classe pela primeira característica, mas a segunda
+    // cai perto de pontos da outra classe
+    let testes = pontos![[0.1, 2100.0] => "A", [0.9, 8900.0] => "B", [0.2, 6100.0] => "A", [0.8, 4900.0] => "B"];
+    for teste in &testes {
+        // Sem normalizar, a segunda característica decide sozinha, e o knn erra
+        let sem_normalizar = knn(&treinamento, teste, 1).unwrap();
+        assert_ne!(sem_normalizar, teste.rotulo, "{:?}", teste.caracteristicas);
+
+        // Com o mesmo normalizador aplicado ao teste, a primeira característica volta a pesar
+        let normalizado_teste = normalizador.transformar_ponto(teste);
+        assert_eq!(knn(&normalizado, &normalizado_teste, 3).unwrap(), teste.rotulo, "{:?}", teste.caracteristicas);
+    }
+}
+
+#[test]
+fn normalizar_e_inverter_devolve_o_conjunto_original() {
+    // Escalas bem diferentes, valores negativos e uma característica constante
+    let mut rng = Rng::novo(11);
+    let dados: Vec<Ponto> = (0..50)
+        .map(|i| {
+            let caracteristicas = vec![rng.proximo_f64(), rng.proximo_f64() * 10_000.0, -50.0 + rng.proximo_f64(), 7.0];
+            Ponto::novo(caracteristicas, format!("classe {}", i % 3))
+        })
+        .collect();
+    let normalizador = NormalizadorMinMax::ajustar(&dados);
+    let normalizado = normalizador.transformar(&dados);
+
+    // Tudo em [0, 1], e a característica constante em 0
+    assert!(normalizado.iter().flat_map(|ponto| &ponto.caracteristicas).all(|valor| (0.0..=1.0).contains(valor)));
+    assert!(normalizado.iter().all(|ponto| ponto.caracteristicas[3] == 0.0));
+
+    for (original, normalizado) in dados.iter().zip(&normalizado) {
+        let invertido = normalizador.inverter_ponto(normalizado);
+        assert_eq!(invertido.rotulo, original.rotulo);
+        for (valor, esperado) in invertido.caracteristicas.iter().zip(&original.caracteristicas) {
+            assert!((valor - esperado).abs() <= 1e-9 * esperado.abs().max(1.0), "{} != {}", valor, esperado);
+        }
+    }
+
+    // Um ponto de teste fora do intervalo do treinamento também faz a ida e a volta
+    let teste = ponto!([2.0, -500.0, -60.0, 7.0], "?");
+    let invertido = normalizador.inverter_ponto(&normalizador.transformar_ponto(&teste));
+    for (valor, esperado) in invertido.caracteristicas.iter().zip(&teste.caracteristicas) {
+        assert!((valor - esperado).abs() < 1e-9 * esperado.abs().max(1.0), "{} != {}", valor, esperado);
+    }
+}
+
+// 40 pontos bem comportados, com as duas características entre 0 e 20
+fn grosso_dos_dados() -> Vec<Ponto> {
+    (0..40).map(|i| Ponto::novo(vec![(i % 20) as f64, (i / 2) as f64], "A".to_string())).collect()
+}
+
+#[test]
+fn outlier_extremo_quase_nao_muda_o_escalonador_robusto_mas_arrasa_o_z_score() {
+    let grosso = grosso_dos_dados();
+    let mut com_outlier = grosso.clone();
+    com_outlier.push(Ponto::novo(vec![1.0e6, -1.0e6], "A".to_string()));
+
+    // Os mesmos 40 pontos, transformados por escaladores ajustados sem e com o outlier
+    let robusto_sem = EscalonadorRobusto::ajustar(&grosso).transformar(&grosso);
+    let robusto_com = EscalonadorRobusto::ajustar(&com_outlier).transformar(&grosso);
+    let z_score_sem = Padronizador::ajustar(&grosso).transformar(&grosso);
+    let z_score_com = Padronizador::ajustar(&com_outlier).transformar(&grosso);
+
+    // No robusto, mediana e quartis andam no máximo meio passo: o grosso dos dados fica
+    // praticamente no mesmo lugar
+    let mudanca_robusto = maior_diferenca(&robusto_sem, &robusto_com);
+    assert!(mudanca_robusto < 0.1, "robusto mudou {}", mudanca_robusto);
+
+    // No z-score, o outlier infla média e desvio padrão: o grosso dos dados, que ia de
+    // cerca de -1.7 a 1.7, fica espremido perto de zero
+    let mudanca_z_score = maior_diferenca(&z_score_sem, &z_score_com);
+    assert!(mudanca_z_score > 1.0, "z-score mudou {}", mudanca_z_score);
+    let amplitude_com: f64 = z_score_com.iter().map(|ponto| ponto.caracteristicas[0].abs()).fold(0.0, f64::max);
+    assert!(amplitude_com < 0.2, "{}", amplitude_com);
+}
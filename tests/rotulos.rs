@@ -0,0 +1,93 @@
+// Testes de integração dos rótulos genéricos: o mesmo knn com rótulos String, u32 e um
+// enum próprio, e a conversão dos rótulos na leitura do CSV
+
+use std::str::FromStr;
+
+use aprendizagem_knn::{carregar_dados_do_csv, carregar_dados_do_csv_mapeando_rotulos, knn, ErroKnn, PontoGenerico};
+
+const ARQUIVO: &str = "tests/fixtures/rotulos_numericos.csv";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+enum Especie {
+    Setosa,
+    Versicolor,
+    Virginica,
+}
+
+impl FromStr for Especie {
+    type Err = String;
+
+    fn from_str(texto: &str) -> Result<Self, Self::Err> {
+        match texto {
+            "0" => Ok(Especie::Setosa),
+            "1" => Ok(Especie::Versicolor),
+            "2" => Ok(Especie::Virginica),
+            _ => Err(format!("espécie desconhecida: {}", texto)),
+        }
+    }
+}
+
+// Os pontos de teste, um perto de cada grupo do arquivo
+fn testes<R: Clone>(rotulo: R) -> Vec<PontoGenerico<R>> {
+    [[0.4, 0.3], [8.5, 9.0], [0.5, 8.8]].iter().map(|xy| PontoGenerico::novo(xy.to_vec(), rotulo.clone())).collect()
+}
+
+#[test]
+fn knn_com_rotulos_string() {
+    let dados = carregar_dados_do_csv(ARQUIVO).unwrap();
+    let previstos: Vec<String> = testes(String::new()).iter().map(|teste| knn(&dados, teste, 3).unwrap()).collect();
+    assert_eq!(previstos, vec!["0", "1", "2"]);
+}
+
+#[test]
+fn knn_com_rotulos_u32() {
+    let numero = |rotulo: &str| rotulo.parse::<u32>().map_err(|erro| erro.to_string());
+    let dados = carregar_dados_do_csv_mapeando_rotulos(ARQUIVO, numero).unwrap();
+    assert_eq!(dados.iter().map(|ponto| ponto.rotulo).sum::<u32>(), 9);
+
+    let previstos: Vec<u32> = testes(0).iter().map(|teste| knn(&dados, teste, 3).unwrap()).collect();
+    assert_eq!(previstos, vec![0, 1, 2]);
+
+    // No empate vence o menor rótulo pelo Ord: 10 < 9 como texto, mas não como número
+    let empate = vec![PontoGenerico::novo(vec![0.0], 10u32), PontoGenerico::novo(vec![2.0], 9u32)];
+    assert_eq!(knn(&empate, &PontoGenerico::novo(vec![1.0], 0), 2), Ok(9));
+}
+
+#[test]
+fn knn_com_rotulos_enum() {
+    let dados = carregar_dados_do_csv_mapeando_rotulos(ARQUIVO, Especie::from_str).unwrap();
+    let previstos: Vec<Especie> =
+        testes(Especie::Setosa).iter().map(|teste| knn(&dados, teste, 3).unwrap()).collect();
+    assert_eq!(previstos, vec![Especie::Setosa, Especie::Versicolor, Especie::Virginica]);
+
+    // Os mesmos pontos, convertidos depois da leitura
+    let convertidos: Vec<PontoGenerico<Especie>> = carregar_dados_do_csv(ARQUIVO)
+        .unwrap()
+        .into_iter()
+        .map(|ponto| ponto.mapear_rotulo(|rotulo| rotulo.parse().unwrap()))
+        .collect();
+    assert_eq!(convertidos, dados);
+
+    // Os erros de entrada são os mesmos com qualquer tipo de rótulo
+    let teste = PontoGenerico::novo(vec![0.0, 0.0], Especie::Setosa);
+    assert_eq!(knn(&[], &teste, 3), Err(ErroKnn::ConjuntoVazio));
+    assert_eq!(knn(&dados, &teste, 0), Err(ErroKnn::KInvalido { k: 0, maximo: dados.len() }));
+}
+
+#[test]
+fn rotulo_que_nao_converte_e_erro_com_a_linha() {
+    let so_zero_e_um = |rotulo: &str| match rotulo {
+        "0" => Ok(false),
+        "1" => Ok(true),
+        _ => Err("esperava 0 ou 1".to_string()),
+    };
+    let erro = carregar_dados_do_csv_mapeando_rotulos(ARQUIVO, so_zero_e_um).unwrap_err();
+    assert_eq!(
+        erro,
+        ErroKnn::LinhaInvalida {
+            origem: ARQUIVO.to_string(),
+            linha: 8,
+            motivo: "rótulo '2': esperava 0 ou 1".to_string(),
+        }
+    );
+}
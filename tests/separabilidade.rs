@@ -0,0 +1,62 @@
+// Testes de integração das estatísticas de separabilidade: classes bem separadas têm um
+// índice de Fisher muito alto, classes misturadas um índice baixo, e os conjuntos sem
+// pares intra ou interclasse são erro
+
+use aprendizagem_knn::{estatisticas_separabilidade, pontos, ErroKnn, Ponto, Rng};
+
+// Dois grupos de pontos sorteados num quadrado de lado `lado`, o segundo deslocado de
+// `distancia` no eixo x
+fn dois_grupos(distancia: f64, lado: f64, semente: u64) -> Vec<Ponto> {
+    let mut rng = Rng::novo(semente);
+    (0..40)
+        .map(|i| {
+            let (deslocamento, rotulo) = if i < 20 { (0.0, "A") } else { (distancia, "B") };
+            let x = deslocamento + rng.proximo_f64() * lado;
+            Ponto::novo(vec![x, rng.proximo_f64() * lado], rotulo.to_string())
+        })
+        .collect()
+}
+
+#[test]
+fn classes_perfeitamente_separadas_tem_indice_de_fisher_muito_alto() {
+    for semente in 0..5 {
+        let separabilidade = estatisticas_separabilidade(&dois_grupos(100.0, 1.0, semente)).unwrap();
+        assert!(separabilidade.distancia_media_intraclasse < 1.5);
+        assert!(separabilidade.distancia_media_interclasse > 98.0);
+        assert!(separabilidade.indice_fisher > 10_000.0, "semente {}: {:?}", semente, separabilidade);
+    }
+}
+
+#[test]
+fn classes_misturadas_tem_indice_baixo() {
+    // Os dois grupos no mesmo quadrado: as distâncias intra e interclasse se parecem
+    let misturadas = estatisticas_separabilidade(&dois_grupos(0.0, 1.0, 7)).unwrap();
+    let razao = misturadas.distancia_media_interclasse / misturadas.distancia_media_intraclasse;
+    assert!((0.8..1.2).contains(&razao), "{:?}", misturadas);
+    assert!(misturadas.indice_fisher < 10.0, "{:?}", misturadas);
+
+    // Afastar os grupos só aumenta o índice
+    let separadas = estatisticas_separabilidade(&dois_grupos(3.0, 1.0, 7)).unwrap();
+    assert!(separadas.indice_fisher > misturadas.indice_fisher);
+}
+
+#[test]
+fn sem_duas_classes_ou_sem_pares_intraclasse_e_vetor_vazio() {
+    let vetor_vazio = |dados: Vec<Ponto>| matches!(estatisticas_separabilidade(&dados), Err(ErroKnn::VetorVazio(_)));
+    assert!(vetor_vazio(Vec::new()));
+    assert!(vetor_vazio(pontos![[0.0] => "A", [1.0] => "A", [2.0] => "A"]));
+    // Duas classes, mas um ponto em cada
+    assert!(vetor_vazio(pontos![[0.0] => "A", [1.0] => "B"]));
+
+    let dimensoes_diferentes = pontos![[0.0, 1.0] => "A", [1.0] => "B"];
+    let erro = estatisticas_separabilidade(&dimensoes_diferentes).unwrap_err();
+    assert_eq!(erro, ErroKnn::DimensaoIncompativel { esperado: 2, obtido: 1 });
+}
+
+#[test]
+fn pontos_todos_iguais_tem_indice_zero() {
+    let dados = pontos![[1.0, 1.0] => "A", [1.0, 1.0] => "A", [1.0, 1.0] => "B"];
+    let separabilidade = estatisticas_separabilidade(&dados).unwrap();
+    assert_eq!(separabilidade.distancia_media_interclasse, 0.0);
+    assert_eq!(separabilidade.indice_fisher, 0.0);
+}
@@ -0,0 +1,114 @@
+// Testes de integração da leitura de SQLite: cada teste cria um banco em memória, insere
+// as linhas e lê o resultado de uma consulta. Só rodam com `cargo test --features sqlite`
+#![cfg(feature = "sqlite")]
+
+use aprendizagem_knn::{carregar_dados_de_conexao_sqlite, carregar_dados_do_sqlite, knn, ponto, pontos, ErroKnn};
+use rusqlite::Connection;
+
+fn banco_de_flores() -> Connection {
+    let conexao = Connection::open_in_memory().unwrap();
+    conexao
+        .execute_batch(
+            "CREATE TABLE flores (id INTEGER PRIMARY KEY, comprimento REAL, largura REAL, especie TEXT);
+             INSERT INTO flores (comprimento, largura, especie) VALUES
+                 (5.1, 3.5, 'setosa'),
+                 (4.9, 3.0, 'setosa'),
+                 (7.0, 3.2, 'versicolor'),
+                 (6.4, 3.2, 'versicolor'),
+                 (6.3, 3.3, 'virginica'),
+                 (7.1, 3.0, 'virginica');",
+        )
+        .unwrap();
+    conexao
+}
+
+#[test]
+fn carrega_o_resultado_da_consulta() {
+    let conexao = banco_de_flores();
+    let dados = carregar_dados_de_conexao_sqlite(
+        &conexao,
+        "SELECT comprimento, largura, especie FROM flores WHERE especie != 'virginica' ORDER BY id",
+        "especie",
+    )
+    .unwrap();
+    assert_eq!(
+        dados,
+        pontos![
+            [5.1, 3.5] => "setosa",
+            [4.9, 3.0] => "setosa",
+            [7.0, 3.2] => "versicolor",
+            [6.4, 3.2] => "versicolor",
+        ]
+    );
+}
+
+#[test]
+fn knn_classifica_com_os_dados_do_banco() {
+    let conexao = banco_de_flores();
+    // O rótulo não precisa ser a última coluna
+    let dados =
+        carregar_dados_de_conexao_sqlite(&conexao, "SELECT especie, comprimento, largura FROM flores", "especie")
+            .unwrap();
+    assert_eq!(dados.len(), 6);
+    assert_eq!(knn(&dados, &ponto!([5.0, 3.4], "?"), 1).unwrap(), "setosa");
+    assert_eq!(knn(&dados, &ponto!([6.5, 3.2], "?"), 1).unwrap(), "versicolor");
+}
+
+#[test]
+fn nulos_e_ausentes_em_texto_viram_nan() {
+    let conexao = Connection::open_in_memory().unwrap();
+    conexao
+        .execute_batch(
+            "CREATE TABLE t (x, y, classe);
+             INSERT INTO t VALUES (NULL, 1, 'A'), ('NA', '2.5', 'B'), ('', 3, 4);",
+        )
+        .unwrap();
+    let dados = carregar_dados_de_conexao_sqlite(&conexao, "SELECT * FROM t", "classe").unwrap();
+    assert!(dados.iter().all(|ponto| ponto.caracteristicas[0].is_nan()));
+    let y: Vec<f64> = dados.iter().map(|ponto| ponto.caracteristicas[1]).collect();
+    assert_eq!(y, vec![1.0, 2.5, 3.0]);
+    // Rótulo inteiro vira texto
+    assert_eq!(dados[2].rotulo, "4");
+}
+
+#[test]
+fn erros_citam_a_coluna() {
+    let conexao = banco_de_flores();
+
+    // Característica que não é numérica
+    let erro = carregar_dados_de_conexao_sqlite(&conexao, "SELECT especie AS nome, id, especie FROM flores", "id")
+        .unwrap_err()
+        .to_string();
+    assert!(erro.contains("linha 1, coluna 'nome'") && erro.contains("'setosa'"), "{}", erro);
+
+    // Rótulo real ou NULL
+    let erro = carregar_dados_de_conexao_sqlite(&conexao, "SELECT id, comprimento FROM flores", "comprimento")
+        .unwrap_err()
+        .to_string();
+    assert!(erro.contains("coluna 'comprimento'") && erro.contains("texto ou inteiro"), "{}", erro);
+    let erro = carregar_dados_de_conexao_sqlite(&conexao, "SELECT 1 AS x, NULL AS classe", "classe")
+        .unwrap_err()
+        .to_string();
+    assert!(erro.contains("NULL"), "{}", erro);
+
+    // Coluna de rótulo que não está na consulta e consulta inválida
+    let erro = carregar_dados_de_conexao_sqlite(&conexao, "SELECT id FROM flores", "especie").unwrap_err().to_string();
+    assert!(erro.contains("'especie'"), "{}", erro);
+    let erro = carregar_dados_de_conexao_sqlite(&conexao, "SELECT * FROM jardim", "especie").unwrap_err();
+    assert!(matches!(&erro, ErroKnn::Arquivo(mensagem) if mensagem.contains("consulta inválida")), "{}", erro);
+}
+
+#[test]
+fn le_de_um_arquivo() {
+    let caminho = std::env::temp_dir().join("aprendizagem_knn_teste_flores.sqlite");
+    let _ = std::fs::remove_file(&caminho);
+    // VACUUM INTO grava uma cópia do banco em memória no arquivo
+    banco_de_flores().execute("VACUUM INTO ?1", [caminho.to_str().unwrap()]).unwrap();
+
+    let consulta = "SELECT comprimento, largura, especie FROM flores";
+    let dados = carregar_dados_do_sqlite(caminho.to_str().unwrap(), consulta, "especie").unwrap();
+    assert_eq!(dados.len(), 6);
+
+    let erro = carregar_dados_do_sqlite("tests/fixtures/nao_existe.sqlite", "SELECT 1", "x").unwrap_err();
+    assert!(erro.to_string().contains("nao_existe.sqlite"), "{}", erro);
+}
@@ -0,0 +1,79 @@
+// Testes de integração do leave-one-out: os dois caminhos do loocv (com a matriz de
+// distâncias e calculando as distâncias na hora) precisam dar o mesmo resultado que tirar
+// cada ponto e rodar o knn nos demais, inclusive quando algumas distâncias dão NaN
+
+use aprendizagem_knn::{knn, loocv, loocv_com_limite, ErroKnn, Ponto, Rng};
+
+// Três classes sobrepostas, para que haja erros e empates de votos
+fn dados_sobrepostos(n: usize, semente: u64) -> Vec<Ponto> {
+    let mut rng = Rng::novo(semente);
+    (0..n)
+        .map(|i| {
+            let classe = i % 3;
+            let centro = classe as f64 * 1.5;
+            let caracteristicas = vec![centro + rng.proximo_f64() * 3.0, rng.proximo_f64() * 3.0];
+            Ponto::novo(caracteristicas, ["A", "B", "C"][classe].to_string())
+        })
+        .collect()
+}
+
+// A acurácia do leave-one-out pela definição: o knn com cada ponto fora do treinamento
+fn loocv_por_forca_bruta(dados: &[Ponto], k: usize) -> f64 {
+    let acertos = (0..dados.len())
+        .filter(|&i| {
+            let mut outros = dados.to_vec();
+            let ponto = outros.remove(i);
+            knn(&outros, &ponto, k).unwrap() == ponto.rotulo
+        })
+        .count();
+    acertos as f64 / dados.len() as f64
+}
+
+#[test]
+fn com_e_sem_matriz_dao_o_mesmo_que_o_knn() {
+    for semente in [1, 2, 3] {
+        let dados = dados_sobrepostos(60, semente);
+        for k in [1, 2, 4, 7, 59, 100] {
+            let esperado = loocv_por_forca_bruta(&dados, k);
+            // Limite acima de n: monta a matriz; limite 0: calcula as distâncias na hora
+            assert_eq!(loocv_com_limite(&dados, k, dados.len()), Ok(esperado), "com matriz, k = {k}");
+            assert_eq!(loocv_com_limite(&dados, k, 0), Ok(esperado), "sem matriz, k = {k}");
+            assert_eq!(loocv(&dados, k), Ok(esperado), "loocv, k = {k}");
+        }
+    }
+}
+
+#[test]
+fn entradas_invalidas_sao_erros_nos_dois_caminhos() {
+    let dados = dados_sobrepostos(5, 1);
+    for limite in [0, 10] {
+        assert_eq!(loocv_com_limite(&[], 3, limite), Err(ErroKnn::ConjuntoVazio));
+        assert_eq!(loocv_com_limite(&dados[..1], 3, limite), Err(ErroKnn::ConjuntoVazio));
+        assert_eq!(loocv_com_limite(&dados, 0, limite), Err(ErroKnn::KInvalido { k: 0, maximo: 4 }));
+    }
+}
+
+#[test]
+fn distancias_nan_ficam_de_fora_como_no_knn() {
+    // Entre dois pontos com infinito na mesma coordenada a distância é NaN (inf - inf);
+    // até os pontos finitos ela é infinita. Com k grande, os NaN mudariam a votação
+    let mut dados = dados_sobrepostos(30, 4);
+    for (i, rotulo) in ["A", "C", "C", "B", "C"].iter().enumerate() {
+        dados.push(Ponto::novo(vec![f64::INFINITY, i as f64], rotulo.to_string()));
+    }
+    for k in [1, 3, 30, 100] {
+        let esperado = loocv_por_forca_bruta(&dados, k);
+        assert_eq!(loocv_com_limite(&dados, k, dados.len()), Ok(esperado), "com matriz, k = {k}");
+        assert_eq!(loocv_com_limite(&dados, k, 0), Ok(esperado), "sem matriz, k = {k}");
+    }
+}
+
+#[test]
+fn ponto_so_com_distancias_nan_e_vetor_vazio() {
+    let mut dados = dados_sobrepostos(10, 5);
+    dados.push(Ponto::novo(vec![f64::NAN, 1.0], "A".to_string()));
+    for limite in [0, dados.len()] {
+        let erro = loocv_com_limite(&dados, 3, limite).unwrap_err();
+        assert!(matches!(erro, ErroKnn::VetorVazio(_)), "{erro:?}");
+    }
+}
@@ -0,0 +1,64 @@
+// Testes de integração da leitura de números com vírgula decimal (arquivos do Excel em
+// português), a partir dos arquivos de exemplo em tests/fixtures
+
+use aprendizagem_knn::{carregar_dados_do_csv_com_opcoes, pontos, ErroKnn, OpcoesCsv};
+
+const VIRGULA_DECIMAL: &str = "tests/fixtures/virgula_decimal.csv";
+const MISTURADO: &str = "tests/fixtures/virgula_decimal_misturado.csv";
+
+fn opcoes(virgula_decimal: Option<bool>) -> OpcoesCsv {
+    OpcoesCsv { delimitador: Some(b';'), virgula_decimal, ..OpcoesCsv::default() }
+}
+
+#[test]
+fn converte_virgula_decimal_milhar_e_negativos() {
+    let (colunas, dados) = carregar_dados_do_csv_com_opcoes(VIRGULA_DECIMAL, &opcoes(Some(true))).unwrap();
+    assert_eq!(colunas, vec!["comprimento", "largura"]);
+    assert_eq!(
+        dados,
+        pontos![
+            [5.1, 3.5] => "setosa",
+            [4.9, 3.0] => "setosa",
+            [1234.5, -0.25] => "grande; \"rara\"",
+            [-1_000_000.0, 0.5] => "negativa",
+        ]
+    );
+}
+
+#[test]
+fn ponto_e_virgula_ativa_a_virgula_decimal_automaticamente() {
+    let automatico = carregar_dados_do_csv_com_opcoes(VIRGULA_DECIMAL, &opcoes(None)).unwrap();
+    let explicito = carregar_dados_do_csv_com_opcoes(VIRGULA_DECIMAL, &opcoes(Some(true))).unwrap();
+    assert_eq!(automatico, explicito);
+
+    // Também quando o próprio delimitador é detectado
+    let detectado = OpcoesCsv { delimitador: None, virgula_decimal: None, ..OpcoesCsv::default() };
+    assert_eq!(carregar_dados_do_csv_com_opcoes(VIRGULA_DECIMAL, &detectado).unwrap(), explicito);
+}
+
+#[test]
+fn sem_a_opcao_a_virgula_decimal_e_erro() {
+    let erro = carregar_dados_do_csv_com_opcoes(VIRGULA_DECIMAL, &opcoes(Some(false))).unwrap_err().to_string();
+    assert!(erro.contains("linha 2") && erro.contains("'5,1'"), "{}", erro);
+}
+
+#[test]
+fn arquivo_misturado_e_recusado_com_o_valor_e_a_posicao() {
+    // A linha 3 tem "4.9", com ponto decimal, no meio de valores com vírgula
+    let erro = carregar_dados_do_csv_com_opcoes(MISTURADO, &opcoes(Some(true))).unwrap_err();
+    assert!(matches!(erro, ErroKnn::LinhaInvalida { linha: 3, .. }), "{:?}", erro);
+    let erro = erro.to_string();
+    assert!(erro.contains(MISTURADO), "{}", erro);
+    assert!(erro.contains("linha 3: coluna 1 ('comprimento')"), "{}", erro);
+    assert!(erro.contains("'4.9'"), "{}", erro);
+}
+
+#[test]
+fn milhar_sem_virgula_e_ambiguo() {
+    let caminho = std::env::temp_dir().join("aprendizagem_knn_teste_milhar_ambiguo.csv");
+    std::fs::write(&caminho, "x;classe\n1.234;A\n").unwrap();
+    let erro = carregar_dados_do_csv_com_opcoes(caminho.to_str().unwrap(), &opcoes(Some(true)))
+        .unwrap_err()
+        .to_string();
+    assert!(erro.contains("linha 2") && erro.contains("ambíguo"), "{}", erro);
+}
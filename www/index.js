@@ -0,0 +1,49 @@
+// Demonstração do KNN compilado para WebAssembly.
+// O pacote em ./pkg é gerado por:
+//     wasm-pack build --target web --features wasm --out-dir www/pkg
+import init, { carregar_csv_wasm, knn_wasm } from "./pkg/aprendizagem_knn.js";
+
+const arquivo = document.getElementById("arquivo");
+const resumo = document.getElementById("resumo");
+const campoCaracteristicas = document.getElementById("caracteristicas");
+const campoK = document.getElementById("k");
+const botao = document.getElementById("classificar");
+const resultado = document.getElementById("resultado");
+
+// Treinamento em JSON, devolvido por carregar_csv_wasm e repassado a knn_wasm
+let treinamentoJson = null;
+
+function mostrar(texto, ehErro = false) {
+    resultado.textContent = texto;
+    resultado.className = ehErro ? "erro" : "";
+}
+
+await init();
+
+arquivo.addEventListener("change", async () => {
+    const [selecionado] = arquivo.files;
+    if (!selecionado) return;
+    try {
+        treinamentoJson = carregar_csv_wasm(await selecionado.text());
+        resumo.textContent = `${JSON.parse(treinamentoJson).length} pontos carregados`;
+        botao.disabled = false;
+        mostrar("");
+    } catch (erro) {
+        // Os erros do Rust chegam como exceções
+        treinamentoJson = null;
+        botao.disabled = true;
+        mostrar(erro.message, true);
+    }
+});
+
+botao.addEventListener("click", () => {
+    const caracteristicas = Float64Array.from(
+        campoCaracteristicas.value.split(",").map((valor) => Number(valor.trim()))
+    );
+    try {
+        const rotulo = knn_wasm(treinamentoJson, caracteristicas, "Desconhecido", Number(campoK.value));
+        mostrar(`Rótulo previsto: ${rotulo}`);
+    } catch (erro) {
+        mostrar(erro.message, true);
+    }
+});